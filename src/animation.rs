@@ -0,0 +1,540 @@
+use std::time::{Duration, Instant};
+use crate::transform::Transform;
+
+/// The time shared by everything rendered in one frame, so animations started together stay in step.
+#[derive(Copy, Clone, Debug)]
+pub struct FrameClock {
+    start: Instant,
+    now: Instant,
+    delta: Duration,
+    frame: u64,
+}
+
+impl FrameClock {
+    pub fn new() -> Self {
+        let now = Instant::now();
+
+        Self {
+            start: now,
+            now,
+            delta: Duration::ZERO,
+            frame: 0,
+        }
+    }
+
+    /// Advances the clock to the current time. Called once at the start of every frame.
+    pub fn tick(&mut self) {
+        self.tick_to(Instant::now());
+    }
+
+    /// Advances the clock to `now`, which must not be before the last frame.
+    pub fn tick_to(&mut self, now: Instant) {
+        self.delta = now - self.now;
+        self.now = now;
+        self.frame += 1;
+    }
+
+    pub fn get_now(&self) -> Instant {
+        self.now
+    }
+
+    /// The time since the last frame.
+    pub fn get_delta(&self) -> Duration {
+        self.delta
+    }
+
+    /// The time since the clock was created.
+    pub fn get_elapsed(&self) -> Duration {
+        self.now - self.start
+    }
+
+    pub fn get_frame(&self) -> u64 {
+        self.frame
+    }
+}
+
+impl Default for FrameClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Maps the linear progress of an animation between 0 and 1 to the progress of its value.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Easing {
+    Linear,
+    EaseIn,
+    EaseOut,
+    EaseInOut,
+    /// Like `cubic-bezier(x1, y1, x2, y2)` in CSS.
+    CubicBezier(f32, f32, f32, f32),
+    /// A damped spring of unit mass released at the start value. It is cut off at the end of the duration,
+    /// so the duration should be long enough for the spring to settle.
+    Spring { stiffness: f32, damping: f32 },
+}
+
+impl Easing {
+    /// Applies the curve to `progress`. Springs need the `duration` of the animation, as they run in real time.
+    pub fn apply(&self, progress: f32, duration: Duration) -> f32 {
+        if progress <= 0.0 {
+            return 0.0;
+        }
+        if progress >= 1.0 {
+            return 1.0;
+        }
+
+        match *self {
+            Easing::Linear => progress,
+            Easing::EaseIn => cubic_bezier((0.42, 0.0), (1.0, 1.0), progress),
+            Easing::EaseOut => cubic_bezier((0.0, 0.0), (0.58, 1.0), progress),
+            Easing::EaseInOut => cubic_bezier((0.42, 0.0), (0.58, 1.0), progress),
+            Easing::CubicBezier(x1, y1, x2, y2) => cubic_bezier((x1, y1), (x2, y2), progress),
+            Easing::Spring { stiffness, damping } => spring(stiffness, damping, progress * duration.as_secs_f32()),
+        }
+    }
+}
+
+fn bezier(p1: f32, p2: f32, s: f32) -> f32 {
+    let inverse = 1.0 - s;
+    3.0 * inverse * inverse * s * p1 + 3.0 * inverse * s * s * p2 + s * s * s
+}
+
+fn bezier_slope(p1: f32, p2: f32, s: f32) -> f32 {
+    let inverse = 1.0 - s;
+    3.0 * inverse * inverse * p1 + 6.0 * inverse * s * (p2 - p1) + 3.0 * s * s * (1.0 - p2)
+}
+
+/// Finds the curve parameter for `x` with Newton's method, falling back to bisection where the curve is flat.
+fn cubic_bezier(p1: (f32, f32), p2: (f32, f32), x: f32) -> f32 {
+    let mut s = x;
+    for _ in 0..8 {
+        let error = bezier(p1.0, p2.0, s) - x;
+        if error.abs() < 1e-5 {
+            return bezier(p1.1, p2.1, s);
+        }
+
+        let slope = bezier_slope(p1.0, p2.0, s);
+        if slope.abs() < 1e-6 {
+            break;
+        }
+        s -= error / slope;
+    }
+
+    let (mut low, mut high) = (0.0, 1.0);
+    s = x;
+    for _ in 0..32 {
+        let value = bezier(p1.0, p2.0, s);
+        if (value - x).abs() < 1e-5 {
+            break;
+        }
+
+        if value < x {
+            low = s;
+        } else {
+            high = s;
+        }
+        s = (low + high) / 2.0;
+    }
+
+    bezier(p1.1, p2.1, s)
+}
+
+fn spring(stiffness: f32, damping: f32, time: f32) -> f32 {
+    let natural_frequency = stiffness.max(0.0).sqrt();
+    if natural_frequency == 0.0 {
+        return 1.0;
+    }
+
+    let damping_ratio = damping / (2.0 * natural_frequency);
+    if damping_ratio < 1.0 {
+        let damped_frequency = natural_frequency * (1.0 - damping_ratio * damping_ratio).sqrt();
+        let decay = damping_ratio * natural_frequency;
+
+        1.0 - (-decay * time).exp() * ((damped_frequency * time).cos() + decay / damped_frequency * (damped_frequency * time).sin())
+    } else {
+        // Overdamped springs are approximated by a critically damped one
+        1.0 - (-natural_frequency * time).exp() * (1.0 + natural_frequency * time)
+    }
+}
+
+/// A value that can be animated.
+pub trait Animatable: Copy + 'static {
+    /// Returns the value `progress` of the way from `self` to `to`. Springs overshoot, so `progress` may leave 0 to 1.
+    fn interpolate(&self, to: &Self, progress: f32) -> Self;
+}
+
+impl Animatable for f32 {
+    fn interpolate(&self, to: &Self, progress: f32) -> Self {
+        self + (to - self) * progress
+    }
+}
+
+impl<const N: usize> Animatable for [f32; N] {
+    fn interpolate(&self, to: &Self, progress: f32) -> Self {
+        let mut value = *self;
+        for (value, to) in value.iter_mut().zip(to) {
+            *value = value.interpolate(to, progress);
+        }
+        value
+    }
+}
+
+impl<A: Animatable, B: Animatable> Animatable for (A, B) {
+    fn interpolate(&self, to: &Self, progress: f32) -> Self {
+        (self.0.interpolate(&to.0, progress), self.1.interpolate(&to.1, progress))
+    }
+}
+
+impl Animatable for Transform {
+    /// Interpolates the matrix entries. Good for translations and scales, rotations shrink on the way.
+    fn interpolate(&self, to: &Self, progress: f32) -> Self {
+        Transform {
+            a: self.a.interpolate(&to.a, progress),
+            b: self.b.interpolate(&to.b, progress),
+            c: self.c.interpolate(&to.c, progress),
+            d: self.d.interpolate(&to.d, progress),
+            tx: self.tx.interpolate(&to.tx, progress),
+            ty: self.ty.interpolate(&to.ty, progress),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Repeat {
+    Once,
+    Times(u32),
+    Forever,
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Segment<T> {
+    to: T,
+    duration: Duration,
+    easing: Easing,
+}
+
+/// A sequence of tweens starting at one value, built like a `Transform`:
+/// `Animation::new(0.0).to(1.0, duration, Easing::EaseOut).then_hold(pause).repeat(Repeat::Forever)`.
+#[derive(Clone, Debug)]
+pub struct Animation<T> {
+    from: T,
+    segments: Vec<Segment<T>>,
+    delay: Duration,
+    repeat: Repeat,
+    alternate: bool,
+}
+
+impl<T: Animatable> Animation<T> {
+    pub fn new(from: T) -> Self {
+        Self {
+            from,
+            segments: vec![],
+            delay: Duration::ZERO,
+            repeat: Repeat::Once,
+            alternate: false,
+        }
+    }
+
+    /// A single tween from `from` to `to`.
+    pub fn tween(from: T, to: T, duration: Duration, easing: Easing) -> Self {
+        Self::new(from).to(to, duration, easing)
+    }
+
+    /// Appends a tween from the end of the sequence to `to`.
+    pub fn to(mut self, to: T, duration: Duration, easing: Easing) -> Self {
+        self.segments.push(Segment { to, duration, easing });
+        self
+    }
+
+    /// Appends a pause at the end of the sequence.
+    pub fn then_hold(self, duration: Duration) -> Self {
+        let value = self.get_last();
+        self.to(value, duration, Easing::Linear)
+    }
+
+    /// Waits before the first run. The animation shows its start value in the meantime.
+    pub fn delay(mut self, delay: Duration) -> Self {
+        self.delay = delay;
+        self
+    }
+
+    pub fn repeat(mut self, repeat: Repeat) -> Self {
+        self.repeat = repeat;
+        self
+    }
+
+    /// Plays every other run backwards.
+    pub fn alternate(mut self) -> Self {
+        self.alternate = true;
+        self
+    }
+
+    pub fn get_from(&self) -> T {
+        self.from
+    }
+
+    /// The duration of one run of the sequence.
+    pub fn get_run_duration(&self) -> Duration {
+        self.segments.iter().map(|segment| segment.duration).sum()
+    }
+
+    /// The duration including the delay and all repetitions, `None` if it repeats forever.
+    pub fn get_total_duration(&self) -> Option<Duration> {
+        match self.repeat {
+            Repeat::Once => Some(self.delay + self.get_run_duration()),
+            Repeat::Times(times) => Some(self.delay + self.get_run_duration() * times),
+            Repeat::Forever => None,
+        }
+    }
+
+    pub fn is_finished(&self, elapsed: Duration) -> bool {
+        self.get_total_duration().is_some_and(|total| elapsed >= total)
+    }
+
+    /// The value once the animation has finished.
+    pub fn get_end(&self) -> T {
+        let runs = match self.repeat {
+            Repeat::Once => 1,
+            Repeat::Times(times) => times,
+            Repeat::Forever => 1,
+        };
+
+        if self.alternate && runs % 2 == 0 {
+            self.from
+        } else {
+            self.get_last()
+        }
+    }
+
+    /// The value `elapsed` after the animation was started.
+    pub fn value_at(&self, elapsed: Duration) -> T {
+        if elapsed < self.delay {
+            return self.from;
+        }
+        if self.is_finished(elapsed) {
+            return self.get_end();
+        }
+
+        let run_duration = self.get_run_duration();
+        if run_duration.is_zero() {
+            return self.get_end();
+        }
+
+        let elapsed = (elapsed - self.delay).as_secs_f64();
+        let run_duration = run_duration.as_secs_f64();
+
+        let run = (elapsed / run_duration).floor();
+        let mut time = elapsed - run * run_duration;
+        if self.alternate && run as u64 % 2 == 1 {
+            time = run_duration - time;
+        }
+
+        let mut start = self.from;
+        for segment in self.segments.iter() {
+            let duration = segment.duration.as_secs_f64();
+            if time < duration {
+                let progress = segment.easing.apply((time / duration) as f32, segment.duration);
+                return start.interpolate(&segment.to, progress);
+            }
+
+            time -= duration;
+            start = segment.to;
+        }
+
+        start
+    }
+
+    fn get_last(&self) -> T {
+        self.segments.last().map_or(self.from, |segment| segment.to)
+    }
+}
+
+/// How an `Animated` value moves to a new value, like `transition` in CSS.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transition {
+    pub duration: Duration,
+    pub delay: Duration,
+    pub easing: Easing,
+}
+
+impl Transition {
+    pub fn new(duration: Duration, easing: Easing) -> Self {
+        Self {
+            duration,
+            delay: Duration::ZERO,
+            easing,
+        }
+    }
+}
+
+/// A property that animates towards every new value it is set to if it has a transition,
+/// and can run explicit animations.
+#[derive(Clone, Debug)]
+pub struct Animated<T> {
+    target: T,
+    running: Option<(Animation<T>, Instant)>,
+    transition: Option<Transition>,
+}
+
+impl<T: Animatable + PartialEq> Animated<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            target: value,
+            running: None,
+            transition: None,
+        }
+    }
+
+    pub fn get_transition(&self) -> Option<Transition> {
+        self.transition
+    }
+
+    pub fn set_transition(&mut self, transition: Option<Transition>) {
+        self.transition = transition;
+    }
+
+    /// The value the property ends up at.
+    pub fn get_target(&self) -> T {
+        self.target
+    }
+
+    /// The value at `now`.
+    pub fn get(&self, now: Instant) -> T {
+        match self.running.as_ref() {
+            Some((animation, start)) => animation.value_at(now.saturating_duration_since(*start)),
+            None => self.target,
+        }
+    }
+
+    /// Moves to `value`, starting the transition at `now` from the current value.
+    pub fn set(&mut self, value: T, now: Instant) {
+        if value == self.target {
+            return;
+        }
+
+        match self.transition {
+            Some(transition) => {
+                let animation = Animation::tween(self.get(now), value, transition.duration, transition.easing)
+                    .delay(transition.delay);
+                self.animate(animation, now);
+            }
+            None => self.set_immediately(value),
+        }
+    }
+
+    /// Jumps to `value`, stopping any running animation.
+    pub fn set_immediately(&mut self, value: T) {
+        self.target = value;
+        self.running = None;
+    }
+
+    /// Runs `animation` from `now`. The property keeps its end value afterwards.
+    pub fn animate(&mut self, animation: Animation<T>, now: Instant) {
+        self.target = animation.get_end();
+        self.running = Some((animation, now));
+    }
+
+    /// Whether the value still changes after `now`. Finished animations are dropped.
+    pub fn is_active(&mut self, now: Instant) -> bool {
+        if let Some((animation, start)) = self.running.as_ref() {
+            if animation.is_finished(now.saturating_duration_since(*start)) {
+                self.running = None;
+            }
+        }
+
+        self.running.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SECOND: Duration = Duration::from_secs(1);
+
+    fn assert_near(value: f32, expected: f32) {
+        assert!((value - expected).abs() < 1e-3, "{} is not {}", value, expected);
+    }
+
+    #[test]
+    fn every_easing_starts_at_0_and_ends_at_1() {
+        let easings = [
+            Easing::Linear,
+            Easing::EaseIn,
+            Easing::EaseOut,
+            Easing::EaseInOut,
+            Easing::CubicBezier(0.68, -0.55, 0.27, 1.55),
+            Easing::Spring { stiffness: 170.0, damping: 26.0 },
+        ];
+
+        for easing in easings {
+            assert_eq!(easing.apply(0.0, SECOND), 0.0, "{:?}", easing);
+            assert_eq!(easing.apply(1.0, SECOND), 1.0, "{:?}", easing);
+            assert_eq!(easing.apply(-0.5, SECOND), 0.0, "{:?}", easing);
+            assert_eq!(easing.apply(1.5, SECOND), 1.0, "{:?}", easing);
+        }
+
+        assert_near(Easing::EaseInOut.apply(0.5, SECOND), 0.5);
+        assert!(Easing::EaseIn.apply(0.25, SECOND) < 0.25);
+        assert!(Easing::EaseOut.apply(0.25, SECOND) > 0.25);
+    }
+
+    #[test]
+    fn cubic_bezier_finds_the_parameter_where_the_curve_is_flat() {
+        // Curves with y = x give back their input, the first one is flat in the middle so Newton's method stalls there
+        for (p1, p2) in [((1.0, 1.0), (0.0, 0.0)), ((0.9, 0.9), (0.1, 0.1)), ((0.25, 0.25), (0.75, 0.75))] {
+            for step in 0..=20 {
+                let x = step as f32 / 20.0;
+                assert!((cubic_bezier(p1, p2, x) - x).abs() < 1e-3, "{:?} {:?} at {}", p1, p2, x);
+            }
+        }
+
+        // The CSS ease curve at a point computed with a high precision solver
+        assert!((cubic_bezier((0.25, 0.1), (0.25, 1.0), 0.5) - 0.8024).abs() < 1e-3);
+    }
+
+    #[test]
+    fn springs_settle_at_the_end_value() {
+        let bouncy = Easing::Spring { stiffness: 200.0, damping: 5.0 };
+        let samples: Vec<f32> = (1..100).map(|step| bouncy.apply(step as f32 / 100.0, Duration::from_secs(4))).collect();
+        assert!(samples.iter().any(|value| *value > 1.05), "an underdamped spring overshoots");
+        assert_near(*samples.last().unwrap(), 1.0);
+
+        // Overdamped springs never overshoot
+        let stiff = Easing::Spring { stiffness: 100.0, damping: 40.0 };
+        let samples: Vec<f32> = (1..100).map(|step| stiff.apply(step as f32 / 100.0, SECOND)).collect();
+        assert!(samples.windows(2).all(|pair| pair[0] <= pair[1] && pair[1] <= 1.0));
+        assert_near(*samples.last().unwrap(), 1.0);
+
+        assert_eq!(spring(0.0, 1.0, 0.1), 1.0);
+    }
+
+    #[test]
+    fn delayed_alternating_repeats_follow_the_frame_clock() {
+        let mut clock = FrameClock::new();
+        let start = clock.get_now();
+        let mut value = Animated::new(0.0);
+        value.animate(Animation::tween(0.0, 10.0, SECOND, Easing::Linear)
+                          .delay(SECOND / 2)
+                          .repeat(Repeat::Times(3))
+                          .alternate(), clock.get_now());
+
+        let mut at = |millis: u64| {
+            clock.tick_to(start + Duration::from_millis(millis));
+            (value.get(clock.get_now()), value.is_active(clock.get_now()))
+        };
+
+        // The start value is shown during the delay, the second run plays backwards
+        let expected = [(250, 0.0), (1000, 5.0), (1250, 7.5), (1750, 7.5), (2250, 2.5), (2750, 2.5), (3250, 7.5)];
+        for (millis, expected) in expected {
+            let (value, active) = at(millis);
+            assert_near(value, expected);
+            assert!(active, "still running at {} ms", millis);
+        }
+
+        // Three runs end forwards
+        assert_eq!(at(3500), (10.0, false));
+        assert_eq!(clock.get_delta(), Duration::from_millis(250));
+        assert_eq!(clock.get_elapsed(), Duration::from_millis(3500));
+    }
+}
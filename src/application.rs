@@ -0,0 +1,55 @@
+use std::any::Any;
+use std::rc::Rc;
+use crate::components::component::Component;
+use crate::stylesheet::Stylesheet;
+use crate::theme::Theme;
+
+/// The application logic, kept outside of the widgets.
+/// Components emit messages while they handle events and the messages are passed to `update` afterwards.
+pub trait Application: 'static {
+    type Message: 'static;
+
+    /// Builds the component tree. It is called when the window opened and after every `update`.
+    /// Later trees are reconciled with the retained one, so only what changed is rebuilt.
+    fn view(&self) -> Box<dyn Component>;
+    fn update(&mut self, message: Self::Message);
+
+    /// The theme styles are resolved with. Like `view`, it is asked again after every `update`.
+    fn theme(&self) -> Theme {
+        Theme::default()
+    }
+
+    /// The rules styles are cascaded from, below the styles set on the components. It is asked again after every `update`.
+    fn stylesheet(&self) -> Rc<Stylesheet> {
+        Rc::default()
+    }
+}
+
+/// An application with its message type erased, so `State` does not need to be generic.
+pub(crate) trait AnyApplication {
+    fn view(&self) -> Box<dyn Component>;
+    /// Messages of another type than the application message are dropped.
+    fn update(&mut self, message: Box<dyn Any>);
+    fn theme(&self) -> Theme;
+    fn stylesheet(&self) -> Rc<Stylesheet>;
+}
+
+impl<A: Application> AnyApplication for A {
+    fn view(&self) -> Box<dyn Component> {
+        Application::view(self)
+    }
+
+    fn update(&mut self, message: Box<dyn Any>) {
+        if let Ok(message) = message.downcast::<A::Message>() {
+            Application::update(self, *message);
+        }
+    }
+
+    fn theme(&self) -> Theme {
+        Application::theme(self)
+    }
+
+    fn stylesheet(&self) -> Rc<Stylesheet> {
+        Application::stylesheet(self)
+    }
+}
@@ -0,0 +1,104 @@
+use wgpu::{BindGroup, BindGroupLayout, CommandEncoder, Device, RenderPipeline, SurfaceConfiguration, TextureView};
+use crate::profiler;
+use crate::texture::Texture;
+
+/// Keeps the last frame, so only the damaged part of the window has to be rendered again.
+/// The surface texture is not retained between frames, so the whole back buffer is copied onto it every frame.
+pub struct BackBuffer {
+    texture: Texture,
+    bind_group_layout: BindGroupLayout,
+    bind_group: BindGroup,
+    pipeline: RenderPipeline,
+}
+
+impl BackBuffer {
+    pub fn new(config: &SurfaceConfiguration, device: &Device) -> Self {
+        let bind_group_layout = Texture::create_bind_group_layout(device, "Back Buffer Bind Group Layout");
+
+        let texture = Texture::create_render_target(device, config, "Back Buffer");
+        let bind_group = Self::create_bind_group(&texture, &bind_group_layout, device);
+        let pipeline = Self::create_pipeline(config, &bind_group_layout, device);
+
+        Self {
+            texture,
+            bind_group_layout,
+            bind_group,
+            pipeline,
+        }
+    }
+
+    fn create_bind_group(texture: &Texture, layout: &BindGroupLayout, device: &Device) -> BindGroup {
+        texture.create_bind_group(layout, device, "Back Buffer Bind Group")
+    }
+
+    fn create_pipeline(config: &SurfaceConfiguration, layout: &BindGroupLayout, device: &Device) -> RenderPipeline {
+        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+            label: Some("Blit Shader"),
+            source: wgpu::ShaderSource::Wgsl(include_str!("blit.wgsl").into()),
+        });
+
+        let pipeline_layout = device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Blit Pipeline Layout"),
+            bind_group_layouts: &[layout],
+            push_constant_ranges: &[],
+        });
+
+        profiler::count_pipeline();
+        device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+            label: Some("Blit Pipeline"),
+            layout: Some(&pipeline_layout),
+            vertex: wgpu::VertexState {
+                module: &shader,
+                entry_point: "vs_main",
+                buffers: &[],
+            },
+            fragment: Some(wgpu::FragmentState {
+                module: &shader,
+                entry_point: "fs_main",
+                targets: &[Some(wgpu::ColorTargetState {
+                    format: config.format,
+                    blend: Some(wgpu::BlendState::REPLACE),
+                    write_mask: wgpu::ColorWrites::ALL,
+                })],
+            }),
+            primitive: wgpu::PrimitiveState::default(),
+            depth_stencil: None,
+            multisample: wgpu::MultisampleState::default(),
+            multiview: None,
+        })
+    }
+
+    /// Recreates the texture for the new surface size. Its content is lost, so the next frame has to be drawn completely.
+    pub fn resize(&mut self, config: &SurfaceConfiguration, device: &Device) {
+        self.texture = Texture::create_render_target(device, config, "Back Buffer");
+        self.bind_group = Self::create_bind_group(&self.texture, &self.bind_group_layout, device);
+    }
+
+    /// The view everything is rendered to.
+    pub fn get_view(&self) -> &TextureView {
+        &self.texture.view
+    }
+
+    /// Copies the back buffer onto `target`.
+    pub fn present(&self, target: &TextureView, encoder: &mut CommandEncoder) {
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("Blit Pass"),
+            color_attachments: &[
+                Some(wgpu::RenderPassColorAttachment {
+                    view: target,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: true,
+                    },
+                })
+            ],
+            depth_stencil_attachment: None,
+        });
+
+        render_pass.set_pipeline(&self.pipeline);
+        render_pass.set_bind_group(0, &self.bind_group, &[]);
+        render_pass.draw(0..3, 0..1);
+        profiler::count_draw_call();
+    }
+}
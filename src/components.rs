@@ -1,4 +1,35 @@
 pub mod component;
 pub mod layout;
 pub mod plain;
-pub mod clickable;
\ No newline at end of file
+pub mod clickable;
+pub mod clip;
+pub mod event;
+pub mod scroll_view;
+pub mod virtual_list;
+pub mod overlay;
+pub mod focus;
+pub mod button;
+pub mod press;
+pub mod reactive;
+pub mod view;
+pub mod oval;
+pub mod label;
+pub mod image;
+pub mod reconcile;
+pub mod invalidation;
+pub mod animated;
+pub mod styled_box;
+pub mod markup;
+pub mod dump;
+pub mod inspector;
+pub mod performance_hud;
+pub mod checkbox;
+pub mod radio_group;
+pub mod toggle;
+pub mod slider;
+pub mod dropdown;
+pub mod dialog;
+pub mod toast;
+pub mod tooltip;
+pub mod stack;
+pub mod tabs;
@@ -0,0 +1,228 @@
+use std::time::Instant;
+use wgpu::RenderPass;
+use crate::animation::{Animatable, Animated, Animation, Transition};
+use crate::components::component::{Component, ComponentPlacement, DirtyFlags, Rect, RenderContext, downcast_component};
+use crate::components::event::{ComponentEvent, EventContext};
+use crate::components::tooltip::TooltipOptions;
+use crate::style::Style;
+use crate::transform::Transform;
+
+/// Steps a custom property animation and returns whether it is still running.
+type PropertyAnimation<C> = Box<dyn FnMut(&mut C, Instant) -> bool>;
+
+/// Wraps a component and animates its rectangle, transform and opacity, plus any other property through `animate`.
+/// With a transition set, `resize`, `set_transform` and `set_opacity` move there smoothly instead of jumping.
+pub struct AnimatedComponent<C: Component> {
+    component: C,
+
+    rect: Animated<Rect>,
+    transform: Animated<Transform>,
+    opacity: Animated<f32>,
+    properties: Vec<PropertyAnimation<C>>,
+}
+
+impl<C: Component> AnimatedComponent<C> {
+    pub fn new(component: C) -> Self {
+        Self {
+            rect: Animated::new((component.get_top_left(), component.get_bottom_right())),
+            transform: Animated::new(component.get_transform()),
+            opacity: Animated::new(1.0),
+            properties: vec![],
+            component,
+        }
+    }
+
+    pub fn get_component(&self) -> &C {
+        &self.component
+    }
+
+    pub fn get_component_mut(&mut self) -> &mut C {
+        &mut self.component
+    }
+
+    /// Sets the transition used when the rectangle, transform or opacity change.
+    pub fn set_transition(&mut self, transition: Option<Transition>) {
+        self.rect.set_transition(transition);
+        self.transform.set_transition(transition);
+        self.opacity.set_transition(transition);
+    }
+
+    pub fn get_opacity(&self) -> f32 {
+        self.opacity.get_target()
+    }
+
+    pub fn set_opacity(&mut self, opacity: f32) {
+        self.opacity.set(opacity, Instant::now());
+    }
+
+    pub fn animate_rect(&mut self, animation: Animation<Rect>) {
+        self.rect.animate(animation, Instant::now());
+    }
+
+    pub fn animate_transform(&mut self, animation: Animation<Transform>) {
+        self.transform.animate(animation, Instant::now());
+    }
+
+    pub fn animate_opacity(&mut self, animation: Animation<f32>) {
+        self.opacity.animate(animation, Instant::now());
+    }
+
+    /// Animates any other property, like a color, by passing every frame's value to `apply`.
+    pub fn animate<T: Animatable>(&mut self, animation: Animation<T>, mut apply: impl FnMut(&mut C, T) + 'static) {
+        let start = Instant::now();
+
+        self.properties.push(Box::new(move |component, now| {
+            let elapsed = now.saturating_duration_since(start);
+            apply(component, animation.value_at(elapsed));
+            !animation.is_finished(elapsed)
+        }));
+    }
+}
+
+impl<C: Component> Component for AnimatedComponent<C> {
+    fn render<'a>(&'a mut self, parent_top_left: &(f32, f32), parent_bottom_right: &(f32, f32), render_pass: &mut RenderPass<'a>, context: &RenderContext) {
+        let now = context.clock.get_now();
+
+        let (top_left, bottom_right) = self.rect.get(now);
+        if top_left != self.component.get_top_left() || bottom_right != self.component.get_bottom_right() {
+            self.component.resize(top_left, bottom_right);
+        }
+
+        let transform = self.transform.get(now);
+        if transform != self.component.get_transform() {
+            self.component.set_transform(transform);
+        }
+
+        let component = &mut self.component;
+        self.properties.retain_mut(|property| property(component, now));
+
+        // A moving component is drawn somewhere else next frame, which is not known yet.
+        // Otherwise the placement of the last frame is damaged, the component is borrowed while it renders.
+        let placement = *self.component.get_placement();
+        if self.rect.is_active(now) || self.transform.is_active(now) || placement.window_size == (0, 0) {
+            context.invalidation.request_redraw();
+        } else if self.opacity.is_active(now) || !self.properties.is_empty() {
+            context.invalidation.invalidate_placement(&placement);
+        }
+
+        let opacity = context.opacity * self.opacity.get(now);
+        self.component.render(parent_top_left, parent_bottom_right, render_pass, &context.with_opacity(opacity));
+    }
+
+    fn get_top_left(&self) -> (f32, f32) {
+        self.rect.get_target().0
+    }
+
+    fn get_bottom_right(&self) -> (f32, f32) {
+        self.rect.get_target().1
+    }
+
+    fn get_transform(&self) -> Transform {
+        self.transform.get_target()
+    }
+
+    fn set_transform(&mut self, transform: Transform) {
+        self.transform.set(transform, Instant::now());
+    }
+
+    fn get_z_index(&self) -> i32 {
+        self.component.get_z_index()
+    }
+
+    fn set_z_index(&mut self, z_index: i32) {
+        self.component.set_z_index(z_index);
+    }
+
+    fn get_key(&self) -> Option<&str> {
+        self.component.get_key()
+    }
+
+    fn set_key(&mut self, key: &str) {
+        self.component.set_key(key);
+    }
+
+    fn get_type_name(&self) -> &'static str {
+        self.component.get_type_name()
+    }
+
+    fn get_id(&self) -> Option<&str> {
+        self.component.get_id()
+    }
+
+    fn set_id(&mut self, id: &str) {
+        self.component.set_id(id);
+    }
+
+    fn get_classes(&self) -> &[String] {
+        self.component.get_classes()
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.component.add_class(class);
+    }
+
+    fn get_style(&self) -> Option<&Style> {
+        self.component.get_style()
+    }
+
+    fn get_children(&self) -> Vec<&dyn Component> {
+        self.component.get_children()
+    }
+
+    fn get_dirty_flags(&self) -> DirtyFlags {
+        self.component.get_dirty_flags()
+    }
+
+    fn get_event_handlers(&self) -> Vec<&'static str> {
+        self.component.get_event_handlers()
+    }
+
+    fn get_tooltip(&self) -> Option<&TooltipOptions> {
+        self.component.get_tooltip()
+    }
+
+    fn get_placement(&self) -> &ComponentPlacement {
+        self.component.get_placement()
+    }
+
+    fn layout(&mut self, parent: &ComponentPlacement) {
+        self.component.layout(parent);
+    }
+
+    fn on_event(&mut self, event: &ComponentEvent, context: &mut EventContext) -> bool {
+        self.component.on_event(event, context)
+    }
+
+    fn in_bound(&self, point: (f32, f32)) -> bool {
+        self.component.in_bound(point)
+    }
+
+    fn reconcile(&mut self, new: Box<dyn Component>) -> Result<(), Box<dyn Component>> {
+        let new = *downcast_component::<Self>(new)?;
+
+        if let Err(component) = self.component.reconcile(Box::new(new.component)) {
+            self.component = *downcast_component::<C>(component).ok().expect("the component has the same type");
+        }
+
+        // Running animations are kept and the animations of the rebuilt component are dropped,
+        // so a rebuild does not restart an entry animation. Changed values transition to their new targets.
+        let now = Instant::now();
+
+        self.rect.set_transition(new.rect.get_transition());
+        self.rect.set(new.rect.get_target(), now);
+        self.transform.set_transition(new.transform.get_transition());
+        self.transform.set(new.transform.get_target(), now);
+        self.opacity.set_transition(new.opacity.get_transition());
+        self.opacity.set(new.opacity.get_target(), now);
+
+        Ok(())
+    }
+
+    fn resize(&mut self, new_box_top_left: (f32, f32), new_box_bottom_right: (f32, f32)) {
+        self.rect.set((new_box_top_left, new_box_bottom_right), Instant::now());
+    }
+
+    fn on_resize(&mut self) {
+        self.component.on_resize();
+    }
+}
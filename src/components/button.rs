@@ -0,0 +1,529 @@
+use wgpu::RenderPass;
+use winit::event::VirtualKeyCode;
+use crate::Shape;
+use crate::animation::{Animated, Transition};
+use crate::components::component::{Component, ComponentBasicResizeData, ComponentPlacement, DirtyFlags, RenderContext, downcast_component};
+use crate::components::event::{ComponentEvent, EventCallback, EventContext};
+use crate::components::focus::FocusId;
+use crate::components::press::{Press, Pressable, animated_color};
+use crate::components::reconcile::reconcile_component;
+use crate::shapes::pipeline::StencilMode;
+use crate::shapes::rounded_quad::RoundedQuad;
+use crate::stylesheet::{ElementState, StyleElement};
+use crate::transform::Transform;
+
+/// The visual state a button is drawn in.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ButtonState {
+    Normal,
+    Hovered,
+    Pressed,
+    Disabled,
+    Focused,
+}
+
+/// How a button looks in one state. Sizes are given in pixels.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ButtonStyle {
+    pub background: [f32; 3],
+    pub border: [f32; 3],
+    pub border_width: f32,
+    pub corner_radius: f32,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ButtonStyles {
+    pub normal: ButtonStyle,
+    pub hovered: ButtonStyle,
+    pub pressed: ButtonStyle,
+    pub disabled: ButtonStyle,
+    pub focused: ButtonStyle,
+}
+
+impl ButtonStyles {
+    pub fn get(&self, state: ButtonState) -> &ButtonStyle {
+        match state {
+            ButtonState::Normal => &self.normal,
+            ButtonState::Hovered => &self.hovered,
+            ButtonState::Pressed => &self.pressed,
+            ButtonState::Disabled => &self.disabled,
+            ButtonState::Focused => &self.focused,
+        }
+    }
+}
+
+impl Default for ButtonStyles {
+    fn default() -> Self {
+        let normal = ButtonStyle {
+            background: [0.2, 0.4, 0.8],
+            border: [0.1, 0.2, 0.5],
+            border_width: 2.0,
+            corner_radius: 6.0,
+        };
+
+        Self {
+            normal,
+            hovered: ButtonStyle { background: [0.3, 0.5, 0.9], ..normal },
+            pressed: ButtonStyle { background: [0.1, 0.3, 0.6], ..normal },
+            disabled: ButtonStyle { background: [0.5, 0.5, 0.5], border: [0.4, 0.4, 0.4], ..normal },
+            focused: ButtonStyle { border: [1.0, 1.0, 1.0], ..normal },
+        }
+    }
+}
+
+/// A button that calls its `on_press` callback when it is pressed and released inside,
+/// or activated with Space or Enter while focused.
+pub struct Button {
+    basic: ComponentBasicResizeData,
+
+    styles: ButtonStyles,
+    content: Option<Box<dyn Component>>,
+    on_press: Option<EventCallback>,
+
+    pressable: Pressable,
+
+    /// The colors fade between the styles of the states.
+    background_color: Animated<[f32; 3]>,
+    border_color: Animated<[f32; 3]>,
+
+    /// The style of the last frame. Only a change of its sizes creates new shapes, colors are set as tints.
+    drawn_style: Option<ButtonStyle>,
+    border: Option<RoundedQuad>,
+    background: Option<RoundedQuad>,
+}
+
+impl Button {
+    pub fn new(top_left: (f32, f32), bottom_right: (f32, f32)) -> Self {
+        let styles = ButtonStyles::default();
+
+        Self {
+            basic: ComponentBasicResizeData {
+                top_left,
+                bottom_right,
+                needs_resize: true,
+                transform: Transform::IDENTITY,
+                z_index: 0,
+                key: None,
+                id: None,
+                classes: vec![],
+                placement: ComponentPlacement::default(),
+            },
+            styles,
+            content: None,
+            on_press: None,
+            pressable: Pressable::new(Self::is_activation_key),
+            background_color: animated_color(styles.normal.background),
+            border_color: animated_color(styles.normal.border),
+            drawn_style: None,
+            border: None,
+            background: None,
+        }
+    }
+
+    /// Sets the callback called when the button is activated. Use `EventContext::emit` to notify the application.
+    pub fn set_on_press(&mut self, on_press: impl FnMut(&mut EventContext) + 'static) {
+        self.on_press = Some(Box::new(on_press));
+    }
+
+    /// Emits a clone of `message` every time the button is activated.
+    pub fn set_on_press_message<M: Clone + 'static>(&mut self, message: M) {
+        self.set_on_press(move |context| context.emit(message.clone()));
+    }
+
+    /// Sets the label or icon drawn inside the button. Its rectangle is relative to the button.
+    pub fn set_content(&mut self, content: Box<dyn Component>) {
+        self.content = Some(content);
+    }
+
+    pub fn get_styles(&self) -> &ButtonStyles {
+        &self.styles
+    }
+
+    pub fn set_styles(&mut self, styles: ButtonStyles) {
+        self.styles = styles;
+        self.basic.needs_resize = true;
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.pressable.is_enabled()
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.pressable.set_enabled(enabled);
+    }
+
+    pub fn get_transition(&self) -> Option<Transition> {
+        self.background_color.get_transition()
+    }
+
+    /// Sets how the colors change between states, `None` switches them immediately.
+    pub fn set_transition(&mut self, transition: Option<Transition>) {
+        self.background_color.set_transition(transition);
+        self.border_color.set_transition(transition);
+    }
+
+    pub fn get_focus_id(&self) -> FocusId {
+        self.pressable.get_focus_id()
+    }
+
+    pub fn get_state(&self) -> ButtonState {
+        if !self.pressable.is_enabled() {
+            ButtonState::Disabled
+        } else if self.pressable.is_pressed() {
+            ButtonState::Pressed
+        } else if self.pressable.is_hovered() {
+            ButtonState::Hovered
+        } else if self.pressable.is_focused() {
+            ButtonState::Focused
+        } else {
+            ButtonState::Normal
+        }
+    }
+
+    /// Unlike `get_state`, several pseudo-classes can match at once, like `:focus:hover`.
+    fn get_element_state(&self) -> ElementState {
+        self.pressable.get_element_state(false)
+    }
+
+    fn press(&mut self, context: &mut EventContext) {
+        if let Some(on_press) = self.on_press.as_mut() {
+            on_press(context);
+        }
+    }
+
+    fn is_activation_key(key: &VirtualKeyCode) -> bool {
+        matches!(key, VirtualKeyCode::Space | VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter)
+    }
+
+    fn handle_event(&mut self, event: &ComponentEvent, context: &mut EventContext) -> bool {
+        let inside = event.position().is_some_and(|position| self.in_bound(position));
+        let press = self.pressable.handle_event(event, inside, context);
+
+        if press == Press::Activated {
+            self.press(context);
+        }
+        press.is_consumed()
+    }
+
+    /// The shapes are white, `render` tints them with the current colors.
+    fn create_shapes(&mut self, style: ButtonStyle, context: &RenderContext) {
+        let top_left = self.basic.placement.absolute_top_left;
+        let bottom_right = self.basic.placement.absolute_bottom_right;
+
+        let radius = self.basic.placement.radius_to_view(style.corner_radius);
+
+        if style.border_width > 0.0 {
+            let inset = self.basic.placement.radius_to_view(style.border_width);
+
+            self.border = Some(RoundedQuad::new(top_left,
+                                                bottom_right,
+                                                radius,
+                                                [1.0; 3],
+                                                StencilMode::Test,
+                                                context.device,
+                                                context.pipelines));
+
+            let inner_radius = self.basic.placement.radius_to_view((style.corner_radius - style.border_width).max(0.0));
+            self.background = Some(RoundedQuad::new((top_left.0 + inset.0, top_left.1 - inset.1),
+                                                    (bottom_right.0 - inset.0, bottom_right.1 + inset.1),
+                                                    inner_radius,
+                                                    [1.0; 3],
+                                                    StencilMode::Test,
+                                                    context.device,
+                                                    context.pipelines));
+        } else {
+            self.border = None;
+            self.background = Some(RoundedQuad::new(top_left,
+                                                    bottom_right,
+                                                    radius,
+                                                    [1.0; 3],
+                                                    StencilMode::Test,
+                                                    context.device,
+                                                    context.pipelines));
+        }
+    }
+}
+
+impl Component for Button {
+    fn render<'a>(&'a mut self, parent_top_left: &(f32, f32), parent_bottom_right: &(f32, f32), render_pass: &mut RenderPass<'a>, context: &RenderContext) {
+        self.basic.placement.update(*parent_top_left, *parent_bottom_right, self.basic.top_left, self.basic.bottom_right, &self.basic.transform, context);
+
+        self.pressable.update_focus(context.focused);
+        let state = self.get_state();
+
+        // The stylesheet overrides the styles set from code, so `Button:hover` rules apply on hover
+        let mut target = *self.styles.get(state);
+        let type_name = self.get_type_name();
+        let element_state = self.get_element_state();
+        let sheet = context.stylesheet.compute(&context.get_style_path(StyleElement {
+            type_name,
+            id: self.basic.id.as_deref(),
+            classes: &self.basic.classes,
+            state: element_state,
+        }));
+        if let Some(background) = sheet.background {
+            target.background = context.theme.color(background);
+        }
+        if let Some(border) = sheet.border {
+            target.border = context.theme.color(border.color);
+            target.border_width = context.theme.length(border.width);
+        }
+        if let Some(radius) = sheet.radius {
+            target.corner_radius = context.theme.length(radius);
+        }
+
+        let now = context.clock.get_now();
+        self.background_color.set(target.background, now);
+        self.border_color.set(target.border, now);
+
+        let style = ButtonStyle {
+            background: self.background_color.get(now),
+            border: self.border_color.get(now),
+            ..target
+        };
+
+        let resized = self.drawn_style.is_none_or(|drawn| drawn.border_width != style.border_width || drawn.corner_radius != style.corner_radius);
+        if self.basic.needs_resize || resized {
+            self.create_shapes(style, context);
+            self.basic.needs_resize = false;
+        }
+        self.drawn_style = Some(style);
+
+        if self.background_color.is_active(now) || self.border_color.is_active(now) {
+            context.invalidation.invalidate_placement(&self.basic.placement);
+        }
+
+        let world_transform = self.basic.placement.world_transform;
+
+        if let Some(border) = self.border.as_mut() {
+            border.set_transform(world_transform, context.queue);
+            border.set_opacity(context.opacity, context.queue);
+            border.set_tint(style.border, context.queue);
+            border.draw(render_pass);
+        }
+
+        if let Some(background) = self.background.as_mut() {
+            background.set_transform(world_transform, context.queue);
+            background.set_opacity(context.opacity, context.queue);
+            background.set_tint(style.background, context.queue);
+            background.draw(render_pass);
+        }
+
+        let path = context.get_style_path(StyleElement {
+            type_name,
+            id: self.basic.id.as_deref(),
+            classes: &self.basic.classes,
+            state: element_state,
+        });
+        let content_context = context.with_transform(world_transform)
+            .with_inherited(sheet.resolve(context.theme, &context.inherited).inherited);
+
+        if let Some(content) = self.content.as_mut() {
+            content.render(&self.basic.placement.absolute_top_left,
+                           &self.basic.placement.absolute_bottom_right,
+                           render_pass,
+                           &content_context.with_style_path(&path));
+        }
+    }
+
+    fn get_top_left(&self) -> (f32, f32) {
+        self.basic.top_left
+    }
+
+    fn get_bottom_right(&self) -> (f32, f32) {
+        self.basic.bottom_right
+    }
+
+    fn get_transform(&self) -> Transform {
+        self.basic.transform
+    }
+
+    fn set_transform(&mut self, transform: Transform) {
+        self.basic.transform = transform;
+    }
+
+    fn get_z_index(&self) -> i32 {
+        self.basic.z_index
+    }
+
+    fn set_z_index(&mut self, z_index: i32) {
+        self.basic.z_index = z_index;
+    }
+
+    fn get_key(&self) -> Option<&str> {
+        self.basic.key.as_deref()
+    }
+
+    fn set_key(&mut self, key: &str) {
+        self.basic.key = Some(key.to_string());
+    }
+
+    fn get_id(&self) -> Option<&str> {
+        self.basic.id.as_deref()
+    }
+
+    fn set_id(&mut self, id: &str) {
+        self.basic.id = Some(id.to_string());
+    }
+
+    fn get_classes(&self) -> &[String] {
+        &self.basic.classes
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.basic.add_class(class);
+    }
+
+    fn get_children(&self) -> Vec<&dyn Component> {
+        self.content.iter().map(|content| content.as_ref()).collect()
+    }
+
+    fn get_dirty_flags(&self) -> DirtyFlags {
+        DirtyFlags {
+            needs_resize: self.basic.needs_resize,
+            ..DirtyFlags::default()
+        }
+    }
+
+    fn get_event_handlers(&self) -> Vec<&'static str> {
+        self.on_press.iter().map(|_| "on_press").collect()
+    }
+
+    fn get_placement(&self) -> &ComponentPlacement {
+        &self.basic.placement
+    }
+
+    fn layout(&mut self, parent: &ComponentPlacement) {
+        self.basic.placement.update_in(parent, self.basic.top_left, self.basic.bottom_right, &self.basic.transform);
+
+        if let Some(content) = self.content.as_mut() {
+            content.layout(&self.basic.placement);
+        }
+    }
+
+    fn on_event(&mut self, event: &ComponentEvent, context: &mut EventContext) -> bool {
+        let state = self.get_state();
+        let consumed = self.handle_event(event, context);
+
+        if self.get_state() != state {
+            context.invalidate(&self.basic.placement);
+        }
+
+        consumed
+    }
+
+    fn reconcile(&mut self, new: Box<dyn Component>) -> Result<(), Box<dyn Component>> {
+        let new = *downcast_component::<Self>(new)?;
+
+        // The focus id, hover and press state are kept, so a rebuild does not interrupt the user
+        self.basic.reconcile(new.basic);
+        if self.styles != new.styles {
+            self.set_styles(new.styles);
+        }
+        self.set_enabled(new.pressable.is_enabled());
+        self.set_transition(new.background_color.get_transition());
+        self.on_press = new.on_press;
+
+        match (self.content.as_mut(), new.content) {
+            (Some(content), Some(new_content)) => reconcile_component(content, new_content),
+            (_, new_content) => self.content = new_content,
+        }
+
+        Ok(())
+    }
+
+    fn in_bound(&self, point: (f32, f32)) -> bool {
+        let corner_radius = self.drawn_style.unwrap_or(self.styles.normal).corner_radius;
+        self.basic.placement.contains_rounded(point, corner_radius)
+    }
+
+    fn resize(&mut self, new_box_top_left: (f32, f32), new_box_bottom_right: (f32, f32)) {
+        self.basic.top_left = new_box_top_left;
+        self.basic.bottom_right = new_box_bottom_right;
+
+        self.on_resize();
+    }
+
+    fn on_resize(&mut self) {
+        self.basic.needs_resize = true;
+
+        if let Some(content) = self.content.as_mut() {
+            content.on_resize();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use winit::event::MouseButton;
+    use super::*;
+    use crate::components::component::layout;
+
+    fn button() -> Button {
+        let mut button = Button::new((-1.0, 1.0), (1.0, -1.0));
+        button.set_on_press_message("pressed");
+        // A 100 by 100 pixel button filling the window
+        layout(&mut button, (100, 100));
+        button
+    }
+
+    fn messages(context: &mut EventContext) -> Vec<&'static str> {
+        context.take_messages().into_iter().filter_map(|message| message.downcast::<&'static str>().ok()).map(|message| *message).collect()
+    }
+
+    #[test]
+    fn press_and_release_inside_activates() {
+        let mut button = button();
+        let mut context = EventContext::new(None);
+
+        assert!(button.on_event(&ComponentEvent::MousePressed { position: (0.0, 0.0), button: MouseButton::Left }, &mut context));
+        assert_eq!(button.get_state(), ButtonState::Normal);
+        assert!(button.on_event(&ComponentEvent::MouseReleased { position: (0.5, 0.5), button: MouseButton::Left }, &mut context));
+        assert_eq!(messages(&mut context), vec!["pressed"]);
+    }
+
+    #[test]
+    fn release_outside_cancels() {
+        let mut button = button();
+        let mut context = EventContext::new(None);
+
+        button.on_event(&ComponentEvent::MousePressed { position: (0.0, 0.0), button: MouseButton::Left }, &mut context);
+        assert!(button.on_event(&ComponentEvent::MouseReleased { position: (2.0, 0.0), button: MouseButton::Left }, &mut context));
+        assert!(messages(&mut context).is_empty());
+    }
+
+    #[test]
+    fn disabled_button_swallows_presses_without_activating() {
+        let mut button = button();
+        button.set_enabled(false);
+        let mut context = EventContext::new(None);
+
+        assert!(button.on_event(&ComponentEvent::MousePressed { position: (0.0, 0.0), button: MouseButton::Left }, &mut context));
+        assert!(!button.on_event(&ComponentEvent::MouseReleased { position: (0.0, 0.0), button: MouseButton::Left }, &mut context));
+        assert!(messages(&mut context).is_empty());
+    }
+
+    #[test]
+    fn keys_activate_only_while_focused() {
+        let mut button = button();
+        let mut context = EventContext::new(None);
+        assert!(!button.on_event(&ComponentEvent::KeyPressed { key: VirtualKeyCode::Space }, &mut context));
+
+        let mut context = EventContext::new(Some(button.get_focus_id()));
+        assert!(button.on_event(&ComponentEvent::KeyPressed { key: VirtualKeyCode::Space }, &mut context));
+        assert_eq!(button.get_state(), ButtonState::Pressed);
+        assert!(button.on_event(&ComponentEvent::KeyReleased { key: VirtualKeyCode::Space }, &mut context));
+        assert_eq!(messages(&mut context), vec!["pressed"]);
+    }
+
+    #[test]
+    fn corners_are_hit_tested_with_the_drawn_radius() {
+        let mut button = button();
+        let corner = (-0.95, 0.95);
+        assert!(button.in_bound(corner));
+
+        button.drawn_style = Some(ButtonStyle { corner_radius: 40.0, ..button.styles.hovered });
+        assert!(!button.in_bound(corner));
+        assert!(button.in_bound((0.0, 0.0)));
+    }
+}
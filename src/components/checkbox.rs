@@ -0,0 +1,500 @@
+use wgpu::RenderPass;
+use winit::event::VirtualKeyCode;
+use crate::Shape;
+use crate::animation::Animated;
+use crate::components::component::{Component, ComponentBasicResizeData, ComponentPlacement, DirtyFlags, RenderContext, downcast_component};
+use crate::components::event::{ChangeCallback, ComponentEvent, EventContext};
+use crate::components::focus::FocusId;
+use crate::components::press::{Press, Pressable, animated_color};
+use crate::shapes::pipeline::StencilMode;
+use crate::shapes::quad::Quad;
+use crate::shapes::rounded_quad::RoundedQuad;
+use crate::style::Style;
+use crate::stylesheet::{ElementState, StyleElement};
+use crate::theme::Theme;
+use crate::transform::Transform;
+
+/// Disabled checkboxes, radio groups and toggles are drawn with this opacity.
+pub(crate) const DISABLED_OPACITY: f32 = 0.5;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum CheckState {
+    Unchecked,
+    Checked,
+    /// Neither checked nor unchecked, e.g. for a checkbox that selects a partially selected list.
+    Indeterminate,
+}
+
+impl CheckState {
+    /// The state after a click. Only tri-state checkboxes can be made indeterminate by the user.
+    pub fn next(&self, tri_state: bool) -> CheckState {
+        match self {
+            CheckState::Unchecked => CheckState::Checked,
+            CheckState::Checked if tri_state => CheckState::Indeterminate,
+            CheckState::Checked | CheckState::Indeterminate => CheckState::Unchecked,
+        }
+    }
+}
+
+/// How checkboxes, radio options and toggles look. Sizes are given in pixels.
+///
+/// The stylesheet overrides it: `background` replaces the fill in every state, so use `:checked` rules for the checked one,
+/// `foreground` replaces the mark.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CheckStyle {
+    pub background: [f32; 3],
+    pub checked_background: [f32; 3],
+    pub border: [f32; 3],
+    pub focused_border: [f32; 3],
+    /// The check mark, the dot of the selected radio option or the knob of a toggle.
+    pub mark: [f32; 3],
+    pub border_width: f32,
+    pub corner_radius: f32,
+}
+
+impl Default for CheckStyle {
+    fn default() -> Self {
+        Self {
+            background: [0.15, 0.15, 0.15],
+            checked_background: [0.2, 0.4, 0.8],
+            border: [0.5, 0.5, 0.5],
+            focused_border: [1.0, 1.0, 1.0],
+            mark: [1.0, 1.0, 1.0],
+            border_width: 2.0,
+            corner_radius: 4.0,
+        }
+    }
+}
+
+/// The colors and sizes of a `CheckStyle` in one state.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub(crate) struct ResolvedCheckStyle {
+    pub fill: [f32; 3],
+    pub border: [f32; 3],
+    pub mark: [f32; 3],
+    pub border_width: f32,
+    pub corner_radius: f32,
+}
+
+impl CheckStyle {
+    pub(crate) fn resolve(&self, checked: bool, focused: bool, sheet: &Style, theme: &Theme) -> ResolvedCheckStyle {
+        let mut resolved = ResolvedCheckStyle {
+            fill: if checked { self.checked_background } else { self.background },
+            border: if focused { self.focused_border } else { self.border },
+            mark: self.mark,
+            border_width: self.border_width,
+            corner_radius: self.corner_radius,
+        };
+
+        if let Some(background) = sheet.background {
+            resolved.fill = theme.color(background);
+        }
+        if let Some(border) = sheet.border {
+            // The focus ring stays visible over the stylesheet border
+            if !focused {
+                resolved.border = theme.color(border.color);
+            }
+            resolved.border_width = theme.length(border.width);
+        }
+        if let Some(radius) = sheet.radius {
+            resolved.corner_radius = theme.length(radius);
+        }
+        if let Some(foreground) = sheet.foreground {
+            resolved.mark = theme.color(foreground);
+        }
+
+        resolved
+    }
+}
+
+/// Whether Space toggles or selects, like it activates buttons.
+pub(crate) fn is_toggle_key(key: &VirtualKeyCode) -> bool {
+    matches!(key, VirtualKeyCode::Space)
+}
+
+/// A straight line from `from` to `to`, given as fractions of the placement with y pointing down.
+/// Returns the line as an axis aligned quad and the rotation that turns it into place.
+pub(crate) fn create_stroke(placement: &ComponentPlacement, from: (f32, f32), to: (f32, f32), thickness: f32, color: [f32; 3], context: &RenderContext) -> (Quad, Transform) {
+    let (left, top) = placement.absolute_top_left;
+    let (right, bottom) = placement.absolute_bottom_right;
+    let size = placement.pixel_size();
+
+    let delta = ((to.0 - from.0) * size.0, (to.1 - from.1) * size.1);
+    // The line is longer by its thickness, so two lines meeting at a corner leave no gap
+    let length = (delta.0 * delta.0 + delta.1 * delta.1).sqrt() + thickness;
+
+    let center = (left + (from.0 + to.0) / 2.0 * (right - left),
+                  top - (from.1 + to.1) / 2.0 * (top - bottom));
+    let half = (placement.radius_to_view(length / 2.0).0, placement.radius_to_view(thickness / 2.0).1);
+
+    let quad = Quad::new((center.0 - half.0, center.1 + half.1),
+                         (center.0 + half.0, center.1 - half.1),
+                         color,
+                         context.device,
+                         context.pipelines);
+    let rotation = Transform::rotation(delta.1.atan2(delta.0))
+        .to_view_space(center, placement.window_size.0, placement.window_size.1);
+
+    (quad, rotation)
+}
+
+/// A box that is checked and unchecked by clicking it or pressing Space while it is focused.
+/// Tri-state checkboxes cycle through the indeterminate state as well.
+///
+/// The state set by the view wins when the tree is reconciled, so keep it in the application and update it from `on_change`.
+pub struct Checkbox {
+    basic: ComponentBasicResizeData,
+
+    state: CheckState,
+    tri_state: bool,
+    style: CheckStyle,
+    on_change: Option<ChangeCallback<CheckState>>,
+
+    pressable: Pressable,
+
+    fill_color: Animated<[f32; 3]>,
+    border_color: Animated<[f32; 3]>,
+
+    drawn_style: Option<(CheckState, ResolvedCheckStyle)>,
+    border: Option<RoundedQuad>,
+    background: Option<RoundedQuad>,
+    /// The lines of the check mark or the dash, with their rotations.
+    mark: Vec<(Quad, Transform)>,
+}
+
+impl Checkbox {
+    pub fn new(top_left: (f32, f32), bottom_right: (f32, f32), state: CheckState) -> Self {
+        let style = CheckStyle::default();
+
+        Self {
+            basic: ComponentBasicResizeData {
+                top_left,
+                bottom_right,
+                needs_resize: true,
+                transform: Transform::IDENTITY,
+                z_index: 0,
+                key: None,
+                id: None,
+                classes: vec![],
+                placement: ComponentPlacement::default(),
+            },
+            state,
+            tri_state: false,
+            style,
+            on_change: None,
+            pressable: Pressable::new(is_toggle_key),
+            fill_color: animated_color(style.background),
+            border_color: animated_color(style.border),
+            drawn_style: None,
+            border: None,
+            background: None,
+            mark: vec![],
+        }
+    }
+
+    pub fn get_state(&self) -> CheckState {
+        self.state
+    }
+
+    /// Sets the state without calling `on_change`.
+    pub fn set_state(&mut self, state: CheckState) {
+        self.state = state;
+    }
+
+    pub fn is_checked(&self) -> bool {
+        self.state == CheckState::Checked
+    }
+
+    pub fn is_tri_state(&self) -> bool {
+        self.tri_state
+    }
+
+    pub fn set_tri_state(&mut self, tri_state: bool) {
+        self.tri_state = tri_state;
+    }
+
+    pub fn get_check_style(&self) -> &CheckStyle {
+        &self.style
+    }
+
+    pub fn set_check_style(&mut self, style: CheckStyle) {
+        self.style = style;
+        self.basic.needs_resize = true;
+    }
+
+    /// Sets the callback called with the new state when the user changes it.
+    pub fn set_on_change(&mut self, on_change: impl FnMut(CheckState, &mut EventContext) + 'static) {
+        self.on_change = Some(Box::new(on_change));
+    }
+
+    /// Emits the message `message` returns for the new state every time the user changes it.
+    pub fn set_on_change_message<M: 'static>(&mut self, message: impl Fn(CheckState) -> M + 'static) {
+        self.set_on_change(move |state, context| context.emit(message(state)));
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.pressable.is_enabled()
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.pressable.set_enabled(enabled);
+    }
+
+    pub fn get_focus_id(&self) -> FocusId {
+        self.pressable.get_focus_id()
+    }
+
+    fn get_element_state(&self) -> ElementState {
+        self.pressable.get_element_state(self.state == CheckState::Checked)
+    }
+
+    fn toggle(&mut self, context: &mut EventContext) {
+        self.state = self.state.next(self.tri_state);
+
+        if let Some(on_change) = self.on_change.as_mut() {
+            on_change(self.state, context);
+        }
+    }
+
+    fn handle_event(&mut self, event: &ComponentEvent, context: &mut EventContext) -> bool {
+        let inside = event.position().is_some_and(|position| self.in_bound(position));
+        let press = self.pressable.handle_event(event, inside, context);
+
+        if press == Press::Activated {
+            self.toggle(context);
+        }
+        press.is_consumed()
+    }
+
+    fn create_shapes(&mut self, style: ResolvedCheckStyle, context: &RenderContext) {
+        let placement = &self.basic.placement;
+        let top_left = placement.absolute_top_left;
+        let bottom_right = placement.absolute_bottom_right;
+
+        let inset = placement.radius_to_view(style.border_width);
+        let radius = placement.radius_to_view(style.corner_radius);
+        let inner_radius = placement.radius_to_view((style.corner_radius - style.border_width).max(0.0));
+
+        self.border = (style.border_width > 0.0).then(|| RoundedQuad::new(top_left,
+                                                                         bottom_right,
+                                                                         radius,
+                                                                         style.border,
+                                                                         StencilMode::Test,
+                                                                         context.device,
+                                                                         context.pipelines));
+        self.background = Some(RoundedQuad::new((top_left.0 + inset.0, top_left.1 - inset.1),
+                                                (bottom_right.0 - inset.0, bottom_right.1 + inset.1),
+                                                inner_radius,
+                                                style.fill,
+                                                StencilMode::Test,
+                                                context.device,
+                                                context.pipelines));
+
+        let size = placement.pixel_size();
+        let thickness = (size.0.min(size.1) * 0.12).max(1.0);
+        self.mark = match self.state {
+            CheckState::Unchecked => vec![],
+            CheckState::Checked => vec![
+                create_stroke(placement, (0.22, 0.52), (0.42, 0.72), thickness, style.mark, context),
+                create_stroke(placement, (0.42, 0.72), (0.8, 0.3), thickness, style.mark, context),
+            ],
+            CheckState::Indeterminate => vec![
+                create_stroke(placement, (0.25, 0.5), (0.75, 0.5), thickness, style.mark, context),
+            ],
+        };
+    }
+}
+
+impl Component for Checkbox {
+    fn render<'a>(&'a mut self, parent_top_left: &(f32, f32), parent_bottom_right: &(f32, f32), render_pass: &mut RenderPass<'a>, context: &RenderContext) {
+        self.basic.placement.update(*parent_top_left, *parent_bottom_right, self.basic.top_left, self.basic.bottom_right, &self.basic.transform, context);
+
+        self.pressable.update_focus(context.focused);
+        let sheet = context.stylesheet.compute(&context.get_style_path(StyleElement {
+            type_name: self.get_type_name(),
+            id: self.basic.id.as_deref(),
+            classes: &self.basic.classes,
+            state: self.get_element_state(),
+        }));
+        let target = self.style.resolve(self.state != CheckState::Unchecked, self.pressable.is_focused(), &sheet, context.theme);
+
+        let now = context.clock.get_now();
+        self.fill_color.set(target.fill, now);
+        self.border_color.set(target.border, now);
+
+        let style = ResolvedCheckStyle {
+            fill: self.fill_color.get(now),
+            border: self.border_color.get(now),
+            ..target
+        };
+
+        if self.basic.needs_resize || self.drawn_style != Some((self.state, style)) {
+            self.create_shapes(style, context);
+
+            self.drawn_style = Some((self.state, style));
+            self.basic.needs_resize = false;
+        }
+
+        if self.fill_color.is_active(now) || self.border_color.is_active(now) {
+            context.invalidation.invalidate_placement(&self.basic.placement);
+        }
+
+        let world_transform = self.basic.placement.world_transform;
+        let opacity = if self.pressable.is_enabled() { context.opacity } else { context.opacity * DISABLED_OPACITY };
+
+        for shape in self.border.iter_mut().chain(self.background.iter_mut()) {
+            shape.set_transform(world_transform, context.queue);
+            shape.set_opacity(opacity, context.queue);
+            shape.draw(render_pass);
+        }
+
+        for (line, rotation) in self.mark.iter_mut() {
+            line.set_transform(rotation.then(&world_transform), context.queue);
+            line.set_opacity(opacity, context.queue);
+            line.draw(render_pass);
+        }
+    }
+
+    fn get_top_left(&self) -> (f32, f32) {
+        self.basic.top_left
+    }
+
+    fn get_bottom_right(&self) -> (f32, f32) {
+        self.basic.bottom_right
+    }
+
+    fn get_transform(&self) -> Transform {
+        self.basic.transform
+    }
+
+    fn set_transform(&mut self, transform: Transform) {
+        self.basic.transform = transform;
+    }
+
+    fn get_z_index(&self) -> i32 {
+        self.basic.z_index
+    }
+
+    fn set_z_index(&mut self, z_index: i32) {
+        self.basic.z_index = z_index;
+    }
+
+    fn get_key(&self) -> Option<&str> {
+        self.basic.key.as_deref()
+    }
+
+    fn set_key(&mut self, key: &str) {
+        self.basic.key = Some(key.to_string());
+    }
+
+    fn get_id(&self) -> Option<&str> {
+        self.basic.id.as_deref()
+    }
+
+    fn set_id(&mut self, id: &str) {
+        self.basic.id = Some(id.to_string());
+    }
+
+    fn get_classes(&self) -> &[String] {
+        &self.basic.classes
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.basic.add_class(class);
+    }
+
+    fn get_dirty_flags(&self) -> DirtyFlags {
+        DirtyFlags {
+            needs_resize: self.basic.needs_resize,
+            ..DirtyFlags::default()
+        }
+    }
+
+    fn get_event_handlers(&self) -> Vec<&'static str> {
+        self.on_change.iter().map(|_| "on_change").collect()
+    }
+
+    fn get_placement(&self) -> &ComponentPlacement {
+        &self.basic.placement
+    }
+
+    fn layout(&mut self, parent: &ComponentPlacement) {
+        self.basic.placement.update_in(parent, self.basic.top_left, self.basic.bottom_right, &self.basic.transform);
+    }
+
+    fn on_event(&mut self, event: &ComponentEvent, context: &mut EventContext) -> bool {
+        let state = (self.state, self.get_element_state());
+        let consumed = self.handle_event(event, context);
+
+        if (self.state, self.get_element_state()) != state {
+            context.invalidate(&self.basic.placement);
+        }
+
+        consumed
+    }
+
+    fn reconcile(&mut self, new: Box<dyn Component>) -> Result<(), Box<dyn Component>> {
+        let new = *downcast_component::<Self>(new)?;
+
+        // The focus id, hover and press state are kept, the checked state comes from the new view
+        self.basic.reconcile(new.basic);
+        if self.style != new.style {
+            self.set_check_style(new.style);
+        }
+        self.state = new.state;
+        self.tri_state = new.tri_state;
+        self.set_enabled(new.pressable.is_enabled());
+        self.on_change = new.on_change;
+
+        Ok(())
+    }
+
+    fn in_bound(&self, point: (f32, f32)) -> bool {
+        self.basic.placement.contains_rounded(point, self.style.corner_radius)
+    }
+
+    fn resize(&mut self, new_box_top_left: (f32, f32), new_box_bottom_right: (f32, f32)) {
+        self.basic.top_left = new_box_top_left;
+        self.basic.bottom_right = new_box_bottom_right;
+
+        self.on_resize();
+    }
+
+    fn on_resize(&mut self) {
+        self.basic.needs_resize = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use winit::event::MouseButton;
+    use super::*;
+    use crate::components::component::layout;
+
+    #[test]
+    fn only_tri_state_checkboxes_cycle_through_indeterminate() {
+        assert_eq!(CheckState::Unchecked.next(false), CheckState::Checked);
+        assert_eq!(CheckState::Checked.next(false), CheckState::Unchecked);
+        assert_eq!(CheckState::Indeterminate.next(false), CheckState::Unchecked);
+
+        assert_eq!(CheckState::Unchecked.next(true), CheckState::Checked);
+        assert_eq!(CheckState::Checked.next(true), CheckState::Indeterminate);
+        assert_eq!(CheckState::Indeterminate.next(true), CheckState::Unchecked);
+    }
+
+    #[test]
+    fn clicks_cycle_a_tri_state_checkbox() {
+        let mut checkbox = Checkbox::new((-1.0, 1.0), (1.0, -1.0), CheckState::Unchecked);
+        checkbox.set_tri_state(true);
+        layout(&mut checkbox, (100, 100));
+        let mut context = EventContext::new(None);
+
+        let mut states = vec![];
+        for _ in 0..3 {
+            checkbox.on_event(&ComponentEvent::MousePressed { position: (0.0, 0.0), button: MouseButton::Left }, &mut context);
+            checkbox.on_event(&ComponentEvent::MouseReleased { position: (0.0, 0.0), button: MouseButton::Left }, &mut context);
+            states.push(checkbox.get_state());
+        }
+        assert_eq!(states, vec![CheckState::Checked, CheckState::Indeterminate, CheckState::Unchecked]);
+    }
+}
@@ -0,0 +1,305 @@
+use std::rc::Rc;
+use wgpu::{RenderPass, RenderPipeline, SurfaceConfiguration};
+use crate::components::component::{ComponentPlacement, RenderContext};
+use crate::Shape;
+use crate::shapes::pipeline::StencilMode;
+use crate::shapes::rounded_quad::RoundedQuad;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Overflow {
+    /// Children are drawn wherever their rectangles land.
+    Visible,
+    /// Children are cut off at the bounds of the container.
+    Clip,
+}
+
+/// A scissor rectangle in pixels.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct ScissorRect {
+    pub x: u32,
+    pub y: u32,
+    pub width: u32,
+    pub height: u32,
+}
+
+impl ScissorRect {
+    pub fn full(config: &SurfaceConfiguration) -> Self {
+        ScissorRect {
+            x: 0,
+            y: 0,
+            width: config.width,
+            height: config.height,
+        }
+    }
+
+    /// The smallest scissor rectangle containing all given view points, limited to the window.
+    pub fn from_view_points(points: &[(f32, f32)], config: &SurfaceConfiguration) -> Self {
+        let (width, height) = (config.width as f32, config.height as f32);
+
+        let mut min = (f32::MAX, f32::MAX);
+        let mut max = (f32::MIN, f32::MIN);
+
+        for point in points {
+            let pixel = ((point.0 + 1.0) / 2.0 * width, (1.0 - point.1) / 2.0 * height);
+
+            min = (min.0.min(pixel.0), min.1.min(pixel.1));
+            max = (max.0.max(pixel.0), max.1.max(pixel.1));
+        }
+
+        let left = min.0.floor().clamp(0.0, width) as u32;
+        let top = min.1.floor().clamp(0.0, height) as u32;
+        let right = max.0.ceil().clamp(0.0, width) as u32;
+        let bottom = max.1.ceil().clamp(0.0, height) as u32;
+
+        ScissorRect {
+            x: left,
+            y: top,
+            width: right.saturating_sub(left),
+            height: bottom.saturating_sub(top),
+        }
+    }
+
+    pub fn intersect(&self, other: &ScissorRect) -> ScissorRect {
+        let left = self.x.max(other.x);
+        let top = self.y.max(other.y);
+        let right = (self.x + self.width).min(other.x + other.width);
+        let bottom = (self.y + self.height).min(other.y + other.height);
+
+        ScissorRect {
+            x: left,
+            y: top,
+            width: right.saturating_sub(left),
+            height: bottom.saturating_sub(top),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.width == 0 || self.height == 0
+    }
+
+    pub fn apply(&self, render_pass: &mut RenderPass) {
+        render_pass.set_scissor_rect(self.x, self.y, self.width, self.height);
+    }
+}
+
+/// Writes the shape of a container into the stencil buffer and removes it again.
+pub struct ClipMask {
+    shape: RoundedQuad,
+    decrement_pipeline: Rc<RenderPipeline>,
+}
+
+impl ClipMask {
+    pub fn new(top_left: (f32, f32), bottom_right: (f32, f32), radius: (f32, f32), context: &RenderContext) -> Self {
+        Self {
+            shape: RoundedQuad::new(top_left, bottom_right, radius, [0.0, 0.0, 0.0], StencilMode::Increment, context.device, context.pipelines),
+            decrement_pipeline: context.pipelines.get(StencilMode::Decrement, context.device),
+        }
+    }
+
+    fn matches(&self, top_left: (f32, f32), bottom_right: (f32, f32), radius: (f32, f32)) -> bool {
+        self.shape.top_left == top_left && self.shape.bottom_right == bottom_right && self.shape.radius == radius
+    }
+
+    /// Raises the stencil value inside the mask from `depth` to `depth + 1`.
+    pub fn push<'a>(&'a self, depth: u32, render_pass: &mut RenderPass<'a>) {
+        render_pass.set_stencil_reference(depth);
+        self.shape.draw(render_pass);
+        render_pass.set_stencil_reference(depth + 1);
+    }
+
+    /// Lowers the stencil value inside the mask from `depth + 1` back to `depth`.
+    pub fn pop<'a>(&'a self, depth: u32, render_pass: &mut RenderPass<'a>) {
+        render_pass.set_stencil_reference(depth + 1);
+        self.shape.draw_with_pipeline(&self.decrement_pipeline, render_pass);
+        render_pass.set_stencil_reference(depth);
+    }
+}
+
+/// Clips the children of a container. Axis aligned containers only need a scissor rectangle,
+/// rounded or rotated ones additionally write their shape into the stencil buffer.
+pub struct Clipper {
+    mask: Option<ClipMask>,
+    scissor: ScissorRect,
+    use_stencil: bool,
+}
+
+impl Default for Clipper {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clipper {
+    pub fn new() -> Self {
+        Self {
+            mask: None,
+            scissor: ScissorRect { x: 0, y: 0, width: 0, height: 0 },
+            use_stencil: false,
+        }
+    }
+
+    /// Updates the clip to the current placement of the container. `corner_radius` is given in pixels.
+    pub fn prepare(&mut self, placement: &ComponentPlacement, corner_radius: f32, context: &RenderContext) {
+        self.update_scissor(placement, corner_radius, context.config, &context.clip);
+
+        if !self.use_stencil {
+            return;
+        }
+
+        let top_left = placement.absolute_top_left;
+        let bottom_right = placement.absolute_bottom_right;
+        let world_transform = placement.world_transform;
+        let radius = placement.radius_to_view(corner_radius);
+
+        if !matches!(&self.mask, Some(mask) if mask.matches(top_left, bottom_right, radius)) {
+            self.mask = Some(ClipMask::new(top_left, bottom_right, radius, context));
+        }
+
+        if let Some(mask) = self.mask.as_mut() {
+            mask.shape.set_transform(world_transform, context.queue);
+        }
+    }
+
+    /// The scissor rectangle of the container within the clip of its parent, and whether its shape needs the stencil buffer.
+    fn update_scissor(&mut self, placement: &ComponentPlacement, corner_radius: f32, config: &SurfaceConfiguration, parent_clip: &ScissorRect) {
+        let top_left = placement.absolute_top_left;
+        let bottom_right = placement.absolute_bottom_right;
+        let world_transform = placement.world_transform;
+
+        let corners = [
+            world_transform.apply(top_left),
+            world_transform.apply((bottom_right.0, top_left.1)),
+            world_transform.apply(bottom_right),
+            world_transform.apply((top_left.0, bottom_right.1)),
+        ];
+
+        self.scissor = ScissorRect::from_view_points(&corners, config).intersect(parent_clip);
+        self.use_stencil = corner_radius > 0.0 || !world_transform.is_axis_aligned();
+    }
+
+    /// The scissor rectangle and stencil depth of the children inside a parent clip with `stencil_depth`,
+    /// or `None` if nothing of them would be visible.
+    fn get_children_clip(&self, stencil_depth: u32) -> Option<(ScissorRect, u32)> {
+        if self.scissor.is_empty() {
+            return None;
+        }
+
+        let stencil_depth = if self.use_stencil { stencil_depth + 1 } else { stencil_depth };
+        Some((self.scissor, stencil_depth))
+    }
+
+    /// Starts clipping and returns the context the children have to be rendered with,
+    /// or `None` if nothing of them would be visible.
+    pub fn begin<'a, 'r>(&'a self, render_pass: &mut RenderPass<'a>, context: &RenderContext<'r>) -> Option<RenderContext<'r>> {
+        let (clip, stencil_depth) = self.get_children_clip(context.stencil_depth)?;
+
+        self.scissor.apply(render_pass);
+
+        if self.use_stencil {
+            if let Some(mask) = self.mask.as_ref() {
+                mask.push(context.stencil_depth, render_pass);
+            }
+        }
+
+        Some(RenderContext {
+            clip,
+            stencil_depth,
+            ..*context
+        })
+    }
+
+    /// Restores the clip of `context`, which has to be the one passed to `begin`.
+    pub fn end<'a>(&'a self, render_pass: &mut RenderPass<'a>, context: &RenderContext) {
+        if self.use_stencil {
+            if let Some(mask) = self.mask.as_ref() {
+                mask.pop(context.stencil_depth, render_pass);
+            }
+        }
+
+        context.clip.apply(render_pass);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::transform::Transform;
+
+    fn config(width: u32, height: u32) -> SurfaceConfiguration {
+        SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            width,
+            height,
+            present_mode: wgpu::PresentMode::Fifo,
+        }
+    }
+
+    fn rect(x: u32, y: u32, width: u32, height: u32) -> ScissorRect {
+        ScissorRect { x, y, width, height }
+    }
+
+    #[test]
+    fn view_points_are_rounded_outwards_to_pixels() {
+        let config = config(200, 100);
+        assert_eq!(ScissorRect::from_view_points(&[(-1.0, 1.0), (1.0, -1.0)], &config), ScissorRect::full(&config));
+        assert_eq!(ScissorRect::from_view_points(&[(0.0, 0.0), (-1.0, 1.0)], &config), rect(0, 0, 100, 50));
+
+        // Points are in any order and fractions of pixels are covered
+        assert_eq!(ScissorRect::from_view_points(&[(0.5, -0.5), (-0.004, 0.006), (0.2, 0.0)], &config), rect(99, 49, 51, 26));
+    }
+
+    #[test]
+    fn off_window_points_are_clamped_to_the_window() {
+        let config = config(100, 100);
+        assert_eq!(ScissorRect::from_view_points(&[(-3.0, 0.0), (0.0, -3.0)], &config), rect(0, 50, 50, 50));
+
+        let outside = ScissorRect::from_view_points(&[(1.5, 1.5), (3.0, 3.0)], &config);
+        assert!(outside.is_empty());
+        assert_eq!((outside.x, outside.y), (100, 0));
+    }
+
+    #[test]
+    fn intersections_of_disjoint_rects_are_empty() {
+        let left = rect(0, 0, 50, 100);
+        assert_eq!(left.intersect(&rect(25, 25, 50, 50)), rect(25, 25, 25, 50));
+        assert_eq!(left.intersect(&rect(10, 10, 5, 5)), rect(10, 10, 5, 5));
+
+        // Touching edges share no pixel
+        assert!(left.intersect(&rect(50, 0, 50, 100)).is_empty());
+        assert!(left.intersect(&rect(60, 120, 10, 10)).is_empty());
+        assert!(rect(5, 5, 0, 10).is_empty());
+        assert!(rect(5, 5, 10, 0).is_empty());
+        assert!(!rect(5, 5, 1, 1).is_empty());
+    }
+
+    #[test]
+    fn nested_clips_intersect_and_raise_the_stencil_depth_for_rounded_shapes() {
+        let config = config(100, 100);
+        let window = ComponentPlacement::window((100, 100));
+        let full = ScissorRect::full(&config);
+
+        // The top left quarter
+        let mut outer = Clipper::new();
+        outer.update_scissor(&window.with_rect((-1.0, 1.0), (0.0, 0.0)), 0.0, &config, &full);
+        let (outer_clip, outer_depth) = outer.get_children_clip(0).unwrap();
+        assert_eq!((outer_clip, outer_depth), (rect(0, 0, 50, 50), 0));
+
+        // A rounded child reaching out of its parent is cut to it and pushes a stencil mask
+        let mut inner = Clipper::new();
+        inner.update_scissor(&window.with_rect((-0.5, 0.5), (1.0, -1.0)), 4.0, &config, &outer_clip);
+        assert_eq!(inner.get_children_clip(outer_depth), Some((rect(25, 25, 25, 25), 1)));
+
+        // A rotated grandchild needs the stencil as well, its depth is popped back for the siblings of its parent
+        let mut rotated = Clipper::new();
+        let placement = ComponentPlacement { world_transform: Transform::rotation(0.5), ..window.with_rect((-0.4, 0.4), (-0.2, 0.2)) };
+        rotated.update_scissor(&placement, 0.0, &config, &inner.get_children_clip(outer_depth).unwrap().0);
+        assert_eq!(rotated.get_children_clip(1).map(|(_, depth)| depth), Some(2));
+        assert_eq!(outer.get_children_clip(0), Some((outer_clip, 0)));
+
+        // A child outside of the clip of its parent renders nothing
+        let mut hidden = Clipper::new();
+        hidden.update_scissor(&window.with_rect((0.5, -0.5), (1.0, -1.0)), 0.0, &config, &outer_clip);
+        assert_eq!(hidden.get_children_clip(outer_depth), None);
+    }
+}
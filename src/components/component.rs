@@ -1,36 +1,450 @@
-use wgpu::{Device, RenderPass, SurfaceConfiguration};
+use std::any::Any;
+use wgpu::{Device, Queue, RenderPass, SurfaceConfiguration};
+use winit::event::MouseButton;
+use crate::animation::FrameClock;
+use crate::components::clip::{Overflow, ScissorRect};
+use crate::components::focus::FocusId;
+use crate::components::invalidation::Invalidation;
+use crate::components::event::{ComponentEvent, EventContext};
+use crate::components::tooltip::TooltipOptions;
+use crate::profiler;
+use crate::shapes::pipeline::PipelineCache;
+use crate::style::{Edges, InheritedStyle, ResolvedStyle, Style};
+use crate::stylesheet::{StyleElement, StylePath, Stylesheet};
+use crate::theme::Theme;
+use crate::transform::Transform;
+
+/// The top left and bottom right corners of a rectangle.
+pub type Rect = ((f32, f32), (f32, f32));
 
 pub struct ComponentBasicData{
     pub top_left: (f32, f32),
     pub bottom_right: (f32, f32),
+    pub transform: Transform,
+    pub z_index: i32,
+    pub key: Option<String>,
+    pub id: Option<String>,
+    pub classes: Vec<String>,
+    pub placement: ComponentPlacement,
 }
 
 pub struct ComponentBasicResizeData{
     pub top_left: (f32, f32),
     pub bottom_right: (f32, f32),
-    pub needs_resize: bool
+    pub needs_resize: bool,
+    pub transform: Transform,
+    pub z_index: i32,
+    pub key: Option<String>,
+    pub id: Option<String>,
+    pub classes: Vec<String>,
+    pub placement: ComponentPlacement,
+}
+
+impl ComponentBasicData {
+    /// Takes the description of a newly built component, keeping the placement of the last render.
+    pub fn reconcile(&mut self, new: ComponentBasicData) {
+        self.top_left = new.top_left;
+        self.bottom_right = new.bottom_right;
+        self.transform = new.transform;
+        self.z_index = new.z_index;
+        self.key = new.key;
+        self.id = new.id;
+        self.classes = new.classes;
+    }
+
+    pub fn add_class(&mut self, class: &str) {
+        if !self.classes.iter().any(|existing| existing == class) {
+            self.classes.push(class.to_string());
+        }
+    }
+}
+
+impl ComponentBasicResizeData {
+    /// Takes the description of a newly built component, keeping the placement of the last render.
+    /// The shapes are only recreated if the rectangle changed.
+    pub fn reconcile(&mut self, new: ComponentBasicResizeData) {
+        if self.top_left != new.top_left || self.bottom_right != new.bottom_right {
+            self.needs_resize = true;
+        }
+
+        self.top_left = new.top_left;
+        self.bottom_right = new.bottom_right;
+        self.transform = new.transform;
+        self.z_index = new.z_index;
+        self.key = new.key;
+        self.id = new.id;
+        self.classes = new.classes;
+    }
+
+    pub fn add_class(&mut self, class: &str) {
+        if !self.classes.iter().any(|existing| existing == class) {
+            self.classes.push(class.to_string());
+        }
+    }
+}
+
+/// Work a component has left for its next render.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct DirtyFlags {
+    /// The shapes are recreated because the rectangle or the window changed.
+    pub needs_resize: bool,
+    /// The positions of the children are measured again.
+    pub needs_layout: bool,
+    /// A signal read by a reactive component changed.
+    pub needs_refresh: bool,
+}
+
+impl DirtyFlags {
+    pub fn is_clean(&self) -> bool {
+        *self == DirtyFlags::default()
+    }
 }
 
-pub trait Component {
-    fn render<'a>(&'a mut self, parent_top_left: &(f32, f32), parent_bottom_right: &(f32, f32), render_pass: &mut RenderPass<'a>, device: &Device, config: &SurfaceConfiguration);
+/// Where a component ended up on screen during the last render.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct ComponentPlacement {
+    pub absolute_top_left: (f32, f32),
+    pub absolute_bottom_right: (f32, f32),
+    pub world_transform: Transform,
+    pub window_size: (u32, u32),
+}
+
+impl ComponentPlacement {
+    /// The whole window, the parent of a root component.
+    pub fn window(window_size: (u32, u32)) -> Self {
+        Self {
+            absolute_top_left: (-1.0, 1.0),
+            absolute_bottom_right: (1.0, -1.0),
+            world_transform: Transform::IDENTITY,
+            window_size,
+        }
+    }
+
+    /// Recomputes the absolute rectangle and the world transform of a component
+    /// with the given relative rectangle and local transform.
+    pub fn update(&mut self, parent_top_left: (f32, f32), parent_bottom_right: (f32, f32), top_left: (f32, f32), bottom_right: (f32, f32), transform: &Transform, context: &RenderContext) {
+        let parent = ComponentPlacement {
+            absolute_top_left: parent_top_left,
+            absolute_bottom_right: parent_bottom_right,
+            world_transform: context.transform,
+            window_size: (context.config.width, context.config.height),
+        };
+        profiler::time_layout(|| self.update_in(&parent, top_left, bottom_right, transform));
+    }
+
+    /// Like `update`, but without a renderer, so components can be laid out and hit-tested without a GPU.
+    pub fn update_in(&mut self, parent: &ComponentPlacement, top_left: (f32, f32), bottom_right: (f32, f32), transform: &Transform) {
+        let window_size = parent.window_size;
+        let parent_transform = &parent.world_transform;
+
+        let (absolute_top_left, absolute_bottom_right) = ComponentUtils::calculate_absolute_from_relative_view_points(parent.absolute_top_left,
+                                                                                                                      parent.absolute_bottom_right,
+                                                                                                                      top_left,
+                                                                                                                      bottom_right);
+        self.absolute_top_left = absolute_top_left;
+        self.absolute_bottom_right = absolute_bottom_right;
+        self.window_size = window_size;
+
+        self.world_transform = if transform.is_identity() {
+            *parent_transform
+        } else {
+            let center = ((absolute_top_left.0 + absolute_bottom_right.0) / 2.0,
+                          (absolute_top_left.1 + absolute_bottom_right.1) / 2.0);
+            transform.to_view_space(center, window_size.0, window_size.1)
+                .then(parent_transform)
+        };
+    }
+
+    /// The parent placement of children drawn in another absolute rectangle with the same world transform.
+    pub fn with_rect(&self, top_left: (f32, f32), bottom_right: (f32, f32)) -> Self {
+        Self {
+            absolute_top_left: top_left,
+            absolute_bottom_right: bottom_right,
+            ..*self
+        }
+    }
+
+    /// Checks whether a point in view space lies inside the transformed rectangle.
+    pub fn contains(&self, point: (f32, f32)) -> bool {
+        self.contains_rounded(point, 0.0)
+    }
+
+    /// Like `contains`, but with corners rounded by `corner_radius` pixels.
+    pub fn contains_rounded(&self, point: (f32, f32), corner_radius: f32) -> bool {
+        let point = match self.world_transform.inverse() {
+            None => return false,
+            Some(inverse) => inverse.apply(point),
+        };
+
+        let (left, top) = self.absolute_top_left;
+        let (right, bottom) = self.absolute_bottom_right;
+
+        if !(point.0 > left && point.0 < right && point.1 < top && point.1 > bottom) {
+            return false;
+        }
+
+        let radius = self.radius_to_view(corner_radius);
+        if radius.0 <= 0.0 || radius.1 <= 0.0 {
+            return true;
+        }
+
+        let radius = (radius.0.min((right - left) / 2.0), radius.1.min((top - bottom) / 2.0));
+        let corner = (point.0.clamp(left + radius.0, right - radius.0),
+                      point.1.clamp(bottom + radius.1, top - radius.1));
+
+        let distance = ((point.0 - corner.0) / radius.0).powi(2) + ((point.1 - corner.1) / radius.1).powi(2);
+        distance <= 1.0
+    }
+
+    /// Maps a view point into the untransformed space of the absolute rectangle.
+    pub fn to_local(&self, point: (f32, f32)) -> Option<(f32, f32)> {
+        self.world_transform.inverse().map(|inverse| inverse.apply(point))
+    }
+
+    /// The size of the absolute rectangle in pixels.
+    pub fn pixel_size(&self) -> (f32, f32) {
+        ((self.absolute_bottom_right.0 - self.absolute_top_left.0) * self.window_size.0 as f32 / 2.0,
+         (self.absolute_top_left.1 - self.absolute_bottom_right.1) * self.window_size.1 as f32 / 2.0)
+    }
+
+    /// Shrinks the absolute rectangle by `edges` pixels, for margins and paddings.
+    pub fn inset(&mut self, edges: &Edges<f32>) {
+        let (left, top) = (self.radius_to_view(edges.left).0, self.radius_to_view(edges.top).1);
+        let (right, bottom) = (self.radius_to_view(edges.right).0, self.radius_to_view(edges.bottom).1);
+
+        self.absolute_top_left = (self.absolute_top_left.0 + left, self.absolute_top_left.1 - top);
+        self.absolute_bottom_right = (self.absolute_bottom_right.0 - right, self.absolute_bottom_right.1 + bottom);
+    }
+
+    /// Converts a length in pixels to view units along both axes.
+    pub fn radius_to_view(&self, pixels: f32) -> (f32, f32) {
+        (pixels * 2.0 / self.window_size.0.max(1) as f32,
+         pixels * 2.0 / self.window_size.1.max(1) as f32)
+    }
+}
+
+/// Everything a component needs from the renderer while rendering.
+#[derive(Copy, Clone)]
+pub struct RenderContext<'r> {
+    pub device: &'r Device,
+    pub queue: &'r Queue,
+    pub config: &'r SurfaceConfiguration,
+    pub pipelines: &'r PipelineCache,
+    /// The world transform of the parent component.
+    pub transform: Transform,
+    /// Multiplied into the alpha of everything drawn, lowered by fading parents.
+    pub opacity: f32,
+    /// The scissor rectangle currently set on the render pass.
+    pub clip: ScissorRect,
+    /// The stencil value inside the current clip, raised by every stencil clipped container.
+    pub stencil_depth: u32,
+    /// The component that currently receives keyboard input.
+    pub focused: Option<FocusId>,
+    /// Components that keep animating request the next frame here.
+    pub invalidation: &'r Invalidation,
+    /// The time of the current frame. Animations read it instead of the system time.
+    pub clock: &'r FrameClock,
+    /// Resolves the colors and sizes of styles.
+    pub theme: &'r Theme,
+    /// The foreground color and font of the parent.
+    pub inherited: InheritedStyle,
+    pub stylesheet: &'r Stylesheet,
+    /// The styled ancestors, matched by descendant selectors.
+    pub style_path: Option<&'r StylePath<'r>>,
+}
+
+impl<'r> RenderContext<'r> {
+    pub fn with_transform(&self, transform: Transform) -> Self {
+        RenderContext {
+            transform,
+            ..*self
+        }
+    }
+
+    pub fn with_opacity(&self, opacity: f32) -> Self {
+        RenderContext {
+            opacity,
+            ..*self
+        }
+    }
+
+    pub fn with_inherited(&self, inherited: InheritedStyle) -> Self {
+        RenderContext {
+            inherited,
+            ..*self
+        }
+    }
+
+    /// The context for the children of the component at the end of `path`.
+    pub fn with_style_path<'p>(&self, path: &'p StylePath<'p>) -> RenderContext<'p> where 'r: 'p {
+        RenderContext {
+            style_path: Some(path),
+            ..*self
+        }
+    }
+
+    /// Appends a component to the styled ancestors.
+    pub fn get_style_path<'e>(&self, element: StyleElement<'e>) -> StylePath<'e> where 'r: 'e {
+        StylePath {
+            element,
+            parent: self.style_path,
+        }
+    }
+
+    /// Cascades the stylesheet rules matching `path` with the component's own `style` on top and applies the theme.
+    pub fn resolve_style(&self, path: &StylePath, style: &Style) -> ResolvedStyle {
+        profiler::time_layout(|| {
+            let style = if self.stylesheet.is_empty() {
+                *style
+            } else {
+                self.stylesheet.compute(path).merge(style)
+            };
+
+            style.resolve(self.theme, &self.inherited)
+        })
+    }
+}
+
+/// Gives access to the concrete type behind a `dyn Component`.
+pub trait AsAny: Any {
+    fn as_any(&self) -> &dyn Any;
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<T: Any> AsAny for T {
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+/// Returns the concrete component, or the component itself if it has another type.
+pub fn downcast_component<C: Component>(component: Box<dyn Component>) -> Result<Box<C>, Box<dyn Component>> {
+    if AsAny::as_any(component.as_ref()).is::<C>() {
+        Ok(component.into_any().downcast::<C>().expect("the type was checked above"))
+    } else {
+        Err(component)
+    }
+}
+
+pub trait Component: AsAny {
+    fn render<'a>(&'a mut self, parent_top_left: &(f32, f32), parent_bottom_right: &(f32, f32), render_pass: &mut RenderPass<'a>, context: &RenderContext);
     fn get_top_left(&self) -> (f32, f32);
     fn get_bottom_right(&self) -> (f32, f32);
 
-    fn on_click(&self, position: (f32, f32)) {}
-    fn in_bound(&self, point: (f32, f32)) -> bool {
-        if point.0 > self.get_top_left().0 && point.0 < self.get_bottom_right().0 {
-            if point.1 < self.get_top_left().1 && point.1 > self.get_bottom_right().1 {
-                return true;
+    fn get_transform(&self) -> Transform;
+    /// Sets the transform of the component. It is given in pixels and applied around the center of the component.
+    fn set_transform(&mut self, transform: Transform);
+    fn get_placement(&self) -> &ComponentPlacement;
+    /// Places the component and its children in `parent` like `render` does, but without a renderer.
+    /// Rules of the stylesheet are not applied, only the component's own style with the default theme.
+    fn layout(&mut self, parent: &ComponentPlacement);
+
+    fn get_z_index(&self) -> i32;
+    /// Siblings with a higher z-index are drawn above and get events first. Equal ones keep their insertion order.
+    fn set_z_index(&mut self, z_index: i32);
+
+    fn get_key(&self) -> Option<&str>;
+    /// Identifies the component among its siblings when a rebuilt view is reconciled with the retained tree.
+    fn set_key(&mut self, key: &str);
+
+    /// The name type selectors of a stylesheet match, the type without its path and generics by default.
+    fn get_type_name(&self) -> &'static str {
+        let name = std::any::type_name::<Self>();
+        let name = name.split('<').next().unwrap_or(name);
+        name.rsplit("::").next().unwrap_or(name)
+    }
+    fn get_id(&self) -> Option<&str>;
+    /// Names the component for `#id` selectors of a stylesheet.
+    fn set_id(&mut self, id: &str);
+    fn get_classes(&self) -> &[String];
+    /// Adds a class for `.class` selectors of a stylesheet.
+    fn add_class(&mut self, class: &str);
+    /// The component's own style, without the stylesheet rules.
+    fn get_style(&self) -> Option<&Style> {
+        None
+    }
+
+    /// The children in drawing order, for tools that walk the tree.
+    fn get_children(&self) -> Vec<&dyn Component> {
+        Vec::new()
+    }
+    /// Whether the children are cut off at the bounds of the component, they can then only be hit inside of it.
+    fn get_overflow(&self) -> Overflow {
+        Overflow::Visible
+    }
+    fn get_dirty_flags(&self) -> DirtyFlags {
+        DirtyFlags::default()
+    }
+    /// The names of the callbacks that are set, like `on_click`.
+    fn get_event_handlers(&self) -> Vec<&'static str> {
+        Vec::new()
+    }
+    /// The tooltip shown while the pointer rests on the component.
+    fn get_tooltip(&self) -> Option<&TooltipOptions> {
+        None
+    }
+
+    /// Updates the component in place from a newly built component of the same type,
+    /// keeping its interaction state and GPU resources. Returns `new` if it cannot be applied,
+    /// the caller then replaces this component with it.
+    fn reconcile(&mut self, new: Box<dyn Component>) -> Result<(), Box<dyn Component>> {
+        Err(new)
+    }
+
+    fn on_click(&mut self, _position: (f32, f32), _context: &mut EventContext) {}
+    /// Handles an input event and returns whether it was consumed.
+    fn on_event(&mut self, event: &ComponentEvent, context: &mut EventContext) -> bool {
+        if let ComponentEvent::MousePressed { position, button: MouseButton::Left } = event {
+            if self.in_bound(*position) {
+                self.on_click(*position, context);
             }
         }
 
         false
     }
+    fn in_bound(&self, point: (f32, f32)) -> bool {
+        self.get_placement().contains(point)
+    }
 
     fn resize(&mut self, new_box_top_left: (f32, f32), new_box_bottom_right: (f32, f32));
     fn on_resize(&mut self);
 }
 
+/// Lays out a tree in a window of `window_size` pixels without a GPU, so it can be hit-tested and dumped in tests.
+pub fn layout(root: &mut dyn Component, window_size: (u32, u32)) {
+    root.layout(&ComponentPlacement::window(window_size));
+}
+
+/// The deepest component at `position` of a tree that was laid out or rendered. Later children lie above earlier ones.
+pub fn hit_test(root: &dyn Component, position: (f32, f32)) -> Option<&dyn Component> {
+    if root.get_overflow() == Overflow::Clip && !root.in_bound(position) {
+        return None;
+    }
+
+    for child in root.get_children().into_iter().rev() {
+        if let Some(hit) = hit_test(child, position) {
+            return Some(hit);
+        }
+    }
+
+    root.in_bound(position).then_some(root)
+}
+
+/// Resolves a style the way `layout` sees it, without a stylesheet and with the default theme.
+pub(crate) fn resolve_own_style(style: &Style) -> ResolvedStyle {
+    let theme = Theme::default();
+    style.resolve(&theme, &theme.get_inherited())
+}
+
 pub struct ComponentUtils {}
 
 impl ComponentUtils {
@@ -79,4 +493,107 @@ impl ComponentUtils {
 
         (top_left, bottom_right)
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::layout::LayoutComponent;
+    use crate::components::plain::PlainComponent;
+    use crate::style::Length;
+
+    fn assert_close(actual: (f32, f32), expected: (f32, f32)) {
+        assert!((actual.0 - expected.0).abs() < 1e-5 && (actual.1 - expected.1).abs() < 1e-5, "{:?} != {:?}", actual, expected);
+    }
+
+    /// A root filling the window with a child in its right half and a rotated one in its top left quarter.
+    fn tree() -> LayoutComponent {
+        let mut root = LayoutComponent::new((-1.0, 1.0), (1.0, -1.0));
+        let mut right = PlainComponent::new((0.0, 1.0), (1.0, -1.0), [1.0, 0.0, 0.0]);
+        right.set_id("right");
+        root.add_component(Box::new(right));
+
+        let mut rotated = PlainComponent::new((-1.0, 1.0), (0.0, 0.0), [0.0, 1.0, 0.0]);
+        rotated.set_id("rotated");
+        rotated.set_transform(Transform::rotation(std::f32::consts::FRAC_PI_4));
+        root.add_component(Box::new(rotated));
+        root
+    }
+
+    #[test]
+    fn layout_places_a_tree_without_a_device() {
+        let mut root = tree();
+        layout(&mut root, (200, 100));
+
+        let children = root.get_children();
+        let right = children[0].get_placement();
+        assert_close(right.absolute_top_left, (0.0, 1.0));
+        assert_close(right.absolute_bottom_right, (1.0, -1.0));
+        assert_eq!(right.window_size, (200, 100));
+        assert_close(right.pixel_size(), (100.0, 100.0));
+
+        let rotated = children[1].get_placement();
+        assert_close(rotated.absolute_top_left, (-1.0, 1.0));
+        assert_close(rotated.absolute_bottom_right, (0.0, 0.0));
+        assert!(!rotated.world_transform.is_identity());
+    }
+
+    #[test]
+    fn layout_applies_margins_and_paddings_of_the_own_style() {
+        let mut root = LayoutComponent::new((-1.0, 1.0), (1.0, -1.0));
+        root.set_style(Style {
+            padding: Some(Edges::all(Length::Px(10.0))),
+            ..Style::default()
+        });
+        root.add_component(Box::new(PlainComponent::new((-1.0, 1.0), (1.0, -1.0), [1.0; 3])));
+        layout(&mut root, (100, 100));
+
+        let child = root.get_children()[0].get_placement();
+        assert_close(child.absolute_top_left, (-0.8, 0.8));
+        assert_close(child.pixel_size(), (80.0, 80.0));
+    }
+
+    #[test]
+    fn hit_test_finds_the_topmost_deepest_component() {
+        let mut root = tree();
+        layout(&mut root, (200, 100));
+
+        assert_eq!(hit_test(&root, (0.5, 0.0)).and_then(Component::get_id), Some("right"));
+        assert_eq!(hit_test(&root, (-0.5, 0.5)).and_then(Component::get_id), Some("rotated"));
+        // The corner of the top left quarter is outside of it once it is rotated
+        assert_eq!(hit_test(&root, (-0.99, 0.98)).map(Component::get_type_name), Some("LayoutComponent"));
+        assert!(hit_test(&root, (1.5, 0.0)).is_none());
+    }
+
+    #[test]
+    fn children_of_clipped_containers_are_only_hit_inside_of_them() {
+        let mut root = LayoutComponent::new((-1.0, 1.0), (1.0, -1.0));
+        let mut clipped = LayoutComponent::new((-1.0, 1.0), (0.0, 0.0));
+        clipped.set_overflow(Overflow::Clip);
+        // Twice as wide as its parent, the right half sticks out
+        let mut wide = PlainComponent::new((-1.0, 1.0), (3.0, -1.0), [1.0; 3]);
+        wide.set_id("wide");
+        clipped.add_component(Box::new(wide));
+        root.add_component(Box::new(clipped));
+        layout(&mut root, (100, 100));
+
+        assert_eq!(hit_test(&root, (-0.5, 0.5)).and_then(Component::get_id), Some("wide"));
+        // Outside of the clipped container only the root is left
+        assert!(std::ptr::eq(hit_test(&root, (0.5, 0.5)).unwrap().get_placement(), root.get_placement()));
+    }
+
+    #[test]
+    fn relative_rectangles_are_mapped_into_the_parent() {
+        let (top_left, bottom_right) = ComponentUtils::calculate_absolute_from_relative_view_points((0.0, 1.0), (1.0, 0.0), (-1.0, 0.0), (0.0, -1.0));
+        assert_close(top_left, (0.0, 0.5));
+        assert_close(bottom_right, (0.5, 0.0));
+    }
+
+    #[test]
+    fn rounded_corners_are_not_hit() {
+        let placement = ComponentPlacement::window((100, 100));
+        assert!(placement.contains_rounded((-0.99, 0.99), 0.0));
+        assert!(!placement.contains_rounded((-0.99, 0.99), 20.0));
+        assert!(placement.contains_rounded((0.0, 0.0), 20.0));
+    }
+}
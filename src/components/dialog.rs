@@ -0,0 +1,460 @@
+use std::time::{Duration, Instant};
+use wgpu::RenderPass;
+use winit::event::{MouseButton, VirtualKeyCode};
+use crate::Shape;
+use crate::animation::Easing;
+use crate::components::component::{Component, ComponentBasicResizeData, ComponentPlacement, RenderContext};
+use crate::components::event::{ComponentEvent, EventCallback, EventContext};
+use crate::components::focus::FocusId;
+use crate::components::overlay::{Overlay, OverlayId, OverlayOptions};
+use crate::shapes::pipeline::StencilMode;
+use crate::shapes::quad::Quad;
+use crate::shapes::rounded_quad::RoundedQuad;
+use crate::transform::Transform;
+
+/// Dialogs are drawn above the usual overlays, but below popups opened from their content.
+pub const DIALOG_Z_INDEX: i32 = 50;
+const FADE_DURATION: Duration = Duration::from_millis(150);
+
+/// How a dialog and its backdrop look. Sizes are given in pixels.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DialogStyle {
+    pub backdrop: [f32; 3],
+    /// The opacity of the backdrop once it faded in.
+    pub backdrop_opacity: f32,
+    pub background: [f32; 3],
+    pub border: [f32; 3],
+    pub border_width: f32,
+    pub corner_radius: f32,
+}
+
+impl Default for DialogStyle {
+    fn default() -> Self {
+        Self {
+            backdrop: [0.0, 0.0, 0.0],
+            backdrop_opacity: 0.5,
+            background: [0.15, 0.15, 0.15],
+            border: [0.5, 0.5, 0.5],
+            border_width: 1.0,
+            corner_radius: 8.0,
+        }
+    }
+}
+
+/// Closes an open dialog, passed to the content so its buttons can close it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct DialogHandle {
+    id: OverlayId,
+    /// The focus before the dialog was shown, restored when it closes.
+    previous_focus: Option<FocusId>,
+}
+
+impl DialogHandle {
+    pub fn get_overlay_id(&self) -> OverlayId {
+        self.id
+    }
+
+    /// Closes the dialog and gives the focus back to the component that had it before.
+    /// The `on_close` callback of the dialog is not called.
+    pub fn close(&self, context: &mut EventContext) {
+        context.dismiss_overlay(self.id);
+        match self.previous_focus {
+            Some(focus) => context.request_focus(focus),
+            None => context.clear_focus(),
+        }
+    }
+}
+
+/// A modal component centered over a dimmed backdrop.
+///
+/// While it is open, no input reaches the components below it and the keys go to its content.
+pub struct Dialog {
+    /// The size of the panel in pixels.
+    size: (f32, f32),
+    style: DialogStyle,
+    dismissible: bool,
+    on_close: Option<EventCallback>,
+}
+
+impl Dialog {
+    pub fn new(size: (f32, f32)) -> Self {
+        Self {
+            size,
+            style: DialogStyle::default(),
+            dismissible: true,
+            on_close: None,
+        }
+    }
+
+    pub fn get_dialog_style(&self) -> &DialogStyle {
+        &self.style
+    }
+
+    pub fn set_dialog_style(&mut self, style: DialogStyle) {
+        self.style = style;
+    }
+
+    pub fn is_dismissible(&self) -> bool {
+        self.dismissible
+    }
+
+    /// Dismissible dialogs are closed by Escape and by presses on the backdrop.
+    pub fn set_dismissible(&mut self, dismissible: bool) {
+        self.dismissible = dismissible;
+    }
+
+    /// Sets the callback called when the dialog is dismissed by Escape or a press on the backdrop.
+    pub fn set_on_close(&mut self, on_close: impl FnMut(&mut EventContext) + 'static) {
+        self.on_close = Some(Box::new(on_close));
+    }
+
+    /// Emits a clone of `message` every time the dialog is dismissed.
+    pub fn set_on_close_message<M: Clone + 'static>(&mut self, message: M) {
+        self.set_on_close(move |context| context.emit(message.clone()));
+    }
+
+    /// Opens the dialog with the component built by `content`. Its rectangle is relative to the panel.
+    /// The focus is taken from the tree until the dialog closes.
+    pub fn show(self, context: &mut EventContext, content: impl FnOnce(DialogHandle) -> Box<dyn Component>) -> DialogHandle {
+        let handle = DialogHandle {
+            id: OverlayId::next(),
+            previous_focus: context.get_focused(),
+        };
+        context.clear_focus();
+
+        let component = DialogOverlay::new(self, handle, content(handle));
+        context.insert_overlay(Overlay {
+            id: handle.id,
+            component: Box::new(component),
+            options: OverlayOptions {
+                z_index: DIALOG_Z_INDEX,
+                dismiss_on_outside_click: false,
+                modal: true,
+                on_dismiss: None,
+            },
+        });
+        handle
+    }
+}
+
+/// Covers the whole window with the backdrop and draws the panel in its center.
+struct DialogOverlay {
+    basic: ComponentBasicResizeData,
+    dialog: Dialog,
+    handle: DialogHandle,
+    content: Box<dyn Component>,
+    /// The panel in view coordinates, placed when the dialog is rendered.
+    panel: ComponentPlacement,
+
+    shown: Option<Instant>,
+    drawn_size: Option<(u32, u32)>,
+    backdrop: Option<Quad>,
+    border: Option<RoundedQuad>,
+    background: Option<RoundedQuad>,
+}
+
+impl DialogOverlay {
+    fn new(dialog: Dialog, handle: DialogHandle, content: Box<dyn Component>) -> Self {
+        Self {
+            basic: ComponentBasicResizeData {
+                top_left: (-1.0, 1.0),
+                bottom_right: (1.0, -1.0),
+                needs_resize: true,
+                transform: Transform::IDENTITY,
+                z_index: 0,
+                key: None,
+                id: None,
+                classes: vec![],
+                placement: ComponentPlacement::default(),
+            },
+            dialog,
+            handle,
+            content,
+            panel: ComponentPlacement::default(),
+            shown: None,
+            drawn_size: None,
+            backdrop: None,
+            border: None,
+            background: None,
+        }
+    }
+
+    fn dismiss(&mut self, context: &mut EventContext) {
+        self.handle.close(context);
+        if let Some(on_close) = self.dialog.on_close.as_mut() {
+            on_close(context);
+        }
+    }
+
+    /// Centers the panel in the window.
+    fn update_panel(&mut self) {
+        let half_size = self.basic.placement.radius_to_view(1.0);
+        let half_size = (half_size.0 * self.dialog.size.0 / 2.0, half_size.1 * self.dialog.size.1 / 2.0);
+        self.panel = self.basic.placement.with_rect((-half_size.0.min(1.0), half_size.1.min(1.0)),
+                                                    (half_size.0.min(1.0), -half_size.1.min(1.0)));
+    }
+
+    fn create_shapes(&mut self, context: &RenderContext) {
+        let style = self.dialog.style;
+        let (top_left, bottom_right) = (self.panel.absolute_top_left, self.panel.absolute_bottom_right);
+        let inset = self.panel.radius_to_view(style.border_width);
+        let radius = self.panel.radius_to_view(style.corner_radius);
+        let inner_radius = self.panel.radius_to_view((style.corner_radius - style.border_width).max(0.0));
+
+        self.backdrop = Some(Quad::new((-1.0, 1.0), (1.0, -1.0), style.backdrop, context.device, context.pipelines));
+        self.border = (style.border_width > 0.0).then(|| RoundedQuad::new(top_left,
+                                                                         bottom_right,
+                                                                         radius,
+                                                                         style.border,
+                                                                         StencilMode::Test,
+                                                                         context.device,
+                                                                         context.pipelines));
+        self.background = Some(RoundedQuad::new((top_left.0 + inset.0, top_left.1 - inset.1),
+                                                (bottom_right.0 - inset.0, bottom_right.1 + inset.1),
+                                                inner_radius,
+                                                style.background,
+                                                StencilMode::Test,
+                                                context.device,
+                                                context.pipelines));
+    }
+}
+
+impl Component for DialogOverlay {
+    fn render<'a>(&'a mut self, parent_top_left: &(f32, f32), parent_bottom_right: &(f32, f32), render_pass: &mut RenderPass<'a>, context: &RenderContext) {
+        self.basic.placement.update(*parent_top_left, *parent_bottom_right, self.basic.top_left, self.basic.bottom_right, &self.basic.transform, context);
+
+        let window_size = self.basic.placement.window_size;
+        self.update_panel();
+
+        if self.basic.needs_resize || self.drawn_size != Some(window_size) {
+            self.create_shapes(context);
+            self.drawn_size = Some(window_size);
+            self.basic.needs_resize = false;
+        }
+
+        let now = context.clock.get_now();
+        let shown = *self.shown.get_or_insert(now);
+        let progress = (now - shown).as_secs_f32() / FADE_DURATION.as_secs_f32();
+        if progress < 1.0 {
+            context.invalidation.request_redraw();
+        }
+        let fade = Easing::EaseOut.apply(progress, FADE_DURATION);
+
+        let world_transform = self.basic.placement.world_transform;
+        if let Some(backdrop) = self.backdrop.as_mut() {
+            backdrop.set_transform(world_transform, context.queue);
+            backdrop.set_opacity(context.opacity * fade * self.dialog.style.backdrop_opacity, context.queue);
+            backdrop.draw(render_pass);
+        }
+        for shape in self.border.iter_mut().chain(self.background.iter_mut()) {
+            shape.set_transform(world_transform, context.queue);
+            shape.set_opacity(context.opacity * fade, context.queue);
+            shape.draw(render_pass);
+        }
+
+        let content_context = context.with_opacity(context.opacity * fade);
+        self.content.render(&self.panel.absolute_top_left, &self.panel.absolute_bottom_right, render_pass, &content_context);
+    }
+
+    fn get_top_left(&self) -> (f32, f32) {
+        self.basic.top_left
+    }
+
+    fn get_bottom_right(&self) -> (f32, f32) {
+        self.basic.bottom_right
+    }
+
+    fn get_transform(&self) -> Transform {
+        self.basic.transform
+    }
+
+    fn set_transform(&mut self, transform: Transform) {
+        self.basic.transform = transform;
+    }
+
+    fn get_z_index(&self) -> i32 {
+        self.basic.z_index
+    }
+
+    fn set_z_index(&mut self, z_index: i32) {
+        self.basic.z_index = z_index;
+    }
+
+    fn get_key(&self) -> Option<&str> {
+        self.basic.key.as_deref()
+    }
+
+    fn set_key(&mut self, key: &str) {
+        self.basic.key = Some(key.to_string());
+    }
+
+    fn get_id(&self) -> Option<&str> {
+        self.basic.id.as_deref()
+    }
+
+    fn set_id(&mut self, id: &str) {
+        self.basic.id = Some(id.to_string());
+    }
+
+    fn get_classes(&self) -> &[String] {
+        &self.basic.classes
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.basic.add_class(class);
+    }
+
+    fn get_children(&self) -> Vec<&dyn Component> {
+        vec![self.content.as_ref()]
+    }
+
+    fn get_placement(&self) -> &ComponentPlacement {
+        &self.basic.placement
+    }
+
+    fn layout(&mut self, parent: &ComponentPlacement) {
+        self.basic.placement.update_in(parent, self.basic.top_left, self.basic.bottom_right, &self.basic.transform);
+        self.update_panel();
+        self.content.layout(&self.panel);
+    }
+
+    fn on_event(&mut self, event: &ComponentEvent, context: &mut EventContext) -> bool {
+        if self.content.on_event(event, context) {
+            return true;
+        }
+
+        match event {
+            ComponentEvent::MousePressed { position, button: MouseButton::Left } => {
+                let radius = self.dialog.style.corner_radius;
+                if self.dialog.dismissible && !self.panel.contains_rounded(*position, radius) {
+                    self.dismiss(context);
+                }
+                true
+            }
+            ComponentEvent::KeyPressed { key: VirtualKeyCode::Escape } if self.dialog.dismissible => {
+                self.dismiss(context);
+                true
+            }
+            // Nothing below the dialog is reachable, the focus stays inside of it
+            _ => event.is_targeted(),
+        }
+    }
+
+    fn in_bound(&self, _point: (f32, f32)) -> bool {
+        true
+    }
+
+    fn resize(&mut self, new_box_top_left: (f32, f32), new_box_bottom_right: (f32, f32)) {
+        self.basic.top_left = new_box_top_left;
+        self.basic.bottom_right = new_box_bottom_right;
+
+        self.on_resize();
+    }
+
+    fn on_resize(&mut self) {
+        self.basic.needs_resize = true;
+        self.content.on_resize();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::button::Button;
+    use crate::components::component::layout;
+    use crate::components::overlay::OverlayLayer;
+
+    fn press(position: (f32, f32)) -> ComponentEvent {
+        ComponentEvent::MousePressed { position, button: MouseButton::Left }
+    }
+
+    fn release(position: (f32, f32)) -> ComponentEvent {
+        ComponentEvent::MouseReleased { position, button: MouseButton::Left }
+    }
+
+    fn messages(context: &mut EventContext) -> Vec<&'static str> {
+        context.take_messages().into_iter().filter_map(|message| message.downcast::<&'static str>().ok()).map(|message| *message).collect()
+    }
+
+    fn button(message: &'static str) -> Box<dyn Component> {
+        let mut button = Button::new((-1.0, 1.0), (1.0, -1.0));
+        button.set_on_press_message(message);
+        Box::new(button)
+    }
+
+    /// Opens `dialog` with a button filling its panel in a 200 by 100 pixel window,
+    /// the panel of a 100 by 50 pixel dialog covers the center quarter of the view.
+    fn open(dialog: Dialog, layer: &mut OverlayLayer, context: &mut EventContext) -> DialogHandle {
+        let handle = dialog.show(context, |_| button("dialog"));
+        context.apply_overlays(layer);
+        layout(layer.get_component_mut(handle.get_overlay_id()).unwrap().as_mut(), (200, 100));
+        handle
+    }
+
+    #[test]
+    fn showing_takes_the_focus_and_closing_gives_it_back() {
+        let focus = FocusId::next();
+        let mut layer = OverlayLayer::new();
+        let mut context = EventContext::new(Some(focus));
+
+        let handle = open(Dialog::new((100.0, 50.0)), &mut layer, &mut context);
+        assert_eq!(context.take_focus_request(), Some(None));
+        assert!(layer.is_open(handle.get_overlay_id()));
+
+        let mut context = EventContext::new(None);
+        handle.close(&mut context);
+        assert_eq!(context.take_focus_request(), Some(Some(focus)));
+        context.apply_overlays(&mut layer);
+        assert!(!layer.is_open(handle.get_overlay_id()));
+    }
+
+    #[test]
+    fn closing_clears_the_focus_when_nothing_had_it() {
+        let mut layer = OverlayLayer::new();
+        let mut context = EventContext::new(None);
+        let handle = open(Dialog::new((100.0, 50.0)), &mut layer, &mut context);
+
+        // A component of the dialog was focused meanwhile
+        let mut context = EventContext::new(Some(FocusId::next()));
+        handle.close(&mut context);
+        assert_eq!(context.take_focus_request(), Some(None));
+    }
+
+    #[test]
+    fn the_modal_dialog_blocks_the_overlays_below_it() {
+        let mut layer = OverlayLayer::new();
+        layer.show(button("below"), OverlayOptions::default());
+        let mut context = EventContext::new(None);
+        let mut dialog = Dialog::new((100.0, 50.0));
+        dialog.set_dismissible(false);
+        let handle = open(dialog, &mut layer, &mut context);
+        assert!(layer.has_modal());
+
+        // Outside of the panel nothing reacts, but the press is still consumed
+        assert!(layer.on_event(&press((0.9, 0.9)), &mut context));
+        layer.on_event(&release((0.9, 0.9)), &mut context);
+        assert!(messages(&mut context).is_empty());
+        assert!(layer.is_open(handle.get_overlay_id()));
+
+        // Inside of it the content gets the press
+        assert!(layer.on_event(&press((0.0, 0.0)), &mut context));
+        layer.on_event(&release((0.0, 0.0)), &mut context);
+        assert_eq!(messages(&mut context), vec!["dialog"]);
+    }
+
+    #[test]
+    fn dismissible_dialogs_close_on_escape_and_backdrop_presses() {
+        for event in [ComponentEvent::KeyPressed { key: VirtualKeyCode::Escape }, press((0.9, 0.9))] {
+            let mut layer = OverlayLayer::new();
+            let mut context = EventContext::new(None);
+            let mut dialog = Dialog::new((100.0, 50.0));
+            dialog.set_on_close_message("closed");
+            let handle = open(dialog, &mut layer, &mut context);
+
+            assert!(layer.on_event(&event, &mut context));
+            assert_eq!(messages(&mut context), vec!["closed"]);
+            context.apply_overlays(&mut layer);
+            assert!(!layer.is_open(handle.get_overlay_id()));
+        }
+    }
+}
@@ -0,0 +1,1114 @@
+use std::cell::RefCell;
+use std::ops::Range;
+use std::rc::{Rc, Weak};
+use std::time::{Duration, Instant};
+use wgpu::RenderPass;
+use winit::event::{MouseButton, VirtualKeyCode};
+use crate::Shape;
+use crate::components::checkbox::{DISABLED_OPACITY, create_stroke};
+use crate::components::component::{Component, ComponentBasicResizeData, ComponentPlacement, DirtyFlags, Rect, RenderContext, downcast_component};
+use crate::components::event::{ChangeCallback, ComponentEvent, EventContext};
+use crate::components::focus::FocusId;
+use crate::components::overlay::{OverlayId, OverlayOptions};
+use crate::components::reconcile::reconcile_component;
+use crate::shapes::pipeline::StencilMode;
+use crate::shapes::quad::Quad;
+use crate::shapes::rounded_quad::RoundedQuad;
+use crate::stylesheet::{ElementState, StyleElement};
+use crate::transform::Transform;
+
+/// Builds the component shown for an item, in the dropdown and in its popup. Its rectangle is relative to the row.
+pub type ItemBuilder = Rc<dyn Fn(usize) -> Box<dyn Component>>;
+
+/// The popup is drawn above the usual overlays.
+const POPUP_Z_INDEX: i32 = 100;
+/// Typed characters are joined into one search while less than this time passes between them.
+const SEARCH_TIMEOUT: Duration = Duration::from_secs(1);
+/// Width of the marker in front of the selected item in pixels.
+const MARKER_WIDTH: f32 = 3.0;
+
+/// How a dropdown and its popup look. Sizes are given in pixels.
+///
+/// The stylesheet overrides the background, the border and the radius of the dropdown itself.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct DropdownStyle {
+    pub background: [f32; 3],
+    pub border: [f32; 3],
+    pub focused_border: [f32; 3],
+    pub arrow: [f32; 3],
+    pub popup_background: [f32; 3],
+    /// The item under the pointer or picked with the keys.
+    pub highlight: [f32; 3],
+    /// The marker in front of the selected item.
+    pub selected: [f32; 3],
+    pub border_width: f32,
+    pub corner_radius: f32,
+}
+
+impl Default for DropdownStyle {
+    fn default() -> Self {
+        Self {
+            background: [0.15, 0.15, 0.15],
+            border: [0.5, 0.5, 0.5],
+            focused_border: [1.0, 1.0, 1.0],
+            arrow: [0.8, 0.8, 0.8],
+            popup_background: [0.12, 0.12, 0.12],
+            highlight: [0.2, 0.4, 0.8],
+            selected: [1.0, 1.0, 1.0],
+            border_width: 2.0,
+            corner_radius: 4.0,
+        }
+    }
+}
+
+/// Finds items by the first letters of their labels as they are typed.
+#[derive(Default)]
+struct TypeSearch {
+    text: String,
+    last_key: Option<Instant>,
+}
+
+impl TypeSearch {
+    /// Adds a character typed at `now` and returns the first matching item after `current`.
+    /// Typing the same letter repeatedly cycles through the items starting with it.
+    fn push(&mut self, character: char, labels: &[String], current: Option<usize>, now: Instant) -> Option<usize> {
+        if self.last_key.is_none_or(|last_key| now - last_key > SEARCH_TIMEOUT) {
+            self.text.clear();
+        }
+        self.last_key = Some(now);
+        self.text.push(character);
+
+        let repeated = self.text.chars().all(|other| other == character);
+        let (text, skip) = if repeated { (character.to_string(), 1) } else { (self.text.clone(), 0) };
+        let start = current.map_or(0, |current| current + skip);
+
+        (0..labels.len())
+            .map(|offset| (start + offset) % labels.len())
+            .find(|index| labels[*index].to_lowercase().starts_with(&text))
+    }
+}
+
+/// The letter or digit a key types, for the search.
+fn get_typed_character(key: &VirtualKeyCode) -> Option<char> {
+    let key = *key as u32;
+    let letters = VirtualKeyCode::A as u32..=VirtualKeyCode::Z as u32;
+    let digits = VirtualKeyCode::Key1 as u32..=VirtualKeyCode::Key0 as u32;
+    let numpad = VirtualKeyCode::Numpad0 as u32..=VirtualKeyCode::Numpad9 as u32;
+
+    if letters.contains(&key) {
+        char::from_u32('a' as u32 + key - VirtualKeyCode::A as u32)
+    } else if key == VirtualKeyCode::Key0 as u32 {
+        Some('0')
+    } else if digits.contains(&key) {
+        char::from_u32('1' as u32 + key - VirtualKeyCode::Key1 as u32)
+    } else if numpad.contains(&key) {
+        char::from_u32('0' as u32 + key - VirtualKeyCode::Numpad0 as u32)
+    } else {
+        None
+    }
+}
+
+/// What the dropdown and its popup both work on. The popup lives in the overlay layer, outside of the tree.
+struct DropdownShared {
+    labels: Vec<String>,
+    item_builder: Option<ItemBuilder>,
+    /// Increased whenever the items change, so the popup builds its rows again.
+    revision: u64,
+    selected: Option<usize>,
+    on_change: Option<ChangeCallback<usize>>,
+    style: DropdownStyle,
+    max_visible_items: usize,
+
+    focus_id: FocusId,
+    open: Option<OverlayId>,
+    highlighted: Option<usize>,
+    search: TypeSearch,
+    /// The window rectangle of the dropdown during the last render, the popup opens below or above it.
+    anchor: Rect,
+}
+
+impl DropdownShared {
+    fn select(&mut self, index: usize, context: &mut EventContext) {
+        if self.selected == Some(index) {
+            return;
+        }
+
+        self.selected = Some(index);
+        if let Some(on_change) = self.on_change.as_mut() {
+            on_change(index, context);
+        }
+    }
+
+    fn close(&mut self, context: &mut EventContext) {
+        if let Some(id) = self.open.take() {
+            context.dismiss_overlay(id);
+        }
+    }
+}
+
+/// Shows the selected item and opens a list of all items above everything else when it is pressed,
+/// or when Space, Enter or an arrow key is pressed while it is focused.
+///
+/// While the list is open the arrow keys, Page Up, Page Down, Home and End move the highlight, Enter and Space select
+/// the highlighted item and Escape closes it, as does a press outside of it. Typing the first letters of a label
+/// highlights the item, or selects it while the list is closed. The items are labeled for the search and drawn by the item builder.
+///
+/// The selection set by the view wins when the tree is reconciled, so keep it in the application and update it from `on_change`.
+pub struct Dropdown {
+    basic: ComponentBasicResizeData,
+    shared: Rc<RefCell<DropdownShared>>,
+
+    enabled: bool,
+    focused: bool,
+    hovered: bool,
+
+    /// The component of the selected item and the index it was built for.
+    content: Option<Box<dyn Component>>,
+    content_index: Option<usize>,
+    content_revision: u64,
+
+    drawn_style: Option<DropdownStyle>,
+    border: Option<RoundedQuad>,
+    background: Option<RoundedQuad>,
+    arrow: Vec<(Quad, Transform)>,
+}
+
+impl Dropdown {
+    pub fn new(top_left: (f32, f32), bottom_right: (f32, f32), labels: Vec<String>, selected: Option<usize>) -> Self {
+        let selected = selected.filter(|index| *index < labels.len());
+
+        Self {
+            basic: ComponentBasicResizeData {
+                top_left,
+                bottom_right,
+                needs_resize: true,
+                transform: Transform::IDENTITY,
+                z_index: 0,
+                key: None,
+                id: None,
+                classes: vec![],
+                placement: ComponentPlacement::default(),
+            },
+            shared: Rc::new(RefCell::new(DropdownShared {
+                labels,
+                item_builder: None,
+                revision: 0,
+                selected,
+                on_change: None,
+                style: DropdownStyle::default(),
+                max_visible_items: 8,
+                focus_id: FocusId::next(),
+                open: None,
+                highlighted: None,
+                search: TypeSearch::default(),
+                anchor: ((-1.0, 1.0), (1.0, -1.0)),
+            })),
+            enabled: true,
+            focused: false,
+            hovered: false,
+            content: None,
+            content_index: None,
+            content_revision: 0,
+            drawn_style: None,
+            border: None,
+            background: None,
+            arrow: vec![],
+        }
+    }
+
+    pub fn get_labels(&self) -> Vec<String> {
+        self.shared.borrow().labels.clone()
+    }
+
+    pub fn get_selected(&self) -> Option<usize> {
+        self.shared.borrow().selected
+    }
+
+    /// Selects an item without calling `on_change`. Indices past the last item clear the selection.
+    pub fn set_selected(&mut self, selected: Option<usize>) {
+        let mut shared = self.shared.borrow_mut();
+        shared.selected = selected.filter(|index| *index < shared.labels.len());
+    }
+
+    /// Sets the builder of the components that show the items.
+    pub fn set_item_builder(&mut self, item_builder: impl Fn(usize) -> Box<dyn Component> + 'static) {
+        let mut shared = self.shared.borrow_mut();
+        shared.item_builder = Some(Rc::new(item_builder));
+        shared.revision += 1;
+    }
+
+    pub fn get_dropdown_style(&self) -> DropdownStyle {
+        self.shared.borrow().style
+    }
+
+    pub fn set_dropdown_style(&mut self, style: DropdownStyle) {
+        self.shared.borrow_mut().style = style;
+        self.basic.needs_resize = true;
+    }
+
+    /// Longer lists scroll in the popup.
+    pub fn set_max_visible_items(&mut self, max_visible_items: usize) {
+        self.shared.borrow_mut().max_visible_items = max_visible_items.max(1);
+    }
+
+    /// Sets the callback called with the index of the item the user selected.
+    pub fn set_on_change(&mut self, on_change: impl FnMut(usize, &mut EventContext) + 'static) {
+        self.shared.borrow_mut().on_change = Some(Box::new(on_change));
+    }
+
+    /// Emits the message `message` returns for the selected index every time the user selects an item.
+    pub fn set_on_change_message<M: 'static>(&mut self, message: impl Fn(usize) -> M + 'static) {
+        self.set_on_change(move |index, context| context.emit(message(index)));
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.shared.borrow().open.is_some()
+    }
+
+    pub fn get_focus_id(&self) -> FocusId {
+        self.shared.borrow().focus_id
+    }
+
+    fn open(&self, context: &mut EventContext) {
+        let mut shared = self.shared.borrow_mut();
+        if shared.open.is_some() || shared.labels.is_empty() {
+            return;
+        }
+        shared.highlighted = shared.selected.or(Some(0));
+
+        let weak = Rc::downgrade(&self.shared);
+        let id = context.show_overlay(Box::new(DropdownPopup::new(Rc::downgrade(&self.shared))), OverlayOptions {
+            z_index: POPUP_Z_INDEX,
+            dismiss_on_outside_click: true,
+            modal: false,
+            on_dismiss: Some(Box::new(move || {
+                if let Some(shared) = weak.upgrade() {
+                    shared.borrow_mut().open = None;
+                }
+            })),
+        });
+        shared.open = Some(id);
+    }
+
+    fn get_element_state(&self) -> ElementState {
+        ElementState {
+            hovered: self.enabled && self.hovered,
+            pressed: self.is_open(),
+            focused: self.focused,
+            disabled: !self.enabled,
+            checked: false,
+        }
+    }
+
+    fn handle_key(&mut self, key: &VirtualKeyCode, context: &mut EventContext) -> bool {
+        if self.shared.borrow().open.is_none() {
+            return match key {
+                VirtualKeyCode::Space | VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter | VirtualKeyCode::Up | VirtualKeyCode::Down => {
+                    self.open(context);
+                    true
+                }
+                key => match get_typed_character(key) {
+                    Some(character) => {
+                        let mut shared = self.shared.borrow_mut();
+                        let selected = shared.selected;
+                        let labels = std::mem::take(&mut shared.labels);
+                        let found = shared.search.push(character, &labels, selected, Instant::now());
+                        shared.labels = labels;
+
+                        if let Some(index) = found {
+                            shared.select(index, context);
+                        }
+                        true
+                    }
+                    None => false,
+                },
+            };
+        }
+
+        let mut shared = self.shared.borrow_mut();
+        let count = shared.labels.len();
+        let page = shared.max_visible_items.saturating_sub(1).max(1);
+        let highlighted = shared.highlighted.unwrap_or(0);
+
+        let highlight = match key {
+            VirtualKeyCode::Up => Some(highlighted.saturating_sub(1)),
+            VirtualKeyCode::Down => Some(highlighted + 1),
+            VirtualKeyCode::PageUp => Some(highlighted.saturating_sub(page)),
+            VirtualKeyCode::PageDown => Some(highlighted + page),
+            VirtualKeyCode::Home => Some(0),
+            VirtualKeyCode::End => Some(count.saturating_sub(1)),
+            VirtualKeyCode::Space | VirtualKeyCode::Return | VirtualKeyCode::NumpadEnter => {
+                if let Some(index) = shared.highlighted {
+                    shared.select(index, context);
+                }
+                shared.close(context);
+                None
+            }
+            VirtualKeyCode::Escape => {
+                shared.close(context);
+                None
+            }
+            key => match get_typed_character(key) {
+                Some(character) => {
+                    let labels = std::mem::take(&mut shared.labels);
+                    let found = shared.search.push(character, &labels, Some(highlighted), Instant::now());
+                    shared.labels = labels;
+                    found
+                }
+                None => return false,
+            },
+        };
+
+        if let Some(index) = highlight {
+            shared.highlighted = Some(index.min(count.saturating_sub(1)));
+        }
+        // The popup is not in the tree, so it cannot be invalidated on its own
+        context.request_redraw();
+        true
+    }
+
+    fn handle_event(&mut self, event: &ComponentEvent, context: &mut EventContext) -> bool {
+        match event {
+            ComponentEvent::CursorMoved { position } => {
+                self.hovered = self.in_bound(*position);
+                false
+            }
+            ComponentEvent::MousePressed { position, button: MouseButton::Left } => {
+                if !self.in_bound(*position) {
+                    return false;
+                }
+
+                // A press while the popup is open closes it before it gets here
+                if self.enabled {
+                    context.request_focus(self.get_focus_id());
+                    self.open(context);
+                }
+                true
+            }
+            ComponentEvent::KeyPressed { key } => {
+                if !self.enabled || !context.is_focused(self.get_focus_id()) {
+                    return false;
+                }
+
+                self.handle_key(key, context)
+            }
+            _ => false,
+        }
+    }
+
+    fn create_shapes(&mut self, style: DropdownStyle, context: &RenderContext) {
+        let placement = &self.basic.placement;
+        let top_left = placement.absolute_top_left;
+        let bottom_right = placement.absolute_bottom_right;
+
+        let inset = placement.radius_to_view(style.border_width);
+        let radius = placement.radius_to_view(style.corner_radius);
+        let inner_radius = placement.radius_to_view((style.corner_radius - style.border_width).max(0.0));
+
+        self.border = (style.border_width > 0.0).then(|| RoundedQuad::new(top_left,
+                                                                         bottom_right,
+                                                                         radius,
+                                                                         style.border,
+                                                                         StencilMode::Test,
+                                                                         context.device,
+                                                                         context.pipelines));
+        self.background = Some(RoundedQuad::new((top_left.0 + inset.0, top_left.1 - inset.1),
+                                                (bottom_right.0 - inset.0, bottom_right.1 + inset.1),
+                                                inner_radius,
+                                                style.background,
+                                                StencilMode::Test,
+                                                context.device,
+                                                context.pipelines));
+
+        // A chevron in a square on the right, given as fractions of the whole dropdown
+        let size = placement.pixel_size();
+        let square = if size.0 > 0.0 { (size.1 / size.0).min(1.0) } else { 0.0 };
+        let (left, right) = (1.0 - square * 0.65, 1.0 - square * 0.35);
+        let center = (left + right) / 2.0;
+        let thickness = (size.1 * 0.08).max(1.0);
+        self.arrow = vec![
+            create_stroke(placement, (left, 0.42), (center, 0.58), thickness, style.arrow, context),
+            create_stroke(placement, (center, 0.58), (right, 0.42), thickness, style.arrow, context),
+        ];
+    }
+
+    /// Builds the component of the selected item when the selection or the items changed, reusing the previous one.
+    /// The popup is placed in window coordinates, at the bounding box of the transformed dropdown.
+    fn update_anchor(&mut self) {
+        let placement = &self.basic.placement;
+        let (left, top) = placement.absolute_top_left;
+        let (right, bottom) = placement.absolute_bottom_right;
+        let corners = [(left, top), (right, top), (left, bottom), (right, bottom)].map(|corner| placement.world_transform.apply(corner));
+        let anchor = ((corners.iter().map(|corner| corner.0).fold(f32::MAX, f32::min), corners.iter().map(|corner| corner.1).fold(f32::MIN, f32::max)),
+                      (corners.iter().map(|corner| corner.0).fold(f32::MIN, f32::max), corners.iter().map(|corner| corner.1).fold(f32::MAX, f32::min)));
+        self.shared.borrow_mut().anchor = anchor;
+    }
+
+    /// The content takes the part left of the arrow.
+    fn get_content_rect(&self) -> Rect {
+        let placement = &self.basic.placement;
+        let (left, top) = placement.absolute_top_left;
+        let (right, bottom) = placement.absolute_bottom_right;
+        let arrow_width = placement.radius_to_view(placement.pixel_size().1).0.min(right - left);
+        ((left, top), (right - arrow_width, bottom))
+    }
+
+    fn update_content(&mut self) {
+        let (selected, revision, item_builder) = {
+            let shared = self.shared.borrow();
+            (shared.selected, shared.revision, shared.item_builder.clone())
+        };
+        if self.content_index == selected && self.content_revision == revision {
+            return;
+        }
+        self.content_index = selected;
+        self.content_revision = revision;
+
+        match (selected, item_builder) {
+            (Some(index), Some(item_builder)) => {
+                let new_content = item_builder(index);
+                match self.content.as_mut() {
+                    Some(content) => reconcile_component(content, new_content),
+                    None => self.content = Some(new_content),
+                }
+            }
+            _ => self.content = None,
+        }
+    }
+}
+
+impl Component for Dropdown {
+    fn render<'a>(&'a mut self, parent_top_left: &(f32, f32), parent_bottom_right: &(f32, f32), render_pass: &mut RenderPass<'a>, context: &RenderContext) {
+        self.basic.placement.update(*parent_top_left, *parent_bottom_right, self.basic.top_left, self.basic.bottom_right, &self.basic.transform, context);
+
+        let world_transform = self.basic.placement.world_transform;
+        self.focused = context.focused == Some(self.get_focus_id());
+        self.update_anchor();
+
+        let type_name = self.get_type_name();
+        let element_state = self.get_element_state();
+        let sheet = context.stylesheet.compute(&context.get_style_path(StyleElement {
+            type_name,
+            id: self.basic.id.as_deref(),
+            classes: &self.basic.classes,
+            state: element_state,
+        }));
+
+        let mut style = self.shared.borrow().style;
+        if let Some(background) = sheet.background {
+            style.background = context.theme.color(background);
+        }
+        if let Some(border) = sheet.border {
+            style.border = context.theme.color(border.color);
+            style.border_width = context.theme.length(border.width);
+        }
+        if let Some(radius) = sheet.radius {
+            style.corner_radius = context.theme.length(radius);
+        }
+        if self.focused {
+            style.border = style.focused_border;
+        }
+
+        if self.basic.needs_resize || self.drawn_style != Some(style) {
+            self.create_shapes(style, context);
+
+            self.drawn_style = Some(style);
+            self.basic.needs_resize = false;
+        }
+        self.update_content();
+        let (top_left, bottom_right) = self.get_content_rect();
+
+        let opacity = if self.enabled { context.opacity } else { context.opacity * DISABLED_OPACITY };
+
+        for shape in self.border.iter_mut().chain(self.background.iter_mut()) {
+            shape.set_transform(world_transform, context.queue);
+            shape.set_opacity(opacity, context.queue);
+            shape.draw(render_pass);
+        }
+
+        for (line, rotation) in self.arrow.iter_mut() {
+            line.set_transform(rotation.then(&world_transform), context.queue);
+            line.set_opacity(opacity, context.queue);
+            line.draw(render_pass);
+        }
+
+        let path = context.get_style_path(StyleElement {
+            type_name,
+            id: self.basic.id.as_deref(),
+            classes: &self.basic.classes,
+            state: element_state,
+        });
+        let content_context = context.with_transform(world_transform)
+            .with_opacity(opacity)
+            .with_inherited(sheet.resolve(context.theme, &context.inherited).inherited);
+
+        if let Some(content) = self.content.as_mut() {
+            content.render(&top_left, &bottom_right, render_pass, &content_context.with_style_path(&path));
+        }
+    }
+
+    fn get_top_left(&self) -> (f32, f32) {
+        self.basic.top_left
+    }
+
+    fn get_bottom_right(&self) -> (f32, f32) {
+        self.basic.bottom_right
+    }
+
+    fn get_transform(&self) -> Transform {
+        self.basic.transform
+    }
+
+    fn set_transform(&mut self, transform: Transform) {
+        self.basic.transform = transform;
+    }
+
+    fn get_z_index(&self) -> i32 {
+        self.basic.z_index
+    }
+
+    fn set_z_index(&mut self, z_index: i32) {
+        self.basic.z_index = z_index;
+    }
+
+    fn get_key(&self) -> Option<&str> {
+        self.basic.key.as_deref()
+    }
+
+    fn set_key(&mut self, key: &str) {
+        self.basic.key = Some(key.to_string());
+    }
+
+    fn get_id(&self) -> Option<&str> {
+        self.basic.id.as_deref()
+    }
+
+    fn set_id(&mut self, id: &str) {
+        self.basic.id = Some(id.to_string());
+    }
+
+    fn get_classes(&self) -> &[String] {
+        &self.basic.classes
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.basic.add_class(class);
+    }
+
+    fn get_children(&self) -> Vec<&dyn Component> {
+        self.content.iter().map(|content| content.as_ref()).collect()
+    }
+
+    fn get_dirty_flags(&self) -> DirtyFlags {
+        DirtyFlags {
+            needs_resize: self.basic.needs_resize,
+            ..DirtyFlags::default()
+        }
+    }
+
+    fn get_event_handlers(&self) -> Vec<&'static str> {
+        self.shared.borrow().on_change.iter().map(|_| "on_change").collect()
+    }
+
+    fn get_placement(&self) -> &ComponentPlacement {
+        &self.basic.placement
+    }
+
+    fn layout(&mut self, parent: &ComponentPlacement) {
+        self.basic.placement.update_in(parent, self.basic.top_left, self.basic.bottom_right, &self.basic.transform);
+        self.update_anchor();
+        self.update_content();
+
+        let (top_left, bottom_right) = self.get_content_rect();
+        if let Some(content) = self.content.as_mut() {
+            content.layout(&self.basic.placement.with_rect(top_left, bottom_right));
+        }
+    }
+
+    fn on_event(&mut self, event: &ComponentEvent, context: &mut EventContext) -> bool {
+        let state = (self.get_element_state(), self.get_selected());
+        let consumed = self.handle_event(event, context);
+
+        if (self.get_element_state(), self.get_selected()) != state {
+            context.invalidate(&self.basic.placement);
+        }
+
+        consumed
+    }
+
+    fn reconcile(&mut self, new: Box<dyn Component>) -> Result<(), Box<dyn Component>> {
+        let new = *downcast_component::<Self>(new)?;
+
+        // The shared state stays, so an open popup keeps working. The items and the selection come from the new view
+        self.basic.reconcile(new.basic);
+        self.set_enabled(new.enabled);
+
+        let new_shared = match Rc::try_unwrap(new.shared) {
+            Ok(new_shared) => new_shared.into_inner(),
+            Err(_) => return Ok(()),
+        };
+        let mut shared = self.shared.borrow_mut();
+        if shared.style != new_shared.style {
+            shared.style = new_shared.style;
+            self.basic.needs_resize = true;
+        }
+        shared.highlighted = shared.highlighted.filter(|index| *index < new_shared.labels.len());
+        // Closures cannot be compared, so the items are rebuilt and reconciled with every new view
+        shared.labels = new_shared.labels;
+        shared.item_builder = new_shared.item_builder;
+        shared.revision += 1;
+        shared.selected = new_shared.selected;
+        shared.on_change = new_shared.on_change;
+        shared.max_visible_items = new_shared.max_visible_items;
+
+        Ok(())
+    }
+
+    fn in_bound(&self, point: (f32, f32)) -> bool {
+        let corner_radius = self.drawn_style.map_or(0.0, |style| style.corner_radius);
+        self.basic.placement.contains_rounded(point, corner_radius)
+    }
+
+    fn resize(&mut self, new_box_top_left: (f32, f32), new_box_bottom_right: (f32, f32)) {
+        self.basic.top_left = new_box_top_left;
+        self.basic.bottom_right = new_box_bottom_right;
+
+        self.on_resize();
+    }
+
+    fn on_resize(&mut self) {
+        self.basic.needs_resize = true;
+
+        if let Some(content) = self.content.as_mut() {
+            content.on_resize();
+        }
+    }
+}
+
+/// The list of a `Dropdown` in the overlay layer. It scrolls by whole rows, so it needs no clipping.
+/// It only handles the pointer, the keys go to the focused dropdown.
+struct DropdownPopup {
+    basic: ComponentBasicResizeData,
+    /// The popup outlives a dropdown that is removed from the tree while it is open. It then draws nothing
+    /// and closes on the next press.
+    shared: Weak<RefCell<DropdownShared>>,
+
+    /// The components of the items with the revision they were built for, built when they first become visible.
+    rows: Vec<Option<(u64, Box<dyn Component>)>>,
+    first_visible: usize,
+    visible_count: usize,
+    /// The highlight of the last render, the list scrolls to it when it changes.
+    shown_highlight: Option<usize>,
+    pressed: Option<usize>,
+
+    drawn: Option<(Rect, DropdownStyle)>,
+    border: Option<Quad>,
+    background: Option<Quad>,
+    /// Created on the first row and moved by their transforms.
+    highlight: Option<Quad>,
+    marker: Option<Quad>,
+}
+
+impl DropdownPopup {
+    fn new(shared: Weak<RefCell<DropdownShared>>) -> Self {
+        Self {
+            basic: ComponentBasicResizeData {
+                top_left: (-1.0, 1.0),
+                bottom_right: (-1.0, 1.0),
+                needs_resize: true,
+                transform: Transform::IDENTITY,
+                z_index: 0,
+                key: None,
+                id: None,
+                classes: vec![],
+                placement: ComponentPlacement::default(),
+            },
+            shared,
+            rows: vec![],
+            first_visible: 0,
+            visible_count: 0,
+            shown_highlight: None,
+            pressed: None,
+            drawn: None,
+            border: None,
+            background: None,
+            highlight: None,
+            marker: None,
+        }
+    }
+
+    /// The height of a row in view units.
+    fn get_visible_rows(&self) -> Range<usize> {
+        self.first_visible..(self.first_visible + self.visible_count).min(self.rows.len())
+    }
+
+    fn get_row_rect(&self, index: usize) -> Rect {
+        let (left, top) = self.basic.placement.absolute_top_left;
+        let (right, _) = self.basic.placement.absolute_bottom_right;
+        let row_height = self.get_row_height();
+        let row_top = top - (index - self.first_visible) as f32 * row_height;
+        ((left, row_top), (right, row_top - row_height))
+    }
+
+    /// Builds the visible rows that are missing. Rows built for older items are reconciled with new ones,
+    /// so they keep their GPU buffers.
+    fn update_rows(&mut self, shared: &RefCell<DropdownShared>) {
+        let (item_builder, revision) = {
+            let shared = shared.borrow();
+            match shared.item_builder.clone() {
+                None => return,
+                Some(item_builder) => (item_builder, shared.revision),
+            }
+        };
+
+        let visible = self.get_visible_rows();
+        for (index, row) in self.rows.iter_mut().enumerate().skip(visible.start).take(visible.len()) {
+            match row.as_mut() {
+                Some((built, component)) if *built != revision => {
+                    reconcile_component(component, item_builder(index));
+                    *built = revision;
+                }
+                Some(_) => {}
+                None => *row = Some((revision, item_builder(index))),
+            }
+        }
+    }
+
+    fn get_row_height(&self) -> f32 {
+        (self.basic.top_left.1 - self.basic.bottom_right.1) / self.visible_count.max(1) as f32
+    }
+
+    fn get_row_at(&self, position: (f32, f32)) -> Option<usize> {
+        if self.shared.upgrade().is_none() || !self.basic.placement.contains(position) {
+            return None;
+        }
+
+        let row = ((self.basic.top_left.1 - position.1) / self.get_row_height()) as usize;
+        (row < self.visible_count).then_some(self.first_visible + row)
+    }
+
+    /// Places the popup below the dropdown, or above it if there is more room there.
+    fn update_rect(&mut self, shared: &DropdownShared, window_height: u32) {
+        let ((left, top), (right, bottom)) = shared.anchor;
+        let count = shared.labels.len();
+        self.rows.resize_with(count, || None);
+        let row_height = top - bottom;
+        let height_pixels = row_height * window_height as f32 / 2.0;
+
+        let fitting = |space: f32| ((space / row_height) as usize).max(1);
+        let below = bottom + 1.0;
+        let above = 1.0 - top;
+        let max_visible = shared.max_visible_items.min(count).max(1);
+
+        let (visible_count, top) = if fitting(below) >= max_visible || below >= above {
+            (max_visible.min(fitting(below)), bottom)
+        } else {
+            let visible_count = max_visible.min(fitting(above));
+            (visible_count, top + visible_count as f32 * row_height)
+        };
+
+        self.visible_count = if height_pixels > 0.0 { visible_count } else { 0 };
+        let rect = ((left, top), (right, top - self.visible_count as f32 * row_height));
+        if (self.basic.top_left, self.basic.bottom_right) != rect {
+            self.basic.top_left = rect.0;
+            self.basic.bottom_right = rect.1;
+            self.basic.needs_resize = true;
+        }
+
+        let max_first = count.saturating_sub(self.visible_count);
+        self.first_visible = self.first_visible.min(max_first);
+    }
+
+    fn scroll_to(&mut self, index: usize) {
+        if index < self.first_visible {
+            self.first_visible = index;
+        } else if index >= self.first_visible + self.visible_count {
+            self.first_visible = index + 1 - self.visible_count;
+        }
+    }
+
+    fn create_shapes(&mut self, style: DropdownStyle, context: &RenderContext) {
+        let placement = &self.basic.placement;
+        let top_left = placement.absolute_top_left;
+        let bottom_right = placement.absolute_bottom_right;
+        let inset = placement.radius_to_view(1.0);
+        let marker_width = placement.radius_to_view(MARKER_WIDTH).0;
+        let row_bottom = top_left.1 - self.get_row_height();
+
+        let quad = |top_left: (f32, f32), bottom_right: (f32, f32), color: [f32; 3]| Quad::new(top_left, bottom_right, color, context.device, context.pipelines);
+        self.border = Some(quad(top_left, bottom_right, style.border));
+        self.background = Some(quad((top_left.0 + inset.0, top_left.1 - inset.1), (bottom_right.0 - inset.0, bottom_right.1 + inset.1), style.popup_background));
+        self.highlight = Some(quad((top_left.0 + inset.0, top_left.1), (bottom_right.0 - inset.0, row_bottom), style.highlight));
+        self.marker = Some(quad((top_left.0 + inset.0, top_left.1), (top_left.0 + inset.0 + marker_width, row_bottom), style.selected));
+    }
+}
+
+impl Component for DropdownPopup {
+    fn render<'a>(&'a mut self, parent_top_left: &(f32, f32), parent_bottom_right: &(f32, f32), render_pass: &mut RenderPass<'a>, context: &RenderContext) {
+        let shared_cell = match self.shared.upgrade() {
+            Some(shared) => shared,
+            None => return,
+        };
+        let shared = shared_cell.borrow();
+
+        self.update_rect(&shared, context.config.height);
+        self.basic.placement.update(*parent_top_left, *parent_bottom_right, self.basic.top_left, self.basic.bottom_right, &self.basic.transform, context);
+
+        if shared.highlighted != self.shown_highlight {
+            self.shown_highlight = shared.highlighted;
+            if let Some(highlighted) = shared.highlighted {
+                self.scroll_to(highlighted);
+            }
+        }
+
+        let rect = (self.basic.top_left, self.basic.bottom_right);
+        if self.basic.needs_resize || self.drawn != Some((rect, shared.style)) {
+            self.create_shapes(shared.style, context);
+
+            self.drawn = Some((rect, shared.style));
+            self.basic.needs_resize = false;
+        }
+
+        let (highlighted, selected) = (shared.highlighted, shared.selected);
+        drop(shared);
+        self.update_rows(&shared_cell);
+
+        let row_height = self.get_row_height();
+        let world_transform = self.basic.placement.world_transform;
+        let row_transform = |index: usize| Transform::translation(0.0, -((index - self.first_visible) as f32) * row_height).then(&world_transform);
+        let visible = self.get_visible_rows();
+        let row_rects: Vec<Rect> = visible.clone().map(|index| self.get_row_rect(index)).collect();
+
+        for quad in self.border.iter_mut().chain(self.background.iter_mut()) {
+            quad.set_transform(world_transform, context.queue);
+            quad.set_opacity(context.opacity, context.queue);
+            quad.draw(render_pass);
+        }
+
+        for (quad, index) in [(&mut self.highlight, highlighted), (&mut self.marker, selected)] {
+            if let (Some(quad), Some(index)) = (quad.as_mut(), index.filter(|index| visible.contains(index))) {
+                quad.set_transform(row_transform(index), context.queue);
+                quad.set_opacity(context.opacity, context.queue);
+                quad.draw(render_pass);
+            }
+        }
+
+        let row_context = context.with_transform(world_transform);
+        for (row, (top_left, bottom_right)) in self.rows.iter_mut().skip(visible.start).take(visible.len()).zip(row_rects) {
+            if let Some((_, component)) = row.as_mut() {
+                component.render(&top_left, &bottom_right, render_pass, &row_context);
+            }
+        }
+    }
+
+    fn get_top_left(&self) -> (f32, f32) {
+        self.basic.top_left
+    }
+
+    fn get_bottom_right(&self) -> (f32, f32) {
+        self.basic.bottom_right
+    }
+
+    fn get_transform(&self) -> Transform {
+        self.basic.transform
+    }
+
+    fn set_transform(&mut self, transform: Transform) {
+        self.basic.transform = transform;
+    }
+
+    fn get_z_index(&self) -> i32 {
+        self.basic.z_index
+    }
+
+    fn set_z_index(&mut self, z_index: i32) {
+        self.basic.z_index = z_index;
+    }
+
+    fn get_key(&self) -> Option<&str> {
+        self.basic.key.as_deref()
+    }
+
+    fn set_key(&mut self, key: &str) {
+        self.basic.key = Some(key.to_string());
+    }
+
+    fn get_id(&self) -> Option<&str> {
+        self.basic.id.as_deref()
+    }
+
+    fn set_id(&mut self, id: &str) {
+        self.basic.id = Some(id.to_string());
+    }
+
+    fn get_classes(&self) -> &[String] {
+        &self.basic.classes
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.basic.add_class(class);
+    }
+
+    fn get_children(&self) -> Vec<&dyn Component> {
+        self.rows.iter().flatten().map(|(_, row)| row.as_ref()).collect()
+    }
+
+    fn get_placement(&self) -> &ComponentPlacement {
+        &self.basic.placement
+    }
+
+    fn layout(&mut self, parent: &ComponentPlacement) {
+        let shared = match self.shared.upgrade() {
+            Some(shared) => shared,
+            None => return,
+        };
+
+        self.update_rect(&shared.borrow(), parent.window_size.1);
+        self.basic.placement.update_in(parent, self.basic.top_left, self.basic.bottom_right, &self.basic.transform);
+        self.update_rows(&shared);
+
+        for index in self.get_visible_rows() {
+            let (top_left, bottom_right) = self.get_row_rect(index);
+            let row_parent = self.basic.placement.with_rect(top_left, bottom_right);
+            if let Some((_, component)) = self.rows[index].as_mut() {
+                component.layout(&row_parent);
+            }
+        }
+    }
+
+    fn on_event(&mut self, event: &ComponentEvent, context: &mut EventContext) -> bool {
+        let shared = match self.shared.upgrade() {
+            Some(shared) => shared,
+            None => return false,
+        };
+
+        match event {
+            ComponentEvent::CursorMoved { position } => {
+                if let Some(index) = self.get_row_at(*position) {
+                    let mut shared = shared.borrow_mut();
+                    if shared.highlighted != Some(index) {
+                        shared.highlighted = Some(index);
+                        // Following the pointer never scrolls the list
+                        self.shown_highlight = Some(index);
+                        context.invalidate(&self.basic.placement);
+                    }
+                }
+                false
+            }
+            ComponentEvent::MousePressed { position, button: MouseButton::Left } => {
+                // Presses in the popup would take the focus away from the dropdown otherwise
+                context.request_focus(shared.borrow().focus_id);
+                self.pressed = self.get_row_at(*position);
+                true
+            }
+            ComponentEvent::MouseReleased { position, button: MouseButton::Left } => {
+                let pressed = match self.pressed.take() {
+                    Some(pressed) => pressed,
+                    None => return false,
+                };
+
+                if self.get_row_at(*position) == Some(pressed) {
+                    let mut shared = shared.borrow_mut();
+                    shared.select(pressed, context);
+                    shared.close(context);
+                }
+                true
+            }
+            ComponentEvent::MouseWheel { position, delta } => {
+                if !self.in_bound(*position) {
+                    return false;
+                }
+
+                let rows = (delta.1 / (self.get_row_height() * self.basic.placement.window_size.1 as f32 / 2.0)).round() as isize;
+                let rows = if rows == 0 { delta.1.signum() as isize } else { rows };
+                let max_first = shared.borrow().labels.len().saturating_sub(self.visible_count);
+                self.first_visible = (self.first_visible as isize - rows).clamp(0, max_first as isize) as usize;
+                context.invalidate(&self.basic.placement);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn in_bound(&self, point: (f32, f32)) -> bool {
+        self.shared.upgrade().is_some() && self.basic.placement.contains(point)
+    }
+
+    fn resize(&mut self, new_box_top_left: (f32, f32), new_box_bottom_right: (f32, f32)) {
+        self.basic.top_left = new_box_top_left;
+        self.basic.bottom_right = new_box_bottom_right;
+
+        self.on_resize();
+    }
+
+    fn on_resize(&mut self) {
+        self.basic.needs_resize = true;
+
+        for (_, row) in self.rows.iter_mut().flatten() {
+            row.on_resize();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels() -> Vec<String> {
+        ["Apple", "Banana", "blueberry", "Cherry"].map(str::to_string).to_vec()
+    }
+
+    #[test]
+    fn typed_letters_find_items_by_prefix() {
+        let mut search = TypeSearch::default();
+        let start = Instant::now();
+
+        assert_eq!(search.push('b', &labels(), None, start), Some(1));
+        assert_eq!(search.push('l', &labels(), Some(1), start), Some(2));
+        assert_eq!(search.push('x', &labels(), Some(2), start), None);
+
+        // A pause starts a new search
+        assert_eq!(search.push('c', &labels(), Some(2), start + SEARCH_TIMEOUT * 2), Some(3));
+        assert_eq!(search.push('a', &[], None, start + SEARCH_TIMEOUT * 4), None);
+    }
+
+    #[test]
+    fn repeated_letters_cycle_through_the_matching_items() {
+        let mut search = TypeSearch::default();
+        let start = Instant::now();
+
+        assert_eq!(search.push('b', &labels(), Some(0), start), Some(1));
+        assert_eq!(search.push('b', &labels(), Some(1), start), Some(2));
+        assert_eq!(search.push('b', &labels(), Some(2), start), Some(1));
+    }
+
+    #[test]
+    fn letter_and_digit_keys_type_characters() {
+        assert_eq!(get_typed_character(&VirtualKeyCode::A), Some('a'));
+        assert_eq!(get_typed_character(&VirtualKeyCode::Z), Some('z'));
+        assert_eq!(get_typed_character(&VirtualKeyCode::Key1), Some('1'));
+        assert_eq!(get_typed_character(&VirtualKeyCode::Key0), Some('0'));
+        assert_eq!(get_typed_character(&VirtualKeyCode::Numpad7), Some('7'));
+        assert_eq!(get_typed_character(&VirtualKeyCode::Space), None);
+    }
+
+    #[test]
+    fn typing_on_a_closed_dropdown_selects_the_match() {
+        let mut dropdown = Dropdown::new((-1.0, 1.0), (1.0, -1.0), labels(), None);
+        let mut context = EventContext::new(Some(dropdown.get_focus_id()));
+
+        assert!(dropdown.on_event(&ComponentEvent::KeyPressed { key: VirtualKeyCode::C }, &mut context));
+        assert_eq!(dropdown.get_selected(), Some(3));
+        assert!(!dropdown.is_open());
+        assert!(!dropdown.on_event(&ComponentEvent::KeyPressed { key: VirtualKeyCode::A }, &mut EventContext::new(None)));
+        assert_eq!(dropdown.get_selected(), Some(3));
+    }
+}
@@ -0,0 +1,891 @@
+use std::fmt::{Display, Formatter, Write};
+use crate::components::component::{Component, DirtyFlags, Rect};
+use crate::style::{Border, Color, Edges, Font, FontFamily, Length, Style};
+use crate::transform::Transform;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DumpError {
+    /// Byte offset into the dump.
+    pub position: usize,
+    pub message: String,
+}
+
+impl Display for DumpError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "at {}: {}", self.position, self.message)
+    }
+}
+
+impl std::error::Error for DumpError {}
+
+/// A snapshot of the data of a component tree, for finding layout bugs and comparing trees in headless tests.
+///
+/// Components are rendered into GPU buffers, so a dump only keeps what they were built and laid out with.
+/// `to_json` and `from_json` round-trip exactly. `to_text` is for reading and `from_text` reads it back exactly,
+/// as long as ids, classes and handlers contain no whitespace, `#`, `.` or `,`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ComponentDump {
+    pub type_name: String,
+    pub id: Option<String>,
+    pub classes: Vec<String>,
+    pub key: Option<String>,
+    pub z_index: i32,
+    /// Relative to the parent box, top left and bottom right.
+    pub rect: Rect,
+    /// In view space as computed during the last render, without the transform.
+    pub absolute_rect: Rect,
+    pub transform: Transform,
+    pub style: Option<Style>,
+    pub dirty: DirtyFlags,
+    /// The names of the callbacks that are set.
+    pub handlers: Vec<String>,
+    pub children: Vec<ComponentDump>,
+}
+
+impl ComponentDump {
+    pub fn capture(component: &dyn Component) -> Self {
+        let placement = component.get_placement();
+
+        Self {
+            type_name: component.get_type_name().to_string(),
+            id: component.get_id().map(str::to_string),
+            classes: component.get_classes().to_vec(),
+            key: component.get_key().map(str::to_string),
+            z_index: component.get_z_index(),
+            rect: (component.get_top_left(), component.get_bottom_right()),
+            absolute_rect: (placement.absolute_top_left, placement.absolute_bottom_right),
+            transform: component.get_transform(),
+            style: component.get_style().copied(),
+            dirty: component.get_dirty_flags(),
+            handlers: component.get_event_handlers().into_iter().map(str::to_string).collect(),
+            children: component.get_children().into_iter().map(ComponentDump::capture).collect(),
+        }
+    }
+
+    /// Finds the first component with the given id, depth first.
+    pub fn find(&self, id: &str) -> Option<&ComponentDump> {
+        if self.id.as_deref() == Some(id) {
+            return Some(self);
+        }
+        self.children.iter().find_map(|child| child.find(id))
+    }
+
+    /// Describes where `self` differs from `expected`, one line per difference, each prefixed by the path of the component.
+    pub fn diff(&self, expected: &ComponentDump) -> Vec<String> {
+        let mut differences = vec![];
+        self.diff_into(expected, &self.get_label(), &mut differences);
+        differences
+    }
+
+    fn diff_into(&self, expected: &ComponentDump, path: &str, differences: &mut Vec<String>) {
+        let mut compare = |name: &str, actual: String, expected: String| {
+            if actual != expected {
+                differences.push(format!("{}: {} is {}, expected {}", path, name, actual, expected));
+            }
+        };
+
+        compare("type", self.type_name.clone(), expected.type_name.clone());
+        compare("id", format!("{:?}", self.id), format!("{:?}", expected.id));
+        compare("classes", format!("{:?}", self.classes), format!("{:?}", expected.classes));
+        compare("key", format!("{:?}", self.key), format!("{:?}", expected.key));
+        compare("z-index", self.z_index.to_string(), expected.z_index.to_string());
+        compare("rect", format!("{:?}", self.rect), format!("{:?}", expected.rect));
+        compare("absolute rect", format!("{:?}", self.absolute_rect), format!("{:?}", expected.absolute_rect));
+        compare("transform", format!("{:?}", self.transform), format!("{:?}", expected.transform));
+        compare("style", format!("{:?}", self.style), format!("{:?}", expected.style));
+        compare("dirty flags", format!("{:?}", self.dirty), format!("{:?}", expected.dirty));
+        compare("handlers", format!("{:?}", self.handlers), format!("{:?}", expected.handlers));
+        compare("child count", self.children.len().to_string(), expected.children.len().to_string());
+
+        for (index, (child, expected_child)) in self.children.iter().zip(expected.children.iter()).enumerate() {
+            child.diff_into(expected_child, &format!("{} > {}[{}]", path, child.get_label(), index), differences);
+        }
+    }
+
+    /// The type followed by the id and the classes, like a stylesheet selector.
+    pub fn get_label(&self) -> String {
+        let mut label = self.type_name.clone();
+        if let Some(id) = &self.id {
+            let _ = write!(label, "#{}", id);
+        }
+        for class in self.classes.iter() {
+            let _ = write!(label, ".{}", class);
+        }
+        label
+    }
+
+    /// One line per component, children indented below their parent.
+    pub fn to_text(&self) -> String {
+        let mut text = String::new();
+        self.write_text(&mut text, 0);
+        text
+    }
+
+    fn write_text(&self, text: &mut String, depth: usize) {
+        let ((left, top), (right, bottom)) = self.rect;
+        let ((absolute_left, absolute_top), (absolute_right, absolute_bottom)) = self.absolute_rect;
+
+        let _ = write!(text, "{:indent$}{} [{} {} {} {}] -> [{} {} {} {}]", "", self.get_label(),
+                       left, top, right, bottom, absolute_left, absolute_top, absolute_right, absolute_bottom,
+                       indent = depth * 2);
+
+        if let Some(key) = &self.key {
+            let _ = write!(text, " key={}", quote(key));
+        }
+        if self.z_index != 0 {
+            let _ = write!(text, " z={}", self.z_index);
+        }
+        if !self.transform.is_identity() {
+            let Transform { a, b, c, d, tx, ty } = self.transform;
+            let _ = write!(text, " transform=[{} {} {} {} {} {}]", a, b, c, d, tx, ty);
+        }
+        if let Some(style) = &self.style {
+            let _ = write!(text, " style={{{}}}", style_to_json(style));
+        }
+        for (flag, set) in dirty_flags(&self.dirty) {
+            if set {
+                let _ = write!(text, " {}", flag);
+            }
+        }
+        if !self.handlers.is_empty() {
+            let _ = write!(text, " handlers={}", self.handlers.join(","));
+        }
+        text.push('\n');
+
+        for child in self.children.iter() {
+            child.write_text(text, depth + 1);
+        }
+    }
+
+    pub fn from_text(text: &str) -> Result<Self, DumpError> {
+        // The components of the current branch, the root first
+        let mut branch: Vec<ComponentDump> = vec![];
+        let mut root = None;
+        let mut line_start = 0;
+
+        for line in text.split('\n') {
+            let position = line_start;
+            line_start += line.len() + 1;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let indent = line.len() - line.trim_start_matches(' ').len();
+            let depth = indent / 2;
+            if indent % 2 != 0 || depth > branch.len() {
+                return Err(DumpError { position, message: "unexpected indentation".to_string() });
+            }
+
+            Self::close_branch(&mut branch, depth, &mut root);
+            if depth == 0 && root.is_some() {
+                return Err(DumpError { position, message: "expected a single root".to_string() });
+            }
+
+            let mut parser = JsonParser { source: line, position: indent };
+            let dump = Self::parse_text_line(&mut parser).map_err(|error| DumpError { position: position + error.position, ..error })?;
+            branch.push(dump);
+        }
+
+        Self::close_branch(&mut branch, 0, &mut root);
+        root.ok_or_else(|| DumpError { position: 0, message: "the dump is empty".to_string() })
+    }
+
+    /// Moves the components deeper than `depth` into their parents, the root into `root`.
+    fn close_branch(branch: &mut Vec<ComponentDump>, depth: usize, root: &mut Option<ComponentDump>) {
+        while branch.len() > depth {
+            let dump = branch.pop().expect("the branch is longer than the depth");
+            match branch.last_mut() {
+                None => *root = Some(dump),
+                Some(parent) => parent.children.push(dump),
+            }
+        }
+    }
+
+    fn parse_text_line(parser: &mut JsonParser) -> Result<Self, DumpError> {
+        let label = parser.parse_word();
+        let end = label.find(['#', '.']).unwrap_or(label.len());
+        let mut dump = ComponentDump {
+            type_name: label[..end].to_string(),
+            ..ComponentDump::default()
+        };
+
+        // The prefixes are ASCII, so the names start one byte after them
+        let mut rest = &label[end..];
+        while let Some(prefix) = rest.chars().next() {
+            let end = rest[1..].find(['#', '.']).map_or(rest.len(), |end| end + 1);
+            match prefix {
+                '#' => dump.id = Some(rest[1..end].to_string()),
+                _ => dump.classes.push(rest[1..end].to_string()),
+            }
+            rest = &rest[end..];
+        }
+
+        let rect = parser.parse_text_numbers(4)?;
+        dump.rect = ((rect[0], rect[1]), (rect[2], rect[3]));
+        parser.skip_whitespace();
+        if !parser.rest().starts_with("->") {
+            return Err(parser.error("expected '->'"));
+        }
+        parser.position += 2;
+        let absolute_rect = parser.parse_text_numbers(4)?;
+        dump.absolute_rect = ((absolute_rect[0], absolute_rect[1]), (absolute_rect[2], absolute_rect[3]));
+
+        loop {
+            parser.skip_whitespace();
+            if parser.rest().is_empty() {
+                return Ok(dump);
+            }
+
+            let field_start = parser.position;
+            let name = parser.rest().split([' ', '=']).next().unwrap_or_default();
+            parser.position += name.len();
+            if name != "needs_resize" && name != "needs_layout" && name != "needs_refresh" {
+                parser.expect('=')?;
+            }
+
+            match name {
+                "key" => dump.key = Some(parser.parse_value()?.as_str()?.to_string()),
+                "z" => dump.z_index = parser.parse_word().parse().map_err(|_| parser.error("invalid z-index"))?,
+                "transform" => {
+                    let transform = parser.parse_text_numbers(6)?;
+                    dump.transform = Transform { a: transform[0], b: transform[1], c: transform[2], d: transform[3], tx: transform[4], ty: transform[5] };
+                }
+                "style" => dump.style = Some(style_from_json(&parser.parse_value()?)?),
+                "handlers" => dump.handlers = parser.parse_word().split(',').map(str::to_string).collect(),
+                "needs_resize" => dump.dirty.needs_resize = true,
+                "needs_layout" => dump.dirty.needs_layout = true,
+                "needs_refresh" => dump.dirty.needs_refresh = true,
+                _ => {
+                    parser.position = field_start;
+                    return Err(parser.error(&format!("unknown field '{}'", name)));
+                }
+            }
+        }
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut json = String::new();
+        self.write_json(&mut json, 0);
+        json
+    }
+
+    fn write_json(&self, json: &mut String, depth: usize) {
+        let indent = "  ".repeat(depth + 1);
+        let rect = |((left, top), (right, bottom)): Rect| format!("[{}, {}, {}, {}]", left, top, right, bottom);
+        let Transform { a, b, c, d, tx, ty } = self.transform;
+
+        let mut fields = vec![
+            ("type", quote(&self.type_name)),
+            ("id", self.id.as_deref().map_or("null".to_string(), quote)),
+            ("classes", format!("[{}]", self.classes.iter().map(|class| quote(class)).collect::<Vec<_>>().join(", "))),
+            ("key", self.key.as_deref().map_or("null".to_string(), quote)),
+            ("z_index", self.z_index.to_string()),
+            ("rect", rect(self.rect)),
+            ("absolute_rect", rect(self.absolute_rect)),
+            ("transform", format!("[{}, {}, {}, {}, {}, {}]", a, b, c, d, tx, ty)),
+            ("style", self.style.as_ref().map_or("null".to_string(), |style| format!("{{{}}}", style_to_json(style)))),
+            ("dirty", format!("[{}]", dirty_flags(&self.dirty).into_iter().filter(|(_, set)| *set).map(|(flag, _)| quote(flag)).collect::<Vec<_>>().join(", "))),
+            ("handlers", format!("[{}]", self.handlers.iter().map(|handler| quote(handler)).collect::<Vec<_>>().join(", "))),
+        ];
+
+        let mut children = String::from("[");
+        for (index, child) in self.children.iter().enumerate() {
+            children.push_str(if index == 0 { "\n" } else { ",\n" });
+            children.push_str(&indent);
+            children.push_str("  ");
+            child.write_json(&mut children, depth + 2);
+        }
+        if !self.children.is_empty() {
+            children.push('\n');
+            children.push_str(&indent);
+        }
+        children.push(']');
+        fields.push(("children", children));
+
+        json.push_str("{\n");
+        for (index, (name, value)) in fields.iter().enumerate() {
+            let separator = if index + 1 < fields.len() { "," } else { "" };
+            let _ = writeln!(json, "{}\"{}\": {}{}", indent, name, value, separator);
+        }
+        json.push_str(&"  ".repeat(depth));
+        json.push('}');
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, DumpError> {
+        let mut parser = JsonParser { source: json, position: 0 };
+        let value = parser.parse_value()?;
+
+        parser.skip_whitespace();
+        if parser.position < json.len() {
+            return Err(parser.error("expected the end of the dump"));
+        }
+
+        Self::from_value(&value)
+    }
+
+    fn from_value(value: &Spanned) -> Result<Self, DumpError> {
+        let rect = |value: &Spanned| -> Result<Rect, DumpError> {
+            let numbers = value.as_numbers(4)?;
+            Ok(((numbers[0], numbers[1]), (numbers[2], numbers[3])))
+        };
+        let optional_string = |value: &Spanned| -> Result<Option<String>, DumpError> {
+            match &value.json {
+                Json::Null => Ok(None),
+                _ => value.as_str().map(|string| Some(string.to_string())),
+            }
+        };
+
+        let transform = value.field("transform")?.as_numbers(6)?;
+
+        let mut dirty = DirtyFlags::default();
+        for flag in value.field("dirty")?.as_array()? {
+            match flag.as_str()? {
+                "needs_resize" => dirty.needs_resize = true,
+                "needs_layout" => dirty.needs_layout = true,
+                "needs_refresh" => dirty.needs_refresh = true,
+                other => return Err(flag.error(&format!("unknown dirty flag '{}'", other))),
+            }
+        }
+
+        let style = value.field("style")?;
+        let style = match style.json {
+            Json::Null => None,
+            _ => Some(style_from_json(style)?),
+        };
+
+        Ok(Self {
+            type_name: value.field("type")?.as_str()?.to_string(),
+            id: optional_string(value.field("id")?)?,
+            classes: value.field("classes")?.as_array()?.iter()
+                .map(|class| class.as_str().map(str::to_string))
+                .collect::<Result<_, _>>()?,
+            key: optional_string(value.field("key")?)?,
+            z_index: value.field("z_index")?.parse_number()?,
+            rect: rect(value.field("rect")?)?,
+            absolute_rect: rect(value.field("absolute_rect")?)?,
+            transform: Transform { a: transform[0], b: transform[1], c: transform[2], d: transform[3], tx: transform[4], ty: transform[5] },
+            style,
+            dirty,
+            handlers: value.field("handlers")?.as_array()?.iter()
+                .map(|handler| handler.as_str().map(str::to_string))
+                .collect::<Result<_, _>>()?,
+            children: value.field("children")?.as_array()?.iter()
+                .map(ComponentDump::from_value)
+                .collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+fn dirty_flags(dirty: &DirtyFlags) -> [(&'static str, bool); 3] {
+    [
+        ("needs_resize", dirty.needs_resize),
+        ("needs_layout", dirty.needs_layout),
+        ("needs_refresh", dirty.needs_refresh),
+    ]
+}
+
+fn quote(string: &str) -> String {
+    let mut quoted = String::from("\"");
+    for c in string.chars() {
+        match c {
+            '"' => quoted.push_str("\\\""),
+            '\\' => quoted.push_str("\\\\"),
+            '\n' => quoted.push_str("\\n"),
+            c if c.is_control() => {
+                let _ = write!(quoted, "\\u{:04x}", c as u32);
+            }
+            c => quoted.push(c),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+const COLOR_TOKENS: [(Color, &str); 9] = [
+    (Color::Primary, "primary"),
+    (Color::OnPrimary, "on-primary"),
+    (Color::Secondary, "secondary"),
+    (Color::Surface, "surface"),
+    (Color::OnSurface, "on-surface"),
+    (Color::Background, "background"),
+    (Color::Error, "error"),
+    (Color::OnError, "on-error"),
+    (Color::Outline, "outline"),
+];
+
+fn color_to_json(color: &Color) -> String {
+    match color {
+        Color::Rgb([r, g, b]) => format!("[{}, {}, {}]", r, g, b),
+        token => quote(COLOR_TOKENS.iter().find(|(color, _)| color == token).map(|(_, name)| *name).expect("every token is listed")),
+    }
+}
+
+fn length_to_json(length: &Length) -> String {
+    match length {
+        Length::Px(pixels) => format!("\"{}px\"", pixels),
+        Length::Spacing(step) => format!("\"spacing({})\"", step),
+    }
+}
+
+fn edges_to_json(edges: &Edges<Length>) -> String {
+    format!("[{}, {}, {}, {}]", length_to_json(&edges.top), length_to_json(&edges.right), length_to_json(&edges.bottom), length_to_json(&edges.left))
+}
+
+/// The fields of the style object without the braces. Properties left at `None` are omitted.
+fn style_to_json(style: &Style) -> String {
+    let mut fields = vec![];
+
+    if let Some(background) = &style.background {
+        fields.push(format!("\"background\": {}", color_to_json(background)));
+    }
+    if let Some(border) = &style.border {
+        fields.push(format!("\"border\": {{\"color\": {}, \"width\": {}}}", color_to_json(&border.color), length_to_json(&border.width)));
+    }
+    if let Some(radius) = &style.radius {
+        fields.push(format!("\"radius\": {}", length_to_json(radius)));
+    }
+    if let Some(padding) = &style.padding {
+        fields.push(format!("\"padding\": {}", edges_to_json(padding)));
+    }
+    if let Some(margin) = &style.margin {
+        fields.push(format!("\"margin\": {}", edges_to_json(margin)));
+    }
+    if let Some(family) = &style.font.family {
+        let family = match family {
+            FontFamily::SansSerif => "sans-serif",
+            FontFamily::Serif => "serif",
+            FontFamily::Monospace => "monospace",
+        };
+        fields.push(format!("\"font-family\": \"{}\"", family));
+    }
+    if let Some(size) = &style.font.size {
+        fields.push(format!("\"font-size\": {}", length_to_json(size)));
+    }
+    if let Some(bold) = &style.font.bold {
+        fields.push(format!("\"font-bold\": {}", bold));
+    }
+    if let Some(foreground) = &style.foreground {
+        fields.push(format!("\"foreground\": {}", color_to_json(foreground)));
+    }
+
+    fields.join(", ")
+}
+
+fn color_from_json(value: &Spanned) -> Result<Color, DumpError> {
+    if let Json::Array(_) = value.json {
+        let channels = value.as_numbers(3)?;
+        return Ok(Color::Rgb([channels[0], channels[1], channels[2]]));
+    }
+
+    let name = value.as_str()?;
+    COLOR_TOKENS.iter()
+        .find(|(_, token)| *token == name)
+        .map(|(color, _)| *color)
+        .ok_or_else(|| value.error(&format!("unknown color '{}'", name)))
+}
+
+fn length_from_json(value: &Spanned) -> Result<Length, DumpError> {
+    let length = value.as_str()?;
+
+    let parsed = if let Some(step) = length.strip_prefix("spacing(").and_then(|length| length.strip_suffix(')')) {
+        step.parse().ok().map(Length::Spacing)
+    } else {
+        length.strip_suffix("px").and_then(|pixels| pixels.parse().ok()).map(Length::Px)
+    };
+    parsed.ok_or_else(|| value.error(&format!("invalid length '{}'", length)))
+}
+
+fn edges_from_json(value: &Spanned) -> Result<Edges<Length>, DumpError> {
+    match value.as_array()? {
+        [top, right, bottom, left] => Ok(Edges {
+            top: length_from_json(top)?,
+            right: length_from_json(right)?,
+            bottom: length_from_json(bottom)?,
+            left: length_from_json(left)?,
+        }),
+        _ => Err(value.error("expected four lengths")),
+    }
+}
+
+fn style_from_json(value: &Spanned) -> Result<Style, DumpError> {
+    let mut style = Style::default();
+
+    for (name, property) in value.as_object()? {
+        match name.as_str() {
+            "background" => style.background = Some(color_from_json(property)?),
+            "border" => style.border = Some(Border {
+                color: color_from_json(property.field("color")?)?,
+                width: length_from_json(property.field("width")?)?,
+            }),
+            "radius" => style.radius = Some(length_from_json(property)?),
+            "padding" => style.padding = Some(edges_from_json(property)?),
+            "margin" => style.margin = Some(edges_from_json(property)?),
+            "font-family" => style.font = Font {
+                family: Some(match property.as_str()? {
+                    "sans-serif" => FontFamily::SansSerif,
+                    "serif" => FontFamily::Serif,
+                    "monospace" => FontFamily::Monospace,
+                    other => return Err(property.error(&format!("unknown font family '{}'", other))),
+                }),
+                ..style.font
+            },
+            "font-size" => style.font.size = Some(length_from_json(property)?),
+            "font-bold" => style.font.bold = Some(match property.json {
+                Json::Bool(bold) => bold,
+                _ => return Err(property.error("expected true or false")),
+            }),
+            "foreground" => style.foreground = Some(color_from_json(property)?),
+            other => return Err(property.error(&format!("unknown style property '{}'", other))),
+        }
+    }
+
+    Ok(style)
+}
+
+/// A parsed JSON value. Numbers keep their text, so floats read back exactly as they were written.
+#[derive(Debug)]
+enum Json {
+    Null,
+    Bool(bool),
+    Number(String),
+    String(String),
+    Array(Vec<Spanned>),
+    Object(Vec<(String, Spanned)>),
+}
+
+/// A value with the position it was parsed at, for error messages.
+#[derive(Debug)]
+struct Spanned {
+    json: Json,
+    position: usize,
+}
+
+impl Spanned {
+    fn error(&self, message: &str) -> DumpError {
+        DumpError {
+            position: self.position,
+            message: message.to_string(),
+        }
+    }
+
+    fn as_str(&self) -> Result<&str, DumpError> {
+        match &self.json {
+            Json::String(string) => Ok(string),
+            _ => Err(self.error("expected a string")),
+        }
+    }
+
+    fn as_array(&self) -> Result<&[Spanned], DumpError> {
+        match &self.json {
+            Json::Array(values) => Ok(values),
+            _ => Err(self.error("expected an array")),
+        }
+    }
+
+    fn as_object(&self) -> Result<&[(String, Spanned)], DumpError> {
+        match &self.json {
+            Json::Object(fields) => Ok(fields),
+            _ => Err(self.error("expected an object")),
+        }
+    }
+
+    fn field(&self, name: &str) -> Result<&Spanned, DumpError> {
+        self.as_object()?.iter()
+            .find(|(field, _)| field == name)
+            .map(|(_, value)| value)
+            .ok_or_else(|| self.error(&format!("missing field '{}'", name)))
+    }
+
+    fn parse_number<T: std::str::FromStr>(&self) -> Result<T, DumpError> {
+        match &self.json {
+            Json::Number(number) => number.parse().map_err(|_| self.error(&format!("invalid number '{}'", number))),
+            _ => Err(self.error("expected a number")),
+        }
+    }
+
+    fn as_numbers(&self, count: usize) -> Result<Vec<f32>, DumpError> {
+        let numbers = self.as_array()?.iter()
+            .map(|number| number.parse_number())
+            .collect::<Result<Vec<f32>, _>>()?;
+
+        if numbers.len() != count {
+            return Err(self.error(&format!("expected {} numbers, found {}", count, numbers.len())));
+        }
+        Ok(numbers)
+    }
+}
+
+struct JsonParser<'s> {
+    source: &'s str,
+    position: usize,
+}
+
+impl<'s> JsonParser<'s> {
+    fn rest(&self) -> &'s str {
+        &self.source[self.position..]
+    }
+
+    fn error(&self, message: &str) -> DumpError {
+        DumpError {
+            position: self.position,
+            message: message.to_string(),
+        }
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    fn expect(&mut self, expected: char) -> Result<(), DumpError> {
+        self.skip_whitespace();
+        if !self.rest().starts_with(expected) {
+            return Err(self.error(&format!("expected '{}'", expected)));
+        }
+        self.position += expected.len_utf8();
+        Ok(())
+    }
+
+    /// The text up to the next whitespace.
+    fn parse_word(&mut self) -> &'s str {
+        self.skip_whitespace();
+        let rest = self.rest();
+        let length = rest.find(char::is_whitespace).unwrap_or(rest.len());
+        self.position += length;
+        &rest[..length]
+    }
+
+    /// Numbers separated by whitespace in brackets, like the rectangles of `to_text`.
+    fn parse_text_numbers(&mut self, count: usize) -> Result<Vec<f32>, DumpError> {
+        self.expect('[')?;
+        let end = self.rest().find(']').ok_or_else(|| self.error("expected ']'"))?;
+        let numbers = self.rest()[..end].split_whitespace()
+            .map(|number| number.parse::<f32>().map_err(|_| self.error(&format!("invalid number '{}'", number))))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if numbers.len() != count {
+            return Err(self.error(&format!("expected {} numbers, found {}", count, numbers.len())));
+        }
+        self.position += end + 1;
+        Ok(numbers)
+    }
+
+    fn parse_value(&mut self) -> Result<Spanned, DumpError> {
+        self.skip_whitespace();
+        let position = self.position;
+
+        let json = match self.rest().chars().next() {
+            Some('{') => self.parse_object()?,
+            Some('[') => self.parse_array()?,
+            Some('"') => Json::String(self.parse_string()?),
+            Some(c) if c == '-' || c.is_ascii_digit() => {
+                let rest = self.rest();
+                let length = rest.find(|c: char| !(c.is_ascii_digit() || matches!(c, '-' | '+' | '.' | 'e' | 'E'))).unwrap_or(rest.len());
+                self.position += length;
+                Json::Number(rest[..length].to_string())
+            }
+            _ => {
+                let literal = [("null", Json::Null), ("true", Json::Bool(true)), ("false", Json::Bool(false))]
+                    .into_iter()
+                    .find(|(literal, _)| self.rest().starts_with(literal));
+
+                match literal {
+                    None => return Err(self.error("expected a value")),
+                    Some((literal, json)) => {
+                        self.position += literal.len();
+                        json
+                    }
+                }
+            }
+        };
+
+        Ok(Spanned { json, position })
+    }
+
+    fn parse_object(&mut self) -> Result<Json, DumpError> {
+        self.expect('{')?;
+        let mut fields = vec![];
+
+        self.skip_whitespace();
+        if self.rest().starts_with('}') {
+            self.position += 1;
+            return Ok(Json::Object(fields));
+        }
+
+        loop {
+            self.skip_whitespace();
+            let name = self.parse_string()?;
+            self.expect(':')?;
+            fields.push((name, self.parse_value()?));
+
+            self.skip_whitespace();
+            match self.rest().chars().next() {
+                Some(',') => self.position += 1,
+                Some('}') => {
+                    self.position += 1;
+                    return Ok(Json::Object(fields));
+                }
+                _ => return Err(self.error("expected ',' or '}'")),
+            }
+        }
+    }
+
+    fn parse_array(&mut self) -> Result<Json, DumpError> {
+        self.expect('[')?;
+        let mut values = vec![];
+
+        self.skip_whitespace();
+        if self.rest().starts_with(']') {
+            self.position += 1;
+            return Ok(Json::Array(values));
+        }
+
+        loop {
+            values.push(self.parse_value()?);
+
+            self.skip_whitespace();
+            match self.rest().chars().next() {
+                Some(',') => self.position += 1,
+                Some(']') => {
+                    self.position += 1;
+                    return Ok(Json::Array(values));
+                }
+                _ => return Err(self.error("expected ',' or ']'")),
+            }
+        }
+    }
+
+    fn parse_string(&mut self) -> Result<String, DumpError> {
+        if !self.rest().starts_with('"') {
+            return Err(self.error("expected a string"));
+        }
+        self.position += 1;
+
+        let mut string = String::new();
+        let mut chars = self.rest().char_indices();
+        while let Some((index, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.position += index + 1;
+                    return Ok(string);
+                }
+                '\\' => match chars.next() {
+                    Some((_, '"')) => string.push('"'),
+                    Some((_, '\\')) => string.push('\\'),
+                    Some((_, '/')) => string.push('/'),
+                    Some((_, 'n')) => string.push('\n'),
+                    Some((_, 't')) => string.push('\t'),
+                    Some((_, 'r')) => string.push('\r'),
+                    Some((escape, 'u')) => {
+                        let code = self.rest().get(escape + 1..escape + 5)
+                            .and_then(|code| u32::from_str_radix(code, 16).ok())
+                            .and_then(char::from_u32);
+                        match code {
+                            None => return Err(self.error("invalid unicode escape")),
+                            Some(code) => string.push(code),
+                        }
+                        for _ in 0..4 {
+                            chars.next();
+                        }
+                    }
+                    _ => return Err(self.error("invalid escape")),
+                },
+                c => string.push(c),
+            }
+        }
+
+        Err(self.error("the string is never closed"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::button::Button;
+    use crate::components::component::layout;
+    use crate::components::layout::LayoutComponent;
+    use crate::components::plain::PlainComponent;
+
+    /// A padded root with a keyed child, a transformed one and a button with a handler.
+    fn tree() -> LayoutComponent {
+        let mut root = LayoutComponent::new((-1.0, 1.0), (1.0, -1.0));
+        root.set_id("root");
+        root.add_class("card");
+        root.set_style(Style {
+            background: Some(Color::Surface),
+            border: Some(Border { color: Color::Rgb([0.1, 0.2, 0.3]), width: Length::Px(2.0) }),
+            padding: Some(Edges::all(Length::Spacing(2))),
+            font: Font { family: Some(FontFamily::Monospace), size: Some(Length::Px(18.0)), bold: Some(true) },
+            ..Style::default()
+        });
+
+        let mut stripe = PlainComponent::new((-1.0, 1.0), (1.0, 0.5), [1.0, 0.5, 0.0]);
+        stripe.set_key("stripe \"0\"");
+        stripe.add_class("stripe");
+        stripe.set_z_index(-2);
+        root.add_component(Box::new(stripe));
+
+        let mut rotated = PlainComponent::new((-0.5, 0.3), (0.5, -0.3), [0.0, 1.0, 0.0]);
+        rotated.set_transform(Transform::rotation(0.3).scale(0.8, 0.8));
+        root.add_component(Box::new(rotated));
+
+        let mut button = Button::new((0.0, -0.5), (1.0, -1.0));
+        button.set_on_press(|_| {});
+        root.add_component(Box::new(button));
+        root
+    }
+
+    fn captured() -> ComponentDump {
+        let mut root = tree();
+        layout(&mut root, (200, 100));
+        ComponentDump::capture(&root)
+    }
+
+    #[test]
+    fn json_dumps_round_trip() {
+        let dump = captured();
+        let parsed = ComponentDump::from_json(&dump.to_json()).unwrap();
+
+        assert_eq!(parsed.diff(&dump), Vec::<String>::new());
+        assert_eq!(parsed, dump);
+    }
+
+    #[test]
+    fn text_dumps_round_trip() {
+        let dump = captured();
+        let parsed = ComponentDump::from_text(&dump.to_text()).unwrap();
+
+        assert_eq!(parsed.diff(&dump), Vec::<String>::new());
+        assert_eq!(parsed, dump);
+    }
+
+    #[test]
+    fn a_headless_layout_matches_the_stored_dump() {
+        let expected = ComponentDump::from_text(STORED_LAYOUT).unwrap();
+
+        assert_eq!(captured().diff(&expected), Vec::<String>::new());
+    }
+
+    #[test]
+    fn differences_name_the_component() {
+        let dump = captured();
+        let mut changed = dump.clone();
+        changed.children[0].z_index = 3;
+
+        assert_eq!(dump.diff(&changed), ["LayoutComponent#root.card > PlainComponent.stripe[0]: z-index is -2, expected 3"]);
+    }
+
+    #[test]
+    fn malformed_text_dumps_are_errors() {
+        for text in ["", "Plain [0 0 0]", "Plain [0 0 0 0] -> [0 0 0 0] size=2", "Plain [0 0 0 0] -> [0 0 0 0]\n    Child [0 0 0 0] -> [0 0 0 0]"] {
+            assert!(ComponentDump::from_text(text).is_err(), "{}", text);
+        }
+    }
+
+    /// The tree in a window of 200 by 100 pixels, its padding of 8 pixels is 0.08 wide and 0.16 high in view space.
+    const STORED_LAYOUT: &str = r#"LayoutComponent#root.card [-1 1 1 -1] -> [-1 1 1 -1] style={"background": "surface", "border": {"color": [0.1, 0.2, 0.3], "width": "2px"}, "padding": ["spacing(2)", "spacing(2)", "spacing(2)", "spacing(2)"], "font-family": "monospace", "font-size": "18px", "font-bold": true}
+  PlainComponent.stripe [-1 1 1 0.5] -> [-0.92 0.84000003 0.9200001 0.42000002] key="stripe \"0\"" z=-2 style={"background": [1, 0.5, 0]} needs_resize
+  PlainComponent [-0.5 0.3 0.5 -0.3] -> [-0.45999998 0.25200003 0.46000004 -0.25199997] transform=[0.76426923 0.23641618 -0.23641618 0.76426923 0 0] style={"background": [0, 1, 0]} needs_resize
+  Button [0 -0.5 1 -1] -> [0 -0.41999996 0.9200001 -0.84000003] needs_resize handlers=on_press
+"#;
+}
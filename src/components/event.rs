@@ -0,0 +1,191 @@
+use std::any::Any;
+use winit::event::{MouseButton, VirtualKeyCode};
+use crate::components::component::{Component, ComponentPlacement};
+use crate::components::focus::FocusId;
+use crate::components::invalidation::Invalidation;
+use crate::components::overlay::{Overlay, OverlayId, OverlayLayer, OverlayOptions};
+use crate::components::stack::Navigation;
+
+/// Input events as they are passed down the component tree. Positions are view points.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum ComponentEvent {
+    CursorMoved { position: (f32, f32) },
+    MousePressed { position: (f32, f32), button: MouseButton },
+    MouseReleased { position: (f32, f32), button: MouseButton },
+    /// `delta` is given in pixels. Positive values move the content right and down.
+    MouseWheel { position: (f32, f32), delta: (f32, f32) },
+    KeyPressed { key: VirtualKeyCode },
+    KeyReleased { key: VirtualKeyCode },
+}
+
+impl ComponentEvent {
+    /// The cursor position of pointer events.
+    pub fn position(&self) -> Option<(f32, f32)> {
+        match self {
+            ComponentEvent::CursorMoved { position }
+            | ComponentEvent::MousePressed { position, .. }
+            | ComponentEvent::MouseReleased { position, .. }
+            | ComponentEvent::MouseWheel { position, .. } => Some(*position),
+            ComponentEvent::KeyPressed { .. }
+            | ComponentEvent::KeyReleased { .. } => None,
+        }
+    }
+
+    /// Whether the event should only reach the topmost component under the cursor.
+    /// All other events are passed to every component so they can finish drags and update hover states.
+    /// Key events are passed to every component as well, only the focused one should act on them.
+    pub fn is_targeted(&self) -> bool {
+        matches!(self, ComponentEvent::MousePressed { .. } | ComponentEvent::MouseWheel { .. })
+    }
+}
+
+/// A callback of a component, called while it handles an event.
+pub type EventCallback = Box<dyn FnMut(&mut EventContext)>;
+
+/// A callback of an input component, called with its new value when the user changes it.
+pub type ChangeCallback<T> = Box<dyn FnMut(T, &mut EventContext)>;
+
+/// Lets components act on the application while they handle an event.
+/// The requests are applied once the event went through the whole tree.
+#[derive(Default)]
+pub struct EventContext {
+    pub(crate) shown_overlays: Vec<Overlay>,
+    pub(crate) dismissed_overlays: Vec<OverlayId>,
+    /// Taken by the innermost stack that handles the event, dropped if there is none.
+    pub(crate) navigation: Vec<Navigation>,
+
+    focused: Option<FocusId>,
+    requested_focus: Option<Option<FocusId>>,
+
+    messages: Vec<Box<dyn Any>>,
+
+    invalidation: Invalidation,
+}
+
+impl EventContext {
+    pub fn new(focused: Option<FocusId>) -> Self {
+        Self {
+            focused,
+            ..Self::default()
+        }
+    }
+
+    /// The component that receives keyboard input while the event is handled.
+    pub fn get_focused(&self) -> Option<FocusId> {
+        self.focused
+    }
+
+    pub fn is_focused(&self, id: FocusId) -> bool {
+        self.focused == Some(id)
+    }
+
+    pub fn request_focus(&mut self, id: FocusId) {
+        self.requested_focus = Some(Some(id));
+        self.invalidation.request_redraw();
+    }
+
+    pub fn clear_focus(&mut self) {
+        self.requested_focus = Some(None);
+        self.invalidation.request_redraw();
+    }
+
+    /// Redraws the whole window on the next frame.
+    pub fn request_redraw(&mut self) {
+        self.invalidation.request_redraw();
+    }
+
+    /// Redraws the area the component covered during the last render on the next frame.
+    pub fn invalidate(&mut self, placement: &ComponentPlacement) {
+        self.invalidation.invalidate_placement(placement);
+    }
+
+    pub fn get_invalidation(&self) -> &Invalidation {
+        &self.invalidation
+    }
+
+    /// Queues a message for `Application::update`.
+    pub fn emit<M: 'static>(&mut self, message: M) {
+        self.messages.push(Box::new(message));
+        // The application builds a new view after handling it
+        self.invalidation.request_redraw();
+    }
+
+    pub fn take_messages(&mut self) -> Vec<Box<dyn Any>> {
+        std::mem::take(&mut self.messages)
+    }
+
+    /// The focus after the event, or `None` if no component asked to change it.
+    pub fn take_focus_request(&mut self) -> Option<Option<FocusId>> {
+        self.requested_focus.take()
+    }
+
+    /// Shows a component in the overlay layer. Its rectangle is relative to the whole window.
+    pub fn show_overlay(&mut self, component: Box<dyn Component>, options: OverlayOptions) -> OverlayId {
+        let id = OverlayId::next();
+        self.insert_overlay(Overlay { id, component, options });
+        id
+    }
+
+    /// Like `show_overlay`, for overlays whose component needs to know its id.
+    pub fn insert_overlay(&mut self, overlay: Overlay) {
+        self.shown_overlays.push(overlay);
+    }
+
+    pub fn dismiss_overlay(&mut self, id: OverlayId) {
+        self.dismissed_overlays.push(id);
+    }
+
+    /// Shows `page` above the current page of the innermost `Stack` around the component.
+    pub fn push_page(&mut self, page: Box<dyn Component>) {
+        self.navigation.push(Navigation::Push(page));
+    }
+
+    /// Goes back to the previous page of the innermost `Stack` around the component.
+    pub fn pop_page(&mut self) {
+        self.navigation.push(Navigation::Pop);
+    }
+
+    /// Goes back to the first page of the innermost `Stack` around the component.
+    pub fn pop_to_root(&mut self) {
+        self.navigation.push(Navigation::PopToRoot);
+    }
+
+    /// Applies all overlay requests to the layer.
+    pub fn apply_overlays(&mut self, overlays: &mut OverlayLayer) {
+        if !self.dismissed_overlays.is_empty() || !self.shown_overlays.is_empty() {
+            self.invalidation.request_redraw();
+        }
+
+        for id in self.dismissed_overlays.drain(..) {
+            overlays.dismiss(id);
+        }
+
+        for overlay in self.shown_overlays.drain(..) {
+            overlays.insert(overlay);
+        }
+    }
+}
+
+/// Helpers for the tests of components that are operated with the keyboard.
+#[cfg(test)]
+pub(crate) mod testing {
+    use std::cell::RefCell;
+    use std::rc::Rc;
+    use winit::event::VirtualKeyCode;
+    use crate::components::component::Component;
+    use super::{ComponentEvent, EventContext};
+
+    /// Presses `key` without releasing it. Returns whether the component consumed it.
+    pub(crate) fn press_key(component: &mut dyn Component, key: VirtualKeyCode, context: &mut EventContext) -> bool {
+        component.on_event(&ComponentEvent::KeyPressed { key }, context)
+    }
+
+    pub(crate) type Changes<T> = Rc<RefCell<Vec<T>>>;
+
+    /// An `on_change` callback and the values it was called with.
+    pub(crate) fn record_changes<T: 'static>() -> (Changes<T>, impl FnMut(T, &mut EventContext)) {
+        let changes = Rc::new(RefCell::new(vec![]));
+        let recorded = changes.clone();
+        (changes, move |value, _: &mut EventContext| recorded.borrow_mut().push(value))
+    }
+}
@@ -0,0 +1,12 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Identifies a component that can receive keyboard input.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct FocusId(u64);
+
+impl FocusId {
+    pub fn next() -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        FocusId(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
@@ -0,0 +1,196 @@
+use std::path::Path;
+use std::rc::Rc;
+use image::RgbaImage;
+use wgpu::RenderPass;
+use crate::Shape;
+use crate::components::component::{Component, ComponentBasicResizeData, ComponentPlacement, DirtyFlags, RenderContext, downcast_component, resolve_own_style};
+use crate::components::styled_box::StyledBox;
+use crate::shapes::textured_quad::TexturedQuad;
+use crate::style::Style;
+use crate::stylesheet::{ElementState, StyleElement};
+use crate::texture::Texture;
+use crate::transform::Transform;
+
+/// An image stretched over its rectangle inside the padding. The background of the style shows through transparent pixels.
+pub struct ImageComponent {
+    basic: ComponentBasicResizeData,
+    image: Rc<RgbaImage>,
+    style: Style,
+
+    styled_box: StyledBox,
+    /// Uploaded on the first frame and kept until the image changes.
+    texture: Option<Rc<Texture>>,
+    quad: Option<TexturedQuad>,
+}
+
+impl ImageComponent {
+    pub fn new(top_left: (f32, f32), bottom_right: (f32, f32), image: Rc<RgbaImage>) -> Self {
+        Self {
+            basic: ComponentBasicResizeData {
+                top_left,
+                bottom_right,
+                needs_resize: true,
+                transform: Transform::IDENTITY,
+                z_index: 0,
+                key: None,
+                id: None,
+                classes: vec![],
+                placement: ComponentPlacement::default(),
+            },
+            image,
+            style: Style::default(),
+            styled_box: StyledBox::new(),
+            texture: None,
+            quad: None,
+        }
+    }
+
+    /// Decodes a PNG or JPEG file.
+    pub fn load<P: AsRef<Path>>(top_left: (f32, f32), bottom_right: (f32, f32), path: P) -> anyhow::Result<Self> {
+        let image = image::open(path)?.to_rgba8();
+        Ok(Self::new(top_left, bottom_right, Rc::new(image)))
+    }
+
+    pub fn get_image(&self) -> &Rc<RgbaImage> {
+        &self.image
+    }
+
+    pub fn set_image(&mut self, image: Rc<RgbaImage>) {
+        if !Rc::ptr_eq(&self.image, &image) {
+            self.image = image;
+            self.texture = None;
+            self.quad = None;
+        }
+    }
+
+    /// Sets the style, it overrides the rules of the stylesheet.
+    pub fn set_style(&mut self, style: Style) {
+        self.style = style;
+    }
+}
+
+impl Component for ImageComponent {
+    fn render<'a>(&'a mut self, parent_top_left: &(f32, f32), parent_bottom_right: &(f32, f32), render_pass: &mut RenderPass<'a>, context: &RenderContext) {
+        self.basic.placement.update(*parent_top_left, *parent_bottom_right, self.basic.top_left, self.basic.bottom_right, &self.basic.transform, context);
+
+        let element = StyleElement {
+            type_name: self.get_type_name(),
+            id: self.basic.id.as_deref(),
+            classes: &self.basic.classes,
+            state: ElementState::default(),
+        };
+        let style = context.resolve_style(&context.get_style_path(element), &self.style);
+        self.basic.placement.inset(&style.margin);
+        self.styled_box.prepare(&style, &self.basic.placement, context);
+
+        let mut content = self.basic.placement;
+        content.inset(&style.padding);
+        let rect = (content.absolute_top_left, content.absolute_bottom_right);
+
+        let image = &self.image;
+        let texture = self.texture.get_or_insert_with(|| Rc::new(Texture::from_rgba(context.device, context.queue, image, wgpu::FilterMode::Linear, Some("Image"))));
+        let moved = self.quad.as_ref().is_some_and(|quad| (quad.top_left, quad.bottom_right) != rect);
+        if self.basic.needs_resize || moved {
+            self.quad = None;
+        }
+        let quad = self.quad.get_or_insert_with(|| TexturedQuad::new(rect.0, rect.1, texture.clone(), context.device, context.pipelines));
+        self.basic.needs_resize = false;
+
+        self.styled_box.draw(&self.basic.placement, render_pass, context);
+        quad.set_transform(self.basic.placement.world_transform, context.queue);
+        quad.set_opacity(context.opacity, context.queue);
+        quad.draw(render_pass);
+    }
+
+    fn get_top_left(&self) -> (f32, f32) {
+        self.basic.top_left
+    }
+
+    fn get_bottom_right(&self) -> (f32, f32) {
+        self.basic.bottom_right
+    }
+
+    fn get_transform(&self) -> Transform {
+        self.basic.transform
+    }
+
+    fn set_transform(&mut self, transform: Transform) {
+        self.basic.transform = transform;
+    }
+
+    fn get_z_index(&self) -> i32 {
+        self.basic.z_index
+    }
+
+    fn set_z_index(&mut self, z_index: i32) {
+        self.basic.z_index = z_index;
+    }
+
+    fn get_key(&self) -> Option<&str> {
+        self.basic.key.as_deref()
+    }
+
+    fn set_key(&mut self, key: &str) {
+        self.basic.key = Some(key.to_string());
+    }
+
+    fn get_id(&self) -> Option<&str> {
+        self.basic.id.as_deref()
+    }
+
+    fn set_id(&mut self, id: &str) {
+        self.basic.id = Some(id.to_string());
+    }
+
+    fn get_classes(&self) -> &[String] {
+        &self.basic.classes
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.basic.add_class(class);
+    }
+
+    fn get_style(&self) -> Option<&Style> {
+        Some(&self.style)
+    }
+
+    fn get_dirty_flags(&self) -> DirtyFlags {
+        DirtyFlags {
+            needs_resize: self.basic.needs_resize,
+            ..DirtyFlags::default()
+        }
+    }
+
+    fn get_placement(&self) -> &ComponentPlacement {
+        &self.basic.placement
+    }
+
+    fn layout(&mut self, parent: &ComponentPlacement) {
+        self.basic.placement.update_in(parent, self.basic.top_left, self.basic.bottom_right, &self.basic.transform);
+        self.basic.placement.inset(&resolve_own_style(&self.style).margin);
+    }
+
+    fn reconcile(&mut self, new: Box<dyn Component>) -> Result<(), Box<dyn Component>> {
+        let new = *downcast_component::<Self>(new)?;
+
+        // Rebuilt views often decode the same file again, the texture is kept while the pixels are the same
+        self.basic.reconcile(new.basic);
+        if !Rc::ptr_eq(&self.image, &new.image) && *self.image != *new.image {
+            self.set_image(new.image);
+        }
+        self.style = new.style;
+
+        Ok(())
+    }
+
+    fn resize(&mut self, new_box_top_left: (f32, f32), new_box_bottom_right: (f32, f32)) {
+        self.basic.top_left = new_box_top_left;
+        self.basic.bottom_right = new_box_bottom_right;
+
+        self.on_resize();
+    }
+
+    fn on_resize(&mut self) {
+        self.basic.needs_resize = true;
+    }
+}
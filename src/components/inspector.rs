@@ -0,0 +1,582 @@
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::rc::Rc;
+use wgpu::{RenderPass, SurfaceConfiguration};
+use winit::event::{ElementState, KeyboardInput, ModifiersState, MouseButton, MouseScrollDelta, VirtualKeyCode, WindowEvent};
+use crate::components::clip::Overflow;
+use crate::components::component::{Component, ComponentPlacement, Rect, RenderContext};
+use crate::font;
+use crate::shape::Shape;
+use crate::shapes::quad::Quad;
+use crate::shapes::textured_quad::TexturedQuad;
+use crate::style::{Edges, InheritedStyle, ResolvedStyle};
+use crate::stylesheet::{self, StyleElement, StylePath, Stylesheet};
+use crate::texture::Texture;
+use crate::theme::Theme;
+use crate::transform::Transform;
+
+/// Width of the tree panel in pixels, at most half of the window.
+const PANEL_WIDTH: f32 = 320.0;
+const ROW_HEIGHT: f32 = 18.0;
+/// Indentation of every level of the tree in pixels.
+const INDENT: f32 = 12.0;
+const TOGGLE_SIZE: f32 = 8.0;
+const OUTLINE_WIDTH: f32 = 2.0;
+/// Screen pixels per pixel of the font.
+const TEXT_SCALE: u32 = 2;
+/// Space around the details of the selection in pixels.
+const DETAILS_PADDING: f32 = 8.0;
+
+const PANEL_COLOR: [f32; 3] = [0.08, 0.08, 0.1];
+const DETAILS_COLOR: [f32; 3] = [0.13, 0.13, 0.16];
+const TEXT_COLOR: [f32; 3] = [0.85, 0.85, 0.85];
+const HOVERED_COLOR: [f32; 3] = [0.3, 0.6, 1.0];
+const SELECTED_COLOR: [f32; 3] = [1.0, 0.6, 0.1];
+/// Rows are colored by the type of their component, so equal types stand out.
+const TYPE_COLORS: [[f32; 3]; 6] = [
+    [0.9, 0.35, 0.35],
+    [0.35, 0.8, 0.4],
+    [0.4, 0.55, 0.95],
+    [0.9, 0.8, 0.3],
+    [0.75, 0.45, 0.9],
+    [0.3, 0.8, 0.85],
+];
+
+/// A visible row of the tree panel.
+#[derive(Clone, Debug, PartialEq)]
+struct TreeRow {
+    /// The indices of the children leading from the root to the component.
+    path: Vec<usize>,
+    depth: usize,
+    type_name: &'static str,
+    /// The type followed by the id.
+    label: String,
+    has_children: bool,
+    collapsed: bool,
+}
+
+/// What the panel was built for, its shapes are only rebuilt when this changes.
+#[derive(PartialEq)]
+struct PanelState {
+    window_size: (u32, u32),
+    rows: Vec<TreeRow>,
+    hovered: Option<Vec<usize>>,
+    selected: Option<Vec<usize>>,
+    details: Option<String>,
+}
+
+/// The rectangles, transforms and colors the outlines were built for.
+type OutlineState = ((u32, u32), Vec<(Rect, Transform, [f32; 3])>);
+
+/// A developer tool drawn above everything else, opened and closed with Ctrl+Shift+I.
+///
+/// While it is open the tree gets no pointer input. The component under the cursor is outlined,
+/// and a panel on the right shows the hierarchy, one row per component, indented by depth.
+/// Clicking the square of a row collapses or expands it, clicking anywhere else selects the component.
+/// The bottom of the panel then shows its rectangles, classes, event handlers and the style
+/// the stylesheet and the theme resolve for it. Escape closes it.
+#[derive(Default)]
+pub struct Inspector {
+    enabled: bool,
+    modifiers: ModifiersState,
+
+    hovered: Option<Vec<usize>>,
+    selected: Option<Vec<usize>>,
+    collapsed: HashSet<Vec<usize>>,
+    /// Rows scrolled out of the top of the panel.
+    scroll: usize,
+    /// The height of the details of the selection in pixels, measured by the last `prepare`.
+    details_height: f32,
+
+    outlines: Vec<Quad>,
+    drawn_outlines: Option<OutlineState>,
+    panel: Vec<Quad>,
+    texts: Vec<TexturedQuad>,
+    drawn_panel: Option<PanelState>,
+    /// Rasterized text by content, kept while it is shown.
+    text_textures: HashMap<String, Rc<Texture>>,
+}
+
+impl Inspector {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        self.hovered = None;
+    }
+
+    /// Handles an input event before the tree and returns whether it was consumed.
+    /// `position` is the cursor in view space.
+    pub fn input(&mut self, event: &WindowEvent, position: (f32, f32), root: &dyn Component, config: &SurfaceConfiguration) -> bool {
+        match event {
+            WindowEvent::ModifiersChanged(modifiers) => {
+                self.modifiers = *modifiers;
+                false
+            }
+            WindowEvent::KeyboardInput { input: KeyboardInput { state: ElementState::Pressed, virtual_keycode: Some(key), .. }, .. } => {
+                match key {
+                    VirtualKeyCode::I if self.modifiers.ctrl() && self.modifiers.shift() => {
+                        self.set_enabled(!self.enabled);
+                        true
+                    }
+                    VirtualKeyCode::Escape if self.enabled => {
+                        self.set_enabled(false);
+                        true
+                    }
+                    _ => false,
+                }
+            }
+            _ if !self.enabled => false,
+            WindowEvent::CursorMoved { .. } => {
+                self.hovered = match self.get_row_at(position, root, config) {
+                    Some(row) => Some(row.path),
+                    None if self.is_over_panel(position, config) => None,
+                    None => find_component_at(root, position),
+                };
+                true
+            }
+            WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Left, .. } => {
+                match self.get_row_at(position, root, config) {
+                    Some(row) if row.has_children && self.is_over_toggle(position, &row, config) => {
+                        if !self.collapsed.remove(&row.path) {
+                            self.collapsed.insert(row.path);
+                        }
+                    }
+                    Some(row) => self.selected = Some(row.path),
+                    None if self.is_over_panel(position, config) => {}
+                    None => self.selected = find_component_at(root, position),
+                }
+                true
+            }
+            WindowEvent::MouseWheel { delta, .. } if self.is_over_panel(position, config) => {
+                let rows = match delta {
+                    MouseScrollDelta::LineDelta(_, y) => *y,
+                    MouseScrollDelta::PixelDelta(position) => position.y as f32 / ROW_HEIGHT,
+                };
+                let row_count = self.get_rows(root).len();
+                self.scroll = (self.scroll as f32 - rows).clamp(0.0, row_count.saturating_sub(1) as f32).round() as usize;
+                true
+            }
+            WindowEvent::MouseInput { .. } | WindowEvent::Touch(_) => true,
+            _ => false,
+        }
+    }
+
+    fn get_rows(&self, root: &dyn Component) -> Vec<TreeRow> {
+        let mut rows = vec![];
+        self.collect_rows(root, &mut vec![], &mut rows);
+        rows
+    }
+
+    fn collect_rows(&self, component: &dyn Component, path: &mut Vec<usize>, rows: &mut Vec<TreeRow>) {
+        let children = component.get_children();
+        let collapsed = self.collapsed.contains(path);
+        let mut label = component.get_type_name().to_string();
+        if let Some(id) = component.get_id() {
+            let _ = write!(label, "#{}", id);
+        }
+        rows.push(TreeRow {
+            path: path.clone(),
+            depth: path.len(),
+            type_name: component.get_type_name(),
+            label,
+            has_children: !children.is_empty(),
+            collapsed,
+        });
+
+        if collapsed {
+            return;
+        }
+        for (index, child) in children.into_iter().enumerate() {
+            path.push(index);
+            self.collect_rows(child, path, rows);
+            path.pop();
+        }
+    }
+
+    fn get_panel_width(config: &SurfaceConfiguration) -> f32 {
+        PANEL_WIDTH.min(config.width as f32 / 2.0)
+    }
+
+    fn is_over_panel(&self, position: (f32, f32), config: &SurfaceConfiguration) -> bool {
+        to_pixels(position, config).0 >= config.width as f32 - Self::get_panel_width(config)
+    }
+
+    /// The height of the rows above the details of the selection in pixels.
+    fn get_tree_height(&self, config: &SurfaceConfiguration) -> f32 {
+        config.height as f32 - self.details_height
+    }
+
+    fn get_row_at(&self, position: (f32, f32), root: &dyn Component, config: &SurfaceConfiguration) -> Option<TreeRow> {
+        let y = to_pixels(position, config).1;
+        if !self.is_over_panel(position, config) || y >= self.get_tree_height(config) {
+            return None;
+        }
+
+        let index = (y / ROW_HEIGHT) as usize + self.scroll;
+        let mut rows = self.get_rows(root);
+        (index < rows.len()).then(|| rows.swap_remove(index))
+    }
+
+    fn is_over_toggle(&self, position: (f32, f32), row: &TreeRow, config: &SurfaceConfiguration) -> bool {
+        let x = to_pixels(position, config).0 - (config.width as f32 - Self::get_panel_width(config));
+        let toggle_left = row.depth as f32 * INDENT;
+        x >= toggle_left && x < toggle_left + INDENT
+    }
+
+    /// Builds the shapes of the inspector. The tree is rendered afterwards,
+    /// so the outlines follow the placements of the previous frame.
+    /// Shapes are kept while the tree, the selection and the window look the same as when they were built.
+    pub fn prepare(&mut self, root: &dyn Component, context: &RenderContext) {
+        if !self.enabled {
+            self.outlines.clear();
+            self.panel.clear();
+            self.texts.clear();
+            self.text_textures.clear();
+            self.drawn_outlines = None;
+            self.drawn_panel = None;
+            self.details_height = 0.0;
+            return;
+        }
+
+        let config = context.config;
+        let window_size = (config.width, config.height);
+
+        // The bounds of the hovered and the selected component
+        let mut outlines = vec![];
+        for (path, color) in [(&self.hovered, HOVERED_COLOR), (&self.selected, SELECTED_COLOR)] {
+            let placement = match path.as_ref().and_then(|path| get_component(root, path)) {
+                None => continue,
+                Some(component) => component.get_placement(),
+            };
+            if is_rendered(placement) {
+                outlines.push(((placement.absolute_top_left, placement.absolute_bottom_right), placement.world_transform, color));
+            }
+        }
+        let outline_state = (window_size, outlines);
+        if self.drawn_outlines.as_ref() != Some(&outline_state) {
+            self.outlines = build_outlines(&outline_state.1, config, context);
+            self.drawn_outlines = Some(outline_state);
+        }
+
+        let details = self.selected.as_ref().and_then(|path| describe(root, path, context.theme, context.stylesheet));
+        self.details_height = match details.as_ref() {
+            None => 0.0,
+            Some(details) => (font::measure(details).1 as f32 * TEXT_SCALE as f32 + 2.0 * DETAILS_PADDING).min(config.height as f32 / 2.0),
+        };
+
+        let visible_rows = (self.get_tree_height(config) / ROW_HEIGHT).ceil() as usize;
+        let panel_state = PanelState {
+            window_size,
+            rows: self.get_rows(root).into_iter().skip(self.scroll).take(visible_rows).collect(),
+            hovered: self.hovered.clone(),
+            selected: self.selected.clone(),
+            details,
+        };
+        if self.drawn_panel.as_ref() != Some(&panel_state) {
+            self.build_panel(&panel_state, context);
+            self.drawn_panel = Some(panel_state);
+        }
+    }
+
+    fn build_panel(&mut self, state: &PanelState, context: &RenderContext) {
+        let config = context.config;
+        let mut quads = vec![];
+        let mut quad = |(left, top): (f32, f32), (right, bottom): (f32, f32), color: [f32; 3], opacity: f32| {
+            let mut quad = Quad::new(to_view((left, top), config), to_view((right, bottom), config), color, context.device, context.pipelines);
+            quad.set_opacity(opacity, context.queue);
+            quads.push(quad);
+        };
+
+        let panel_left = config.width as f32 - Self::get_panel_width(config);
+        let right = config.width as f32;
+        quad((panel_left, 0.0), (right, config.height as f32), PANEL_COLOR, 0.9);
+
+        // Text is placed at the top left corner of its pixel rectangle
+        let mut texts = vec![];
+        for (index, row) in state.rows.iter().enumerate() {
+            let top = index as f32 * ROW_HEIGHT;
+            let left = panel_left + row.depth as f32 * INDENT;
+
+            let highlight = if state.selected.as_ref() == Some(&row.path) {
+                Some(SELECTED_COLOR)
+            } else if state.hovered.as_ref() == Some(&row.path) {
+                Some(HOVERED_COLOR)
+            } else {
+                None
+            };
+            if let Some(color) = highlight {
+                quad((panel_left, top), (right, top + ROW_HEIGHT), color, 0.35);
+            }
+
+            // Expanded rows have a filled square, collapsed ones a dim one
+            if row.has_children {
+                let inset = (INDENT - TOGGLE_SIZE) / 2.0;
+                let toggle_top = top + (ROW_HEIGHT - TOGGLE_SIZE) / 2.0;
+                let opacity = if row.collapsed { 0.35 } else { 1.0 };
+                quad((left + inset, toggle_top), (left + inset + TOGGLE_SIZE, toggle_top + TOGGLE_SIZE), TEXT_COLOR, opacity);
+            }
+
+            let text_top = top + (ROW_HEIGHT - (font::GLYPH_SIZE.1 * TEXT_SCALE) as f32) / 2.0;
+            texts.push((row.label.as_str(), (left + INDENT, text_top), get_type_color(row.type_name)));
+        }
+
+        if let Some(details) = state.details.as_ref() {
+            let top = config.height as f32 - self.details_height;
+            quad((panel_left, top), (right, config.height as f32), DETAILS_COLOR, 1.0);
+            texts.push((details.as_str(), (panel_left + DETAILS_PADDING, top + DETAILS_PADDING), TEXT_COLOR));
+        }
+
+        // Textures of text that is still shown are reused
+        let mut text_textures = HashMap::new();
+        self.texts.clear();
+        for (text, (left, top), color) in texts {
+            let texture = match self.text_textures.remove(text).or_else(|| text_textures.remove(text)) {
+                Some(texture) => texture,
+                None => Rc::new(Texture::from_rgba(context.device, context.queue, &font::rasterize(text, false), wgpu::FilterMode::Nearest, Some("Inspector Text"))),
+            };
+            let (width, height) = font::measure(text);
+            let bottom_right = (left + (width * TEXT_SCALE) as f32, top + (height * TEXT_SCALE) as f32);
+            let mut text_quad = TexturedQuad::new(to_view((left, top), config), to_view(bottom_right, config), texture.clone(), context.device, context.pipelines);
+            text_quad.set_tint(color, context.queue);
+            self.texts.push(text_quad);
+            text_textures.insert(text.to_string(), texture);
+        }
+
+        self.text_textures = text_textures;
+        self.panel = quads;
+    }
+
+    pub fn render<'a>(&'a self, render_pass: &mut RenderPass<'a>) {
+        for shape in self.outlines.iter().chain(self.panel.iter()) {
+            shape.draw(render_pass);
+        }
+        for text in self.texts.iter() {
+            text.draw(render_pass);
+        }
+    }
+}
+
+/// The outline of every rectangle, and a translucent fill for the hovered one.
+fn build_outlines(outlines: &[(Rect, Transform, [f32; 3])], config: &SurfaceConfiguration, context: &RenderContext) -> Vec<Quad> {
+    let mut shapes = vec![];
+    let mut quad = |top_left: (f32, f32), bottom_right: (f32, f32), color: [f32; 3], opacity: f32, transform: Transform| {
+        let mut quad = Quad::new(top_left, bottom_right, color, context.device, context.pipelines);
+        quad.set_transform(transform, context.queue);
+        quad.set_opacity(opacity, context.queue);
+        shapes.push(quad);
+    };
+
+    for &(((left, top), (right, bottom)), transform, color) in outlines {
+        let width = OUTLINE_WIDTH * 2.0 / config.width as f32;
+        let height = OUTLINE_WIDTH * 2.0 / config.height as f32;
+
+        if color == HOVERED_COLOR {
+            quad((left, top), (right, bottom), color, 0.25, transform);
+        }
+        quad((left, top), (right, top - height), color, 1.0, transform);
+        quad((left, bottom + height), (right, bottom), color, 1.0, transform);
+        quad((left, top), (left + width, bottom), color, 1.0, transform);
+        quad((right - width, top), (right, bottom), color, 1.0, transform);
+    }
+    shapes
+}
+
+/// The details of the component at `path` shown below the tree, `None` if the tree changed and it leads nowhere.
+fn describe(root: &dyn Component, path: &[usize], theme: &Theme, stylesheet: &Stylesheet) -> Option<String> {
+    let component = get_component(root, path)?;
+    let style = compute_style(root, path, None, &theme.get_inherited(), theme, stylesheet)?;
+    let placement = component.get_placement();
+    let rect = |((left, top), (right, bottom)): Rect| format!("[{:.2} {:.2} {:.2} {:.2}]", left, top, right, bottom);
+    let edges = |edges: Edges<f32>| format!("{} {} {} {}", edges.top, edges.right, edges.bottom, edges.left);
+
+    let mut text = component.get_type_name().to_string();
+    if let Some(id) = component.get_id() {
+        let _ = write!(text, "#{}", id);
+    }
+    for class in component.get_classes() {
+        let _ = write!(text, ".{}", class);
+    }
+    if let Some(key) = component.get_key() {
+        let _ = write!(text, "\nkey {}", key);
+    }
+    if component.get_z_index() != 0 {
+        let _ = write!(text, "\nz {}", component.get_z_index());
+    }
+    let _ = write!(text, "\nrect {}", rect((component.get_top_left(), component.get_bottom_right())));
+    let _ = write!(text, "\nabsolute {}", rect((placement.absolute_top_left, placement.absolute_bottom_right)));
+    let (width, height) = placement.pixel_size();
+    let _ = write!(text, "\nsize {}x{} px", width.round(), height.round());
+    let transform = component.get_transform();
+    if !transform.is_identity() {
+        let Transform { a, b, c, d, tx, ty } = transform;
+        let _ = write!(text, "\ntransform [{:.2} {:.2} {:.2} {:.2} {:.2} {:.2}]", a, b, c, d, tx, ty);
+    }
+    let handlers = component.get_event_handlers();
+    if !handlers.is_empty() {
+        let _ = write!(text, "\nhandlers {}", handlers.join(", "));
+    }
+
+    if let Some(background) = style.background {
+        let _ = write!(text, "\nbackground {}", to_hex(background));
+    }
+    if let Some((color, width)) = style.border {
+        let _ = write!(text, "\nborder {}px {}", width, to_hex(color));
+    }
+    if style.radius != 0.0 {
+        let _ = write!(text, "\nradius {}px", style.radius);
+    }
+    let _ = write!(text, "\npadding {}", edges(style.padding));
+    let _ = write!(text, "\nmargin {}", edges(style.margin));
+    let font = style.inherited.font;
+    let _ = write!(text, "\ncolor {}", to_hex(style.inherited.foreground));
+    let _ = write!(text, "\nfont {}px {:?}{}", font.size, font.family, if font.bold { " bold" } else { "" });
+    Some(text)
+}
+
+/// Cascades the stylesheet down `path` like rendering does, for components that are not hovered, pressed or focused.
+fn compute_style(component: &dyn Component, path: &[usize], parent: Option<&StylePath>, inherited: &InheritedStyle, theme: &Theme, stylesheet: &Stylesheet) -> Option<ResolvedStyle> {
+    let style_path = StylePath {
+        element: StyleElement {
+            type_name: component.get_type_name(),
+            id: component.get_id(),
+            classes: component.get_classes(),
+            state: stylesheet::ElementState::default(),
+        },
+        parent,
+    };
+    let own = component.get_style().copied().unwrap_or_default();
+    let style = stylesheet.compute(&style_path).merge(&own).resolve(theme, inherited);
+
+    match path.split_first() {
+        None => Some(style),
+        Some((index, rest)) => {
+            let child = *component.get_children().get(*index)?;
+            compute_style(child, rest, Some(&style_path), &style.inherited, theme, stylesheet)
+        }
+    }
+}
+
+fn to_hex(color: [f32; 3]) -> String {
+    let [r, g, b] = color.map(|channel| (channel.clamp(0.0, 1.0) * 255.0).round() as u8);
+    format!("#{:02x}{:02x}{:02x}", r, g, b)
+}
+
+/// Follows `path` from `root`, `None` if the tree changed and it leads nowhere.
+fn get_component<'c>(root: &'c dyn Component, path: &[usize]) -> Option<&'c dyn Component> {
+    let mut component = root;
+    for index in path {
+        component = *component.get_children().get(*index)?;
+    }
+    Some(component)
+}
+
+/// The path of the deepest component containing `position`. Later children lie above earlier ones.
+fn find_component_at(root: &dyn Component, position: (f32, f32)) -> Option<Vec<usize>> {
+    fn find(component: &dyn Component, position: (f32, f32), path: &mut Vec<usize>) -> bool {
+        if component.get_overflow() == Overflow::Clip && !component.in_bound(position) {
+            return false;
+        }
+
+        for (index, child) in component.get_children().into_iter().enumerate().rev() {
+            path.push(index);
+            if find(child, position, path) {
+                return true;
+            }
+            path.pop();
+        }
+        is_rendered(component.get_placement()) && component.get_placement().contains(position)
+    }
+
+    let mut path = vec![];
+    find(root, position, &mut path).then_some(path)
+}
+
+fn is_rendered(placement: &ComponentPlacement) -> bool {
+    placement.window_size != (0, 0)
+}
+
+fn get_type_color(type_name: &str) -> [f32; 3] {
+    let hash = type_name.bytes().fold(0usize, |hash, byte| hash.wrapping_mul(31).wrapping_add(byte as usize));
+    TYPE_COLORS[hash % TYPE_COLORS.len()]
+}
+
+/// Converts a view point to pixels from the top left corner of the window.
+fn to_pixels(point: (f32, f32), config: &SurfaceConfiguration) -> (f32, f32) {
+    ((point.0 + 1.0) / 2.0 * config.width as f32, (1.0 - point.1) / 2.0 * config.height as f32)
+}
+
+fn to_view(point: (f32, f32), config: &SurfaceConfiguration) -> (f32, f32) {
+    (point.0 / config.width as f32 * 2.0 - 1.0, 1.0 - point.1 / config.height as f32 * 2.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::button::Button;
+    use crate::components::component::layout;
+    use crate::components::layout::LayoutComponent;
+    use crate::components::plain::PlainComponent;
+    use crate::style::Color;
+
+    /// A root with a nested layout and a button with a handler.
+    fn tree() -> LayoutComponent {
+        let mut root = LayoutComponent::new((-1.0, 1.0), (1.0, -1.0));
+        root.set_id("root");
+
+        let mut nested = LayoutComponent::new((-1.0, 1.0), (0.0, 0.0));
+        nested.add_component(Box::new(PlainComponent::new((-1.0, 1.0), (1.0, -1.0), [1.0, 0.0, 0.0])));
+        root.add_component(Box::new(nested));
+
+        let mut button = Button::new((0.0, 0.0), (1.0, -1.0));
+        button.set_id("ok");
+        button.add_class("primary");
+        button.set_on_press(|_| {});
+        root.add_component(Box::new(button));
+
+        layout(&mut root, (200, 100));
+        root
+    }
+
+    #[test]
+    fn collapsed_rows_hide_their_descendants() {
+        let root = tree();
+        let mut inspector = Inspector::new();
+        let labels = |inspector: &Inspector| inspector.get_rows(&root).into_iter().map(|row| row.label).collect::<Vec<_>>();
+
+        assert_eq!(labels(&inspector), ["LayoutComponent#root", "LayoutComponent", "PlainComponent", "Button#ok"]);
+
+        inspector.collapsed.insert(vec![0]);
+        assert_eq!(labels(&inspector), ["LayoutComponent#root", "LayoutComponent", "Button#ok"]);
+        assert!(inspector.get_rows(&root)[1].collapsed);
+    }
+
+    #[test]
+    fn details_show_the_placement_handlers_and_the_cascaded_style() {
+        let root = tree();
+        let theme = Theme::dark();
+        let stylesheet = Stylesheet::parse("#root { color: error } #root Button { background: primary }").unwrap();
+        let details = describe(&root, &[1], &theme, &stylesheet).unwrap();
+        let lines = details.lines().collect::<Vec<_>>();
+
+        assert_eq!(lines[0], "Button#ok.primary");
+        assert!(lines.contains(&"rect [0.00 0.00 1.00 -1.00]"), "{}", details);
+        assert!(lines.contains(&"size 100x50 px"), "{}", details);
+        assert!(lines.contains(&"handlers on_press"), "{}", details);
+        assert!(lines.contains(&format!("background {}", to_hex(theme.color(Color::Primary))).as_str()), "{}", details);
+        assert!(lines.contains(&format!("color {}", to_hex(theme.color(Color::Error))).as_str()), "{}", details);
+    }
+
+    #[test]
+    fn details_of_a_path_leading_nowhere_are_none() {
+        let root = tree();
+        assert!(describe(&root, &[5], &Theme::dark(), &Stylesheet::default()).is_none());
+        assert!(find_component_at(&root, (0.5, -0.5)) == Some(vec![1]));
+    }
+}
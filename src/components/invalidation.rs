@@ -0,0 +1,90 @@
+use std::cell::Cell;
+use crate::components::component::ComponentPlacement;
+
+/// The part of the window that has to be drawn again.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Damage {
+    Full,
+    /// The bounding box of all damaged areas as view points, top left and bottom right.
+    Region((f32, f32), (f32, f32)),
+}
+
+impl Damage {
+    pub fn union(&self, other: &Damage) -> Damage {
+        match (self, other) {
+            (Damage::Region(top_left, bottom_right), Damage::Region(other_top_left, other_bottom_right)) => {
+                Damage::Region((top_left.0.min(other_top_left.0), top_left.1.max(other_top_left.1)),
+                               (bottom_right.0.max(other_bottom_right.0), bottom_right.1.min(other_bottom_right.1)))
+            }
+            _ => Damage::Full,
+        }
+    }
+}
+
+/// Collects what has to be redrawn. Nothing is rendered while it is empty.
+#[derive(Default)]
+pub struct Invalidation {
+    damage: Cell<Option<Damage>>,
+}
+
+impl Invalidation {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Redraws the whole window on the next frame.
+    pub fn request_redraw(&self) {
+        self.add(Damage::Full);
+    }
+
+    /// Redraws the given view rectangle on the next frame.
+    pub fn invalidate(&self, top_left: (f32, f32), bottom_right: (f32, f32)) {
+        self.add(Damage::Region(top_left, bottom_right));
+    }
+
+    /// Redraws the area a component covered during the last render.
+    pub fn invalidate_placement(&self, placement: &ComponentPlacement) {
+        let top_left = placement.absolute_top_left;
+        let bottom_right = placement.absolute_bottom_right;
+        let world_transform = placement.world_transform;
+
+        let corners = [
+            world_transform.apply(top_left),
+            world_transform.apply((bottom_right.0, top_left.1)),
+            world_transform.apply(bottom_right),
+            world_transform.apply((top_left.0, bottom_right.1)),
+        ];
+
+        let mut min = (f32::MAX, f32::MAX);
+        let mut max = (f32::MIN, f32::MIN);
+        for corner in corners {
+            min = (min.0.min(corner.0), min.1.min(corner.1));
+            max = (max.0.max(corner.0), max.1.max(corner.1));
+        }
+
+        self.invalidate((min.0, max.1), (max.0, min.1));
+    }
+
+    pub fn add(&self, damage: Damage) {
+        let damage = match self.damage.get() {
+            None => damage,
+            Some(current) => current.union(&damage),
+        };
+        self.damage.set(Some(damage));
+    }
+
+    /// Adds everything `other` collected.
+    pub fn merge(&self, other: &Invalidation) {
+        if let Some(damage) = other.damage.get() {
+            self.add(damage);
+        }
+    }
+
+    pub fn is_pending(&self) -> bool {
+        self.damage.get().is_some()
+    }
+
+    pub fn take(&self) -> Option<Damage> {
+        self.damage.take()
+    }
+}
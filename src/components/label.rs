@@ -0,0 +1,239 @@
+use std::rc::Rc;
+use wgpu::RenderPass;
+use crate::Shape;
+use crate::components::component::{Component, ComponentBasicResizeData, ComponentPlacement, DirtyFlags, Rect, RenderContext, downcast_component, resolve_own_style};
+use crate::components::styled_box::StyledBox;
+use crate::font;
+use crate::shapes::textured_quad::TexturedQuad;
+use crate::style::{ResolvedStyle, Style};
+use crate::stylesheet::{ElementState, StyleElement};
+use crate::texture::Texture;
+use crate::transform::Transform;
+
+/// Text in the built in pixel font, starting at the top left corner inside the padding. Lines are separated by `\n`.
+///
+/// The color and font come from the style and are inherited like in CSS. The font size is rounded to a whole
+/// multiple of the font's pixels, so the text stays sharp. Text that does not fit overflows the rectangle.
+pub struct Label {
+    basic: ComponentBasicResizeData,
+    text: String,
+    style: Style,
+
+    styled_box: StyledBox,
+    /// The text and boldness the texture was rasterized for.
+    rasterized: Option<(String, bool)>,
+    texture: Option<Rc<Texture>>,
+    drawn_rect: Option<Rect>,
+    quad: Option<TexturedQuad>,
+}
+
+impl Label {
+    pub fn new(top_left: (f32, f32), bottom_right: (f32, f32), text: &str) -> Self {
+        Self {
+            basic: ComponentBasicResizeData {
+                top_left,
+                bottom_right,
+                needs_resize: true,
+                transform: Transform::IDENTITY,
+                z_index: 0,
+                key: None,
+                id: None,
+                classes: vec![],
+                placement: ComponentPlacement::default(),
+            },
+            text: text.to_string(),
+            style: Style::default(),
+            styled_box: StyledBox::new(),
+            rasterized: None,
+            texture: None,
+            drawn_rect: None,
+            quad: None,
+        }
+    }
+
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.to_string();
+    }
+
+    /// Sets the style, it overrides the rules of the stylesheet. `color` is the color of the text.
+    pub fn set_style(&mut self, style: Style) {
+        self.style = style;
+    }
+
+    /// How many screen pixels one pixel of the font takes at the font size `size` in pixels.
+    pub fn get_scale(size: f32) -> u32 {
+        (size / font::CELL_SIZE.1 as f32).round().max(1.0) as u32
+    }
+
+    /// Rasterizes the text again if it changed since the last frame.
+    fn update_texture(&mut self, bold: bool, context: &RenderContext) {
+        if self.rasterized.as_ref().is_some_and(|(text, rasterized_bold)| *text == self.text && *rasterized_bold == bold) {
+            return;
+        }
+
+        let image = font::rasterize(&self.text, bold);
+        self.texture = Some(Rc::new(Texture::from_rgba(context.device, context.queue, &image, wgpu::FilterMode::Nearest, Some("Label"))));
+        self.rasterized = Some((self.text.clone(), bold));
+        self.quad = None;
+    }
+
+    /// Places the text at the top left corner inside the padding, the quad is only recreated when it moved.
+    fn update_quad(&mut self, style: &ResolvedStyle, context: &RenderContext) {
+        let texture = match self.texture.as_ref() {
+            None => return,
+            Some(texture) => texture.clone(),
+        };
+
+        let mut content = self.basic.placement;
+        content.inset(&style.padding);
+        let scale = Self::get_scale(style.inherited.font.size);
+        let (width, height) = font::measure(&self.text);
+        let size = (content.radius_to_view((width * scale) as f32).0, content.radius_to_view((height * scale) as f32).1);
+        let rect = (content.absolute_top_left, (content.absolute_top_left.0 + size.0, content.absolute_top_left.1 - size.1));
+
+        if self.drawn_rect != Some(rect) {
+            self.quad = None;
+            self.drawn_rect = Some(rect);
+        }
+        self.quad.get_or_insert_with(|| TexturedQuad::new(rect.0, rect.1, texture, context.device, context.pipelines));
+    }
+}
+
+impl Component for Label {
+    fn render<'a>(&'a mut self, parent_top_left: &(f32, f32), parent_bottom_right: &(f32, f32), render_pass: &mut RenderPass<'a>, context: &RenderContext) {
+        self.basic.placement.update(*parent_top_left, *parent_bottom_right, self.basic.top_left, self.basic.bottom_right, &self.basic.transform, context);
+
+        let element = StyleElement {
+            type_name: self.get_type_name(),
+            id: self.basic.id.as_deref(),
+            classes: &self.basic.classes,
+            state: ElementState::default(),
+        };
+        let style = context.resolve_style(&context.get_style_path(element), &self.style);
+        self.basic.placement.inset(&style.margin);
+        self.basic.needs_resize = false;
+
+        self.styled_box.prepare(&style, &self.basic.placement, context);
+        if !self.text.is_empty() {
+            self.update_texture(style.inherited.font.bold, context);
+            self.update_quad(&style, context);
+        } else {
+            self.quad = None;
+        }
+
+        self.styled_box.draw(&self.basic.placement, render_pass, context);
+        if let Some(quad) = self.quad.as_mut() {
+            quad.set_transform(self.basic.placement.world_transform, context.queue);
+            quad.set_opacity(context.opacity, context.queue);
+            quad.set_tint(style.inherited.foreground, context.queue);
+            quad.draw(render_pass);
+        }
+    }
+
+    fn get_top_left(&self) -> (f32, f32) {
+        self.basic.top_left
+    }
+
+    fn get_bottom_right(&self) -> (f32, f32) {
+        self.basic.bottom_right
+    }
+
+    fn get_transform(&self) -> Transform {
+        self.basic.transform
+    }
+
+    fn set_transform(&mut self, transform: Transform) {
+        self.basic.transform = transform;
+    }
+
+    fn get_z_index(&self) -> i32 {
+        self.basic.z_index
+    }
+
+    fn set_z_index(&mut self, z_index: i32) {
+        self.basic.z_index = z_index;
+    }
+
+    fn get_key(&self) -> Option<&str> {
+        self.basic.key.as_deref()
+    }
+
+    fn set_key(&mut self, key: &str) {
+        self.basic.key = Some(key.to_string());
+    }
+
+    fn get_id(&self) -> Option<&str> {
+        self.basic.id.as_deref()
+    }
+
+    fn set_id(&mut self, id: &str) {
+        self.basic.id = Some(id.to_string());
+    }
+
+    fn get_classes(&self) -> &[String] {
+        &self.basic.classes
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.basic.add_class(class);
+    }
+
+    fn get_style(&self) -> Option<&Style> {
+        Some(&self.style)
+    }
+
+    fn get_dirty_flags(&self) -> DirtyFlags {
+        DirtyFlags {
+            needs_resize: self.basic.needs_resize,
+            ..DirtyFlags::default()
+        }
+    }
+
+    fn get_placement(&self) -> &ComponentPlacement {
+        &self.basic.placement
+    }
+
+    fn layout(&mut self, parent: &ComponentPlacement) {
+        self.basic.placement.update_in(parent, self.basic.top_left, self.basic.bottom_right, &self.basic.transform);
+        self.basic.placement.inset(&resolve_own_style(&self.style).margin);
+    }
+
+    fn reconcile(&mut self, new: Box<dyn Component>) -> Result<(), Box<dyn Component>> {
+        let new = *downcast_component::<Self>(new)?;
+
+        // The texture is kept until the text changes
+        self.basic.reconcile(new.basic);
+        self.text = new.text;
+        self.style = new.style;
+
+        Ok(())
+    }
+
+    fn resize(&mut self, new_box_top_left: (f32, f32), new_box_bottom_right: (f32, f32)) {
+        self.basic.top_left = new_box_top_left;
+        self.basic.bottom_right = new_box_bottom_right;
+
+        self.on_resize();
+    }
+
+    fn on_resize(&mut self) {
+        self.basic.needs_resize = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn font_sizes_are_rounded_to_whole_font_pixels() {
+        assert_eq!(Label::get_scale(0.0), 1);
+        assert_eq!(Label::get_scale(font::CELL_SIZE.1 as f32), 1);
+        assert_eq!(Label::get_scale(14.0), 2);
+        assert_eq!(Label::get_scale(font::CELL_SIZE.1 as f32 * 3.0), 3);
+    }
+}
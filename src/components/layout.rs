@@ -1,11 +1,25 @@
-use wgpu::{Device, RenderPass, SurfaceConfiguration};
-use crate::components::component::ComponentBasicData;
-use super::component::{ComponentUtils, Component};
+use wgpu::RenderPass;
+use crate::components::clip::{Clipper, Overflow};
+use crate::components::component::{ComponentBasicData, ComponentPlacement, RenderContext, downcast_component, resolve_own_style};
+use crate::components::reconcile::reconcile_children;
+use crate::components::styled_box::StyledBox;
+use crate::components::event::{ComponentEvent, EventContext};
+use crate::style::Style;
+use crate::stylesheet::{ElementState, StyleElement};
+use crate::transform::Transform;
+use super::component::Component;
 
 pub struct LayoutComponent {
     basic: ComponentBasicData,
 
     components: Vec<Box<dyn Component>>,
+
+    style: Style,
+    styled_box: StyledBox,
+
+    overflow: Overflow,
+    corner_radius: f32,
+    clipper: Clipper,
 }
 
 impl LayoutComponent {
@@ -14,26 +28,94 @@ impl LayoutComponent {
             basic: ComponentBasicData {
                 top_left,
                 bottom_right,
+                transform: Transform::IDENTITY,
+                z_index: 0,
+                key: None,
+                id: None,
+                classes: vec![],
+                placement: ComponentPlacement::default(),
             },
             components: vec![],
+            style: Style::default(),
+            styled_box: StyledBox::new(),
+            overflow: Overflow::Visible,
+            corner_radius: 0.0,
+            clipper: Clipper::new(),
         }
     }
 
     pub fn add_component(&mut self, component: Box<dyn Component>) {
         self.components.push(component);
+        self.sort_components();
+    }
+
+    /// Orders the children by their z-index. The sort is stable, so equal ones keep their insertion order.
+    fn sort_components(&mut self) {
+        self.components.sort_by_key(|component| component.get_z_index());
+    }
+
+    /// Sets the style, it overrides the rules of the stylesheet. The padding shrinks the box of the children and they inherit the foreground and the font.
+    pub fn set_style(&mut self, style: Style) {
+        self.style = style;
+    }
+
+    pub fn set_overflow(&mut self, overflow: Overflow) {
+        self.overflow = overflow;
+    }
+
+    /// Rounds the corners of the clip by `corner_radius` pixels.
+    pub fn set_corner_radius(&mut self, corner_radius: f32) {
+        self.corner_radius = corner_radius;
     }
 }
 
 impl Component for LayoutComponent {
-    fn render<'a>(&'a mut self, parent_top_left: &(f32, f32), parent_bottom_right: &(f32, f32), render_pass: &mut RenderPass<'a>, device: &Device, config: &SurfaceConfiguration) {
-        let (absolute_top_left, absolut_bottom_right) = ComponentUtils::calculate_absolute_from_relative_view_points(parent_top_left.clone(),
-                                                                                                                     parent_bottom_right.clone(),
-                                                                                                                     self.basic.top_left,
-                                                                                                                     self.basic.bottom_right);
+    fn render<'a>(&'a mut self, parent_top_left: &(f32, f32), parent_bottom_right: &(f32, f32), render_pass: &mut RenderPass<'a>, context: &RenderContext) {
+        self.basic.placement.update(*parent_top_left, *parent_bottom_right, self.basic.top_left, self.basic.bottom_right, &self.basic.transform, context);
+
+        // The z-index of a child may have changed since the last frame
+        self.sort_components();
+
+        let element = StyleElement {
+            type_name: self.get_type_name(),
+            id: self.basic.id.as_deref(),
+            classes: &self.basic.classes,
+            state: ElementState::default(),
+        };
+        let path = context.get_style_path(element);
+        let style = context.resolve_style(&path, &self.style);
+        self.basic.placement.inset(&style.margin);
+
+        self.styled_box.prepare(&style, &self.basic.placement, context);
+        self.styled_box.draw(&self.basic.placement, render_pass, context);
+
+        let mut content = self.basic.placement;
+        content.inset(&style.padding);
+
+        let absolute_top_left = content.absolute_top_left;
+        let absolute_bottom_right = content.absolute_bottom_right;
+        let context = context.with_transform(self.basic.placement.world_transform).with_inherited(style.inherited);
+        let context = context.with_style_path(&path);
+
+        if self.overflow == Overflow::Visible {
+            for comp in self.components.iter_mut() {
+                comp.render(&absolute_top_left, &absolute_bottom_right, render_pass, &context);
+            }
+            return;
+        }
+
+        self.clipper.prepare(&self.basic.placement, self.corner_radius, &context);
+
+        let clip_context = match self.clipper.begin(render_pass, &context) {
+            None => return,
+            Some(clip_context) => clip_context,
+        };
 
         for comp in self.components.iter_mut() {
-            comp.render(&absolute_top_left, &absolut_bottom_right, render_pass, device, config);
+            comp.render(&absolute_top_left, &absolute_bottom_right, render_pass, &clip_context);
         }
+
+        self.clipper.end(render_pass, &context);
     }
 
     fn get_top_left(&self) -> (f32, f32) {
@@ -44,15 +126,131 @@ impl Component for LayoutComponent {
         self.basic.bottom_right
     }
 
-    fn on_click(&self, position: (f32, f32)) {
-        for i in 0..self.components.len() {
-            let comp = self.components.get(i).unwrap().as_ref();
+    fn get_transform(&self) -> Transform {
+        self.basic.transform
+    }
+
+    fn set_transform(&mut self, transform: Transform) {
+        self.basic.transform = transform;
+    }
+
+    fn get_z_index(&self) -> i32 {
+        self.basic.z_index
+    }
+
+    fn set_z_index(&mut self, z_index: i32) {
+        self.basic.z_index = z_index;
+    }
+
+    fn get_key(&self) -> Option<&str> {
+        self.basic.key.as_deref()
+    }
+
+    fn set_key(&mut self, key: &str) {
+        self.basic.key = Some(key.to_string());
+    }
+
+    fn get_id(&self) -> Option<&str> {
+        self.basic.id.as_deref()
+    }
+
+    fn set_id(&mut self, id: &str) {
+        self.basic.id = Some(id.to_string());
+    }
+
+    fn get_classes(&self) -> &[String] {
+        &self.basic.classes
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.basic.add_class(class);
+    }
+
+    fn get_style(&self) -> Option<&Style> {
+        Some(&self.style)
+    }
+
+    fn get_children(&self) -> Vec<&dyn Component> {
+        self.components.iter().map(|component| component.as_ref()).collect()
+    }
+
+    fn get_overflow(&self) -> Overflow {
+        self.overflow
+    }
+
+    fn get_placement(&self) -> &ComponentPlacement {
+        &self.basic.placement
+    }
+
+    fn layout(&mut self, parent: &ComponentPlacement) {
+        self.basic.placement.update_in(parent, self.basic.top_left, self.basic.bottom_right, &self.basic.transform);
+        self.sort_components();
+
+        let style = resolve_own_style(&self.style);
+        self.basic.placement.inset(&style.margin);
+        let mut content = self.basic.placement;
+        content.inset(&style.padding);
+
+        for component in self.components.iter_mut() {
+            component.layout(&content);
+        }
+    }
+
+    fn on_click(&mut self, position: (f32, f32), context: &mut EventContext) {
+        if self.overflow == Overflow::Clip && !self.in_bound(position) {
+            return;
+        }
+
+        for comp in self.components.iter_mut() {
             if comp.in_bound(position) {
-                comp.on_click(position);
+                comp.on_click(position, context);
             }
         }
     }
 
+    fn on_event(&mut self, event: &ComponentEvent, context: &mut EventContext) -> bool {
+        if !event.is_targeted() {
+            let mut consumed = false;
+            for comp in self.components.iter_mut() {
+                consumed |= comp.on_event(event, context);
+            }
+            return consumed;
+        }
+
+        if let Some(position) = event.position() {
+            if self.overflow == Overflow::Clip && !self.in_bound(position) {
+                return false;
+            }
+        }
+
+        // Components drawn last are on top, so they get the first chance
+        for comp in self.components.iter_mut().rev() {
+            if comp.on_event(event, context) {
+                return true;
+            }
+        }
+
+        false
+    }
+
+    fn in_bound(&self, point: (f32, f32)) -> bool {
+        self.basic.placement.contains_rounded(point, self.corner_radius)
+    }
+
+    fn reconcile(&mut self, new: Box<dyn Component>) -> Result<(), Box<dyn Component>> {
+        let new = *downcast_component::<Self>(new)?;
+
+        self.basic.reconcile(new.basic);
+        self.style = new.style;
+        self.overflow = new.overflow;
+        self.corner_radius = new.corner_radius;
+
+        reconcile_children(&mut self.components, new.components);
+        self.sort_components();
+
+        Ok(())
+    }
+
     fn resize(&mut self, new_box_top_left: (f32, f32), new_box_bottom_right: (f32, f32)) {
 
         self.basic.top_left = new_box_top_left;
@@ -67,4 +265,4 @@ impl Component for LayoutComponent {
             comp.on_resize()
         }
     }
-}
\ No newline at end of file
+}
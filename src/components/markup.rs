@@ -0,0 +1,232 @@
+use std::path::{Path, PathBuf};
+use std::rc::Rc;
+use std::time::Duration;
+use wgpu::RenderPass;
+use crate::components::component::{Component, ComponentPlacement, DirtyFlags, RenderContext, downcast_component};
+use crate::components::event::{ComponentEvent, EventContext};
+use crate::components::reconcile::reconcile_component;
+use crate::components::tooltip::TooltipOptions;
+use crate::markup::{MarkupError, MarkupNode, MarkupRegistry};
+use crate::style::Style;
+use crate::transform::Transform;
+use crate::watcher::FileWatcher;
+
+/// How often debug builds look for changes of the markup file.
+const WATCH_INTERVAL: Duration = Duration::from_millis(250);
+
+/// A component tree built from a markup file.
+/// In debug builds the file is watched and the tree is rebuilt and reconciled when it changes,
+/// so retained components keep their state. A file that fails to load keeps the previous tree.
+///
+/// The retained tree follows the file, not the views it is reconciled with. Views can build it from a node
+/// parsed once with `from_node` instead of loading the file on every update.
+pub struct MarkupComponent {
+    path: PathBuf,
+    registry: Rc<MarkupRegistry>,
+    root: Box<dyn Component>,
+
+    watcher: Option<FileWatcher>,
+}
+
+impl MarkupComponent {
+    pub fn load<P: AsRef<Path>>(path: P, registry: Rc<MarkupRegistry>) -> anyhow::Result<Self> {
+        let node = MarkupNode::load(&path)?;
+        Ok(Self::from_node(path, &node, registry)?)
+    }
+
+    /// Builds the tree from `node`, which was parsed from the file at `path` before.
+    pub fn from_node<P: AsRef<Path>>(path: P, node: &MarkupNode, registry: Rc<MarkupRegistry>) -> Result<Self, MarkupError> {
+        let root = registry.build(node)?;
+
+        Ok(Self {
+            path: path.as_ref().to_path_buf(),
+            registry,
+            root,
+            watcher: None,
+        })
+    }
+
+    pub fn get_path(&self) -> &Path {
+        &self.path
+    }
+
+    pub fn get_root(&self) -> &dyn Component {
+        self.root.as_ref()
+    }
+
+    pub fn get_root_mut(&mut self) -> &mut Box<dyn Component> {
+        &mut self.root
+    }
+
+    /// Builds the file again and reconciles the new tree with the current one.
+    pub fn reload(&mut self) -> anyhow::Result<()> {
+        let root = self.registry.load(&self.path)?;
+        reconcile_component(&mut self.root, root);
+        Ok(())
+    }
+
+    #[cfg(debug_assertions)]
+    fn reload_if_changed(&mut self) {
+        let path = &self.path;
+        let watcher = self.watcher.get_or_insert_with(|| FileWatcher::new(path.clone(), WATCH_INTERVAL));
+
+        if watcher.take_change() {
+            if let Err(error) = self.reload() {
+                eprintln!("Could not reload {}: {:#}", self.path.display(), error);
+            }
+        }
+    }
+
+    #[cfg(not(debug_assertions))]
+    fn reload_if_changed(&mut self) {}
+}
+
+impl Component for MarkupComponent {
+    fn render<'a>(&'a mut self, parent_top_left: &(f32, f32), parent_bottom_right: &(f32, f32), render_pass: &mut RenderPass<'a>, context: &RenderContext) {
+        self.reload_if_changed();
+        self.root.render(parent_top_left, parent_bottom_right, render_pass, context);
+    }
+
+    fn get_top_left(&self) -> (f32, f32) {
+        self.root.get_top_left()
+    }
+
+    fn get_bottom_right(&self) -> (f32, f32) {
+        self.root.get_bottom_right()
+    }
+
+    fn get_transform(&self) -> Transform {
+        self.root.get_transform()
+    }
+
+    fn set_transform(&mut self, transform: Transform) {
+        self.root.set_transform(transform);
+    }
+
+    fn get_z_index(&self) -> i32 {
+        self.root.get_z_index()
+    }
+
+    fn set_z_index(&mut self, z_index: i32) {
+        self.root.set_z_index(z_index);
+    }
+
+    fn get_key(&self) -> Option<&str> {
+        self.root.get_key()
+    }
+
+    fn set_key(&mut self, key: &str) {
+        self.root.set_key(key);
+    }
+
+    fn get_type_name(&self) -> &'static str {
+        self.root.get_type_name()
+    }
+
+    fn get_id(&self) -> Option<&str> {
+        self.root.get_id()
+    }
+
+    fn set_id(&mut self, id: &str) {
+        self.root.set_id(id);
+    }
+
+    fn get_classes(&self) -> &[String] {
+        self.root.get_classes()
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.root.add_class(class);
+    }
+
+    fn get_style(&self) -> Option<&Style> {
+        self.root.get_style()
+    }
+
+    fn get_children(&self) -> Vec<&dyn Component> {
+        self.root.get_children()
+    }
+
+    fn get_dirty_flags(&self) -> DirtyFlags {
+        self.root.get_dirty_flags()
+    }
+
+    fn get_event_handlers(&self) -> Vec<&'static str> {
+        self.root.get_event_handlers()
+    }
+
+    fn get_tooltip(&self) -> Option<&TooltipOptions> {
+        self.root.get_tooltip()
+    }
+
+    fn get_placement(&self) -> &ComponentPlacement {
+        self.root.get_placement()
+    }
+
+    fn layout(&mut self, parent: &ComponentPlacement) {
+        self.root.layout(parent);
+    }
+
+    fn on_event(&mut self, event: &ComponentEvent, context: &mut EventContext) -> bool {
+        self.root.on_event(event, context)
+    }
+
+    fn in_bound(&self, point: (f32, f32)) -> bool {
+        self.root.in_bound(point)
+    }
+
+    fn reconcile(&mut self, new: Box<dyn Component>) -> Result<(), Box<dyn Component>> {
+        let new = *downcast_component::<Self>(new)?;
+
+        // The watcher already reloaded changes of the same file, the new tree may be older than the retained one
+        self.registry = new.registry;
+        if self.path != new.path {
+            reconcile_component(&mut self.root, new.root);
+            self.path = new.path;
+            self.watcher = None;
+        }
+
+        Ok(())
+    }
+
+    fn resize(&mut self, new_box_top_left: (f32, f32), new_box_bottom_right: (f32, f32)) {
+        self.root.resize(new_box_top_left, new_box_bottom_right);
+    }
+
+    fn on_resize(&mut self) {
+        self.root.on_resize();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn footer(path: &str, color: &str) -> MarkupComponent {
+        let node = MarkupNode::parse(&format!(r#"<Plain rect="-1 1 1 -1" color="{}"/>"#, color)).unwrap();
+        MarkupComponent::from_node(path, &node, Rc::new(MarkupRegistry::new())).unwrap()
+    }
+
+    fn root_address(markup: &MarkupComponent) -> *const () {
+        markup.get_root() as *const dyn Component as *const ()
+    }
+
+    #[test]
+    fn views_of_the_same_file_keep_the_reloaded_tree() {
+        let mut retained = footer("a.ui", "1 0 0");
+        let root = root_address(&retained);
+
+        assert!(retained.reconcile(Box::new(footer("a.ui", "0 1 0"))).is_ok());
+        assert_eq!(root_address(&retained), root);
+        assert_eq!(retained.get_style().and_then(|style| style.background), Some(crate::style::Color::Rgb([1.0, 0.0, 0.0])));
+    }
+
+    #[test]
+    fn views_of_another_file_are_reconciled() {
+        let mut retained = footer("a.ui", "1 0 0");
+
+        assert!(retained.reconcile(Box::new(footer("b.ui", "0 1 0"))).is_ok());
+        assert_eq!(retained.get_path(), Path::new("b.ui"));
+        assert_eq!(retained.get_style().and_then(|style| style.background), Some(crate::style::Color::Rgb([0.0, 1.0, 0.0])));
+    }
+}
@@ -0,0 +1,181 @@
+use wgpu::RenderPass;
+use crate::Shape;
+use crate::components::component::{Component, ComponentBasicResizeData, ComponentPlacement, DirtyFlags, RenderContext, downcast_component};
+use crate::shapes::oval::Oval;
+use crate::transform::Transform;
+
+/// An oval filling its rectangle. The GPU buffers are created on the first render.
+pub struct OvalComponent {
+    basic: ComponentBasicResizeData,
+    color: [f32; 3],
+    triangle_count: u16,
+
+    oval: Option<Oval>,
+}
+
+impl OvalComponent {
+    pub fn new(top_left: (f32, f32), bottom_right: (f32, f32), color: [f32; 3]) -> Self {
+        Self {
+            basic: ComponentBasicResizeData {
+                top_left,
+                bottom_right,
+                needs_resize: true,
+                transform: Transform::IDENTITY,
+                z_index: 0,
+                key: None,
+                id: None,
+                classes: vec![],
+                placement: ComponentPlacement::default(),
+            },
+            color,
+            triangle_count: 64,
+            oval: None,
+        }
+    }
+
+    pub fn get_color(&self) -> [f32; 3] {
+        self.color
+    }
+
+    pub fn set_color(&mut self, color: [f32; 3]) {
+        self.color = color;
+        self.basic.needs_resize = true;
+    }
+
+    pub fn set_triangle_count(&mut self, triangle_count: u16) {
+        self.triangle_count = triangle_count;
+        self.basic.needs_resize = true;
+    }
+}
+
+impl Component for OvalComponent {
+    fn render<'a>(&'a mut self, parent_top_left: &(f32, f32), parent_bottom_right: &(f32, f32), render_pass: &mut RenderPass<'a>, context: &RenderContext) {
+        self.basic.placement.update(*parent_top_left, *parent_bottom_right, self.basic.top_left, self.basic.bottom_right, &self.basic.transform, context);
+
+        if self.basic.needs_resize {
+            let top_left = self.basic.placement.absolute_top_left;
+            let bottom_right = self.basic.placement.absolute_bottom_right;
+
+            // The oval takes the radius per axis
+            self.oval = Some(Oval::new(((top_left.0 + bottom_right.0) / 2.0, (top_left.1 + bottom_right.1) / 2.0),
+                                       ((bottom_right.0 - top_left.0).abs() / 2.0, (top_left.1 - bottom_right.1).abs() / 2.0),
+                                       self.triangle_count,
+                                       self.color,
+                                       context.device,
+                                       context.pipelines));
+
+            self.basic.needs_resize = false;
+        }
+
+        if let Some(oval) = self.oval.as_mut() {
+            oval.set_transform(self.basic.placement.world_transform, context.queue);
+            oval.set_opacity(context.opacity, context.queue);
+            oval.draw(render_pass);
+        }
+    }
+
+    fn get_top_left(&self) -> (f32, f32) {
+        self.basic.top_left
+    }
+
+    fn get_bottom_right(&self) -> (f32, f32) {
+        self.basic.bottom_right
+    }
+
+    fn get_transform(&self) -> Transform {
+        self.basic.transform
+    }
+
+    fn set_transform(&mut self, transform: Transform) {
+        self.basic.transform = transform;
+    }
+
+    fn get_z_index(&self) -> i32 {
+        self.basic.z_index
+    }
+
+    fn set_z_index(&mut self, z_index: i32) {
+        self.basic.z_index = z_index;
+    }
+
+    fn get_key(&self) -> Option<&str> {
+        self.basic.key.as_deref()
+    }
+
+    fn set_key(&mut self, key: &str) {
+        self.basic.key = Some(key.to_string());
+    }
+
+    fn get_id(&self) -> Option<&str> {
+        self.basic.id.as_deref()
+    }
+
+    fn set_id(&mut self, id: &str) {
+        self.basic.id = Some(id.to_string());
+    }
+
+    fn get_classes(&self) -> &[String] {
+        &self.basic.classes
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.basic.add_class(class);
+    }
+
+    fn get_dirty_flags(&self) -> DirtyFlags {
+        DirtyFlags {
+            needs_resize: self.basic.needs_resize,
+            ..DirtyFlags::default()
+        }
+    }
+
+    fn get_placement(&self) -> &ComponentPlacement {
+        &self.basic.placement
+    }
+
+    fn layout(&mut self, parent: &ComponentPlacement) {
+        self.basic.placement.update_in(parent, self.basic.top_left, self.basic.bottom_right, &self.basic.transform);
+    }
+
+    fn in_bound(&self, point: (f32, f32)) -> bool {
+        let placement = &self.basic.placement;
+        let (x, y) = match placement.to_local(point) {
+            None => return false,
+            Some(local) => local,
+        };
+
+        let center = ((placement.absolute_top_left.0 + placement.absolute_bottom_right.0) / 2.0,
+                      (placement.absolute_top_left.1 + placement.absolute_bottom_right.1) / 2.0);
+        let radius = ((placement.absolute_bottom_right.0 - placement.absolute_top_left.0).abs() / 2.0,
+                      (placement.absolute_top_left.1 - placement.absolute_bottom_right.1).abs() / 2.0);
+        if radius.0 <= 0.0 || radius.1 <= 0.0 {
+            return false;
+        }
+
+        ((x - center.0) / radius.0).powi(2) + ((y - center.1) / radius.1).powi(2) <= 1.0
+    }
+
+    fn reconcile(&mut self, new: Box<dyn Component>) -> Result<(), Box<dyn Component>> {
+        let new = *downcast_component::<Self>(new)?;
+
+        self.basic.reconcile(new.basic);
+        if self.color != new.color || self.triangle_count != new.triangle_count {
+            self.color = new.color;
+            self.triangle_count = new.triangle_count;
+            self.basic.needs_resize = true;
+        }
+
+        Ok(())
+    }
+
+    fn resize(&mut self, new_box_top_left: (f32, f32), new_box_bottom_right: (f32, f32)) {
+        self.basic.top_left = new_box_top_left;
+        self.basic.bottom_right = new_box_bottom_right;
+
+        self.on_resize();
+    }
+
+    fn on_resize(&mut self) {
+        self.basic.needs_resize = true;
+    }
+}
@@ -0,0 +1,168 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use wgpu::RenderPass;
+use crate::components::component::{Component, RenderContext};
+use crate::components::event::{ComponentEvent, EventContext};
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct OverlayId(u64);
+
+impl OverlayId {
+    pub fn next() -> Self {
+        static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+        OverlayId(NEXT_ID.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+pub struct OverlayOptions {
+    /// Overlays with a higher z-index are drawn above and get events first.
+    pub z_index: i32,
+    /// A press outside of the overlay closes it. The press is consumed.
+    pub dismiss_on_outside_click: bool,
+    /// No event reaches anything below the overlay.
+    pub modal: bool,
+    /// Called when the overlay is closed by a press outside of it.
+    pub on_dismiss: Option<Box<dyn FnMut()>>,
+}
+
+impl Default for OverlayOptions {
+    fn default() -> Self {
+        Self {
+            z_index: 0,
+            dismiss_on_outside_click: true,
+            modal: false,
+            on_dismiss: None,
+        }
+    }
+}
+
+pub struct Overlay {
+    pub id: OverlayId,
+    /// The rectangle of the component is relative to the whole window.
+    pub component: Box<dyn Component>,
+    pub options: OverlayOptions,
+}
+
+/// Popups, tooltips, dropdowns and modals drawn above the component tree.
+#[derive(Default)]
+pub struct OverlayLayer {
+    overlays: Vec<Overlay>,
+}
+
+impl OverlayLayer {
+    pub fn new() -> Self {
+        Self {
+            overlays: vec![],
+        }
+    }
+
+    pub fn show(&mut self, component: Box<dyn Component>, options: OverlayOptions) -> OverlayId {
+        let id = OverlayId::next();
+        self.insert(Overlay { id, component, options });
+        id
+    }
+
+    pub fn insert(&mut self, overlay: Overlay) {
+        // Keep the overlays sorted by z-index, newer ones above older ones with the same z-index
+        let index = self.overlays.partition_point(|other| other.options.z_index <= overlay.options.z_index);
+        self.overlays.insert(index, overlay);
+    }
+
+    pub fn dismiss(&mut self, id: OverlayId) -> Option<Overlay> {
+        let index = self.overlays.iter().position(|overlay| overlay.id == id)?;
+        Some(self.overlays.remove(index))
+    }
+
+    pub fn is_open(&self, id: OverlayId) -> bool {
+        self.overlays.iter().any(|overlay| overlay.id == id)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.overlays.is_empty()
+    }
+
+    /// Whether an open overlay keeps all input away from the component tree.
+    pub fn has_modal(&self) -> bool {
+        self.overlays.iter().any(|overlay| overlay.options.modal)
+    }
+
+    /// The component of the topmost overlay that covers `position`.
+    pub fn get_component_at(&self, position: (f32, f32)) -> Option<&dyn Component> {
+        self.overlays.iter().rev()
+            .find(|overlay| overlay.component.in_bound(position))
+            .map(|overlay| overlay.component.as_ref())
+    }
+
+    /// The components of all overlays from the bottom one to the top one.
+    pub fn get_components(&self) -> Vec<&dyn Component> {
+        self.overlays.iter().map(|overlay| overlay.component.as_ref()).collect()
+    }
+
+    pub fn get_component_mut(&mut self, id: OverlayId) -> Option<&mut Box<dyn Component>> {
+        self.overlays.iter_mut()
+            .find(|overlay| overlay.id == id)
+            .map(|overlay| &mut overlay.component)
+    }
+
+    pub fn render<'a>(&'a mut self, render_pass: &mut RenderPass<'a>, context: &RenderContext) {
+        for overlay in self.overlays.iter_mut() {
+            overlay.component.render(&(-1.0, 1.0), &(1.0, -1.0), render_pass, context);
+        }
+    }
+
+    pub fn on_resize(&mut self) {
+        for overlay in self.overlays.iter_mut() {
+            overlay.component.on_resize();
+        }
+    }
+
+    /// Passes an event to the overlays from top to bottom and returns whether it was consumed.
+    /// Moves and releases are broadcast and should still reach the component tree, so drags can finish.
+    pub fn on_event(&mut self, event: &ComponentEvent, context: &mut EventContext) -> bool {
+        if !event.is_targeted() {
+            let mut consumed = false;
+            for overlay in self.overlays.iter_mut().rev() {
+                consumed |= overlay.component.on_event(event, context);
+            }
+            return consumed;
+        }
+
+        let position = match event.position() {
+            None => return false,
+            Some(position) => position,
+        };
+        let mut dismissed = vec![];
+        let mut consumed = false;
+
+        for overlay in self.overlays.iter_mut().rev() {
+            if overlay.component.in_bound(position) {
+                consumed = overlay.component.on_event(event, context) || overlay.options.modal;
+                break;
+            }
+
+            if overlay.options.dismiss_on_outside_click && matches!(event, ComponentEvent::MousePressed { .. }) {
+                dismissed.push(overlay.id);
+                consumed = true;
+                continue;
+            }
+
+            if overlay.options.modal {
+                consumed = true;
+                break;
+            }
+        }
+
+        if !dismissed.is_empty() {
+            context.request_redraw();
+        }
+
+        for id in dismissed {
+            if let Some(mut overlay) = self.dismiss(id) {
+                if let Some(on_dismiss) = overlay.options.on_dismiss.as_mut() {
+                    on_dismiss();
+                }
+            }
+        }
+
+        consumed
+    }
+}
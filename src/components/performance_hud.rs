@@ -0,0 +1,157 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+use wgpu::RenderPass;
+use winit::event::{ElementState, KeyboardInput, ModifiersState, VirtualKeyCode, WindowEvent};
+use crate::components::component::RenderContext;
+use crate::profiler::FrameStats;
+use crate::shape::Shape;
+use crate::shapes::quad::Quad;
+use crate::transform::Transform;
+
+/// Number of frames shown, one bar each.
+const FRAMES: usize = 120;
+const BAR_WIDTH: f32 = 2.0;
+const GRAPH_HEIGHT: f32 = 80.0;
+const MARGIN: f32 = 8.0;
+/// The frame time at the top of the graph, twice the budget of a 60 Hz display.
+const GRAPH_MAXIMUM: Duration = Duration::from_micros(33_333);
+const BUDGET: Duration = Duration::from_micros(16_667);
+
+const BACKGROUND_COLOR: [f32; 3] = [0.05, 0.05, 0.05];
+const BUDGET_COLOR: [f32; 3] = [1.0, 0.3, 0.3];
+const GPU_COLOR: [f32; 3] = [1.0, 1.0, 1.0];
+/// The colors of the events, update, layout, encode and submit phases, stacked from the bottom.
+const PHASE_COLORS: [[f32; 3]; 5] = [
+    [0.9, 0.8, 0.3],
+    [0.75, 0.45, 0.9],
+    [0.95, 0.5, 0.3],
+    [0.3, 0.7, 1.0],
+    [0.4, 0.85, 0.4],
+];
+
+/// The shapes of the bar of one frame. Their geometry spans the whole graph and is scaled down by their transforms,
+/// so updating the graph does not create any buffers.
+struct FrameBar {
+    phases: [Quad; 5],
+    /// A tick at the height of the GPU time.
+    gpu: Quad,
+}
+
+/// A graph of the last frame times in the bottom left corner, opened and closed with Ctrl+Shift+P.
+/// Every bar stacks the CPU time of the phases of a frame, a white tick marks its GPU time if it was measured
+/// and the red line is the budget of a 60 Hz display.
+#[derive(Default)]
+pub struct PerformanceHud {
+    enabled: bool,
+    modifiers: ModifiersState,
+
+    window_size: (u32, u32),
+    background: Option<Quad>,
+    budget: Option<Quad>,
+    bars: Vec<FrameBar>,
+}
+
+impl PerformanceHud {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    /// Handles the shortcut and returns whether the event was consumed.
+    pub fn input(&mut self, event: &WindowEvent) -> bool {
+        match event {
+            WindowEvent::ModifiersChanged(modifiers) => {
+                self.modifiers = *modifiers;
+                false
+            }
+            WindowEvent::KeyboardInput { input: KeyboardInput { state: ElementState::Pressed, virtual_keycode: Some(VirtualKeyCode::P), .. }, .. }
+            if self.modifiers.ctrl() && self.modifiers.shift() => {
+                self.set_enabled(!self.enabled);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Updates the graph to `history`, creating the shapes only when the window was resized.
+    pub fn prepare(&mut self, history: &VecDeque<FrameStats>, context: &RenderContext) {
+        if !self.enabled {
+            return;
+        }
+
+        let config = context.config;
+        let pixel = (2.0 / config.width.max(1) as f32, 2.0 / config.height.max(1) as f32);
+        let left = -1.0 + MARGIN * pixel.0;
+        let bottom = -1.0 + MARGIN * pixel.1;
+        let height = GRAPH_HEIGHT * pixel.1;
+        let height_of = |duration: Duration| (duration.as_secs_f32() / GRAPH_MAXIMUM.as_secs_f32()).min(1.0) * height;
+
+        if self.window_size != (config.width, config.height) {
+            self.window_size = (config.width, config.height);
+
+            let quad = |top_left: (f32, f32), bottom_right: (f32, f32), color: [f32; 3]| Quad::new(top_left, bottom_right, color, context.device, context.pipelines);
+            let right = left + FRAMES as f32 * BAR_WIDTH * pixel.0;
+
+            let mut background = quad((left, bottom + height), (right, bottom), BACKGROUND_COLOR);
+            background.set_opacity(0.8, context.queue);
+            self.background = Some(background);
+
+            let budget = bottom + height_of(BUDGET);
+            self.budget = Some(quad((left, budget + pixel.1), (right, budget), BUDGET_COLOR));
+
+            self.bars = (0..FRAMES).map(|index| {
+                let bar_left = left + index as f32 * BAR_WIDTH * pixel.0;
+                let bar_right = bar_left + (BAR_WIDTH - 1.0) * pixel.0;
+
+                FrameBar {
+                    phases: PHASE_COLORS.map(|color| quad((bar_left, bottom + height), (bar_right, bottom), color)),
+                    gpu: quad((bar_left, bottom + pixel.1), (bar_right, bottom), GPU_COLOR),
+                }
+            }).collect();
+        }
+
+        // The newest frame is on the right
+        let frames = history.iter().rev().take(FRAMES).map(Some).chain(std::iter::repeat(None));
+        for (bar, stats) in self.bars.iter_mut().rev().zip(frames) {
+            let phases = stats.map_or([Duration::ZERO; 5], |stats| [stats.events, stats.update, stats.layout, stats.encode, stats.submit]);
+
+            let mut top = bottom;
+            for (quad, phase) in bar.phases.iter_mut().zip(phases) {
+                // Scales the quad from the bottom of the graph to the height of the phase and moves it on the ones below
+                let scale = height_of(phase) / height;
+                quad.set_transform(Transform { d: scale, ty: top - scale * bottom, ..Transform::IDENTITY }, context.queue);
+                top += height_of(phase);
+            }
+
+            let gpu = stats.and_then(|stats| stats.gpu);
+            bar.gpu.set_transform(Transform::translation(0.0, gpu.map_or(0.0, height_of)), context.queue);
+            bar.gpu.set_opacity(if gpu.is_some() { 1.0 } else { 0.0 }, context.queue);
+        }
+    }
+
+    pub fn render<'a>(&'a self, render_pass: &mut RenderPass<'a>) {
+        if !self.enabled {
+            return;
+        }
+
+        if let Some(background) = self.background.as_ref() {
+            background.draw(render_pass);
+        }
+        for bar in self.bars.iter() {
+            for quad in bar.phases.iter() {
+                quad.draw(render_pass);
+            }
+            bar.gpu.draw(render_pass);
+        }
+        if let Some(budget) = self.budget.as_ref() {
+            budget.draw(render_pass);
+        }
+    }
+}
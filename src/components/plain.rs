@@ -1,52 +1,72 @@
-use wgpu::{Device, RenderPass, SurfaceConfiguration};
-use crate::{Shape};
-use crate::components::component::ComponentBasicResizeData;
-use super::super::shapes::quad::Quad;
-use super::component::{ComponentUtils, Component};
+use wgpu::RenderPass;
+use crate::components::component::{ComponentBasicResizeData, ComponentPlacement, DirtyFlags, RenderContext, downcast_component, resolve_own_style};
+use crate::components::styled_box::StyledBox;
+use crate::style::{Color, Style};
+use crate::stylesheet::{ElementState, StyleElement};
+use crate::transform::Transform;
+use super::component::Component;
 
 pub struct PlainComponent {
     basic: ComponentBasicResizeData,
-    color: [f32; 3],
+    style: Style,
 
-    quad: Quad,
+    styled_box: StyledBox,
 }
 
-impl<'a> PlainComponent {
-    pub fn new(top_left: (f32, f32), bottom_right: (f32, f32), color: [f32; 3], device: &Device, config: &SurfaceConfiguration) -> Self {
+impl PlainComponent {
+    pub fn new(top_left: (f32, f32), bottom_right: (f32, f32), color: [f32; 3]) -> Self {
         Self {
             basic: ComponentBasicResizeData {
                 top_left,
                 bottom_right,
                 needs_resize: true,
+                transform: Transform::IDENTITY,
+                z_index: 0,
+                key: None,
+                id: None,
+                classes: vec![],
+                placement: ComponentPlacement::default(),
             },
-            color,
-            quad: Quad::new(top_left,
-                            bottom_right,
-                            color,
-                            device,
-                            config),
+            style: Style {
+                background: Some(Color::Rgb(color)),
+                ..Style::default()
+            },
+            styled_box: StyledBox::new(),
         }
     }
+
+    /// The background color.
+    pub fn get_color(&self) -> Option<Color> {
+        self.style.background
+    }
+
+    pub fn set_color(&mut self, color: [f32; 3]) {
+        self.style.background = Some(Color::Rgb(color));
+    }
+
+    /// Sets the style, it overrides the rules of the stylesheet. Theme colors and sizes are looked up when the component is rendered.
+    pub fn set_style(&mut self, style: Style) {
+        self.style = style;
+    }
 }
 
 impl Component for PlainComponent {
-    fn render<'a>(&'a mut self, parent_top_left: &(f32, f32), parent_bottom_right: &(f32, f32), render_pass: &mut RenderPass<'a>, device: &Device, config: &SurfaceConfiguration) {
-        if self.basic.needs_resize {
-            let (absolute_top_left, absolut_bottom_right) = ComponentUtils::calculate_absolute_from_relative_view_points(parent_top_left.clone(),
-                                                                                                                         parent_bottom_right.clone(),
-                                                                                                                         self.basic.top_left,
-                                                                                                                         self.basic.bottom_right);
-
-            self.quad = Quad::new(absolute_top_left,
-                                  absolut_bottom_right,
-                                  self.color,
-                                  device,
-                                  config);
-
-            self.basic.needs_resize = false;
-        }
+    fn render<'a>(&'a mut self, parent_top_left: &(f32, f32), parent_bottom_right: &(f32, f32), render_pass: &mut RenderPass<'a>, context: &RenderContext) {
+        self.basic.placement.update(*parent_top_left, *parent_bottom_right, self.basic.top_left, self.basic.bottom_right, &self.basic.transform, context);
+
+        let element = StyleElement {
+            type_name: self.get_type_name(),
+            id: self.basic.id.as_deref(),
+            classes: &self.basic.classes,
+            state: ElementState::default(),
+        };
+        let style = context.resolve_style(&context.get_style_path(element), &self.style);
+        self.basic.placement.inset(&style.margin);
 
-        self.quad.draw(render_pass);
+        self.styled_box.prepare(&style, &self.basic.placement, context);
+        self.basic.needs_resize = false;
+
+        self.styled_box.draw(&self.basic.placement, render_pass, context);
     }
 
     fn get_top_left(&self) -> (f32, f32) {
@@ -57,6 +77,75 @@ impl Component for PlainComponent {
         self.basic.bottom_right
     }
 
+    fn get_transform(&self) -> Transform {
+        self.basic.transform
+    }
+
+    fn set_transform(&mut self, transform: Transform) {
+        self.basic.transform = transform;
+    }
+
+    fn get_z_index(&self) -> i32 {
+        self.basic.z_index
+    }
+
+    fn set_z_index(&mut self, z_index: i32) {
+        self.basic.z_index = z_index;
+    }
+
+    fn get_key(&self) -> Option<&str> {
+        self.basic.key.as_deref()
+    }
+
+    fn set_key(&mut self, key: &str) {
+        self.basic.key = Some(key.to_string());
+    }
+
+    fn get_id(&self) -> Option<&str> {
+        self.basic.id.as_deref()
+    }
+
+    fn set_id(&mut self, id: &str) {
+        self.basic.id = Some(id.to_string());
+    }
+
+    fn get_classes(&self) -> &[String] {
+        &self.basic.classes
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.basic.add_class(class);
+    }
+
+    fn get_style(&self) -> Option<&Style> {
+        Some(&self.style)
+    }
+
+    fn get_dirty_flags(&self) -> DirtyFlags {
+        DirtyFlags {
+            needs_resize: self.basic.needs_resize,
+            ..DirtyFlags::default()
+        }
+    }
+
+    fn get_placement(&self) -> &ComponentPlacement {
+        &self.basic.placement
+    }
+
+    fn layout(&mut self, parent: &ComponentPlacement) {
+        self.basic.placement.update_in(parent, self.basic.top_left, self.basic.bottom_right, &self.basic.transform);
+        self.basic.placement.inset(&resolve_own_style(&self.style).margin);
+    }
+
+    fn reconcile(&mut self, new: Box<dyn Component>) -> Result<(), Box<dyn Component>> {
+        let new = *downcast_component::<Self>(new)?;
+
+        self.basic.reconcile(new.basic);
+        self.style = new.style;
+
+        Ok(())
+    }
+
     fn resize(&mut self, new_box_top_left: (f32, f32), new_box_bottom_right: (f32, f32)) {
         self.basic.top_left = new_box_top_left;
         self.basic.bottom_right = new_box_bottom_right;
@@ -67,4 +156,4 @@ impl Component for PlainComponent {
     fn on_resize(&mut self) {
         self.basic.needs_resize = true;
     }
-}
\ No newline at end of file
+}
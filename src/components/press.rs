@@ -0,0 +1,148 @@
+use std::time::Duration;
+use winit::event::{MouseButton, VirtualKeyCode};
+use crate::animation::{Animated, Easing, Transition};
+use crate::components::event::{ComponentEvent, EventContext};
+use crate::components::focus::FocusId;
+use crate::stylesheet::ElementState;
+
+/// What `Pressable::handle_event` did with an event.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Press {
+    Ignored,
+    Consumed,
+    /// The component was pressed and released inside, or its activation key was released while it is focused.
+    Activated,
+}
+
+impl Press {
+    pub fn is_consumed(&self) -> bool {
+        *self != Press::Ignored
+    }
+}
+
+/// The hover, press and focus state of a component that is activated by clicking it,
+/// or by pressing and releasing one of its activation keys while it is focused, like buttons, checkboxes and toggles.
+pub struct Pressable {
+    focus_id: FocusId,
+    is_activation_key: fn(&VirtualKeyCode) -> bool,
+
+    enabled: bool,
+    focused: bool,
+    hovered: bool,
+    pointer_pressed: bool,
+    key_pressed: bool,
+}
+
+impl Pressable {
+    pub fn new(is_activation_key: fn(&VirtualKeyCode) -> bool) -> Self {
+        Self {
+            focus_id: FocusId::next(),
+            is_activation_key,
+            enabled: true,
+            focused: false,
+            hovered: false,
+            pointer_pressed: false,
+            key_pressed: false,
+        }
+    }
+
+    pub fn get_focus_id(&self) -> FocusId {
+        self.focus_id
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    /// Disabling cancels a press that is in progress.
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.pointer_pressed = false;
+            self.key_pressed = false;
+        }
+    }
+
+    pub fn is_focused(&self) -> bool {
+        self.focused
+    }
+
+    /// Takes the focused component of the frame, which is only known while rendering.
+    pub fn update_focus(&mut self, focused: Option<FocusId>) {
+        self.focused = focused == Some(self.focus_id);
+    }
+
+    pub fn is_hovered(&self) -> bool {
+        self.hovered
+    }
+
+    /// Whether releasing now would activate the component.
+    pub fn is_pressed(&self) -> bool {
+        self.key_pressed || (self.pointer_pressed && self.hovered)
+    }
+
+    pub fn get_element_state(&self, checked: bool) -> ElementState {
+        ElementState {
+            hovered: self.enabled && self.hovered,
+            pressed: self.is_pressed(),
+            focused: self.focused,
+            disabled: !self.enabled,
+            checked,
+        }
+    }
+
+    /// `inside` tells whether the position of a pointer event is inside the component.
+    pub fn handle_event(&mut self, event: &ComponentEvent, inside: bool, context: &mut EventContext) -> Press {
+        match event {
+            ComponentEvent::CursorMoved { .. } => {
+                self.hovered = inside;
+                Press::Ignored
+            }
+            ComponentEvent::MousePressed { button: MouseButton::Left, .. } => {
+                if !inside {
+                    return Press::Ignored;
+                }
+
+                if self.enabled {
+                    self.pointer_pressed = true;
+                    context.request_focus(self.focus_id);
+                }
+
+                // Disabled components still swallow the press so nothing below reacts to it
+                Press::Consumed
+            }
+            ComponentEvent::MouseReleased { button: MouseButton::Left, .. } => {
+                if !self.pointer_pressed {
+                    return Press::Ignored;
+                }
+
+                self.pointer_pressed = false;
+                if inside { Press::Activated } else { Press::Consumed }
+            }
+            ComponentEvent::KeyPressed { key } => {
+                if !self.enabled || !context.is_focused(self.focus_id) || !(self.is_activation_key)(key) {
+                    return Press::Ignored;
+                }
+
+                self.key_pressed = true;
+                Press::Consumed
+            }
+            ComponentEvent::KeyReleased { key } => {
+                if !self.key_pressed || !(self.is_activation_key)(key) {
+                    return Press::Ignored;
+                }
+
+                self.key_pressed = false;
+                Press::Activated
+            }
+            _ => Press::Ignored,
+        }
+    }
+}
+
+/// A color that fades between the styles of the states of a pressable component.
+pub(crate) fn animated_color(color: [f32; 3]) -> Animated<[f32; 3]> {
+    let mut animated = Animated::new(color);
+    animated.set_transition(Some(Transition::new(Duration::from_millis(120), Easing::EaseOut)));
+    animated
+}
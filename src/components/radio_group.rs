@@ -0,0 +1,539 @@
+use wgpu::RenderPass;
+use winit::event::{MouseButton, VirtualKeyCode};
+use crate::Shape;
+use crate::components::checkbox::{CheckStyle, DISABLED_OPACITY, ResolvedCheckStyle, is_toggle_key};
+use crate::components::component::{Component, ComponentBasicResizeData, ComponentPlacement, DirtyFlags, RenderContext, downcast_component};
+use crate::components::event::{ChangeCallback, ComponentEvent, EventContext};
+use crate::components::focus::FocusId;
+use crate::components::reconcile::reconcile_component;
+use crate::shapes::oval::Oval;
+use crate::stylesheet::{ElementState, StyleElement};
+use crate::transform::Transform;
+
+const TRIANGLE_COUNT: u16 = 32;
+
+/// The circles of one option.
+struct OptionShapes {
+    border: Oval,
+    background: Oval,
+    /// Only the selected option has a dot.
+    dot: Option<Oval>,
+}
+
+/// Options stacked from top to bottom in rows of equal height, of which at most one is selected.
+/// Every row has a circle on the left and an optional label in the rest of the row.
+/// The user selects an option by clicking its row, with the arrow keys or with Space while the group is focused.
+///
+/// The selection set by the view wins when the tree is reconciled, so keep it in the application and update it from `on_change`.
+pub struct RadioGroup {
+    basic: ComponentBasicResizeData,
+
+    /// The labels of the options. Their rectangles are relative to the part of the row right of the circle.
+    labels: Vec<Option<Box<dyn Component>>>,
+    selected: Option<usize>,
+    style: CheckStyle,
+    on_change: Option<ChangeCallback<usize>>,
+
+    focus_id: FocusId,
+    enabled: bool,
+    focused: bool,
+    hovered: Option<usize>,
+    pressed: Option<usize>,
+    key_pressed: bool,
+    /// The option Space selects, shown with the focus ring while the group is focused.
+    active: usize,
+
+    drawn_style: Option<(Option<usize>, usize, Vec<ResolvedCheckStyle>)>,
+    shapes: Vec<OptionShapes>,
+}
+
+impl RadioGroup {
+    pub fn new(top_left: (f32, f32), bottom_right: (f32, f32)) -> Self {
+        Self {
+            basic: ComponentBasicResizeData {
+                top_left,
+                bottom_right,
+                needs_resize: true,
+                transform: Transform::IDENTITY,
+                z_index: 0,
+                key: None,
+                id: None,
+                classes: vec![],
+                placement: ComponentPlacement::default(),
+            },
+            labels: vec![],
+            selected: None,
+            style: CheckStyle::default(),
+            on_change: None,
+            focus_id: FocusId::next(),
+            enabled: true,
+            focused: false,
+            hovered: None,
+            pressed: None,
+            key_pressed: false,
+            active: 0,
+            drawn_style: None,
+            shapes: vec![],
+        }
+    }
+
+    /// Adds an option below the others.
+    pub fn add_option(&mut self, label: Option<Box<dyn Component>>) {
+        self.labels.push(label);
+        self.basic.needs_resize = true;
+    }
+
+    pub fn get_option_count(&self) -> usize {
+        self.labels.len()
+    }
+
+    pub fn get_selected(&self) -> Option<usize> {
+        self.selected
+    }
+
+    /// Selects an option without calling `on_change`. Indices past the last option clear the selection.
+    pub fn set_selected(&mut self, selected: Option<usize>) {
+        self.selected = selected.filter(|index| *index < self.labels.len());
+        if let Some(index) = self.selected {
+            self.active = index;
+        }
+    }
+
+    pub fn get_check_style(&self) -> &CheckStyle {
+        &self.style
+    }
+
+    pub fn set_check_style(&mut self, style: CheckStyle) {
+        self.style = style;
+        self.basic.needs_resize = true;
+    }
+
+    /// Sets the callback called with the index of the option the user selected.
+    pub fn set_on_change(&mut self, on_change: impl FnMut(usize, &mut EventContext) + 'static) {
+        self.on_change = Some(Box::new(on_change));
+    }
+
+    /// Emits the message `message` returns for the selected index every time the user selects an option.
+    pub fn set_on_change_message<M: 'static>(&mut self, message: impl Fn(usize) -> M + 'static) {
+        self.set_on_change(move |index, context| context.emit(message(index)));
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.pressed = None;
+            self.key_pressed = false;
+        }
+    }
+
+    pub fn get_focus_id(&self) -> FocusId {
+        self.focus_id
+    }
+
+    /// The index of the row under a view point.
+    pub fn get_option_at(&self, point: (f32, f32)) -> Option<usize> {
+        if !self.basic.placement.contains(point) {
+            return None;
+        }
+
+        let point = self.basic.placement.to_local(point)?;
+        let (_, top) = self.basic.placement.absolute_top_left;
+        let (_, bottom) = self.basic.placement.absolute_bottom_right;
+        let index = ((top - point.1) / (top - bottom) * self.labels.len() as f32) as usize;
+
+        (index < self.labels.len()).then_some(index)
+    }
+
+    /// The absolute rectangle of the row of an option.
+    fn get_row(&self, index: usize) -> ((f32, f32), (f32, f32)) {
+        let (left, top) = self.basic.placement.absolute_top_left;
+        let (right, bottom) = self.basic.placement.absolute_bottom_right;
+        let height = (top - bottom) / self.labels.len().max(1) as f32;
+
+        ((left, top - index as f32 * height), (right, top - (index + 1) as f32 * height))
+    }
+
+    /// The circle takes a square on the left of the row, as wide as the row is high.
+    fn get_circle_width(&self) -> f32 {
+        let (left, _) = self.basic.placement.absolute_top_left;
+        let (right, _) = self.basic.placement.absolute_bottom_right;
+        let row_height = self.basic.placement.pixel_size().1 / self.labels.len().max(1) as f32;
+
+        self.basic.placement.radius_to_view(row_height).0.min(right - left)
+    }
+
+    fn get_element_state(&self) -> ElementState {
+        ElementState {
+            hovered: self.enabled && self.hovered.is_some(),
+            pressed: self.key_pressed || (self.pressed.is_some() && self.pressed == self.hovered),
+            focused: self.focused,
+            disabled: !self.enabled,
+            checked: self.selected.is_some(),
+        }
+    }
+
+    fn select(&mut self, index: usize, context: &mut EventContext) {
+        self.active = index;
+        if self.selected == Some(index) {
+            return;
+        }
+
+        self.selected = Some(index);
+        if let Some(on_change) = self.on_change.as_mut() {
+            on_change(index, context);
+        }
+    }
+
+    fn handle_event(&mut self, event: &ComponentEvent, context: &mut EventContext) -> bool {
+        match event {
+            ComponentEvent::CursorMoved { position } => {
+                self.hovered = self.get_option_at(*position);
+                false
+            }
+            ComponentEvent::MousePressed { position, button: MouseButton::Left } => {
+                let index = match self.get_option_at(*position) {
+                    Some(index) => index,
+                    None => return false,
+                };
+
+                if self.enabled {
+                    self.pressed = Some(index);
+                    context.request_focus(self.focus_id);
+                }
+                true
+            }
+            ComponentEvent::MouseReleased { position, button: MouseButton::Left } => {
+                let pressed = match self.pressed.take() {
+                    Some(pressed) => pressed,
+                    None => return false,
+                };
+
+                if self.get_option_at(*position) == Some(pressed) {
+                    self.select(pressed, context);
+                }
+                true
+            }
+            ComponentEvent::KeyPressed { key } => {
+                if !self.enabled || !context.is_focused(self.focus_id) || self.labels.is_empty() {
+                    return false;
+                }
+
+                // Like native radio buttons, the arrow keys select the neighbouring option right away
+                let count = self.labels.len();
+                match key {
+                    VirtualKeyCode::Up | VirtualKeyCode::Left => self.select((self.active + count - 1) % count, context),
+                    VirtualKeyCode::Down | VirtualKeyCode::Right => self.select((self.active + 1) % count, context),
+                    key if is_toggle_key(key) => self.key_pressed = true,
+                    _ => return false,
+                }
+                true
+            }
+            ComponentEvent::KeyReleased { key } => {
+                if !self.key_pressed || !is_toggle_key(key) {
+                    return false;
+                }
+
+                self.key_pressed = false;
+                self.select(self.active.min(self.labels.len().saturating_sub(1)), context);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn create_shapes(&mut self, styles: &[ResolvedCheckStyle], context: &RenderContext) {
+        let circle_width = self.get_circle_width();
+
+        self.shapes = styles.iter().enumerate().map(|(index, style)| {
+            let (top_left, bottom_right) = self.get_row(index);
+            let center = (top_left.0 + circle_width / 2.0, (top_left.1 + bottom_right.1) / 2.0);
+
+            // The circle leaves a fifth of the square free and stays round in any aspect ratio
+            let radius_pixels = circle_width * self.basic.placement.window_size.0 as f32 / 2.0 * 0.4;
+            let radius = self.basic.placement.radius_to_view(radius_pixels);
+            let inner_radius = self.basic.placement.radius_to_view((radius_pixels - style.border_width).max(0.0));
+            let dot_radius = self.basic.placement.radius_to_view(radius_pixels * 0.45);
+
+            let oval = |radius: (f32, f32), color: [f32; 3]| Oval::new(center, radius, TRIANGLE_COUNT, color, context.device, context.pipelines);
+            OptionShapes {
+                border: oval(radius, style.border),
+                background: oval(inner_radius, style.fill),
+                dot: (self.selected == Some(index)).then(|| oval(dot_radius, style.mark)),
+            }
+        }).collect();
+    }
+}
+
+impl Component for RadioGroup {
+    fn render<'a>(&'a mut self, parent_top_left: &(f32, f32), parent_bottom_right: &(f32, f32), render_pass: &mut RenderPass<'a>, context: &RenderContext) {
+        self.basic.placement.update(*parent_top_left, *parent_bottom_right, self.basic.top_left, self.basic.bottom_right, &self.basic.transform, context);
+
+        self.focused = context.focused == Some(self.focus_id);
+        let type_name = self.get_type_name();
+        let element_state = self.get_element_state();
+
+        // Every option is styled like a checkbox that is checked when it is selected
+        let styles: Vec<ResolvedCheckStyle> = (0..self.labels.len()).map(|index| {
+            let state = ElementState {
+                hovered: self.enabled && self.hovered == Some(index),
+                pressed: self.pressed == Some(index) && self.hovered == Some(index),
+                focused: self.focused && self.active == index,
+                checked: self.selected == Some(index),
+                ..element_state
+            };
+            let sheet = context.stylesheet.compute(&context.get_style_path(StyleElement {
+                type_name,
+                id: self.basic.id.as_deref(),
+                classes: &self.basic.classes,
+                state,
+            }));
+            self.style.resolve(state.checked, state.focused, &sheet, context.theme)
+        }).collect();
+
+        let drawn_style = (self.selected, self.labels.len(), styles);
+        if self.basic.needs_resize || self.drawn_style.as_ref() != Some(&drawn_style) {
+            self.create_shapes(&drawn_style.2, context);
+
+            self.drawn_style = Some(drawn_style);
+            self.basic.needs_resize = false;
+        }
+
+        let world_transform = self.basic.placement.world_transform;
+        let opacity = if self.enabled { context.opacity } else { context.opacity * DISABLED_OPACITY };
+        let circle_width = self.get_circle_width();
+        let rows: Vec<_> = (0..self.labels.len()).map(|index| self.get_row(index)).collect();
+
+        for shapes in self.shapes.iter_mut() {
+            for oval in [&mut shapes.border, &mut shapes.background].into_iter().chain(shapes.dot.as_mut()) {
+                oval.set_transform(world_transform, context.queue);
+                oval.set_opacity(opacity, context.queue);
+                oval.draw(render_pass);
+            }
+        }
+
+        let path = context.get_style_path(StyleElement {
+            type_name,
+            id: self.basic.id.as_deref(),
+            classes: &self.basic.classes,
+            state: element_state,
+        });
+        let label_context = context.with_transform(world_transform).with_opacity(opacity);
+        let label_context = label_context.with_style_path(&path);
+
+        for (label, (top_left, bottom_right)) in self.labels.iter_mut().zip(rows) {
+            if let Some(label) = label.as_mut() {
+                label.render(&(top_left.0 + circle_width, top_left.1), &bottom_right, render_pass, &label_context);
+            }
+        }
+    }
+
+    fn get_top_left(&self) -> (f32, f32) {
+        self.basic.top_left
+    }
+
+    fn get_bottom_right(&self) -> (f32, f32) {
+        self.basic.bottom_right
+    }
+
+    fn get_transform(&self) -> Transform {
+        self.basic.transform
+    }
+
+    fn set_transform(&mut self, transform: Transform) {
+        self.basic.transform = transform;
+    }
+
+    fn get_z_index(&self) -> i32 {
+        self.basic.z_index
+    }
+
+    fn set_z_index(&mut self, z_index: i32) {
+        self.basic.z_index = z_index;
+    }
+
+    fn get_key(&self) -> Option<&str> {
+        self.basic.key.as_deref()
+    }
+
+    fn set_key(&mut self, key: &str) {
+        self.basic.key = Some(key.to_string());
+    }
+
+    fn get_id(&self) -> Option<&str> {
+        self.basic.id.as_deref()
+    }
+
+    fn set_id(&mut self, id: &str) {
+        self.basic.id = Some(id.to_string());
+    }
+
+    fn get_classes(&self) -> &[String] {
+        &self.basic.classes
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.basic.add_class(class);
+    }
+
+    fn get_children(&self) -> Vec<&dyn Component> {
+        self.labels.iter().flatten().map(|label| label.as_ref()).collect()
+    }
+
+    fn get_dirty_flags(&self) -> DirtyFlags {
+        DirtyFlags {
+            needs_resize: self.basic.needs_resize,
+            ..DirtyFlags::default()
+        }
+    }
+
+    fn get_event_handlers(&self) -> Vec<&'static str> {
+        self.on_change.iter().map(|_| "on_change").collect()
+    }
+
+    fn get_placement(&self) -> &ComponentPlacement {
+        &self.basic.placement
+    }
+
+    fn layout(&mut self, parent: &ComponentPlacement) {
+        self.basic.placement.update_in(parent, self.basic.top_left, self.basic.bottom_right, &self.basic.transform);
+
+        let circle_width = self.get_circle_width();
+        let rows: Vec<_> = (0..self.labels.len()).map(|index| self.get_row(index)).collect();
+        for (label, (top_left, bottom_right)) in self.labels.iter_mut().zip(rows) {
+            if let Some(label) = label.as_mut() {
+                label.layout(&self.basic.placement.with_rect((top_left.0 + circle_width, top_left.1), bottom_right));
+            }
+        }
+    }
+
+    fn on_event(&mut self, event: &ComponentEvent, context: &mut EventContext) -> bool {
+        let state = (self.selected, self.hovered, self.pressed, self.key_pressed);
+        let consumed = self.handle_event(event, context);
+
+        if (self.selected, self.hovered, self.pressed, self.key_pressed) != state {
+            context.invalidate(&self.basic.placement);
+        }
+
+        consumed
+    }
+
+    fn reconcile(&mut self, new: Box<dyn Component>) -> Result<(), Box<dyn Component>> {
+        let new = *downcast_component::<Self>(new)?;
+
+        // The focus id, the active option, hover and press state are kept, the selection comes from the new view
+        self.basic.reconcile(new.basic);
+        if self.style != new.style {
+            self.set_check_style(new.style);
+        }
+        if self.labels.len() != new.labels.len() {
+            self.basic.needs_resize = true;
+        }
+
+        let mut new_labels = new.labels.into_iter();
+        let mut labels: Vec<_> = self.labels.drain(..).zip(new_labels.by_ref()).map(|pair| match pair {
+            (Some(mut label), Some(new_label)) => {
+                reconcile_component(&mut label, new_label);
+                Some(label)
+            }
+            (_, new_label) => new_label,
+        }).collect();
+        labels.extend(new_labels);
+        self.labels = labels;
+
+        self.set_selected(new.selected);
+        self.active = self.active.min(self.labels.len().saturating_sub(1));
+        self.set_enabled(new.enabled);
+        self.on_change = new.on_change;
+
+        Ok(())
+    }
+
+    fn in_bound(&self, point: (f32, f32)) -> bool {
+        self.get_option_at(point).is_some()
+    }
+
+    fn resize(&mut self, new_box_top_left: (f32, f32), new_box_bottom_right: (f32, f32)) {
+        self.basic.top_left = new_box_top_left;
+        self.basic.bottom_right = new_box_bottom_right;
+
+        self.on_resize();
+    }
+
+    fn on_resize(&mut self) {
+        self.basic.needs_resize = true;
+
+        for label in self.labels.iter_mut().flatten() {
+            label.on_resize();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::component::layout;
+    use crate::components::event::testing::{press_key, record_changes};
+
+    fn radio_group(count: usize) -> RadioGroup {
+        let mut group = RadioGroup::new((-1.0, 1.0), (1.0, -1.0));
+        for _ in 0..count {
+            group.add_option(None);
+        }
+        layout(&mut group, (100, 100));
+        group
+    }
+
+    #[test]
+    fn arrow_keys_wrap_around_the_options() {
+        let mut group = radio_group(3);
+        let (changes, on_change) = record_changes();
+        group.set_on_change(on_change);
+        let mut context = EventContext::new(Some(group.get_focus_id()));
+
+        assert!(press_key(&mut group, VirtualKeyCode::Up, &mut context));
+        assert_eq!(group.get_selected(), Some(2));
+        assert!(press_key(&mut group, VirtualKeyCode::Right, &mut context));
+        assert_eq!(group.get_selected(), Some(0));
+        assert!(press_key(&mut group, VirtualKeyCode::Down, &mut context));
+        assert_eq!(group.get_selected(), Some(1));
+        assert_eq!(*changes.borrow(), [2, 0, 1]);
+    }
+
+    #[test]
+    fn keys_are_ignored_without_focus_or_options() {
+        let mut group = radio_group(3);
+        assert!(!press_key(&mut group, VirtualKeyCode::Down, &mut EventContext::new(None)));
+        assert_eq!(group.get_selected(), None);
+
+        let mut empty = radio_group(0);
+        let mut context = EventContext::new(Some(empty.get_focus_id()));
+        assert!(!press_key(&mut empty, VirtualKeyCode::Down, &mut context));
+    }
+
+    #[test]
+    fn space_selects_the_active_option_on_release() {
+        let mut group = radio_group(2);
+        let mut context = EventContext::new(Some(group.get_focus_id()));
+
+        assert!(press_key(&mut group, VirtualKeyCode::Space, &mut context));
+        assert_eq!(group.get_selected(), None);
+        assert!(group.on_event(&ComponentEvent::KeyReleased { key: VirtualKeyCode::Space }, &mut context));
+        assert_eq!(group.get_selected(), Some(0));
+    }
+
+    #[test]
+    fn clicks_select_the_option_under_the_cursor() {
+        let mut group = radio_group(2);
+        let mut context = EventContext::new(None);
+        let second = group.get_row(1);
+        let center = ((second.0.0 + second.1.0) / 2.0, (second.0.1 + second.1.1) / 2.0);
+
+        assert!(group.on_event(&ComponentEvent::MousePressed { position: center, button: MouseButton::Left }, &mut context));
+        assert!(group.on_event(&ComponentEvent::MouseReleased { position: center, button: MouseButton::Left }, &mut context));
+        assert_eq!(group.get_selected(), Some(1));
+    }
+}
@@ -0,0 +1,243 @@
+use wgpu::RenderPass;
+use crate::components::component::{Component, ComponentPlacement, DirtyFlags, RenderContext, downcast_component};
+use crate::components::event::{ComponentEvent, EventContext};
+use crate::components::invalidation::Invalidation;
+use crate::components::tooltip::TooltipOptions;
+use crate::reactive::Observer;
+use crate::style::Style;
+use crate::transform::Transform;
+
+/// Wraps a component and reruns `update` on it before the next frame whenever a signal read by `update` changed.
+/// Components that did not read a changed signal are left alone.
+pub struct ReactiveComponent<C: Component> {
+    component: C,
+
+    observer: Observer,
+    update: Box<dyn FnMut(&mut C)>,
+}
+
+impl<C: Component> ReactiveComponent<C> {
+    /// `update` is called once before the first render, so the component does not need to be built with the current values.
+    pub fn new(component: C, update: impl FnMut(&mut C) + 'static) -> Self {
+        Self {
+            component,
+            observer: Observer::new(),
+            update: Box::new(update),
+        }
+    }
+
+    pub fn get_component(&self) -> &C {
+        &self.component
+    }
+
+    pub fn get_component_mut(&mut self) -> &mut C {
+        &mut self.component
+    }
+
+    /// Applies the latest signal values if one of them changed.
+    pub fn refresh(&mut self) {
+        if !self.observer.is_dirty() {
+            return;
+        }
+
+        let component = &mut self.component;
+        let update = &mut self.update;
+        self.observer.track(|| update(component));
+    }
+}
+
+/// Damages the area of every component in the tree whose signals changed, so only they are drawn again.
+pub fn invalidate_outdated(component: &dyn Component, invalidation: &Invalidation) {
+    if component.get_dirty_flags().needs_refresh {
+        invalidation.invalidate_placement(component.get_placement());
+    }
+
+    for child in component.get_children() {
+        invalidate_outdated(child, invalidation);
+    }
+}
+
+impl<C: Component> Component for ReactiveComponent<C> {
+    fn render<'a>(&'a mut self, parent_top_left: &(f32, f32), parent_bottom_right: &(f32, f32), render_pass: &mut RenderPass<'a>, context: &RenderContext) {
+        let refreshed = self.observer.is_dirty();
+        let before = (self.component.get_top_left(), self.component.get_bottom_right(), self.component.get_transform());
+        self.refresh();
+
+        // Only the old area was damaged when a signal changed, an update that moved the component damages the new one as well
+        let after = (self.component.get_top_left(), self.component.get_bottom_right(), self.component.get_transform());
+        if refreshed && before != after {
+            let mut placement = ComponentPlacement::default();
+            placement.update(*parent_top_left, *parent_bottom_right, after.0, after.1, &after.2, context);
+            context.invalidation.invalidate_placement(&placement);
+        }
+
+        self.component.render(parent_top_left, parent_bottom_right, render_pass, context);
+    }
+
+    fn get_top_left(&self) -> (f32, f32) {
+        self.component.get_top_left()
+    }
+
+    fn get_bottom_right(&self) -> (f32, f32) {
+        self.component.get_bottom_right()
+    }
+
+    fn get_transform(&self) -> Transform {
+        self.component.get_transform()
+    }
+
+    fn set_transform(&mut self, transform: Transform) {
+        self.component.set_transform(transform);
+    }
+
+    fn get_z_index(&self) -> i32 {
+        self.component.get_z_index()
+    }
+
+    fn set_z_index(&mut self, z_index: i32) {
+        self.component.set_z_index(z_index);
+    }
+
+    fn get_key(&self) -> Option<&str> {
+        self.component.get_key()
+    }
+
+    fn set_key(&mut self, key: &str) {
+        self.component.set_key(key);
+    }
+
+    fn get_type_name(&self) -> &'static str {
+        self.component.get_type_name()
+    }
+
+    fn get_id(&self) -> Option<&str> {
+        self.component.get_id()
+    }
+
+    fn set_id(&mut self, id: &str) {
+        self.component.set_id(id);
+    }
+
+    fn get_classes(&self) -> &[String] {
+        self.component.get_classes()
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.component.add_class(class);
+    }
+
+    fn get_style(&self) -> Option<&Style> {
+        self.component.get_style()
+    }
+
+    fn get_children(&self) -> Vec<&dyn Component> {
+        self.component.get_children()
+    }
+
+    fn get_dirty_flags(&self) -> DirtyFlags {
+        DirtyFlags {
+            needs_refresh: self.observer.is_dirty(),
+            ..self.component.get_dirty_flags()
+        }
+    }
+
+    fn get_event_handlers(&self) -> Vec<&'static str> {
+        self.component.get_event_handlers()
+    }
+
+    fn get_tooltip(&self) -> Option<&TooltipOptions> {
+        self.component.get_tooltip()
+    }
+
+    fn get_placement(&self) -> &ComponentPlacement {
+        self.component.get_placement()
+    }
+
+    fn layout(&mut self, parent: &ComponentPlacement) {
+        self.refresh();
+        self.component.layout(parent);
+    }
+
+    fn on_event(&mut self, event: &ComponentEvent, context: &mut EventContext) -> bool {
+        self.component.on_event(event, context)
+    }
+
+    fn in_bound(&self, point: (f32, f32)) -> bool {
+        self.component.in_bound(point)
+    }
+
+    fn reconcile(&mut self, new: Box<dyn Component>) -> Result<(), Box<dyn Component>> {
+        let new = *downcast_component::<Self>(new)?;
+
+        if let Err(component) = self.component.reconcile(Box::new(new.component)) {
+            self.component = *downcast_component::<C>(component).ok().expect("the component has the same type");
+        }
+
+        // The new closure might read other signals, so it runs again before the next frame
+        self.update = new.update;
+        self.observer.mark_dirty();
+
+        Ok(())
+    }
+
+    fn resize(&mut self, new_box_top_left: (f32, f32), new_box_bottom_right: (f32, f32)) {
+        self.component.resize(new_box_top_left, new_box_bottom_right);
+    }
+
+    fn on_resize(&mut self) {
+        self.component.on_resize();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::component::layout;
+    use crate::components::invalidation::Damage;
+    use crate::components::layout::LayoutComponent;
+    use crate::components::plain::PlainComponent;
+    use crate::reactive::Signal;
+    use crate::style::Color;
+
+    /// A plain component filling `top_left` to `bottom_right` whose color follows `signal`.
+    fn colored(top_left: (f32, f32), bottom_right: (f32, f32), signal: &Signal<f32>) -> ReactiveComponent<PlainComponent> {
+        let signal = signal.clone();
+        ReactiveComponent::new(PlainComponent::new(top_left, bottom_right, [0.0; 3]), move |plain| plain.set_color([signal.get(); 3]))
+    }
+
+    #[test]
+    fn only_components_reading_a_changed_signal_are_damaged() {
+        let left = Signal::new(0.0);
+        let right = Signal::new(0.0);
+        let mut root = LayoutComponent::new((-1.0, 1.0), (1.0, -1.0));
+        root.add_component(Box::new(colored((-1.0, 1.0), (0.0, -1.0), &left)));
+        root.add_component(Box::new(colored((0.0, 1.0), (1.0, -1.0), &right)));
+        layout(&mut root, (100, 100));
+
+        let invalidation = Invalidation::new();
+        invalidate_outdated(&root, &invalidation);
+        assert!(invalidation.take().is_none());
+
+        right.set(1.0);
+        invalidate_outdated(&root, &invalidation);
+        assert!(invalidation.take() == Some(Damage::Region((0.0, 1.0), (1.0, -1.0))));
+    }
+
+    #[test]
+    fn reconciled_components_follow_the_signals_of_the_new_update() {
+        let old = Signal::new(0.0);
+        let new = Signal::new(0.5);
+        let mut component = colored((-1.0, 1.0), (1.0, -1.0), &old);
+        layout(&mut component, (100, 100));
+
+        assert!(component.reconcile(Box::new(colored((-1.0, 1.0), (1.0, -1.0), &new))).is_ok());
+        assert!(component.get_dirty_flags().needs_refresh);
+        layout(&mut component, (100, 100));
+        assert_eq!(component.get_component().get_color(), Some(Color::Rgb([0.5; 3])));
+
+        old.set(1.0);
+        assert!(!component.get_dirty_flags().needs_refresh);
+        new.set(1.0);
+        assert!(component.get_dirty_flags().needs_refresh);
+    }
+}
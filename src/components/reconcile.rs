@@ -0,0 +1,104 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use crate::components::component::Component;
+
+/// Updates `component` from a newly built one, or replaces it if that is not possible.
+pub fn reconcile_component(component: &mut Box<dyn Component>, new: Box<dyn Component>) {
+    if let Err(new) = component.reconcile(new) {
+        *component = new;
+    }
+}
+
+/// Matches the new children with the retained ones and reconciles every pair.
+/// Keyed children are matched by key, all others by their order. A key that is used more than once only
+/// matches its first child, the others are matched by their order. Retained children without a match are
+/// dropped together with their GPU resources.
+pub fn reconcile_children(children: &mut Vec<Box<dyn Component>>, new_children: Vec<Box<dyn Component>>) {
+    let mut keyed: HashMap<String, Box<dyn Component>> = HashMap::new();
+    let mut unkeyed: VecDeque<Box<dyn Component>> = VecDeque::new();
+
+    for child in children.drain(..) {
+        match child.get_key() {
+            Some(key) if !keyed.contains_key(key) => {
+                keyed.insert(key.to_string(), child);
+            }
+            _ => unkeyed.push_back(child),
+        }
+    }
+
+    let mut used_keys: HashSet<String> = HashSet::new();
+    for new_child in new_children {
+        let retained = match new_child.get_key() {
+            Some(key) if used_keys.insert(key.to_string()) => keyed.remove(key),
+            _ => unkeyed.pop_front(),
+        };
+
+        children.push(match retained {
+            None => new_child,
+            Some(mut retained) => {
+                reconcile_component(&mut retained, new_child);
+                retained
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::plain::PlainComponent;
+
+    fn child(key: Option<&str>) -> Box<dyn Component> {
+        let mut child = PlainComponent::new((-1.0, 1.0), (1.0, -1.0), [1.0; 3]);
+        if let Some(key) = key {
+            child.set_key(key);
+        }
+        Box::new(child)
+    }
+
+    fn children(keys: &[Option<&str>]) -> Vec<Box<dyn Component>> {
+        keys.iter().map(|key| child(*key)).collect()
+    }
+
+    /// Identifies the retained boxes, which keep their address when they are reconciled.
+    fn addresses(children: &[Box<dyn Component>]) -> Vec<*const ()> {
+        children.iter().map(|child| child.as_ref() as *const dyn Component as *const ()).collect()
+    }
+
+    #[test]
+    fn reordered_keys_keep_their_components() {
+        let mut retained = children(&[Some("a"), Some("b"), Some("c")]);
+        let before = addresses(&retained);
+
+        reconcile_children(&mut retained, children(&[Some("c"), Some("a"), Some("b")]));
+        assert_eq!(addresses(&retained), vec![before[2], before[0], before[1]]);
+    }
+
+    #[test]
+    fn removed_keys_drop_their_components() {
+        let mut retained = children(&[Some("a"), Some("b"), Some("c")]);
+        let before = addresses(&retained);
+
+        reconcile_children(&mut retained, children(&[Some("a"), Some("c")]));
+        assert_eq!(addresses(&retained), vec![before[0], before[2]]);
+    }
+
+    #[test]
+    fn duplicate_keys_match_the_first_by_key_and_the_rest_by_order() {
+        let mut retained = children(&[Some("a"), Some("a"), Some("b"), None]);
+        let before = addresses(&retained);
+
+        reconcile_children(&mut retained, children(&[Some("b"), Some("a"), Some("a"), None]));
+        assert_eq!(addresses(&retained), vec![before[2], before[0], before[1], before[3]]);
+    }
+
+    #[test]
+    fn unkeyed_children_are_matched_by_order() {
+        let mut retained = children(&[None, Some("a"), None]);
+        let before = addresses(&retained);
+
+        reconcile_children(&mut retained, children(&[None, None, Some("new")]));
+        let after = addresses(&retained);
+        assert_eq!(&after[..2], &[before[0], before[2]]);
+        assert!(!before.contains(&after[2]));
+    }
+}
@@ -0,0 +1,605 @@
+use std::time::Instant;
+use wgpu::RenderPass;
+use winit::event::MouseButton;
+use crate::components::clip::{Clipper, Overflow};
+use crate::components::component::{Component, ComponentBasicData, ComponentPlacement, RenderContext, downcast_component};
+use crate::components::event::{ComponentEvent, EventContext};
+use crate::components::layout::LayoutComponent;
+use crate::Shape;
+use crate::shapes::quad::Quad;
+use crate::stylesheet::{ElementState, StyleElement};
+use crate::transform::Transform;
+
+pub(crate) const SCROLLBAR_THICKNESS: f32 = 8.0;
+pub(crate) const MIN_THUMB_LENGTH: f32 = 24.0;
+/// How fast kinetic scrolling slows down, per second.
+pub(crate) const FRICTION: f32 = 4.0;
+/// Below this speed in pixels per second kinetic scrolling stops.
+pub(crate) const MIN_VELOCITY: f32 = 10.0;
+
+enum ScrollDrag {
+    Content { last_position: (f32, f32), last_time: Instant },
+    Thumb { vertical: bool, last_position: (f32, f32) },
+}
+
+/// Track and thumb of one scrollbar. The thumb is created at the start of the track
+/// and moved along it with its transform.
+pub(crate) struct Scrollbar {
+    pub(crate) track: Quad,
+    pub(crate) thumb: Quad,
+    /// Length of the thumb in view units.
+    pub(crate) thumb_length: f32,
+    /// Distance the thumb can travel in view units.
+    pub(crate) travel: f32,
+}
+
+impl Scrollbar {
+    pub(crate) fn new(vertical: bool, track_top_left: (f32, f32), track_bottom_right: (f32, f32), thumb_length: f32, context: &RenderContext) -> Self {
+        let (thumb_bottom_right, travel) = if vertical {
+            ((track_bottom_right.0, track_top_left.1 - thumb_length), (track_top_left.1 - track_bottom_right.1) - thumb_length)
+        } else {
+            ((track_top_left.0 + thumb_length, track_bottom_right.1), (track_bottom_right.0 - track_top_left.0) - thumb_length)
+        };
+
+        Self {
+            track: Quad::new(track_top_left, track_bottom_right, [0.2, 0.2, 0.2], context.device, context.pipelines),
+            thumb: Quad::new(track_top_left, thumb_bottom_right, [0.6, 0.6, 0.6], context.device, context.pipelines),
+            thumb_length,
+            travel,
+        }
+    }
+
+    pub(crate) fn matches(&self, track_top_left: (f32, f32), track_bottom_right: (f32, f32), thumb_length: f32) -> bool {
+        self.track.top_left == track_top_left && self.track.bottom_right == track_bottom_right && self.thumb_length == thumb_length
+    }
+
+    /// The translation of the thumb in view units for a scroll progress between 0 and 1.
+    pub(crate) fn thumb_translation(&self, vertical: bool, progress: f32) -> (f32, f32) {
+        if vertical {
+            (0.0, -self.travel * progress)
+        } else {
+            (self.travel * progress, 0.0)
+        }
+    }
+
+    pub(crate) fn thumb_contains(&self, vertical: bool, progress: f32, local_point: (f32, f32)) -> bool {
+        let translation = self.thumb_translation(vertical, progress);
+        let point = (local_point.0 - translation.0, local_point.1 - translation.1);
+
+        point.0 >= self.thumb.top_left.0 && point.0 <= self.thumb.bottom_right.0
+            && point.1 <= self.thumb.top_left.1 && point.1 >= self.thumb.bottom_right.1
+    }
+
+    pub(crate) fn track_contains(&self, local_point: (f32, f32)) -> bool {
+        local_point.0 >= self.track.top_left.0 && local_point.0 <= self.track.bottom_right.0
+            && local_point.1 <= self.track.top_left.1 && local_point.1 >= self.track.bottom_right.1
+    }
+}
+
+/// Shows a part of content that is larger than the view itself.
+pub struct ScrollView {
+    basic: ComponentBasicData,
+
+    content: LayoutComponent,
+    /// Size of the content relative to the view.
+    content_size: (f32, f32),
+
+    /// Scroll position in pixels.
+    offset: (f32, f32),
+    /// Kinetic scroll speed in pixels per second.
+    velocity: (f32, f32),
+    kinetic: bool,
+    drag_to_scroll: bool,
+    drag: Option<ScrollDrag>,
+    last_frame: Option<Instant>,
+
+    show_scrollbars: (bool, bool),
+    horizontal_scrollbar: Option<Scrollbar>,
+    vertical_scrollbar: Option<Scrollbar>,
+
+    clipper: Clipper,
+}
+
+impl ScrollView {
+    /// `content_size` is the size of the scrollable content relative to the view, e.g. `(1.0, 3.0)`
+    /// for content as wide as the view and three times as high.
+    pub fn new(top_left: (f32, f32), bottom_right: (f32, f32), content_size: (f32, f32)) -> Self {
+        let content_size = (content_size.0.max(1.0), content_size.1.max(1.0));
+
+        Self {
+            basic: ComponentBasicData {
+                top_left,
+                bottom_right,
+                transform: Transform::IDENTITY,
+                z_index: 0,
+                key: None,
+                id: None,
+                classes: vec![],
+                placement: ComponentPlacement::default(),
+            },
+            content: LayoutComponent::new((-1.0, 1.0), Self::content_bottom_right(content_size)),
+            content_size,
+            offset: (0.0, 0.0),
+            velocity: (0.0, 0.0),
+            kinetic: true,
+            drag_to_scroll: true,
+            drag: None,
+            last_frame: None,
+            show_scrollbars: (true, true),
+            horizontal_scrollbar: None,
+            vertical_scrollbar: None,
+            clipper: Clipper::new(),
+        }
+    }
+
+    fn content_bottom_right(content_size: (f32, f32)) -> (f32, f32) {
+        (-1.0 + 2.0 * content_size.0, 1.0 - 2.0 * content_size.1)
+    }
+
+    /// Adds a component to the content. Its rectangle is relative to the whole content.
+    pub fn add_component(&mut self, component: Box<dyn Component>) {
+        self.content.add_component(component);
+    }
+
+    pub fn set_content_size(&mut self, content_size: (f32, f32)) {
+        self.content_size = (content_size.0.max(1.0), content_size.1.max(1.0));
+        self.content.resize((-1.0, 1.0), Self::content_bottom_right(self.content_size));
+        self.clamp_offset();
+    }
+
+    /// Whether the content keeps moving after a drag was released.
+    pub fn set_kinetic(&mut self, kinetic: bool) {
+        self.kinetic = kinetic;
+        if !kinetic {
+            self.velocity = (0.0, 0.0);
+        }
+    }
+
+    /// Whether dragging the content itself scrolls it, like on a touch screen.
+    pub fn set_drag_to_scroll(&mut self, drag_to_scroll: bool) {
+        self.drag_to_scroll = drag_to_scroll;
+    }
+
+    pub fn set_scrollbars(&mut self, horizontal: bool, vertical: bool) {
+        self.show_scrollbars = (horizontal, vertical);
+    }
+
+    pub fn get_offset(&self) -> (f32, f32) {
+        self.offset
+    }
+
+    /// Scrolls so that the given pixel offset of the content is at the top left of the view.
+    pub fn scroll_to(&mut self, offset: (f32, f32)) {
+        self.offset = offset;
+        self.velocity = (0.0, 0.0);
+        self.clamp_offset();
+    }
+
+    pub fn scroll_by(&mut self, delta: (f32, f32)) {
+        self.scroll_to((self.offset.0 + delta.0, self.offset.1 + delta.1));
+    }
+
+    fn view_size(&self) -> (f32, f32) {
+        self.basic.placement.pixel_size()
+    }
+
+    fn max_offset(&self) -> (f32, f32) {
+        let view_size = self.view_size();
+
+        (view_size.0 * (self.content_size.0 - 1.0), view_size.1 * (self.content_size.1 - 1.0))
+    }
+
+    fn clamp_offset(&mut self) {
+        // Before the first render the size of the view is unknown
+        if self.basic.placement.window_size == (0, 0) {
+            return;
+        }
+
+        let max_offset = self.max_offset();
+
+        self.offset = (self.offset.0.clamp(0.0, max_offset.0), self.offset.1.clamp(0.0, max_offset.1));
+    }
+
+    fn progress(&self) -> (f32, f32) {
+        let max_offset = self.max_offset();
+
+        (if max_offset.0 > 0.0 { self.offset.0 / max_offset.0 } else { 0.0 },
+         if max_offset.1 > 0.0 { self.offset.1 / max_offset.1 } else { 0.0 })
+    }
+
+    fn scrollbar_visible(&self, vertical: bool) -> bool {
+        if vertical {
+            self.show_scrollbars.1 && self.content_size.1 > 1.0
+        } else {
+            self.show_scrollbars.0 && self.content_size.0 > 1.0
+        }
+    }
+
+    fn handle_event(&mut self, event: &ComponentEvent, context: &mut EventContext) -> bool {
+        match *event {
+            ComponentEvent::CursorMoved { position } => {
+                let consumed = self.content.on_event(event, context);
+                if self.drag.is_some() {
+                    self.drag_to(position);
+                    return true;
+                }
+                consumed
+            }
+            ComponentEvent::MouseReleased { button: MouseButton::Left, .. } => {
+                let consumed = self.content.on_event(event, context);
+                match self.drag.take() {
+                    Some(ScrollDrag::Content { last_time, .. }) => {
+                        // Only keep the momentum of a drag that was still moving when it was released
+                        if !self.kinetic || last_time.elapsed().as_secs_f32() > 0.1 {
+                            self.velocity = (0.0, 0.0);
+                        }
+                        self.last_frame = None;
+                        true
+                    }
+                    Some(ScrollDrag::Thumb { .. }) => true,
+                    None => consumed,
+                }
+            }
+            ComponentEvent::MouseReleased { .. } => self.content.on_event(event, context),
+            ComponentEvent::MousePressed { position, button } => {
+                if !self.in_bound(position) {
+                    return false;
+                }
+
+                if button == MouseButton::Left && self.press_scrollbar(position) {
+                    return true;
+                }
+
+                if self.content.on_event(event, context) {
+                    return true;
+                }
+
+                if button == MouseButton::Left && self.drag_to_scroll {
+                    self.velocity = (0.0, 0.0);
+                    self.drag = Some(ScrollDrag::Content { last_position: position, last_time: Instant::now() });
+                    return true;
+                }
+
+                false
+            }
+            ComponentEvent::MouseWheel { position, delta } => {
+                if !self.in_bound(position) {
+                    return false;
+                }
+
+                // Nested scroll views get the first chance
+                if self.content.on_event(event, context) {
+                    return true;
+                }
+
+                let before = self.offset;
+                self.scroll_by((-delta.0, -delta.1));
+                before != self.offset
+            }
+            ComponentEvent::KeyPressed { .. } | ComponentEvent::KeyReleased { .. } => self.content.on_event(event, context),
+        }
+    }
+
+    fn apply_momentum(&mut self) {
+        let now = Instant::now();
+        let delta_time = self.last_frame.map(|last_frame| (now - last_frame).as_secs_f32()).unwrap_or(0.0);
+        self.last_frame = Some(now);
+
+        if self.drag.is_some() || (self.velocity.0.abs() < MIN_VELOCITY && self.velocity.1.abs() < MIN_VELOCITY) {
+            return;
+        }
+
+        self.offset.0 += self.velocity.0 * delta_time;
+        self.offset.1 += self.velocity.1 * delta_time;
+
+        let decay = (-FRICTION * delta_time).exp();
+        self.velocity = (self.velocity.0 * decay, self.velocity.1 * decay);
+
+        let unclamped = self.offset;
+        self.clamp_offset();
+        if unclamped.0 != self.offset.0 {
+            self.velocity.0 = 0.0;
+        }
+        if unclamped.1 != self.offset.1 {
+            self.velocity.1 = 0.0;
+        }
+    }
+
+    fn update_scrollbars(&mut self, context: &RenderContext) {
+        let placement = self.basic.placement;
+        let (left, top) = placement.absolute_top_left;
+        let (right, bottom) = placement.absolute_bottom_right;
+        let thickness = placement.radius_to_view(SCROLLBAR_THICKNESS);
+        let min_thumb_length = placement.radius_to_view(MIN_THUMB_LENGTH);
+
+        let show_horizontal = self.scrollbar_visible(false);
+        let show_vertical = self.scrollbar_visible(true);
+
+        self.vertical_scrollbar = if show_vertical {
+            let track_top_left = (right - thickness.0, top);
+            let track_bottom_right = (right, if show_horizontal { bottom + thickness.1 } else { bottom });
+            let thumb_length = ((track_top_left.1 - track_bottom_right.1) / self.content_size.1).max(min_thumb_length.1);
+
+            match self.vertical_scrollbar.take() {
+                Some(scrollbar) if scrollbar.matches(track_top_left, track_bottom_right, thumb_length) => Some(scrollbar),
+                _ => Some(Scrollbar::new(true, track_top_left, track_bottom_right, thumb_length, context)),
+            }
+        } else {
+            None
+        };
+
+        self.horizontal_scrollbar = if show_horizontal {
+            let track_top_left = (left, bottom + thickness.1);
+            let track_bottom_right = (if show_vertical { right - thickness.0 } else { right }, bottom);
+            let thumb_length = ((track_bottom_right.0 - track_top_left.0) / self.content_size.0).max(min_thumb_length.0);
+
+            match self.horizontal_scrollbar.take() {
+                Some(scrollbar) if scrollbar.matches(track_top_left, track_bottom_right, thumb_length) => Some(scrollbar),
+                _ => Some(Scrollbar::new(false, track_top_left, track_bottom_right, thumb_length, context)),
+            }
+        } else {
+            None
+        };
+
+        let progress = self.progress();
+        let world_transform = placement.world_transform;
+
+        if let Some(scrollbar) = self.vertical_scrollbar.as_mut() {
+            let translation = scrollbar.thumb_translation(true, progress.1);
+            scrollbar.track.set_transform(world_transform, context.queue);
+            scrollbar.track.set_opacity(context.opacity, context.queue);
+            scrollbar.thumb.set_transform(Transform::translation(translation.0, translation.1).then(&world_transform), context.queue);
+            scrollbar.thumb.set_opacity(context.opacity, context.queue);
+        }
+
+        if let Some(scrollbar) = self.horizontal_scrollbar.as_mut() {
+            let translation = scrollbar.thumb_translation(false, progress.0);
+            scrollbar.track.set_transform(world_transform, context.queue);
+            scrollbar.track.set_opacity(context.opacity, context.queue);
+            scrollbar.thumb.set_transform(Transform::translation(translation.0, translation.1).then(&world_transform), context.queue);
+            scrollbar.thumb.set_opacity(context.opacity, context.queue);
+        }
+    }
+
+    /// Starts dragging a scrollbar if the point hits one. A press on the track outside of the thumb jumps a page.
+    fn press_scrollbar(&mut self, position: (f32, f32)) -> bool {
+        let local_point = match self.basic.placement.to_local(position) {
+            None => return false,
+            Some(local_point) => local_point,
+        };
+        let progress = self.progress();
+        let view_size = self.view_size();
+
+        for vertical in [true, false] {
+            let scrollbar = if vertical { &self.vertical_scrollbar } else { &self.horizontal_scrollbar };
+            let scrollbar = match scrollbar {
+                Some(scrollbar) if scrollbar.track_contains(local_point) => scrollbar,
+                _ => continue,
+            };
+
+            let axis_progress = if vertical { progress.1 } else { progress.0 };
+            if scrollbar.thumb_contains(vertical, axis_progress, local_point) {
+                self.drag = Some(ScrollDrag::Thumb { vertical, last_position: position });
+                self.velocity = (0.0, 0.0);
+                return true;
+            }
+
+            let translation = scrollbar.thumb_translation(vertical, axis_progress);
+            if vertical {
+                let thumb_top = scrollbar.thumb.top_left.1 + translation.1;
+                let direction = if local_point.1 > thumb_top { -1.0 } else { 1.0 };
+                self.scroll_by((0.0, direction * view_size.1));
+            } else {
+                let thumb_left = scrollbar.thumb.top_left.0 + translation.0;
+                let direction = if local_point.0 < thumb_left { -1.0 } else { 1.0 };
+                self.scroll_by((direction * view_size.0, 0.0));
+            }
+            return true;
+        }
+
+        false
+    }
+
+    fn drag_to(&mut self, position: (f32, f32)) {
+        let window_size = self.basic.placement.window_size;
+        let to_pixels = |from: (f32, f32), to: (f32, f32)| {
+            ((to.0 - from.0) * window_size.0 as f32 / 2.0, (from.1 - to.1) * window_size.1 as f32 / 2.0)
+        };
+
+        match self.drag.as_mut() {
+            None => {}
+            Some(ScrollDrag::Content { last_position, last_time }) => {
+                let delta = to_pixels(*last_position, position);
+                let now = Instant::now();
+                let elapsed = (now - *last_time).as_secs_f32().max(0.001);
+
+                *last_position = position;
+                *last_time = now;
+
+                self.velocity = (-delta.0 / elapsed, -delta.1 / elapsed);
+                self.offset = (self.offset.0 - delta.0, self.offset.1 - delta.1);
+                self.clamp_offset();
+            }
+            Some(ScrollDrag::Thumb { vertical, last_position }) => {
+                let vertical = *vertical;
+                let delta = to_pixels(*last_position, position);
+                *last_position = position;
+
+                let scrollbar = if vertical { &self.vertical_scrollbar } else { &self.horizontal_scrollbar };
+                let travel = match scrollbar {
+                    None => return,
+                    Some(scrollbar) => scrollbar.travel,
+                };
+
+                // Convert the thumb movement into a content movement
+                let max_offset = self.max_offset();
+                if vertical {
+                    let travel_pixels = travel * window_size.1 as f32 / 2.0;
+                    if travel_pixels > 0.0 {
+                        self.offset.1 += delta.1 / travel_pixels * max_offset.1;
+                    }
+                } else {
+                    let travel_pixels = travel * window_size.0 as f32 / 2.0;
+                    if travel_pixels > 0.0 {
+                        self.offset.0 += delta.0 / travel_pixels * max_offset.0;
+                    }
+                }
+                self.clamp_offset();
+            }
+        }
+    }
+}
+
+impl Component for ScrollView {
+    fn render<'a>(&'a mut self, parent_top_left: &(f32, f32), parent_bottom_right: &(f32, f32), render_pass: &mut RenderPass<'a>, context: &RenderContext) {
+        self.basic.placement.update(*parent_top_left, *parent_bottom_right, self.basic.top_left, self.basic.bottom_right, &self.basic.transform, context);
+
+        self.apply_momentum();
+        if self.velocity.0.abs() >= MIN_VELOCITY || self.velocity.1.abs() >= MIN_VELOCITY {
+            // Keep rendering until the content came to rest
+            context.invalidation.invalidate_placement(&self.basic.placement);
+        }
+        self.clamp_offset();
+        self.content.set_transform(Transform::translation(-self.offset.0, -self.offset.1));
+        self.update_scrollbars(context);
+
+        let absolute_top_left = self.basic.placement.absolute_top_left;
+        let absolute_bottom_right = self.basic.placement.absolute_bottom_right;
+        let context = context.with_transform(self.basic.placement.world_transform);
+
+        // Selectors can match the content as a descendant of this component
+        let path = context.get_style_path(StyleElement {
+            type_name: self.get_type_name(),
+            id: self.basic.id.as_deref(),
+            classes: &self.basic.classes,
+            state: ElementState::default(),
+        });
+        let context = context.with_style_path(&path);
+
+        self.clipper.prepare(&self.basic.placement, 0.0, &context);
+
+        let clip_context = match self.clipper.begin(render_pass, &context) {
+            None => return,
+            Some(clip_context) => clip_context,
+        };
+
+        self.content.render(&absolute_top_left, &absolute_bottom_right, render_pass, &clip_context);
+
+        for scrollbar in self.horizontal_scrollbar.iter().chain(self.vertical_scrollbar.iter()) {
+            scrollbar.track.draw(render_pass);
+            scrollbar.thumb.draw(render_pass);
+        }
+
+        self.clipper.end(render_pass, &context);
+    }
+
+    fn get_top_left(&self) -> (f32, f32) {
+        self.basic.top_left
+    }
+
+    fn get_bottom_right(&self) -> (f32, f32) {
+        self.basic.bottom_right
+    }
+
+    fn get_transform(&self) -> Transform {
+        self.basic.transform
+    }
+
+    fn set_transform(&mut self, transform: Transform) {
+        self.basic.transform = transform;
+    }
+
+    fn get_z_index(&self) -> i32 {
+        self.basic.z_index
+    }
+
+    fn set_z_index(&mut self, z_index: i32) {
+        self.basic.z_index = z_index;
+    }
+
+    fn get_key(&self) -> Option<&str> {
+        self.basic.key.as_deref()
+    }
+
+    fn set_key(&mut self, key: &str) {
+        self.basic.key = Some(key.to_string());
+    }
+
+    fn get_id(&self) -> Option<&str> {
+        self.basic.id.as_deref()
+    }
+
+    fn set_id(&mut self, id: &str) {
+        self.basic.id = Some(id.to_string());
+    }
+
+    fn get_classes(&self) -> &[String] {
+        &self.basic.classes
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.basic.add_class(class);
+    }
+
+    fn get_children(&self) -> Vec<&dyn Component> {
+        vec![&self.content]
+    }
+
+    fn get_overflow(&self) -> Overflow {
+        Overflow::Clip
+    }
+
+    fn get_placement(&self) -> &ComponentPlacement {
+        &self.basic.placement
+    }
+
+    fn layout(&mut self, parent: &ComponentPlacement) {
+        self.basic.placement.update_in(parent, self.basic.top_left, self.basic.bottom_right, &self.basic.transform);
+
+        self.clamp_offset();
+        self.content.set_transform(Transform::translation(-self.offset.0, -self.offset.1));
+        self.content.layout(&self.basic.placement);
+    }
+
+    fn on_event(&mut self, event: &ComponentEvent, context: &mut EventContext) -> bool {
+        let offset = self.offset;
+        let consumed = self.handle_event(event, context);
+
+        // Scrolling, drags and everything the content consumed only change what is inside the view
+        if consumed || self.offset != offset {
+            context.invalidate(&self.basic.placement);
+        }
+
+        consumed
+    }
+
+    fn reconcile(&mut self, new: Box<dyn Component>) -> Result<(), Box<dyn Component>> {
+        let new = *downcast_component::<Self>(new)?;
+
+        // The offset and a running drag or momentum are kept
+        self.basic.reconcile(new.basic);
+        if self.content_size != new.content_size {
+            self.set_content_size(new.content_size);
+        }
+        self.set_kinetic(new.kinetic);
+        self.drag_to_scroll = new.drag_to_scroll;
+        self.show_scrollbars = new.show_scrollbars;
+
+        if let Err(content) = self.content.reconcile(Box::new(new.content)) {
+            self.content = *downcast_component::<LayoutComponent>(content).ok().expect("the content is always a layout");
+        }
+
+        Ok(())
+    }
+
+    fn resize(&mut self, new_box_top_left: (f32, f32), new_box_bottom_right: (f32, f32)) {
+        self.basic.top_left = new_box_top_left;
+        self.basic.bottom_right = new_box_bottom_right;
+
+        self.on_resize();
+    }
+
+    fn on_resize(&mut self) {
+        self.content.on_resize();
+    }
+}
@@ -0,0 +1,893 @@
+use wgpu::RenderPass;
+use winit::event::{MouseButton, VirtualKeyCode};
+use crate::Shape;
+use crate::components::checkbox::DISABLED_OPACITY;
+use crate::components::component::{Component, ComponentBasicResizeData, ComponentPlacement, DirtyFlags, RenderContext, downcast_component};
+use crate::components::event::{ChangeCallback, ComponentEvent, EventContext};
+use crate::components::focus::FocusId;
+use crate::shapes::oval::Oval;
+use crate::shapes::quad::Quad;
+use crate::stylesheet::{ElementState, StyleElement};
+use crate::transform::Transform;
+
+const TRIANGLE_COUNT: u16 = 32;
+/// Width of the focus ring around the thumb in pixels.
+const FOCUS_RING_WIDTH: f32 = 3.0;
+/// Without a step the arrow keys move by this part of the range.
+const KEY_FRACTION: f32 = 0.01;
+/// Page Up and Page Down move by this part of the range, or by one step if that is larger.
+const PAGE_FRACTION: f32 = 0.1;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Orientation {
+    /// The minimum is on the left.
+    Horizontal,
+    /// The minimum is at the bottom.
+    Vertical,
+}
+
+/// How sliders look. Sizes are given in pixels.
+///
+/// The stylesheet overrides it: `background` replaces the track, `foreground` the filled part and the thumbs,
+/// the border color the focus ring.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SliderStyle {
+    pub track: [f32; 3],
+    /// The part of the track between the minimum and the thumb, or between the thumbs of a range.
+    pub fill: [f32; 3],
+    pub thumb: [f32; 3],
+    pub focus_ring: [f32; 3],
+    pub track_thickness: f32,
+    pub thumb_radius: f32,
+}
+
+impl Default for SliderStyle {
+    fn default() -> Self {
+        Self {
+            track: [0.3, 0.3, 0.3],
+            fill: [0.2, 0.4, 0.8],
+            thumb: [1.0, 1.0, 1.0],
+            focus_ring: [0.2, 0.4, 0.8],
+            track_thickness: 4.0,
+            thumb_radius: 8.0,
+        }
+    }
+}
+
+struct SliderShapes {
+    track: Quad,
+    /// Spans the whole track and is scaled to the filled part by its transform.
+    fill: Quad,
+    /// Created at the start of the track and moved by their transforms.
+    thumbs: Vec<Oval>,
+    focus_ring: Oval,
+    /// Where the track starts and how long it is along the axis, in view units.
+    start: f32,
+    travel: f32,
+}
+
+/// The state and the drawing shared by `Slider` and `RangeSlider`, which have one and two thumbs.
+struct SliderCore {
+    basic: ComponentBasicResizeData,
+
+    min: f32,
+    max: f32,
+    /// Values snap to multiples of the step from the minimum, 0 allows any value.
+    step: f32,
+    orientation: Orientation,
+    /// Ascending, the thumbs cannot pass each other.
+    values: Vec<f32>,
+    style: SliderStyle,
+
+    focus_id: FocusId,
+    enabled: bool,
+    focused: bool,
+    hovered: bool,
+    /// The thumb that follows the pointer. It keeps following outside of the slider until the button is released.
+    dragging: Option<usize>,
+    /// The thumb the keys move, the one that was dragged last.
+    active: usize,
+
+    drawn_style: Option<SliderStyle>,
+    shapes: Option<SliderShapes>,
+}
+
+impl SliderCore {
+    fn new(top_left: (f32, f32), bottom_right: (f32, f32), min: f32, max: f32, values: Vec<f32>) -> Self {
+        let mut core = Self {
+            basic: ComponentBasicResizeData {
+                top_left,
+                bottom_right,
+                needs_resize: true,
+                transform: Transform::IDENTITY,
+                z_index: 0,
+                key: None,
+                id: None,
+                classes: vec![],
+                placement: ComponentPlacement::default(),
+            },
+            min,
+            max: max.max(min),
+            step: 0.0,
+            orientation: Orientation::Horizontal,
+            values: vec![],
+            style: SliderStyle::default(),
+            focus_id: FocusId::next(),
+            enabled: true,
+            focused: false,
+            hovered: false,
+            dragging: None,
+            active: 0,
+            drawn_style: None,
+            shapes: None,
+        };
+        core.set_values(values);
+        core
+    }
+
+    fn set_range(&mut self, min: f32, max: f32, step: f32) {
+        self.min = min;
+        self.max = max.max(min);
+        self.step = step.max(0.0);
+        self.set_values(self.values.clone());
+    }
+
+    fn set_orientation(&mut self, orientation: Orientation) {
+        self.orientation = orientation;
+        self.basic.needs_resize = true;
+    }
+
+    fn set_style(&mut self, style: SliderStyle) {
+        self.style = style;
+        self.basic.needs_resize = true;
+    }
+
+    fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+        if !enabled {
+            self.dragging = None;
+        }
+    }
+
+    /// Snaps the values to the steps and sorts them.
+    fn set_values(&mut self, mut values: Vec<f32>) {
+        for value in values.iter_mut() {
+            *value = self.snap(*value);
+        }
+        values.sort_by(|a, b| a.total_cmp(b));
+        self.values = values;
+    }
+
+    fn snap(&self, value: f32) -> f32 {
+        let value = if self.step > 0.0 {
+            self.min + ((value - self.min) / self.step).round() * self.step
+        } else {
+            value
+        };
+        value.clamp(self.min, self.max)
+    }
+
+    /// Moves a thumb, but not past its neighbours. Returns whether the value changed.
+    fn move_thumb(&mut self, index: usize, value: f32) -> bool {
+        let lower = if index > 0 { self.values[index - 1] } else { self.min };
+        let upper = self.values.get(index + 1).copied().unwrap_or(self.max);
+        let value = self.snap(value).clamp(lower, upper);
+
+        let changed = self.values[index] != value;
+        self.values[index] = value;
+        changed
+    }
+
+    fn get_fraction(&self, value: f32) -> f32 {
+        if self.max > self.min {
+            (value - self.min) / (self.max - self.min)
+        } else {
+            0.0
+        }
+    }
+
+    /// The value under a view point, projected onto the track.
+    fn get_value_at(&self, position: (f32, f32)) -> Option<f32> {
+        let shapes = self.shapes.as_ref()?;
+        let point = self.basic.placement.to_local(position)?;
+        let along = match self.orientation {
+            Orientation::Horizontal => point.0,
+            Orientation::Vertical => point.1,
+        };
+
+        let fraction = if shapes.travel > 0.0 { ((along - shapes.start) / shapes.travel).clamp(0.0, 1.0) } else { 0.0 };
+        Some(self.min + fraction * (self.max - self.min))
+    }
+
+    /// The thumb a press at `value` grabs: the closest one, or of thumbs on the same spot the one the pointer is beyond.
+    fn get_closest_thumb(&self, value: f32) -> usize {
+        let mut closest = 0;
+        for (index, thumb) in self.values.iter().enumerate() {
+            let distance = (thumb - value).abs();
+            let closest_distance = (self.values[closest] - value).abs();
+            if distance < closest_distance || (distance == closest_distance && value > *thumb) {
+                closest = index;
+            }
+        }
+        closest
+    }
+
+    fn get_key_value(&self, key: &VirtualKeyCode, value: f32) -> Option<f32> {
+        let range = self.max - self.min;
+        let step = if self.step > 0.0 { self.step } else { range * KEY_FRACTION };
+        let page = step.max(range * PAGE_FRACTION);
+
+        match key {
+            VirtualKeyCode::Right | VirtualKeyCode::Up => Some(value + step),
+            VirtualKeyCode::Left | VirtualKeyCode::Down => Some(value - step),
+            VirtualKeyCode::PageUp => Some(value + page),
+            VirtualKeyCode::PageDown => Some(value - page),
+            VirtualKeyCode::Home => Some(self.min),
+            VirtualKeyCode::End => Some(self.max),
+            _ => None,
+        }
+    }
+
+    fn get_element_state(&self) -> ElementState {
+        ElementState {
+            hovered: self.enabled && self.hovered,
+            pressed: self.dragging.is_some(),
+            focused: self.focused,
+            disabled: !self.enabled,
+            checked: false,
+        }
+    }
+
+    /// Returns whether the event was consumed and whether a value changed.
+    fn handle_event(&mut self, event: &ComponentEvent, context: &mut EventContext) -> (bool, bool) {
+        match event {
+            ComponentEvent::CursorMoved { position } => {
+                self.hovered = self.basic.placement.contains(*position);
+
+                match (self.dragging, self.get_value_at(*position)) {
+                    (Some(index), Some(value)) => (true, self.move_thumb(index, value)),
+                    _ => (false, false),
+                }
+            }
+            ComponentEvent::MousePressed { position, button: MouseButton::Left } => {
+                if !self.basic.placement.contains(*position) || self.values.is_empty() {
+                    return (false, false);
+                }
+
+                // Disabled sliders still swallow the press so nothing below reacts to it
+                if !self.enabled {
+                    return (true, false);
+                }
+
+                context.request_focus(self.focus_id);
+                match self.get_value_at(*position) {
+                    Some(value) => {
+                        let index = self.get_closest_thumb(value);
+                        self.active = index;
+                        self.dragging = Some(index);
+                        (true, self.move_thumb(index, value))
+                    }
+                    None => (true, false),
+                }
+            }
+            ComponentEvent::MouseReleased { button: MouseButton::Left, .. } => (self.dragging.take().is_some(), false),
+            ComponentEvent::KeyPressed { key } => {
+                if !self.enabled || !context.is_focused(self.focus_id) || self.values.is_empty() {
+                    return (false, false);
+                }
+
+                let index = self.active.min(self.values.len() - 1);
+                match self.get_key_value(key, self.values[index]) {
+                    Some(value) => (true, self.move_thumb(index, value)),
+                    None => (false, false),
+                }
+            }
+            _ => (false, false),
+        }
+    }
+
+    /// Returns whether a value changed.
+    fn on_event(&mut self, event: &ComponentEvent, context: &mut EventContext) -> (bool, bool) {
+        let state = (self.get_element_state(), self.active);
+        let (consumed, changed) = self.handle_event(event, context);
+
+        if changed || (self.get_element_state(), self.active) != state {
+            context.invalidate(&self.basic.placement);
+        }
+
+        (consumed, changed)
+    }
+
+    fn create_shapes(&mut self, style: SliderStyle, context: &RenderContext) {
+        let placement = &self.basic.placement;
+        let (left, top) = placement.absolute_top_left;
+        let (right, bottom) = placement.absolute_bottom_right;
+        let center = ((left + right) / 2.0, (top + bottom) / 2.0);
+
+        // The thumbs stay inside the slider at the ends of the track
+        let thumb_radius = placement.radius_to_view(style.thumb_radius);
+        let half_thickness = placement.radius_to_view(style.track_thickness / 2.0);
+        let ring_radius = placement.radius_to_view(style.thumb_radius + FOCUS_RING_WIDTH);
+
+        let (track_top_left, track_bottom_right, thumb_center, start, travel) = match self.orientation {
+            Orientation::Horizontal => ((left + thumb_radius.0, center.1 + half_thickness.1),
+                                        (right - thumb_radius.0, center.1 - half_thickness.1),
+                                        (left + thumb_radius.0, center.1),
+                                        left + thumb_radius.0,
+                                        (right - left - 2.0 * thumb_radius.0).max(0.0)),
+            Orientation::Vertical => ((center.0 - half_thickness.0, top - thumb_radius.1),
+                                      (center.0 + half_thickness.0, bottom + thumb_radius.1),
+                                      (center.0, bottom + thumb_radius.1),
+                                      bottom + thumb_radius.1,
+                                      (top - bottom - 2.0 * thumb_radius.1).max(0.0)),
+        };
+
+        let oval = |radius: (f32, f32), color: [f32; 3]| Oval::new(thumb_center, radius, TRIANGLE_COUNT, color, context.device, context.pipelines);
+        self.shapes = Some(SliderShapes {
+            track: Quad::new(track_top_left, track_bottom_right, style.track, context.device, context.pipelines),
+            fill: Quad::new(track_top_left, track_bottom_right, style.fill, context.device, context.pipelines),
+            thumbs: self.values.iter().map(|_| oval(thumb_radius, style.thumb)).collect(),
+            focus_ring: oval(ring_radius, style.focus_ring),
+            start,
+            travel,
+        });
+    }
+
+    fn render<'a>(&'a mut self, type_name: &str, parent_top_left: &(f32, f32), parent_bottom_right: &(f32, f32), render_pass: &mut RenderPass<'a>, context: &RenderContext) {
+        self.basic.placement.update(*parent_top_left, *parent_bottom_right, self.basic.top_left, self.basic.bottom_right, &self.basic.transform, context);
+
+        self.focused = context.focused == Some(self.focus_id);
+        let sheet = context.stylesheet.compute(&context.get_style_path(StyleElement {
+            type_name,
+            id: self.basic.id.as_deref(),
+            classes: &self.basic.classes,
+            state: self.get_element_state(),
+        }));
+
+        let mut style = self.style;
+        if let Some(background) = sheet.background {
+            style.track = context.theme.color(background);
+        }
+        if let Some(foreground) = sheet.foreground {
+            style.fill = context.theme.color(foreground);
+            style.thumb = style.fill;
+        }
+        if let Some(border) = sheet.border {
+            style.focus_ring = context.theme.color(border.color);
+        }
+
+        let thumb_count = self.shapes.as_ref().map(|shapes| shapes.thumbs.len());
+        if self.basic.needs_resize || self.drawn_style != Some(style) || thumb_count != Some(self.values.len()) {
+            self.create_shapes(style, context);
+
+            self.drawn_style = Some(style);
+            self.basic.needs_resize = false;
+        }
+
+        let fractions: Vec<f32> = self.values.iter().map(|value| self.get_fraction(*value)).collect();
+        let (fill_from, fill_to) = match fractions.as_slice() {
+            [value] => (0.0, *value),
+            [first, .., last] => (*first, *last),
+            [] => (0.0, 0.0),
+        };
+
+        let world_transform = self.basic.placement.world_transform;
+        let opacity = if self.enabled { context.opacity } else { context.opacity * DISABLED_OPACITY };
+        let orientation = self.orientation;
+        let shapes = match self.shapes.as_mut() {
+            Some(shapes) => shapes,
+            None => return,
+        };
+
+        let (start, travel) = (shapes.start, shapes.travel);
+
+        // Moves a point at the start of the track by a fraction of it
+        let along = |fraction: f32| match orientation {
+            Orientation::Horizontal => Transform::translation(fraction * travel, 0.0),
+            Orientation::Vertical => Transform::translation(0.0, fraction * travel),
+        };
+        // Squeezes the whole track into the part between two fractions
+        let scale = fill_to - fill_from;
+        let offset = start + fill_from * travel;
+        let fill_transform = match orientation {
+            Orientation::Horizontal => Transform { a: scale, tx: offset - scale * start, ..Transform::IDENTITY },
+            Orientation::Vertical => Transform { d: scale, ty: offset - scale * start, ..Transform::IDENTITY },
+        };
+
+        shapes.track.set_transform(world_transform, context.queue);
+        shapes.track.set_opacity(opacity, context.queue);
+        shapes.track.draw(render_pass);
+
+        shapes.fill.set_transform(fill_transform.then(&world_transform), context.queue);
+        shapes.fill.set_opacity(opacity, context.queue);
+        shapes.fill.draw(render_pass);
+
+        if self.focused {
+            if let Some(fraction) = fractions.get(self.active) {
+                shapes.focus_ring.set_transform(along(*fraction).then(&world_transform), context.queue);
+                shapes.focus_ring.set_opacity(opacity, context.queue);
+                shapes.focus_ring.draw(render_pass);
+            }
+        }
+
+        for (thumb, fraction) in shapes.thumbs.iter_mut().zip(fractions) {
+            thumb.set_transform(along(fraction).then(&world_transform), context.queue);
+            thumb.set_opacity(opacity, context.queue);
+            thumb.draw(render_pass);
+        }
+    }
+
+    /// Keeps the focus id, the drag and the active thumb, everything else comes from the new view.
+    fn reconcile(&mut self, new: SliderCore) {
+        self.basic.reconcile(new.basic);
+        if self.style != new.style {
+            self.set_style(new.style);
+        }
+        if self.orientation != new.orientation {
+            self.set_orientation(new.orientation);
+        }
+        self.min = new.min;
+        self.max = new.max;
+        self.step = new.step;
+        self.values = new.values;
+        self.set_enabled(new.enabled);
+
+        if self.dragging.is_some_and(|index| index >= self.values.len()) {
+            self.dragging = None;
+        }
+        self.active = self.active.min(self.values.len().saturating_sub(1));
+    }
+
+    fn resize(&mut self, new_box_top_left: (f32, f32), new_box_bottom_right: (f32, f32)) {
+        self.basic.top_left = new_box_top_left;
+        self.basic.bottom_right = new_box_bottom_right;
+        self.basic.needs_resize = true;
+    }
+}
+
+/// Picks a value between a minimum and a maximum by dragging its thumb or clicking the track,
+/// or with the arrow keys, Page Up, Page Down, Home and End while it is focused.
+///
+/// The value set by the view wins when the tree is reconciled, so keep it in the application and update it from `on_change`.
+pub struct Slider {
+    core: SliderCore,
+    on_change: Option<ChangeCallback<f32>>,
+}
+
+impl Slider {
+    pub fn new(top_left: (f32, f32), bottom_right: (f32, f32), min: f32, max: f32, value: f32) -> Self {
+        Self {
+            core: SliderCore::new(top_left, bottom_right, min, max, vec![value]),
+            on_change: None,
+        }
+    }
+
+    pub fn get_value(&self) -> f32 {
+        self.core.values[0]
+    }
+
+    /// Sets the value without calling `on_change`. It is snapped to the steps and clamped to the range.
+    pub fn set_value(&mut self, value: f32) {
+        self.core.set_values(vec![value]);
+    }
+
+    pub fn get_range(&self) -> (f32, f32) {
+        (self.core.min, self.core.max)
+    }
+
+    pub fn get_step(&self) -> f32 {
+        self.core.step
+    }
+
+    /// Values snap to multiples of `step` from `min`, a step of 0 allows any value.
+    pub fn set_range(&mut self, min: f32, max: f32, step: f32) {
+        self.core.set_range(min, max, step);
+    }
+
+    pub fn get_orientation(&self) -> Orientation {
+        self.core.orientation
+    }
+
+    pub fn set_orientation(&mut self, orientation: Orientation) {
+        self.core.set_orientation(orientation);
+    }
+
+    pub fn get_slider_style(&self) -> &SliderStyle {
+        &self.core.style
+    }
+
+    pub fn set_slider_style(&mut self, style: SliderStyle) {
+        self.core.set_style(style);
+    }
+
+    /// Sets the callback called with the new value while the user drags the thumb or presses a key.
+    pub fn set_on_change(&mut self, on_change: impl FnMut(f32, &mut EventContext) + 'static) {
+        self.on_change = Some(Box::new(on_change));
+    }
+
+    /// Emits the message `message` returns for the new value every time the user changes it.
+    pub fn set_on_change_message<M: 'static>(&mut self, message: impl Fn(f32) -> M + 'static) {
+        self.set_on_change(move |value, context| context.emit(message(value)));
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.core.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.core.set_enabled(enabled);
+    }
+
+    pub fn get_focus_id(&self) -> FocusId {
+        self.core.focus_id
+    }
+
+    pub fn is_dragging(&self) -> bool {
+        self.core.dragging.is_some()
+    }
+}
+
+impl Component for Slider {
+    fn render<'a>(&'a mut self, parent_top_left: &(f32, f32), parent_bottom_right: &(f32, f32), render_pass: &mut RenderPass<'a>, context: &RenderContext) {
+        let type_name = self.get_type_name();
+        self.core.render(type_name, parent_top_left, parent_bottom_right, render_pass, context);
+    }
+
+    fn get_top_left(&self) -> (f32, f32) {
+        self.core.basic.top_left
+    }
+
+    fn get_bottom_right(&self) -> (f32, f32) {
+        self.core.basic.bottom_right
+    }
+
+    fn get_transform(&self) -> Transform {
+        self.core.basic.transform
+    }
+
+    fn set_transform(&mut self, transform: Transform) {
+        self.core.basic.transform = transform;
+    }
+
+    fn get_z_index(&self) -> i32 {
+        self.core.basic.z_index
+    }
+
+    fn set_z_index(&mut self, z_index: i32) {
+        self.core.basic.z_index = z_index;
+    }
+
+    fn get_key(&self) -> Option<&str> {
+        self.core.basic.key.as_deref()
+    }
+
+    fn set_key(&mut self, key: &str) {
+        self.core.basic.key = Some(key.to_string());
+    }
+
+    fn get_id(&self) -> Option<&str> {
+        self.core.basic.id.as_deref()
+    }
+
+    fn set_id(&mut self, id: &str) {
+        self.core.basic.id = Some(id.to_string());
+    }
+
+    fn get_classes(&self) -> &[String] {
+        &self.core.basic.classes
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.core.basic.add_class(class);
+    }
+
+    fn get_dirty_flags(&self) -> DirtyFlags {
+        DirtyFlags {
+            needs_resize: self.core.basic.needs_resize,
+            ..DirtyFlags::default()
+        }
+    }
+
+    fn get_event_handlers(&self) -> Vec<&'static str> {
+        self.on_change.iter().map(|_| "on_change").collect()
+    }
+
+    fn get_placement(&self) -> &ComponentPlacement {
+        &self.core.basic.placement
+    }
+
+    fn layout(&mut self, parent: &ComponentPlacement) {
+        let basic = &mut self.core.basic;
+        basic.placement.update_in(parent, basic.top_left, basic.bottom_right, &basic.transform);
+    }
+
+    fn on_event(&mut self, event: &ComponentEvent, context: &mut EventContext) -> bool {
+        let (consumed, changed) = self.core.on_event(event, context);
+
+        if changed {
+            if let Some(on_change) = self.on_change.as_mut() {
+                on_change(self.core.values[0], context);
+            }
+        }
+
+        consumed
+    }
+
+    fn reconcile(&mut self, new: Box<dyn Component>) -> Result<(), Box<dyn Component>> {
+        let new = *downcast_component::<Self>(new)?;
+
+        self.core.reconcile(new.core);
+        self.on_change = new.on_change;
+
+        Ok(())
+    }
+
+    fn resize(&mut self, new_box_top_left: (f32, f32), new_box_bottom_right: (f32, f32)) {
+        self.core.resize(new_box_top_left, new_box_bottom_right);
+    }
+
+    fn on_resize(&mut self) {
+        self.core.basic.needs_resize = true;
+    }
+}
+
+/// Picks a range with two thumbs that cannot pass each other. Clicking the track moves the closest thumb,
+/// the keys move the one that was dragged last.
+///
+/// The range set by the view wins when the tree is reconciled, so keep it in the application and update it from `on_change`.
+pub struct RangeSlider {
+    core: SliderCore,
+    on_change: Option<ChangeCallback<(f32, f32)>>,
+}
+
+impl RangeSlider {
+    pub fn new(top_left: (f32, f32), bottom_right: (f32, f32), min: f32, max: f32, values: (f32, f32)) -> Self {
+        Self {
+            core: SliderCore::new(top_left, bottom_right, min, max, vec![values.0, values.1]),
+            on_change: None,
+        }
+    }
+
+    /// The lower and the upper value.
+    pub fn get_values(&self) -> (f32, f32) {
+        (self.core.values[0], self.core.values[1])
+    }
+
+    /// Sets the values without calling `on_change`. They are snapped to the steps, clamped to the range and sorted.
+    pub fn set_values(&mut self, values: (f32, f32)) {
+        self.core.set_values(vec![values.0, values.1]);
+    }
+
+    pub fn get_range(&self) -> (f32, f32) {
+        (self.core.min, self.core.max)
+    }
+
+    pub fn get_step(&self) -> f32 {
+        self.core.step
+    }
+
+    /// Values snap to multiples of `step` from `min`, a step of 0 allows any value.
+    pub fn set_range(&mut self, min: f32, max: f32, step: f32) {
+        self.core.set_range(min, max, step);
+    }
+
+    pub fn get_orientation(&self) -> Orientation {
+        self.core.orientation
+    }
+
+    pub fn set_orientation(&mut self, orientation: Orientation) {
+        self.core.set_orientation(orientation);
+    }
+
+    pub fn get_slider_style(&self) -> &SliderStyle {
+        &self.core.style
+    }
+
+    pub fn set_slider_style(&mut self, style: SliderStyle) {
+        self.core.set_style(style);
+    }
+
+    /// Sets the callback called with the new values while the user drags a thumb or presses a key.
+    pub fn set_on_change(&mut self, on_change: impl FnMut((f32, f32), &mut EventContext) + 'static) {
+        self.on_change = Some(Box::new(on_change));
+    }
+
+    /// Emits the message `message` returns for the new values every time the user changes them.
+    pub fn set_on_change_message<M: 'static>(&mut self, message: impl Fn((f32, f32)) -> M + 'static) {
+        self.set_on_change(move |values, context| context.emit(message(values)));
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.core.enabled
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.core.set_enabled(enabled);
+    }
+
+    pub fn get_focus_id(&self) -> FocusId {
+        self.core.focus_id
+    }
+
+    /// The thumb that follows the pointer, 0 for the lower and 1 for the upper one.
+    pub fn get_dragging(&self) -> Option<usize> {
+        self.core.dragging
+    }
+}
+
+impl Component for RangeSlider {
+    fn render<'a>(&'a mut self, parent_top_left: &(f32, f32), parent_bottom_right: &(f32, f32), render_pass: &mut RenderPass<'a>, context: &RenderContext) {
+        let type_name = self.get_type_name();
+        self.core.render(type_name, parent_top_left, parent_bottom_right, render_pass, context);
+    }
+
+    fn get_top_left(&self) -> (f32, f32) {
+        self.core.basic.top_left
+    }
+
+    fn get_bottom_right(&self) -> (f32, f32) {
+        self.core.basic.bottom_right
+    }
+
+    fn get_transform(&self) -> Transform {
+        self.core.basic.transform
+    }
+
+    fn set_transform(&mut self, transform: Transform) {
+        self.core.basic.transform = transform;
+    }
+
+    fn get_z_index(&self) -> i32 {
+        self.core.basic.z_index
+    }
+
+    fn set_z_index(&mut self, z_index: i32) {
+        self.core.basic.z_index = z_index;
+    }
+
+    fn get_key(&self) -> Option<&str> {
+        self.core.basic.key.as_deref()
+    }
+
+    fn set_key(&mut self, key: &str) {
+        self.core.basic.key = Some(key.to_string());
+    }
+
+    fn get_id(&self) -> Option<&str> {
+        self.core.basic.id.as_deref()
+    }
+
+    fn set_id(&mut self, id: &str) {
+        self.core.basic.id = Some(id.to_string());
+    }
+
+    fn get_classes(&self) -> &[String] {
+        &self.core.basic.classes
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.core.basic.add_class(class);
+    }
+
+    fn get_dirty_flags(&self) -> DirtyFlags {
+        DirtyFlags {
+            needs_resize: self.core.basic.needs_resize,
+            ..DirtyFlags::default()
+        }
+    }
+
+    fn get_event_handlers(&self) -> Vec<&'static str> {
+        self.on_change.iter().map(|_| "on_change").collect()
+    }
+
+    fn get_placement(&self) -> &ComponentPlacement {
+        &self.core.basic.placement
+    }
+
+    fn layout(&mut self, parent: &ComponentPlacement) {
+        let basic = &mut self.core.basic;
+        basic.placement.update_in(parent, basic.top_left, basic.bottom_right, &basic.transform);
+    }
+
+    fn on_event(&mut self, event: &ComponentEvent, context: &mut EventContext) -> bool {
+        let (consumed, changed) = self.core.on_event(event, context);
+
+        if changed {
+            let values = self.get_values();
+            if let Some(on_change) = self.on_change.as_mut() {
+                on_change(values, context);
+            }
+        }
+
+        consumed
+    }
+
+    fn reconcile(&mut self, new: Box<dyn Component>) -> Result<(), Box<dyn Component>> {
+        let new = *downcast_component::<Self>(new)?;
+
+        self.core.reconcile(new.core);
+        self.on_change = new.on_change;
+
+        Ok(())
+    }
+
+    fn resize(&mut self, new_box_top_left: (f32, f32), new_box_bottom_right: (f32, f32)) {
+        self.core.resize(new_box_top_left, new_box_bottom_right);
+    }
+
+    fn on_resize(&mut self) {
+        self.core.basic.needs_resize = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::event::testing::press_key;
+
+    #[test]
+    fn values_snap_to_steps_from_the_minimum() {
+        let mut slider = Slider::new((-1.0, 1.0), (1.0, -1.0), 1.0, 11.0, 0.0);
+        assert_eq!(slider.get_value(), 1.0);
+
+        slider.set_range(1.0, 11.0, 2.5);
+        slider.set_value(4.7);
+        assert_eq!(slider.get_value(), 3.5);
+        slider.set_value(4.8);
+        assert_eq!(slider.get_value(), 6.0);
+        slider.set_value(100.0);
+        assert_eq!(slider.get_value(), 11.0);
+
+        // Changing the range snaps the current value again
+        slider.set_range(0.0, 10.0, 4.0);
+        assert_eq!(slider.get_value(), 10.0);
+        slider.set_value(9.0);
+        assert_eq!(slider.get_value(), 8.0);
+    }
+
+    #[test]
+    fn keys_move_by_steps_and_pages() {
+        let mut slider = Slider::new((-1.0, 1.0), (1.0, -1.0), 0.0, 100.0, 50.0);
+        let mut context = EventContext::new(Some(slider.get_focus_id()));
+
+        assert!(press_key(&mut slider, VirtualKeyCode::Right, &mut context));
+        assert_eq!(slider.get_value(), 51.0);
+        assert!(press_key(&mut slider, VirtualKeyCode::PageDown, &mut context));
+        assert_eq!(slider.get_value(), 41.0);
+
+        slider.set_range(0.0, 100.0, 20.0);
+        assert_eq!(slider.get_value(), 40.0);
+        assert!(press_key(&mut slider, VirtualKeyCode::PageUp, &mut context));
+        assert_eq!(slider.get_value(), 60.0);
+        assert!(press_key(&mut slider, VirtualKeyCode::End, &mut context));
+        assert_eq!(slider.get_value(), 100.0);
+        assert!(press_key(&mut slider, VirtualKeyCode::Up, &mut context));
+        assert_eq!(slider.get_value(), 100.0);
+
+        assert!(!press_key(&mut slider, VirtualKeyCode::Home, &mut EventContext::new(None)));
+        assert_eq!(slider.get_value(), 100.0);
+    }
+
+    #[test]
+    fn range_thumbs_do_not_pass_each_other() {
+        let mut slider = RangeSlider::new((-1.0, 1.0), (1.0, -1.0), 0.0, 10.0, (8.0, 2.0));
+        assert_eq!(slider.get_values(), (2.0, 8.0));
+
+        let mut context = EventContext::new(Some(slider.get_focus_id()));
+        assert!(press_key(&mut slider, VirtualKeyCode::End, &mut context));
+        assert_eq!(slider.get_values(), (8.0, 8.0));
+        assert!(press_key(&mut slider, VirtualKeyCode::Home, &mut context));
+        assert_eq!(slider.get_values(), (0.0, 8.0));
+    }
+
+    #[test]
+    fn presses_grab_the_closest_thumb() {
+        let slider = RangeSlider::new((-1.0, 1.0), (1.0, -1.0), 0.0, 10.0, (3.0, 3.0));
+        assert_eq!(slider.core.get_closest_thumb(1.0), 0);
+        assert_eq!(slider.core.get_closest_thumb(5.0), 1);
+
+        let slider = RangeSlider::new((-1.0, 1.0), (1.0, -1.0), 0.0, 10.0, (2.0, 6.0));
+        assert_eq!(slider.core.get_closest_thumb(3.9), 0);
+        assert_eq!(slider.core.get_closest_thumb(4.1), 1);
+    }
+}
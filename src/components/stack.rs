@@ -0,0 +1,307 @@
+use wgpu::RenderPass;
+use winit::event::VirtualKeyCode;
+use crate::components::component::{Component, ComponentBasicResizeData, ComponentPlacement, RenderContext, downcast_component};
+use crate::components::event::{ChangeCallback, ComponentEvent, EventContext};
+use crate::components::reconcile::reconcile_component;
+use crate::transform::Transform;
+
+/// A change of the current page of a `Stack`, requested through the `EventContext`.
+pub enum Navigation {
+    Push(Box<dyn Component>),
+    Pop,
+    PopToRoot,
+}
+
+/// Shows the last of a history of pages. Pages are pushed on top and popped to go back.
+/// Only the current page is rendered and gets events, the pages below it keep their state until they are shown again.
+///
+/// Pages navigate with `EventContext::push_page` and `EventContext::pop_page`, which reach the innermost stack around them.
+/// The back keys go back when the current page does not consume them.
+pub struct Stack {
+    basic: ComponentBasicResizeData,
+
+    /// The history from the first page to the current one. Their rectangles are relative to the stack.
+    pages: Vec<Box<dyn Component>>,
+    on_navigate: Option<ChangeCallback<usize>>,
+}
+
+impl Stack {
+    pub fn new(top_left: (f32, f32), bottom_right: (f32, f32), root: Box<dyn Component>) -> Self {
+        Self {
+            basic: ComponentBasicResizeData {
+                top_left,
+                bottom_right,
+                needs_resize: true,
+                transform: Transform::IDENTITY,
+                z_index: 0,
+                key: None,
+                id: None,
+                classes: vec![],
+                placement: ComponentPlacement::default(),
+            },
+            pages: vec![root],
+            on_navigate: None,
+        }
+    }
+
+    pub fn push(&mut self, page: Box<dyn Component>) {
+        self.pages.push(page);
+    }
+
+    /// Removes the current page, unless it is the first one.
+    pub fn pop(&mut self) -> Option<Box<dyn Component>> {
+        if self.can_go_back() {
+            self.pages.pop()
+        } else {
+            None
+        }
+    }
+
+    pub fn pop_to_root(&mut self) {
+        self.pages.truncate(1);
+    }
+
+    /// The number of pages in the history, 1 while the first page is shown.
+    pub fn get_depth(&self) -> usize {
+        self.pages.len()
+    }
+
+    pub fn can_go_back(&self) -> bool {
+        self.pages.len() > 1
+    }
+
+    pub fn get_current(&self) -> &dyn Component {
+        self.pages.last().expect("a stack always has its first page").as_ref()
+    }
+
+    pub fn get_page(&self, index: usize) -> Option<&dyn Component> {
+        self.pages.get(index).map(|page| page.as_ref())
+    }
+
+    /// Sets the callback called with the new depth when a page navigates or the user goes back.
+    pub fn set_on_navigate(&mut self, on_navigate: impl FnMut(usize, &mut EventContext) + 'static) {
+        self.on_navigate = Some(Box::new(on_navigate));
+    }
+
+    /// Emits the message `message` returns for the new depth every time the stack navigates.
+    pub fn set_on_navigate_message<M: 'static>(&mut self, message: impl Fn(usize) -> M + 'static) {
+        self.set_on_navigate(move |depth, context| context.emit(message(depth)));
+    }
+
+    fn get_current_page_mut(&mut self) -> &mut Box<dyn Component> {
+        self.pages.last_mut().expect("a stack always has its first page")
+    }
+
+    /// Applies `navigation` and returns whether the current page changed.
+    fn navigate(&mut self, navigation: Navigation) -> bool {
+        match navigation {
+            Navigation::Push(page) => {
+                self.push(page);
+                true
+            }
+            Navigation::Pop => self.pop().is_some(),
+            Navigation::PopToRoot => {
+                let depth = self.pages.len();
+                self.pop_to_root();
+                self.pages.len() != depth
+            }
+        }
+    }
+}
+
+impl Component for Stack {
+    fn render<'a>(&'a mut self, parent_top_left: &(f32, f32), parent_bottom_right: &(f32, f32), render_pass: &mut RenderPass<'a>, context: &RenderContext) {
+        self.basic.placement.update(*parent_top_left, *parent_bottom_right, self.basic.top_left, self.basic.bottom_right, &self.basic.transform, context);
+        self.basic.needs_resize = false;
+
+        let top_left = self.basic.placement.absolute_top_left;
+        let bottom_right = self.basic.placement.absolute_bottom_right;
+        let page_context = context.with_transform(self.basic.placement.world_transform);
+        self.get_current_page_mut().render(&top_left, &bottom_right, render_pass, &page_context);
+    }
+
+    fn get_top_left(&self) -> (f32, f32) {
+        self.basic.top_left
+    }
+
+    fn get_bottom_right(&self) -> (f32, f32) {
+        self.basic.bottom_right
+    }
+
+    fn get_transform(&self) -> Transform {
+        self.basic.transform
+    }
+
+    fn set_transform(&mut self, transform: Transform) {
+        self.basic.transform = transform;
+    }
+
+    fn get_z_index(&self) -> i32 {
+        self.basic.z_index
+    }
+
+    fn set_z_index(&mut self, z_index: i32) {
+        self.basic.z_index = z_index;
+    }
+
+    fn get_key(&self) -> Option<&str> {
+        self.basic.key.as_deref()
+    }
+
+    fn set_key(&mut self, key: &str) {
+        self.basic.key = Some(key.to_string());
+    }
+
+    fn get_id(&self) -> Option<&str> {
+        self.basic.id.as_deref()
+    }
+
+    fn set_id(&mut self, id: &str) {
+        self.basic.id = Some(id.to_string());
+    }
+
+    fn get_classes(&self) -> &[String] {
+        &self.basic.classes
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.basic.add_class(class);
+    }
+
+    /// Only the current page, the others are neither drawn nor hit-tested.
+    fn get_children(&self) -> Vec<&dyn Component> {
+        vec![self.get_current()]
+    }
+
+    fn get_event_handlers(&self) -> Vec<&'static str> {
+        self.on_navigate.iter().map(|_| "on_navigate").collect()
+    }
+
+    fn get_placement(&self) -> &ComponentPlacement {
+        &self.basic.placement
+    }
+
+    fn layout(&mut self, parent: &ComponentPlacement) {
+        self.basic.placement.update_in(parent, self.basic.top_left, self.basic.bottom_right, &self.basic.transform);
+
+        let placement = self.basic.placement;
+        self.get_current_page_mut().layout(&placement);
+    }
+
+    fn on_event(&mut self, event: &ComponentEvent, context: &mut EventContext) -> bool {
+        // Requests of stacks around this one are set aside, so the page only reaches this stack
+        let outer = std::mem::take(&mut context.navigation);
+        let mut consumed = self.get_current_page_mut().on_event(event, context);
+        let requests = std::mem::replace(&mut context.navigation, outer);
+
+        let mut navigated = false;
+        for navigation in requests {
+            navigated |= self.navigate(navigation);
+        }
+
+        if !consumed && matches!(event, ComponentEvent::KeyPressed { key: VirtualKeyCode::NavigateBackward | VirtualKeyCode::WebBack }) {
+            navigated |= self.pop().is_some();
+            consumed = navigated;
+        }
+
+        if navigated {
+            context.invalidate(&self.basic.placement);
+            let depth = self.pages.len();
+            if let Some(on_navigate) = self.on_navigate.as_mut() {
+                on_navigate(depth, context);
+            }
+        }
+
+        consumed || navigated
+    }
+
+    fn in_bound(&self, point: (f32, f32)) -> bool {
+        self.basic.placement.contains(point)
+    }
+
+    fn reconcile(&mut self, new: Box<dyn Component>) -> Result<(), Box<dyn Component>> {
+        let new = *downcast_component::<Self>(new)?;
+
+        // The history is kept, the pages of the new view are reconciled with its first pages
+        self.basic.reconcile(new.basic);
+        let mut new_pages = new.pages.into_iter();
+        for (page, new_page) in self.pages.iter_mut().zip(new_pages.by_ref()) {
+            reconcile_component(page, new_page);
+        }
+        self.pages.extend(new_pages);
+        self.on_navigate = new.on_navigate;
+
+        Ok(())
+    }
+
+    fn resize(&mut self, new_box_top_left: (f32, f32), new_box_bottom_right: (f32, f32)) {
+        self.basic.top_left = new_box_top_left;
+        self.basic.bottom_right = new_box_bottom_right;
+
+        self.on_resize();
+    }
+
+    fn on_resize(&mut self) {
+        self.basic.needs_resize = true;
+
+        // Hidden pages are resized as well, so they fit once they are shown again
+        for page in self.pages.iter_mut() {
+            page.on_resize();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use winit::event::MouseButton;
+    use super::*;
+    use crate::components::button::Button;
+    use crate::components::component::layout;
+    use crate::components::plain::PlainComponent;
+
+    fn page() -> Box<dyn Component> {
+        Box::new(PlainComponent::new((-1.0, 1.0), (1.0, -1.0), [1.0, 1.0, 1.0]))
+    }
+
+    /// A button filling the page that pushes another page when it is clicked.
+    fn pushing_page() -> Box<dyn Component> {
+        let mut button = Button::new((-1.0, 1.0), (1.0, -1.0));
+        button.set_on_press(|context| context.push_page(page()));
+        Box::new(button)
+    }
+
+    fn click(component: &mut dyn Component, context: &mut EventContext) -> bool {
+        component.on_event(&ComponentEvent::MousePressed { position: (0.0, 0.0), button: MouseButton::Left }, context);
+        component.on_event(&ComponentEvent::MouseReleased { position: (0.0, 0.0), button: MouseButton::Left }, context)
+    }
+
+    #[test]
+    fn pages_push_and_the_back_key_pops() {
+        let mut stack = Stack::new((-1.0, 1.0), (1.0, -1.0), pushing_page());
+        layout(&mut stack, (100, 100));
+        let mut context = EventContext::new(None);
+
+        assert!(click(&mut stack, &mut context));
+        assert_eq!(stack.get_depth(), 2);
+
+        let back = ComponentEvent::KeyPressed { key: VirtualKeyCode::NavigateBackward };
+        assert!(stack.on_event(&back, &mut context));
+        assert_eq!(stack.get_depth(), 1);
+        assert!(!stack.on_event(&back, &mut context));
+        assert!(stack.pop().is_none());
+    }
+
+    #[test]
+    fn requests_reach_only_the_innermost_stack() {
+        let inner = Stack::new((-1.0, 1.0), (1.0, -1.0), pushing_page());
+        let mut outer = Stack::new((-1.0, 1.0), (1.0, -1.0), Box::new(inner));
+        layout(&mut outer, (100, 100));
+        let mut context = EventContext::new(None);
+
+        assert!(click(&mut outer, &mut context));
+        assert_eq!(outer.get_depth(), 1);
+        assert!(context.navigation.is_empty());
+        let inner = outer.get_current().as_any().downcast_ref::<Stack>().unwrap();
+        assert_eq!(inner.get_depth(), 2);
+    }
+}
@@ -0,0 +1,86 @@
+use wgpu::RenderPass;
+use crate::Shape;
+use crate::components::component::{ComponentPlacement, RenderContext};
+use crate::shapes::pipeline::StencilMode;
+use crate::shapes::rounded_quad::RoundedQuad;
+use crate::style::ResolvedStyle;
+
+/// The part of a style that decides the shapes.
+#[derive(Copy, Clone, PartialEq)]
+struct DrawnBox {
+    top_left: (f32, f32),
+    bottom_right: (f32, f32),
+    window_size: (u32, u32),
+    background: Option<[f32; 3]>,
+    border: Option<([f32; 3], f32)>,
+    radius: f32,
+}
+
+/// Draws the background, border and rounded corners of a resolved style.
+/// The border is a filled shape behind the background, so it is only drawn together with a background.
+/// The shapes are only recreated when the style or the rectangle changed, for example after a theme switch.
+pub struct StyledBox {
+    drawn: Option<DrawnBox>,
+    border: Option<RoundedQuad>,
+    background: Option<RoundedQuad>,
+}
+
+impl StyledBox {
+    pub fn new() -> Self {
+        Self {
+            drawn: None,
+            border: None,
+            background: None,
+        }
+    }
+
+    /// Updates the shapes for `style` drawn over the absolute rectangle of `placement`.
+    pub fn prepare(&mut self, style: &ResolvedStyle, placement: &ComponentPlacement, context: &RenderContext) {
+        let drawn = DrawnBox {
+            top_left: placement.absolute_top_left,
+            bottom_right: placement.absolute_bottom_right,
+            window_size: placement.window_size,
+            background: style.background,
+            border: style.border,
+            radius: style.radius,
+        };
+
+        if self.drawn == Some(drawn) {
+            return;
+        }
+        self.drawn = Some(drawn);
+
+        let (top_left, bottom_right) = (drawn.top_left, drawn.bottom_right);
+        let radius = placement.radius_to_view(style.radius);
+
+        let border_width = style.border.map_or(0.0, |(_, width)| width);
+        self.border = style.border
+            .filter(|(_, width)| *width > 0.0 && style.background.is_some())
+            .map(|(color, _)| RoundedQuad::new(top_left, bottom_right, radius, color, StencilMode::Test, context.device, context.pipelines));
+
+        let inset = placement.radius_to_view(border_width);
+        let inner_radius = placement.radius_to_view((style.radius - border_width).max(0.0));
+        self.background = style.background
+            .map(|color| RoundedQuad::new((top_left.0 + inset.0, top_left.1 - inset.1),
+                                          (bottom_right.0 - inset.0, bottom_right.1 + inset.1),
+                                          inner_radius,
+                                          color,
+                                          StencilMode::Test,
+                                          context.device,
+                                          context.pipelines));
+    }
+
+    pub fn draw<'a>(&'a mut self, placement: &ComponentPlacement, render_pass: &mut RenderPass<'a>, context: &RenderContext) {
+        for shape in [self.border.as_mut(), self.background.as_mut()].into_iter().flatten() {
+            shape.set_transform(placement.world_transform, context.queue);
+            shape.set_opacity(context.opacity, context.queue);
+            shape.draw(render_pass);
+        }
+    }
+}
+
+impl Default for StyledBox {
+    fn default() -> Self {
+        Self::new()
+    }
+}
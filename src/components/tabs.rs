@@ -0,0 +1,624 @@
+use std::time::Duration;
+use wgpu::RenderPass;
+use winit::event::{MouseButton, VirtualKeyCode};
+use crate::Shape;
+use crate::animation::{Animated, Easing, Transition};
+use crate::components::component::{Component, ComponentBasicResizeData, ComponentPlacement, DirtyFlags, Rect, RenderContext, downcast_component};
+use crate::components::event::{ChangeCallback, ComponentEvent, EventContext};
+use crate::components::focus::FocusId;
+use crate::components::reconcile::reconcile_component;
+use crate::shapes::quad::Quad;
+use crate::stylesheet::{ElementState, StyleElement};
+use crate::transform::Transform;
+
+const INDICATOR_DURATION: Duration = Duration::from_millis(150);
+
+/// How the tab strip looks. Sizes are given in pixels.
+///
+/// The stylesheet overrides it: `background` replaces the strip, `foreground` the indicator,
+/// the border color the indicator while the tabs are focused.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TabsStyle {
+    pub strip: [f32; 3],
+    pub selected_tab: [f32; 3],
+    /// The line below the selected tab.
+    pub indicator: [f32; 3],
+    pub focused_indicator: [f32; 3],
+    pub strip_height: f32,
+    pub indicator_thickness: f32,
+}
+
+impl Default for TabsStyle {
+    fn default() -> Self {
+        Self {
+            strip: [0.12, 0.12, 0.12],
+            selected_tab: [0.18, 0.18, 0.18],
+            indicator: [0.2, 0.4, 0.8],
+            focused_indicator: [1.0, 1.0, 1.0],
+            strip_height: 32.0,
+            indicator_thickness: 3.0,
+        }
+    }
+}
+
+struct Tab {
+    /// Its rectangle is relative to the tab in the strip.
+    label: Option<Box<dyn Component>>,
+    /// Its rectangle is relative to the area below the strip.
+    page: Box<dyn Component>,
+}
+
+struct TabsShapes {
+    strip: Quad,
+    /// Cover the first tab and are moved to the selected one by their transforms.
+    selected_tab: Quad,
+    indicator: Quad,
+}
+
+/// A strip of equally wide tabs above the page of the selected tab.
+/// Only the selected page is rendered and gets events, the others keep their state until they are selected again.
+/// The user selects a tab by clicking it, or with the arrow keys while the strip is focused.
+///
+/// The selection set by the view wins when the tree is reconciled, so keep it in the application and update it from `on_change`.
+pub struct Tabs {
+    basic: ComponentBasicResizeData,
+
+    tabs: Vec<Tab>,
+    selected: usize,
+    style: TabsStyle,
+    on_change: Option<ChangeCallback<usize>>,
+
+    focus_id: FocusId,
+    focused: bool,
+    hovered: Option<usize>,
+    pressed: Option<usize>,
+    /// The selected index the indicator slides to.
+    indicator_position: Animated<f32>,
+
+    drawn_style: Option<TabsStyle>,
+    shapes: Option<TabsShapes>,
+}
+
+impl Tabs {
+    pub fn new(top_left: (f32, f32), bottom_right: (f32, f32)) -> Self {
+        let mut indicator_position = Animated::new(0.0);
+        indicator_position.set_transition(Some(Transition::new(INDICATOR_DURATION, Easing::EaseOut)));
+
+        Self {
+            basic: ComponentBasicResizeData {
+                top_left,
+                bottom_right,
+                needs_resize: true,
+                transform: Transform::IDENTITY,
+                z_index: 0,
+                key: None,
+                id: None,
+                classes: vec![],
+                placement: ComponentPlacement::default(),
+            },
+            tabs: vec![],
+            selected: 0,
+            style: TabsStyle::default(),
+            on_change: None,
+            focus_id: FocusId::next(),
+            focused: false,
+            hovered: None,
+            pressed: None,
+            indicator_position,
+            drawn_style: None,
+            shapes: None,
+        }
+    }
+
+    /// Adds a tab right of the others.
+    pub fn add_tab(&mut self, label: Option<Box<dyn Component>>, page: Box<dyn Component>) {
+        self.tabs.push(Tab { label, page });
+        self.basic.needs_resize = true;
+    }
+
+    pub fn get_tab_count(&self) -> usize {
+        self.tabs.len()
+    }
+
+    pub fn get_selected(&self) -> usize {
+        self.selected
+    }
+
+    /// Selects a tab without calling `on_change`. Indices past the last tab select the last one.
+    pub fn set_selected(&mut self, selected: usize) {
+        self.selected = selected.min(self.tabs.len().saturating_sub(1));
+    }
+
+    pub fn get_page(&self, index: usize) -> Option<&dyn Component> {
+        self.tabs.get(index).map(|tab| tab.page.as_ref())
+    }
+
+    pub fn get_tabs_style(&self) -> &TabsStyle {
+        &self.style
+    }
+
+    pub fn set_tabs_style(&mut self, style: TabsStyle) {
+        self.style = style;
+        self.basic.needs_resize = true;
+    }
+
+    /// Sets the callback called with the index of the tab the user selected.
+    pub fn set_on_change(&mut self, on_change: impl FnMut(usize, &mut EventContext) + 'static) {
+        self.on_change = Some(Box::new(on_change));
+    }
+
+    /// Emits the message `message` returns for the selected index every time the user selects a tab.
+    pub fn set_on_change_message<M: 'static>(&mut self, message: impl Fn(usize) -> M + 'static) {
+        self.set_on_change(move |index, context| context.emit(message(index)));
+    }
+
+    pub fn get_focus_id(&self) -> FocusId {
+        self.focus_id
+    }
+
+    /// The absolute rectangle of the strip, as high as the style says but never higher than the tabs.
+    fn get_strip(&self) -> Rect {
+        let placement = &self.basic.placement;
+        let (left, top) = placement.absolute_top_left;
+        let (right, bottom) = placement.absolute_bottom_right;
+        let height = placement.radius_to_view(self.style.strip_height).1.min(top - bottom);
+
+        ((left, top), (right, top - height))
+    }
+
+    /// The absolute rectangle of a tab in the strip.
+    fn get_tab(&self, index: usize) -> Rect {
+        let ((left, top), (right, bottom)) = self.get_strip();
+        let width = (right - left) / self.tabs.len().max(1) as f32;
+
+        ((left + index as f32 * width, top), (left + (index + 1) as f32 * width, bottom))
+    }
+
+    /// The absolute rectangle of the pages below the strip.
+    fn get_page_area(&self) -> Rect {
+        let (_, (_, strip_bottom)) = self.get_strip();
+        let (left, _) = self.basic.placement.absolute_top_left;
+
+        ((left, strip_bottom), self.basic.placement.absolute_bottom_right)
+    }
+
+    /// The index of the tab under a view point.
+    pub fn get_tab_at(&self, point: (f32, f32)) -> Option<usize> {
+        let point = self.basic.placement.to_local(point)?;
+        let ((left, top), (right, bottom)) = self.get_strip();
+        if !(point.0 > left && point.0 < right && point.1 < top && point.1 > bottom) {
+            return None;
+        }
+
+        let index = ((point.0 - left) / (right - left) * self.tabs.len() as f32) as usize;
+        (index < self.tabs.len()).then_some(index)
+    }
+
+    fn get_element_state(&self) -> ElementState {
+        ElementState {
+            hovered: self.hovered.is_some(),
+            pressed: self.pressed.is_some() && self.pressed == self.hovered,
+            focused: self.focused,
+            disabled: false,
+            checked: false,
+        }
+    }
+
+    fn select(&mut self, index: usize, context: &mut EventContext) {
+        if self.selected == index {
+            return;
+        }
+
+        self.selected = index;
+        if let Some(on_change) = self.on_change.as_mut() {
+            on_change(index, context);
+        }
+    }
+
+    fn get_selected_page_mut(&mut self) -> Option<&mut Box<dyn Component>> {
+        self.tabs.get_mut(self.selected).map(|tab| &mut tab.page)
+    }
+
+    fn handle_strip_event(&mut self, event: &ComponentEvent, context: &mut EventContext) -> bool {
+        match event {
+            ComponentEvent::CursorMoved { position } => {
+                self.hovered = self.get_tab_at(*position);
+                false
+            }
+            ComponentEvent::MousePressed { position, button: MouseButton::Left } => {
+                let index = match self.get_tab_at(*position) {
+                    Some(index) => index,
+                    None => return false,
+                };
+
+                self.pressed = Some(index);
+                context.request_focus(self.focus_id);
+                true
+            }
+            ComponentEvent::MouseReleased { position, button: MouseButton::Left } => {
+                let pressed = match self.pressed.take() {
+                    Some(pressed) => pressed,
+                    None => return false,
+                };
+
+                if self.get_tab_at(*position) == Some(pressed) {
+                    self.select(pressed, context);
+                }
+                true
+            }
+            ComponentEvent::KeyPressed { key } => {
+                if !context.is_focused(self.focus_id) || self.tabs.is_empty() {
+                    return false;
+                }
+
+                let count = self.tabs.len();
+                match key {
+                    VirtualKeyCode::Left => self.select((self.selected + count - 1) % count, context),
+                    VirtualKeyCode::Right => self.select((self.selected + 1) % count, context),
+                    VirtualKeyCode::Home => self.select(0, context),
+                    VirtualKeyCode::End => self.select(count - 1, context),
+                    _ => return false,
+                }
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn create_shapes(&mut self, style: TabsStyle, context: &RenderContext) {
+        let (strip_top_left, strip_bottom_right) = self.get_strip();
+        let (tab_top_left, tab_bottom_right) = self.get_tab(0);
+        let thickness = self.basic.placement.radius_to_view(style.indicator_thickness).1;
+
+        let quad = |top_left: (f32, f32), bottom_right: (f32, f32), color: [f32; 3]| Quad::new(top_left, bottom_right, color, context.device, context.pipelines);
+        self.shapes = Some(TabsShapes {
+            strip: quad(strip_top_left, strip_bottom_right, style.strip),
+            selected_tab: quad(tab_top_left, tab_bottom_right, style.selected_tab),
+            indicator: quad((tab_top_left.0, tab_bottom_right.1 + thickness), tab_bottom_right, style.indicator),
+        });
+    }
+}
+
+impl Component for Tabs {
+    fn render<'a>(&'a mut self, parent_top_left: &(f32, f32), parent_bottom_right: &(f32, f32), render_pass: &mut RenderPass<'a>, context: &RenderContext) {
+        self.basic.placement.update(*parent_top_left, *parent_bottom_right, self.basic.top_left, self.basic.bottom_right, &self.basic.transform, context);
+
+        self.focused = context.focused == Some(self.focus_id);
+        let type_name = self.get_type_name();
+        let element_state = self.get_element_state();
+        let sheet = context.stylesheet.compute(&context.get_style_path(StyleElement {
+            type_name,
+            id: self.basic.id.as_deref(),
+            classes: &self.basic.classes,
+            state: element_state,
+        }));
+
+        let mut style = self.style;
+        if let Some(background) = sheet.background {
+            style.strip = context.theme.color(background);
+        }
+        if let Some(foreground) = sheet.foreground {
+            style.indicator = context.theme.color(foreground);
+        }
+        if let Some(border) = sheet.border {
+            style.focused_indicator = context.theme.color(border.color);
+        }
+        if self.focused {
+            style.indicator = style.focused_indicator;
+        }
+
+        if self.basic.needs_resize || self.drawn_style != Some(style) {
+            self.create_shapes(style, context);
+
+            self.drawn_style = Some(style);
+            self.basic.needs_resize = false;
+        }
+
+        let now = context.clock.get_now();
+        self.indicator_position.set(self.selected as f32, now);
+        if self.indicator_position.is_active(now) {
+            context.invalidation.invalidate_placement(&self.basic.placement);
+        }
+
+        let world_transform = self.basic.placement.world_transform;
+        let ((tab_left, _), (tab_right, _)) = self.get_tab(0);
+        let selected_transform = Transform::translation(self.indicator_position.get(now) * (tab_right - tab_left), 0.0).then(&world_transform);
+        let tabs: Vec<Rect> = (0..self.tabs.len()).map(|index| self.get_tab(index)).collect();
+        let (page_top_left, page_bottom_right) = self.get_page_area();
+
+        if let Some(shapes) = self.shapes.as_mut() {
+            for (quad, transform) in [(&mut shapes.strip, world_transform), (&mut shapes.selected_tab, selected_transform), (&mut shapes.indicator, selected_transform)] {
+                quad.set_transform(transform, context.queue);
+                quad.set_opacity(context.opacity, context.queue);
+            }
+            shapes.strip.draw(render_pass);
+            if !self.tabs.is_empty() {
+                shapes.selected_tab.draw(render_pass);
+                shapes.indicator.draw(render_pass);
+            }
+        }
+
+        let path = context.get_style_path(StyleElement {
+            type_name,
+            id: self.basic.id.as_deref(),
+            classes: &self.basic.classes,
+            state: element_state,
+        });
+        let child_context = context.with_transform(world_transform);
+        let child_context = child_context.with_style_path(&path);
+
+        let selected = self.selected;
+        for (index, (tab, (top_left, bottom_right))) in self.tabs.iter_mut().zip(tabs).enumerate() {
+            if let Some(label) = tab.label.as_mut() {
+                label.render(&top_left, &bottom_right, render_pass, &child_context);
+            }
+            if index == selected {
+                tab.page.render(&page_top_left, &page_bottom_right, render_pass, &child_context);
+            }
+        }
+    }
+
+    fn get_top_left(&self) -> (f32, f32) {
+        self.basic.top_left
+    }
+
+    fn get_bottom_right(&self) -> (f32, f32) {
+        self.basic.bottom_right
+    }
+
+    fn get_transform(&self) -> Transform {
+        self.basic.transform
+    }
+
+    fn set_transform(&mut self, transform: Transform) {
+        self.basic.transform = transform;
+    }
+
+    fn get_z_index(&self) -> i32 {
+        self.basic.z_index
+    }
+
+    fn set_z_index(&mut self, z_index: i32) {
+        self.basic.z_index = z_index;
+    }
+
+    fn get_key(&self) -> Option<&str> {
+        self.basic.key.as_deref()
+    }
+
+    fn set_key(&mut self, key: &str) {
+        self.basic.key = Some(key.to_string());
+    }
+
+    fn get_id(&self) -> Option<&str> {
+        self.basic.id.as_deref()
+    }
+
+    fn set_id(&mut self, id: &str) {
+        self.basic.id = Some(id.to_string());
+    }
+
+    fn get_classes(&self) -> &[String] {
+        &self.basic.classes
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.basic.add_class(class);
+    }
+
+    /// The labels and the selected page, the other pages are neither drawn nor hit-tested.
+    fn get_children(&self) -> Vec<&dyn Component> {
+        let labels = self.tabs.iter().filter_map(|tab| tab.label.as_deref());
+        labels.chain(self.tabs.get(self.selected).map(|tab| tab.page.as_ref())).collect()
+    }
+
+    fn get_dirty_flags(&self) -> DirtyFlags {
+        DirtyFlags {
+            needs_resize: self.basic.needs_resize,
+            ..DirtyFlags::default()
+        }
+    }
+
+    fn get_event_handlers(&self) -> Vec<&'static str> {
+        self.on_change.iter().map(|_| "on_change").collect()
+    }
+
+    fn get_placement(&self) -> &ComponentPlacement {
+        &self.basic.placement
+    }
+
+    fn layout(&mut self, parent: &ComponentPlacement) {
+        self.basic.placement.update_in(parent, self.basic.top_left, self.basic.bottom_right, &self.basic.transform);
+
+        let placement = self.basic.placement;
+        let tabs: Vec<Rect> = (0..self.tabs.len()).map(|index| self.get_tab(index)).collect();
+        let (page_top_left, page_bottom_right) = self.get_page_area();
+        let selected = self.selected;
+        for (index, (tab, (top_left, bottom_right))) in self.tabs.iter_mut().zip(tabs).enumerate() {
+            if let Some(label) = tab.label.as_mut() {
+                label.layout(&placement.with_rect(top_left, bottom_right));
+            }
+            if index == selected {
+                tab.page.layout(&placement.with_rect(page_top_left, page_bottom_right));
+            }
+        }
+    }
+
+    fn on_event(&mut self, event: &ComponentEvent, context: &mut EventContext) -> bool {
+        let state = (self.selected, self.hovered, self.pressed);
+
+        // Presses and wheels on the strip never reach the page, everything else goes to both
+        let on_strip = event.position().is_some_and(|position| self.get_tab_at(position).is_some());
+        let mut consumed = self.handle_strip_event(event, context);
+        if !(event.is_targeted() && (consumed || on_strip)) {
+            if let Some(page) = self.get_selected_page_mut() {
+                consumed |= page.on_event(event, context);
+            }
+        }
+
+        if (self.selected, self.hovered, self.pressed) != state {
+            context.invalidate(&self.basic.placement);
+        }
+
+        consumed
+    }
+
+    fn in_bound(&self, point: (f32, f32)) -> bool {
+        self.basic.placement.contains(point)
+    }
+
+    fn reconcile(&mut self, new: Box<dyn Component>) -> Result<(), Box<dyn Component>> {
+        let new = *downcast_component::<Self>(new)?;
+
+        // The focus id, hover and press state and the state of every page are kept, the selection comes from the new view
+        self.basic.reconcile(new.basic);
+        if self.style != new.style {
+            self.set_tabs_style(new.style);
+        }
+        if self.tabs.len() != new.tabs.len() {
+            self.basic.needs_resize = true;
+        }
+
+        let mut new_tabs = new.tabs.into_iter();
+        let mut tabs: Vec<_> = self.tabs.drain(..).zip(new_tabs.by_ref()).map(|(mut tab, new_tab)| {
+            tab.label = match (tab.label, new_tab.label) {
+                (Some(mut label), Some(new_label)) => {
+                    reconcile_component(&mut label, new_label);
+                    Some(label)
+                }
+                (_, new_label) => new_label,
+            };
+            reconcile_component(&mut tab.page, new_tab.page);
+            tab
+        }).collect();
+        tabs.extend(new_tabs);
+        self.tabs = tabs;
+
+        self.set_selected(new.selected);
+        self.on_change = new.on_change;
+
+        Ok(())
+    }
+
+    fn resize(&mut self, new_box_top_left: (f32, f32), new_box_bottom_right: (f32, f32)) {
+        self.basic.top_left = new_box_top_left;
+        self.basic.bottom_right = new_box_bottom_right;
+
+        self.on_resize();
+    }
+
+    fn on_resize(&mut self) {
+        self.basic.needs_resize = true;
+
+        // Hidden pages are resized as well, so they fit once they are selected again
+        for tab in self.tabs.iter_mut() {
+            if let Some(label) = tab.label.as_mut() {
+                label.on_resize();
+            }
+            tab.page.on_resize();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::button::Button;
+    use crate::components::checkbox::{CheckState, Checkbox};
+    use crate::components::component::layout;
+    use crate::components::event::testing::{press_key, record_changes};
+
+    /// Tabs filling a 100 by 100 pixel window, the strip covers the top 32 pixels.
+    fn tabs(pages: Vec<Box<dyn Component>>) -> Tabs {
+        let mut tabs = Tabs::new((-1.0, 1.0), (1.0, -1.0));
+        for page in pages {
+            tabs.add_tab(None, page);
+        }
+        layout(&mut tabs, (100, 100));
+        tabs
+    }
+
+    /// A page that emits its index when it is clicked.
+    fn page(index: usize) -> Box<dyn Component> {
+        let mut button = Button::new((-1.0, 1.0), (1.0, -1.0));
+        button.set_on_press_message(index);
+        Box::new(button)
+    }
+
+    fn click(tabs: &mut Tabs, position: (f32, f32), context: &mut EventContext) {
+        tabs.on_event(&ComponentEvent::MousePressed { position, button: MouseButton::Left }, context);
+        tabs.on_event(&ComponentEvent::MouseReleased { position, button: MouseButton::Left }, context);
+        // Pages are laid out when they are selected, like in the next frame
+        layout(tabs, (100, 100));
+    }
+
+    fn clicked_pages(context: &mut EventContext) -> Vec<usize> {
+        context.take_messages().into_iter().filter_map(|message| message.downcast::<usize>().ok()).map(|message| *message).collect()
+    }
+
+    const PAGE_CENTER: (f32, f32) = (0.0, -0.3);
+
+    #[test]
+    fn clicking_a_tab_switches_the_page_that_gets_events() {
+        let mut tabs = tabs(vec![page(0), page(1)]);
+        let (changes, on_change) = record_changes();
+        tabs.set_on_change(on_change);
+        let mut context = EventContext::new(None);
+
+        assert_eq!(tabs.get_tab_at((-0.5, 0.7)), Some(0));
+        assert_eq!(tabs.get_tab_at((0.5, 0.7)), Some(1));
+        assert_eq!(tabs.get_tab_at(PAGE_CENTER), None);
+
+        click(&mut tabs, PAGE_CENTER, &mut context);
+        assert_eq!(clicked_pages(&mut context), vec![0]);
+
+        click(&mut tabs, (0.5, 0.7), &mut context);
+        assert_eq!(tabs.get_selected(), 1);
+        assert_eq!(*changes.borrow(), [1]);
+        // The click on the strip did not reach the page below it
+        assert!(clicked_pages(&mut context).is_empty());
+
+        click(&mut tabs, PAGE_CENTER, &mut context);
+        assert_eq!(clicked_pages(&mut context), vec![1]);
+
+        // Only the selected page is a child, so hit testing skips the hidden ones
+        let children = tabs.get_children();
+        assert_eq!(children.len(), 1);
+        assert!(std::ptr::eq(children[0].get_placement(), tabs.get_page(1).unwrap().get_placement()));
+    }
+
+    #[test]
+    fn hidden_pages_keep_their_state() {
+        let checkbox = || Box::new(Checkbox::new((-1.0, 1.0), (1.0, -1.0), CheckState::Unchecked)) as Box<dyn Component>;
+        let mut tabs = tabs(vec![checkbox(), checkbox()]);
+        let mut context = EventContext::new(None);
+        let state = |tabs: &Tabs, index: usize| tabs.get_page(index).unwrap().as_any().downcast_ref::<Checkbox>().unwrap().get_state();
+
+        click(&mut tabs, PAGE_CENTER, &mut context);
+        assert_eq!(state(&tabs, 0), CheckState::Checked);
+
+        click(&mut tabs, (0.5, 0.7), &mut context);
+        click(&mut tabs, PAGE_CENTER, &mut context);
+        click(&mut tabs, PAGE_CENTER, &mut context);
+        assert_eq!(state(&tabs, 1), CheckState::Unchecked);
+        assert_eq!(state(&tabs, 0), CheckState::Checked);
+
+        click(&mut tabs, (-0.5, 0.7), &mut context);
+        assert_eq!(tabs.get_selected(), 0);
+        assert_eq!(state(&tabs, 0), CheckState::Checked);
+    }
+
+    #[test]
+    fn arrow_keys_wrap_around_the_focused_strip() {
+        let mut tabs = tabs(vec![page(0), page(1), page(2)]);
+        assert!(!press_key(&mut tabs, VirtualKeyCode::Right, &mut EventContext::new(None)));
+
+        let mut context = EventContext::new(Some(tabs.get_focus_id()));
+        assert!(press_key(&mut tabs, VirtualKeyCode::Left, &mut context));
+        assert_eq!(tabs.get_selected(), 2);
+        assert!(press_key(&mut tabs, VirtualKeyCode::Right, &mut context));
+        assert_eq!(tabs.get_selected(), 0);
+        assert!(press_key(&mut tabs, VirtualKeyCode::End, &mut context));
+        assert_eq!(tabs.get_selected(), 2);
+    }
+}
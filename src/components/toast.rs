@@ -0,0 +1,403 @@
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
+use wgpu::RenderPass;
+use crate::Shape;
+use crate::animation::{Animated, Easing, Transition};
+use crate::components::component::{Component, ComponentPlacement, RenderContext};
+use crate::components::event::{ComponentEvent, EventContext};
+use crate::shapes::pipeline::StencilMode;
+use crate::shapes::rounded_quad::RoundedQuad;
+use crate::transform::Transform;
+use crate::watcher;
+
+const ENTER_DURATION: Duration = Duration::from_millis(200);
+const EXIT_DURATION: Duration = Duration::from_millis(200);
+/// Distance of the toasts to the edges of the window in pixels.
+const MARGIN: f32 = 16.0;
+/// Space between stacked toasts in pixels.
+const GAP: f32 = 8.0;
+/// How far toasts slide in from the side in pixels.
+const SLIDE_DISTANCE: f32 = 32.0;
+
+thread_local! {
+    /// Toasts shown since the last frame, picked up by the window.
+    static SHOWN: RefCell<Vec<Toast>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Shows a toast on the next frame. Works anywhere on the UI thread, also in `Application::update`.
+pub fn show_toast(toast: Toast) {
+    SHOWN.with(|shown| shown.borrow_mut().push(toast));
+    watcher::request_redraw();
+}
+
+pub(crate) fn take_shown_toasts() -> Vec<Toast> {
+    SHOWN.with(|shown| std::mem::take(&mut *shown.borrow_mut()))
+}
+
+/// The corner of the window toasts are stacked in.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum ToastCorner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    #[default]
+    BottomRight,
+}
+
+impl ToastCorner {
+    fn is_top(&self) -> bool {
+        matches!(self, ToastCorner::TopLeft | ToastCorner::TopRight)
+    }
+
+    fn is_left(&self) -> bool {
+        matches!(self, ToastCorner::TopLeft | ToastCorner::BottomLeft)
+    }
+}
+
+/// How a toast looks. Sizes are given in pixels.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ToastStyle {
+    pub background: [f32; 3],
+    pub border: [f32; 3],
+    pub border_width: f32,
+    pub corner_radius: f32,
+}
+
+impl Default for ToastStyle {
+    fn default() -> Self {
+        Self {
+            background: [0.2, 0.2, 0.2],
+            border: [0.5, 0.5, 0.5],
+            border_width: 1.0,
+            corner_radius: 6.0,
+        }
+    }
+}
+
+/// A notification that is shown for a while in a corner of the window, above everything else.
+/// A press on it closes it early, unless its content consumes the press.
+pub struct Toast {
+    content: Box<dyn Component>,
+    /// The size in pixels.
+    size: (f32, f32),
+    duration: Duration,
+    corner: ToastCorner,
+    style: ToastStyle,
+}
+
+impl Toast {
+    /// Creates a toast with the given size in pixels. The rectangle of the content is relative to the toast.
+    pub fn new(content: Box<dyn Component>, size: (f32, f32)) -> Self {
+        Self {
+            content,
+            size,
+            duration: Duration::from_secs(4),
+            corner: ToastCorner::default(),
+            style: ToastStyle::default(),
+        }
+    }
+
+    pub fn get_duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// How long the toast stays before it starts to leave.
+    pub fn set_duration(&mut self, duration: Duration) {
+        self.duration = duration;
+    }
+
+    pub fn get_corner(&self) -> ToastCorner {
+        self.corner
+    }
+
+    pub fn set_corner(&mut self, corner: ToastCorner) {
+        self.corner = corner;
+    }
+
+    pub fn get_toast_style(&self) -> &ToastStyle {
+        &self.style
+    }
+
+    pub fn set_toast_style(&mut self, style: ToastStyle) {
+        self.style = style;
+    }
+}
+
+struct ActiveToast {
+    toast: Toast,
+    /// Set on the first frame that draws the toast.
+    shown: Option<Instant>,
+    closing: Option<Instant>,
+    /// The distance to the corner in pixels, moved when toasts above it come and go.
+    offset: Option<Animated<f32>>,
+    /// The rectangle in the corner, moved by the offset and the slide with its world transform.
+    placement: ComponentPlacement,
+
+    drawn_size: Option<(u32, u32)>,
+    border: Option<RoundedQuad>,
+    background: Option<RoundedQuad>,
+}
+
+impl ActiveToast {
+    fn get_expiry(&self) -> Option<Instant> {
+        match (self.shown, self.closing) {
+            (Some(shown), None) => Some(shown + self.toast.duration),
+            _ => None,
+        }
+    }
+
+    fn create_shapes(&mut self, context: &RenderContext) {
+        let style = self.toast.style;
+        let (top_left, bottom_right) = (self.placement.absolute_top_left, self.placement.absolute_bottom_right);
+        let inset = self.placement.radius_to_view(style.border_width);
+        let radius = self.placement.radius_to_view(style.corner_radius);
+        let inner_radius = self.placement.radius_to_view((style.corner_radius - style.border_width).max(0.0));
+
+        self.border = (style.border_width > 0.0).then(|| RoundedQuad::new(top_left,
+                                                                         bottom_right,
+                                                                         radius,
+                                                                         style.border,
+                                                                         StencilMode::Test,
+                                                                         context.device,
+                                                                         context.pipelines));
+        self.background = Some(RoundedQuad::new((top_left.0 + inset.0, top_left.1 - inset.1),
+                                                (bottom_right.0 - inset.0, bottom_right.1 + inset.1),
+                                                inner_radius,
+                                                style.background,
+                                                StencilMode::Test,
+                                                context.device,
+                                                context.pipelines));
+    }
+}
+
+/// Stacks the toasts in their corners and animates them in and out. The newest toast is closest to the corner.
+#[derive(Default)]
+pub struct ToastManager {
+    toasts: Vec<ActiveToast>,
+}
+
+impl ToastManager {
+    pub fn new() -> Self {
+        Self {
+            toasts: vec![],
+        }
+    }
+
+    pub fn show(&mut self, toast: Toast) {
+        self.toasts.push(ActiveToast {
+            toast,
+            shown: None,
+            closing: None,
+            offset: None,
+            placement: ComponentPlacement::default(),
+            drawn_size: None,
+            border: None,
+            background: None,
+        });
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.toasts.is_empty()
+    }
+
+    /// The contents of the shown toasts.
+    pub fn get_contents(&self) -> Vec<&dyn Component> {
+        self.toasts.iter().map(|toast| toast.toast.content.as_ref()).collect()
+    }
+
+    /// Whether a toast was added or ran out at `now`, so the next frame has to be drawn.
+    pub fn is_due(&self, now: Instant) -> bool {
+        self.toasts.iter().any(|toast| toast.shown.is_none() || toast.get_expiry().is_some_and(|expiry| expiry <= now))
+    }
+
+    /// When the next toast runs out, so the window can sleep until then.
+    pub fn get_next_deadline(&self) -> Option<Instant> {
+        self.toasts.iter().filter_map(ActiveToast::get_expiry).min()
+    }
+
+    /// Starts new toasts at `now`, closes the ones that ran out and drops the ones that finished leaving.
+    /// Returns whether a toast was dropped.
+    pub fn update(&mut self, now: Instant) -> bool {
+        let count = self.toasts.len();
+        self.toasts.retain(|toast| toast.closing.is_none_or(|closing| now.saturating_duration_since(closing) < EXIT_DURATION));
+
+        for toast in self.toasts.iter_mut() {
+            toast.shown.get_or_insert(now);
+            if toast.get_expiry().is_some_and(|expiry| expiry <= now) {
+                toast.closing = Some(now);
+            }
+        }
+
+        self.toasts.len() != count
+    }
+
+    pub fn render<'a>(&'a mut self, render_pass: &mut RenderPass<'a>, context: &RenderContext) {
+        let now = context.clock.get_now();
+        let window_size = (context.config.width, context.config.height);
+
+        if self.update(now) {
+            context.invalidation.request_redraw();
+        }
+
+        // Stack the remaining toasts from the newest one, closing ones keep their place while they fade
+        let mut stacked = [0.0; 4];
+        for toast in self.toasts.iter_mut().rev() {
+            if toast.closing.is_some() {
+                continue;
+            }
+
+            let distance = &mut stacked[toast.toast.corner as usize];
+            let offset = toast.offset.get_or_insert_with(|| {
+                let mut offset = Animated::new(*distance);
+                offset.set_transition(Some(Transition::new(ENTER_DURATION, Easing::EaseOut)));
+                offset
+            });
+            offset.set(*distance, now);
+            *distance += toast.toast.size.1 + GAP;
+        }
+
+        let mut animating = false;
+        for toast in self.toasts.iter_mut() {
+            let shown = toast.shown.unwrap_or(now);
+            let corner = toast.toast.corner;
+
+            let root = ComponentPlacement {
+                absolute_top_left: (-1.0, 1.0),
+                absolute_bottom_right: (1.0, -1.0),
+                world_transform: context.transform,
+                window_size,
+            };
+            let margin = root.radius_to_view(MARGIN);
+            let size = root.radius_to_view(1.0);
+            let size = (size.0 * toast.toast.size.0, size.1 * toast.toast.size.1);
+            let left = if corner.is_left() { -1.0 + margin.0 } else { 1.0 - margin.0 - size.0 };
+            let top = if corner.is_top() { 1.0 - margin.1 } else { -1.0 + margin.1 + size.1 };
+
+            let entered = Easing::EaseOut.apply((now - shown).as_secs_f32() / ENTER_DURATION.as_secs_f32(), ENTER_DURATION);
+            let left_over = match toast.closing {
+                Some(closing) => 1.0 - Easing::EaseIn.apply(now.saturating_duration_since(closing).as_secs_f32() / EXIT_DURATION.as_secs_f32(), EXIT_DURATION),
+                None => 1.0,
+            };
+            let visibility = entered.min(left_over);
+
+            let offset = toast.offset.as_mut().map_or(0.0, |offset| {
+                animating |= offset.is_active(now);
+                offset.get(now)
+            });
+            let slide = SLIDE_DISTANCE * (1.0 - visibility);
+            let translation = root.radius_to_view(1.0);
+            let translation = (translation.0 * if corner.is_left() { -slide } else { slide },
+                               translation.1 * if corner.is_top() { -offset } else { offset });
+            animating |= visibility < 1.0;
+
+            toast.placement = ComponentPlacement {
+                absolute_top_left: (left, top),
+                absolute_bottom_right: (left + size.0, top - size.1),
+                world_transform: Transform::translation(translation.0, translation.1).then(&context.transform),
+                window_size,
+            };
+
+            if toast.drawn_size != Some(window_size) {
+                toast.create_shapes(context);
+                toast.toast.content.on_resize();
+                toast.drawn_size = Some(window_size);
+            }
+
+            let opacity = context.opacity * visibility;
+            let world_transform = toast.placement.world_transform;
+            for shape in toast.border.iter_mut().chain(toast.background.iter_mut()) {
+                shape.set_transform(world_transform, context.queue);
+                shape.set_opacity(opacity, context.queue);
+                shape.draw(render_pass);
+            }
+
+            let content_context = context.with_transform(world_transform).with_opacity(opacity);
+            let (top_left, bottom_right) = (toast.placement.absolute_top_left, toast.placement.absolute_bottom_right);
+            toast.toast.content.render(&top_left, &bottom_right, render_pass, &content_context);
+        }
+
+        if animating {
+            context.invalidation.request_redraw();
+        }
+    }
+
+    /// Passes an event to the toasts and returns whether it was consumed. Only presses on a toast are taken
+    /// away from the overlays and the component tree.
+    pub fn on_event(&mut self, event: &ComponentEvent, context: &mut EventContext) -> bool {
+        if !event.is_targeted() {
+            let mut consumed = false;
+            for toast in self.toasts.iter_mut().filter(|toast| toast.closing.is_none()) {
+                consumed |= toast.toast.content.on_event(event, context);
+            }
+            return consumed;
+        }
+
+        let position = match event.position() {
+            None => return false,
+            Some(position) => position,
+        };
+        let toast = match self.toasts.iter_mut().rev().find(|toast| toast.closing.is_none() && toast.placement.contains(position)) {
+            None => return false,
+            Some(toast) => toast,
+        };
+
+        if !toast.toast.content.on_event(event, context) && matches!(event, ComponentEvent::MousePressed { .. }) {
+            toast.closing = Some(Instant::now());
+            context.request_redraw();
+            // Toasts are not part of the focus, the press must not take it from the tree
+            if let Some(focused) = context.get_focused() {
+                context.request_focus(focused);
+            }
+        }
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::plain::PlainComponent;
+
+    fn toast(duration: Duration) -> Toast {
+        let mut toast = Toast::new(Box::new(PlainComponent::new((-1.0, 1.0), (1.0, -1.0), [1.0; 3])), (200.0, 40.0));
+        toast.set_duration(duration);
+        toast
+    }
+
+    #[test]
+    fn toasts_expire_after_their_duration_from_the_first_frame() {
+        let start = Instant::now();
+        let at = |millis: u64| start + Duration::from_millis(millis);
+        let mut toasts = ToastManager::new();
+        toasts.show(toast(Duration::from_secs(1)));
+
+        // The duration starts with the first frame that shows the toast
+        assert!(toasts.is_due(at(500)));
+        assert!(!toasts.update(at(500)));
+        assert_eq!(toasts.get_next_deadline(), Some(at(1500)));
+        assert!(!toasts.is_due(at(1000)));
+        assert!(toasts.is_due(at(1500)));
+
+        // It fades out before it is dropped
+        assert!(!toasts.update(at(1500)));
+        assert_eq!(toasts.get_contents().len(), 1);
+        assert_eq!(toasts.get_next_deadline(), None);
+        assert!(!toasts.update(at(1500) + EXIT_DURATION / 2));
+        assert!(toasts.update(at(1500) + EXIT_DURATION));
+        assert!(toasts.is_empty());
+    }
+
+    #[test]
+    fn the_deadline_is_the_earliest_expiry() {
+        let start = Instant::now();
+        let mut toasts = ToastManager::new();
+        toasts.show(toast(Duration::from_secs(4)));
+        toasts.show(toast(Duration::from_secs(2)));
+        toasts.update(start);
+
+        assert_eq!(toasts.get_next_deadline(), Some(start + Duration::from_secs(2)));
+        toasts.update(start + Duration::from_secs(2));
+        assert_eq!(toasts.get_next_deadline(), Some(start + Duration::from_secs(4)));
+        assert_eq!(toasts.get_contents().len(), 2);
+    }
+}
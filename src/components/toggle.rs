@@ -0,0 +1,390 @@
+use std::time::Duration;
+use wgpu::RenderPass;
+use crate::Shape;
+use crate::animation::{Animated, Easing, Transition};
+use crate::components::checkbox::{CheckStyle, DISABLED_OPACITY, ResolvedCheckStyle, is_toggle_key};
+use crate::components::component::{Component, ComponentBasicResizeData, ComponentPlacement, DirtyFlags, RenderContext, downcast_component};
+use crate::components::event::{ChangeCallback, ComponentEvent, EventContext};
+use crate::components::focus::FocusId;
+use crate::components::press::{Press, Pressable, animated_color};
+use crate::shapes::oval::Oval;
+use crate::shapes::pipeline::StencilMode;
+use crate::shapes::rounded_quad::RoundedQuad;
+use crate::stylesheet::{ElementState, StyleElement};
+use crate::transform::Transform;
+
+/// Space between the knob and the edge of the track in pixels.
+const KNOB_PADDING: f32 = 2.0;
+
+/// A switch with a round knob that slides to the right when it is turned on, by clicking it or pressing Space while it is focused.
+/// The track is fully rounded, `CheckStyle::corner_radius` is not used.
+///
+/// The state set by the view wins when the tree is reconciled, so keep it in the application and update it from `on_change`.
+pub struct Toggle {
+    basic: ComponentBasicResizeData,
+
+    on: bool,
+    style: CheckStyle,
+    on_change: Option<ChangeCallback<bool>>,
+
+    pressable: Pressable,
+
+    track_color: Animated<[f32; 3]>,
+    border_color: Animated<[f32; 3]>,
+    /// Where the knob is between off (0) and on (1).
+    knob_position: Animated<f32>,
+
+    drawn_style: Option<ResolvedCheckStyle>,
+    border: Option<RoundedQuad>,
+    track: Option<RoundedQuad>,
+    /// Created in the off position and moved by its transform.
+    knob: Option<Oval>,
+    /// The distance between the off and the on position in view units.
+    knob_travel: f32,
+}
+
+impl Toggle {
+    pub fn new(top_left: (f32, f32), bottom_right: (f32, f32), on: bool) -> Self {
+        let style = CheckStyle::default();
+        let mut knob_position = Animated::new(if on { 1.0 } else { 0.0 });
+        knob_position.set_transition(Some(Transition::new(Duration::from_millis(150), Easing::EaseOut)));
+
+        Self {
+            basic: ComponentBasicResizeData {
+                top_left,
+                bottom_right,
+                needs_resize: true,
+                transform: Transform::IDENTITY,
+                z_index: 0,
+                key: None,
+                id: None,
+                classes: vec![],
+                placement: ComponentPlacement::default(),
+            },
+            on,
+            style,
+            on_change: None,
+            pressable: Pressable::new(is_toggle_key),
+            track_color: animated_color(if on { style.checked_background } else { style.background }),
+            border_color: animated_color(style.border),
+            knob_position,
+            drawn_style: None,
+            border: None,
+            track: None,
+            knob: None,
+            knob_travel: 0.0,
+        }
+    }
+
+    pub fn is_on(&self) -> bool {
+        self.on
+    }
+
+    /// Turns the toggle on or off without calling `on_change`.
+    pub fn set_on(&mut self, on: bool) {
+        self.on = on;
+    }
+
+    pub fn get_check_style(&self) -> &CheckStyle {
+        &self.style
+    }
+
+    pub fn set_check_style(&mut self, style: CheckStyle) {
+        self.style = style;
+        self.basic.needs_resize = true;
+    }
+
+    /// Sets the callback called with the new state when the user turns the toggle on or off.
+    pub fn set_on_change(&mut self, on_change: impl FnMut(bool, &mut EventContext) + 'static) {
+        self.on_change = Some(Box::new(on_change));
+    }
+
+    /// Emits the message `message` returns for the new state every time the user turns the toggle on or off.
+    pub fn set_on_change_message<M: 'static>(&mut self, message: impl Fn(bool) -> M + 'static) {
+        self.set_on_change(move |on, context| context.emit(message(on)));
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.pressable.is_enabled()
+    }
+
+    pub fn set_enabled(&mut self, enabled: bool) {
+        self.pressable.set_enabled(enabled);
+    }
+
+    pub fn get_focus_id(&self) -> FocusId {
+        self.pressable.get_focus_id()
+    }
+
+    fn get_element_state(&self) -> ElementState {
+        self.pressable.get_element_state(self.on)
+    }
+
+    /// Half the height of the track in pixels, the radius of its rounded ends.
+    fn get_track_radius(&self) -> f32 {
+        let size = self.basic.placement.pixel_size();
+        size.0.min(size.1) / 2.0
+    }
+
+    fn toggle(&mut self, context: &mut EventContext) {
+        self.on = !self.on;
+
+        if let Some(on_change) = self.on_change.as_mut() {
+            on_change(self.on, context);
+        }
+    }
+
+    fn handle_event(&mut self, event: &ComponentEvent, context: &mut EventContext) -> bool {
+        let inside = event.position().is_some_and(|position| self.in_bound(position));
+        let press = self.pressable.handle_event(event, inside, context);
+
+        if press == Press::Activated {
+            self.toggle(context);
+        }
+        press.is_consumed()
+    }
+
+    fn create_shapes(&mut self, style: ResolvedCheckStyle, context: &RenderContext) {
+        let placement = &self.basic.placement;
+        let top_left = placement.absolute_top_left;
+        let bottom_right = placement.absolute_bottom_right;
+
+        let radius_pixels = self.get_track_radius();
+        let radius = placement.radius_to_view(radius_pixels);
+        let inset = placement.radius_to_view(style.border_width);
+        let inner_radius = placement.radius_to_view((radius_pixels - style.border_width).max(0.0));
+
+        self.border = (style.border_width > 0.0).then(|| RoundedQuad::new(top_left,
+                                                                         bottom_right,
+                                                                         radius,
+                                                                         style.border,
+                                                                         StencilMode::Test,
+                                                                         context.device,
+                                                                         context.pipelines));
+        self.track = Some(RoundedQuad::new((top_left.0 + inset.0, top_left.1 - inset.1),
+                                           (bottom_right.0 - inset.0, bottom_right.1 + inset.1),
+                                           inner_radius,
+                                           style.fill,
+                                           StencilMode::Test,
+                                           context.device,
+                                           context.pipelines));
+
+        // The knob sits in the rounded end on the left and travels to the one on the right
+        let center = (top_left.0 + radius.0, (top_left.1 + bottom_right.1) / 2.0);
+        let knob_radius = placement.radius_to_view((radius_pixels - style.border_width - KNOB_PADDING).max(1.0));
+        self.knob = Some(Oval::new(center, knob_radius, 32, style.mark, context.device, context.pipelines));
+        self.knob_travel = (bottom_right.0 - top_left.0 - 2.0 * radius.0).max(0.0);
+    }
+}
+
+impl Component for Toggle {
+    fn render<'a>(&'a mut self, parent_top_left: &(f32, f32), parent_bottom_right: &(f32, f32), render_pass: &mut RenderPass<'a>, context: &RenderContext) {
+        self.basic.placement.update(*parent_top_left, *parent_bottom_right, self.basic.top_left, self.basic.bottom_right, &self.basic.transform, context);
+
+        self.pressable.update_focus(context.focused);
+        let sheet = context.stylesheet.compute(&context.get_style_path(StyleElement {
+            type_name: self.get_type_name(),
+            id: self.basic.id.as_deref(),
+            classes: &self.basic.classes,
+            state: self.get_element_state(),
+        }));
+        let target = self.style.resolve(self.on, self.pressable.is_focused(), &sheet, context.theme);
+
+        let now = context.clock.get_now();
+        self.track_color.set(target.fill, now);
+        self.border_color.set(target.border, now);
+        self.knob_position.set(if self.on { 1.0 } else { 0.0 }, now);
+
+        let style = ResolvedCheckStyle {
+            fill: self.track_color.get(now),
+            border: self.border_color.get(now),
+            ..target
+        };
+
+        if self.basic.needs_resize || self.drawn_style != Some(style) {
+            self.create_shapes(style, context);
+
+            self.drawn_style = Some(style);
+            self.basic.needs_resize = false;
+        }
+
+        if self.track_color.is_active(now) || self.border_color.is_active(now) || self.knob_position.is_active(now) {
+            context.invalidation.invalidate_placement(&self.basic.placement);
+        }
+
+        let world_transform = self.basic.placement.world_transform;
+        let opacity = if self.pressable.is_enabled() { context.opacity } else { context.opacity * DISABLED_OPACITY };
+
+        for shape in self.border.iter_mut().chain(self.track.iter_mut()) {
+            shape.set_transform(world_transform, context.queue);
+            shape.set_opacity(opacity, context.queue);
+            shape.draw(render_pass);
+        }
+
+        if let Some(knob) = self.knob.as_mut() {
+            let offset = Transform::translation(self.knob_position.get(now) * self.knob_travel, 0.0);
+            knob.set_transform(offset.then(&world_transform), context.queue);
+            knob.set_opacity(opacity, context.queue);
+            knob.draw(render_pass);
+        }
+    }
+
+    fn get_top_left(&self) -> (f32, f32) {
+        self.basic.top_left
+    }
+
+    fn get_bottom_right(&self) -> (f32, f32) {
+        self.basic.bottom_right
+    }
+
+    fn get_transform(&self) -> Transform {
+        self.basic.transform
+    }
+
+    fn set_transform(&mut self, transform: Transform) {
+        self.basic.transform = transform;
+    }
+
+    fn get_z_index(&self) -> i32 {
+        self.basic.z_index
+    }
+
+    fn set_z_index(&mut self, z_index: i32) {
+        self.basic.z_index = z_index;
+    }
+
+    fn get_key(&self) -> Option<&str> {
+        self.basic.key.as_deref()
+    }
+
+    fn set_key(&mut self, key: &str) {
+        self.basic.key = Some(key.to_string());
+    }
+
+    fn get_id(&self) -> Option<&str> {
+        self.basic.id.as_deref()
+    }
+
+    fn set_id(&mut self, id: &str) {
+        self.basic.id = Some(id.to_string());
+    }
+
+    fn get_classes(&self) -> &[String] {
+        &self.basic.classes
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.basic.add_class(class);
+    }
+
+    fn get_dirty_flags(&self) -> DirtyFlags {
+        DirtyFlags {
+            needs_resize: self.basic.needs_resize,
+            ..DirtyFlags::default()
+        }
+    }
+
+    fn get_event_handlers(&self) -> Vec<&'static str> {
+        self.on_change.iter().map(|_| "on_change").collect()
+    }
+
+    fn get_placement(&self) -> &ComponentPlacement {
+        &self.basic.placement
+    }
+
+    fn layout(&mut self, parent: &ComponentPlacement) {
+        self.basic.placement.update_in(parent, self.basic.top_left, self.basic.bottom_right, &self.basic.transform);
+    }
+
+    fn on_event(&mut self, event: &ComponentEvent, context: &mut EventContext) -> bool {
+        let state = self.get_element_state();
+        let consumed = self.handle_event(event, context);
+
+        if self.get_element_state() != state {
+            context.invalidate(&self.basic.placement);
+        }
+
+        consumed
+    }
+
+    fn reconcile(&mut self, new: Box<dyn Component>) -> Result<(), Box<dyn Component>> {
+        let new = *downcast_component::<Self>(new)?;
+
+        // The focus id, hover and press state and the knob animation are kept, the state comes from the new view
+        self.basic.reconcile(new.basic);
+        if self.style != new.style {
+            self.set_check_style(new.style);
+        }
+        self.on = new.on;
+        self.set_enabled(new.pressable.is_enabled());
+        self.on_change = new.on_change;
+
+        Ok(())
+    }
+
+    fn in_bound(&self, point: (f32, f32)) -> bool {
+        self.basic.placement.contains_rounded(point, self.get_track_radius())
+    }
+
+    fn resize(&mut self, new_box_top_left: (f32, f32), new_box_bottom_right: (f32, f32)) {
+        self.basic.top_left = new_box_top_left;
+        self.basic.bottom_right = new_box_bottom_right;
+
+        self.on_resize();
+    }
+
+    fn on_resize(&mut self) {
+        self.basic.needs_resize = true;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use winit::event::{MouseButton, VirtualKeyCode};
+    use super::*;
+    use crate::components::component::layout;
+
+    fn toggle() -> Toggle {
+        let mut toggle = Toggle::new((-1.0, 1.0), (1.0, -1.0), false);
+        toggle.set_on_change_message(|on| on);
+        layout(&mut toggle, (100, 50));
+        toggle
+    }
+
+    fn messages(context: &mut EventContext) -> Vec<bool> {
+        context.take_messages().into_iter().filter_map(|message| message.downcast::<bool>().ok()).map(|message| *message).collect()
+    }
+
+    #[test]
+    fn clicking_flips_the_toggle() {
+        let mut toggle = toggle();
+        let mut context = EventContext::new(None);
+
+        for _ in 0..2 {
+            toggle.on_event(&ComponentEvent::MousePressed { position: (0.0, 0.0), button: MouseButton::Left }, &mut context);
+            toggle.on_event(&ComponentEvent::MouseReleased { position: (0.0, 0.0), button: MouseButton::Left }, &mut context);
+        }
+        assert_eq!(messages(&mut context), vec![true, false]);
+        assert!(!toggle.is_on());
+
+        // Releasing outside cancels the click
+        toggle.on_event(&ComponentEvent::MousePressed { position: (0.0, 0.0), button: MouseButton::Left }, &mut context);
+        toggle.on_event(&ComponentEvent::MouseReleased { position: (0.0, 2.0), button: MouseButton::Left }, &mut context);
+        assert!(!toggle.is_on());
+    }
+
+    #[test]
+    fn space_flips_the_focused_toggle_on_release() {
+        let mut toggle = toggle();
+        let mut context = EventContext::new(None);
+        assert!(!toggle.on_event(&ComponentEvent::KeyPressed { key: VirtualKeyCode::Space }, &mut context));
+
+        let mut context = EventContext::new(Some(toggle.get_focus_id()));
+        assert!(!toggle.on_event(&ComponentEvent::KeyPressed { key: VirtualKeyCode::Return }, &mut context));
+        assert!(toggle.on_event(&ComponentEvent::KeyPressed { key: VirtualKeyCode::Space }, &mut context));
+        assert!(!toggle.is_on());
+        assert!(toggle.on_event(&ComponentEvent::KeyReleased { key: VirtualKeyCode::Space }, &mut context));
+        assert!(toggle.is_on());
+        assert_eq!(messages(&mut context), vec![true]);
+    }
+}
@@ -0,0 +1,559 @@
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+use wgpu::RenderPass;
+use crate::Shape;
+use crate::animation::Easing;
+use crate::components::component::{Component, ComponentPlacement, DirtyFlags, Rect, RenderContext, downcast_component};
+use crate::components::event::{ComponentEvent, EventContext};
+use crate::components::overlay::OverlayLayer;
+use crate::components::reconcile::reconcile_component;
+use crate::shapes::pipeline::StencilMode;
+use crate::shapes::rounded_quad::RoundedQuad;
+use crate::style::Style;
+use crate::transform::Transform;
+
+/// Builds the component drawn inside a tooltip. Its rectangle is relative to the popup.
+pub type TooltipBuilder = Rc<dyn Fn() -> Box<dyn Component>>;
+
+const FADE_DURATION: Duration = Duration::from_millis(100);
+/// Space between the component and its tooltip in pixels.
+const GAP: f32 = 6.0;
+/// Tooltips keep this distance to the edges of the window in pixels.
+const MARGIN: f32 = 4.0;
+/// Space around the text in pixels.
+const PADDING: f32 = 6.0;
+/// The average width of a character relative to the font size, to size popups without a given size.
+const CHARACTER_WIDTH: f32 = 0.55;
+
+/// The side of the component a tooltip prefers. It flips to the opposite side if it does not fit there.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub enum TooltipSide {
+    Above,
+    #[default]
+    Below,
+    Left,
+    Right,
+}
+
+/// How a tooltip looks. Sizes are given in pixels.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct TooltipStyle {
+    pub background: [f32; 3],
+    pub border: [f32; 3],
+    pub border_width: f32,
+    pub corner_radius: f32,
+}
+
+impl Default for TooltipStyle {
+    fn default() -> Self {
+        Self {
+            background: [0.1, 0.1, 0.1],
+            border: [0.4, 0.4, 0.4],
+            border_width: 1.0,
+            corner_radius: 4.0,
+        }
+    }
+}
+
+/// What a component shows while the pointer rests on it.
+#[derive(Clone)]
+pub struct TooltipOptions {
+    pub text: String,
+    /// How long the pointer has to rest on the component.
+    pub delay: Duration,
+    pub side: TooltipSide,
+    /// The size of the popup in pixels. Without it, the popup is sized to fit the text.
+    pub size: Option<(f32, f32)>,
+    /// Draws the inside of the popup. There is no text rendering, so the text alone only sizes the popup.
+    pub content: Option<TooltipBuilder>,
+    pub style: TooltipStyle,
+}
+
+impl TooltipOptions {
+    pub fn new(text: &str) -> Self {
+        Self {
+            text: text.to_string(),
+            delay: Duration::from_millis(500),
+            side: TooltipSide::default(),
+            size: None,
+            content: None,
+            style: TooltipStyle::default(),
+        }
+    }
+
+    /// The size of the popup in pixels for text of the given font size.
+    fn get_size(&self, font_size: f32) -> (f32, f32) {
+        self.size.unwrap_or_else(|| {
+            let characters = self.text.chars().count() as f32;
+            (characters * font_size * CHARACTER_WIDTH + 2.0 * PADDING, font_size * 1.3 + 2.0 * PADDING)
+        })
+    }
+}
+
+/// Attaches a tooltip to any component. The wrapper is invisible to the tree and forwards everything to it.
+pub struct Tooltip {
+    component: Box<dyn Component>,
+    options: TooltipOptions,
+}
+
+impl Tooltip {
+    pub fn new(component: Box<dyn Component>, text: &str) -> Self {
+        Self {
+            component,
+            options: TooltipOptions::new(text),
+        }
+    }
+
+    pub fn get_component(&self) -> &dyn Component {
+        self.component.as_ref()
+    }
+
+    pub fn get_component_mut(&mut self) -> &mut Box<dyn Component> {
+        &mut self.component
+    }
+
+    pub fn get_text(&self) -> &str {
+        &self.options.text
+    }
+
+    pub fn set_text(&mut self, text: &str) {
+        self.options.text = text.to_string();
+    }
+
+    pub fn get_delay(&self) -> Duration {
+        self.options.delay
+    }
+
+    pub fn set_delay(&mut self, delay: Duration) {
+        self.options.delay = delay;
+    }
+
+    pub fn get_side(&self) -> TooltipSide {
+        self.options.side
+    }
+
+    pub fn set_side(&mut self, side: TooltipSide) {
+        self.options.side = side;
+    }
+
+    /// Sets the size of the popup in pixels.
+    pub fn set_size(&mut self, size: Option<(f32, f32)>) {
+        self.options.size = size;
+    }
+
+    /// Sets what is drawn inside the popup, built every time it opens.
+    pub fn set_content(&mut self, content: impl Fn() -> Box<dyn Component> + 'static) {
+        self.options.content = Some(Rc::new(content));
+    }
+
+    pub fn get_tooltip_style(&self) -> &TooltipStyle {
+        &self.options.style
+    }
+
+    pub fn set_tooltip_style(&mut self, style: TooltipStyle) {
+        self.options.style = style;
+    }
+}
+
+impl Component for Tooltip {
+    fn render<'a>(&'a mut self, parent_top_left: &(f32, f32), parent_bottom_right: &(f32, f32), render_pass: &mut RenderPass<'a>, context: &RenderContext) {
+        self.component.render(parent_top_left, parent_bottom_right, render_pass, context);
+    }
+
+    fn get_top_left(&self) -> (f32, f32) {
+        self.component.get_top_left()
+    }
+
+    fn get_bottom_right(&self) -> (f32, f32) {
+        self.component.get_bottom_right()
+    }
+
+    fn get_transform(&self) -> Transform {
+        self.component.get_transform()
+    }
+
+    fn set_transform(&mut self, transform: Transform) {
+        self.component.set_transform(transform);
+    }
+
+    fn get_z_index(&self) -> i32 {
+        self.component.get_z_index()
+    }
+
+    fn set_z_index(&mut self, z_index: i32) {
+        self.component.set_z_index(z_index);
+    }
+
+    fn get_key(&self) -> Option<&str> {
+        self.component.get_key()
+    }
+
+    fn set_key(&mut self, key: &str) {
+        self.component.set_key(key);
+    }
+
+    fn get_type_name(&self) -> &'static str {
+        self.component.get_type_name()
+    }
+
+    fn get_id(&self) -> Option<&str> {
+        self.component.get_id()
+    }
+
+    fn set_id(&mut self, id: &str) {
+        self.component.set_id(id);
+    }
+
+    fn get_classes(&self) -> &[String] {
+        self.component.get_classes()
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.component.add_class(class);
+    }
+
+    fn get_style(&self) -> Option<&Style> {
+        self.component.get_style()
+    }
+
+    fn get_children(&self) -> Vec<&dyn Component> {
+        self.component.get_children()
+    }
+
+    fn get_dirty_flags(&self) -> DirtyFlags {
+        self.component.get_dirty_flags()
+    }
+
+    fn get_event_handlers(&self) -> Vec<&'static str> {
+        self.component.get_event_handlers()
+    }
+
+    fn get_tooltip(&self) -> Option<&TooltipOptions> {
+        Some(&self.options)
+    }
+
+    fn reconcile(&mut self, new: Box<dyn Component>) -> Result<(), Box<dyn Component>> {
+        let new = downcast_component::<Self>(new)?;
+
+        self.options = new.options;
+        reconcile_component(&mut self.component, new.component);
+        Ok(())
+    }
+
+    fn get_placement(&self) -> &ComponentPlacement {
+        self.component.get_placement()
+    }
+
+    fn layout(&mut self, parent: &ComponentPlacement) {
+        self.component.layout(parent);
+    }
+
+    fn on_event(&mut self, event: &ComponentEvent, context: &mut EventContext) -> bool {
+        self.component.on_event(event, context)
+    }
+
+    fn in_bound(&self, point: (f32, f32)) -> bool {
+        self.component.in_bound(point)
+    }
+
+    fn resize(&mut self, new_box_top_left: (f32, f32), new_box_bottom_right: (f32, f32)) {
+        self.component.resize(new_box_top_left, new_box_bottom_right);
+    }
+
+    fn on_resize(&mut self) {
+        self.component.on_resize();
+    }
+}
+
+/// The component the pointer rests on and since when.
+struct Hover {
+    /// Only compared, to notice when the pointer moves to another component.
+    target: *const (),
+    options: TooltipOptions,
+    /// The bounds of the transformed component in view coordinates.
+    anchor: Rect,
+    since: Instant,
+}
+
+struct Popup {
+    shown: Instant,
+    rect: Rect,
+    border: Option<RoundedQuad>,
+    background: Option<RoundedQuad>,
+    content: Option<Box<dyn Component>>,
+}
+
+/// Shows the tooltip of the component under the pointer once it rested there long enough.
+/// It hides when the pointer leaves the component, and after a press until the pointer leaves it.
+#[derive(Default)]
+pub struct TooltipManager {
+    hover: Option<Hover>,
+    popup: Option<Popup>,
+    /// The component pressed last, its tooltip stays hidden while the pointer is on it.
+    pressed: Option<*const ()>,
+}
+
+impl TooltipManager {
+    pub fn new() -> Self {
+        Self {
+            hover: None,
+            popup: None,
+            pressed: None,
+        }
+    }
+
+    pub fn is_shown(&self) -> bool {
+        self.popup.is_some()
+    }
+
+    /// The content of the shown tooltip, if it has one.
+    pub fn get_content(&self) -> Option<&dyn Component> {
+        self.popup.as_ref().and_then(|popup| popup.content.as_deref())
+    }
+
+    /// Follows the pointer after an event was handled. Returns whether a shown tooltip was hidden.
+    pub fn on_event(&mut self, event: &ComponentEvent, root: &dyn Component, overlays: &OverlayLayer) -> bool {
+        match event {
+            ComponentEvent::CursorMoved { position } => {
+                // A modal overlay covers the whole window, so nothing below it is hovered
+                let target = match overlays.get_component_at(*position) {
+                    Some(overlay) => find_tooltip(overlay, *position),
+                    None => find_tooltip(root, *position),
+                };
+                let address = target.map(|target| target as *const dyn Component as *const ());
+
+                if self.pressed.is_some() && self.pressed != address {
+                    self.pressed = None;
+                }
+                if address == self.hover.as_ref().map(|hover| hover.target) {
+                    return false;
+                }
+
+                let hidden = self.hide();
+                self.hover = target.filter(|_| self.pressed.is_none()).and_then(|target| {
+                    Some(Hover {
+                        target: target as *const dyn Component as *const (),
+                        options: target.get_tooltip()?.clone(),
+                        anchor: get_bounds(target.get_placement()),
+                        since: Instant::now(),
+                    })
+                });
+                hidden
+            }
+            ComponentEvent::MousePressed { .. } => {
+                self.pressed = self.hover.as_ref().map(|hover| hover.target);
+                self.hide()
+            }
+            _ => false,
+        }
+    }
+
+    /// Hides the tooltip, for example when the pointer leaves the window. Returns whether it was shown.
+    pub fn hide(&mut self) -> bool {
+        self.hover = None;
+        self.popup.take().is_some()
+    }
+
+    /// Whether the pointer rested long enough at `now` and the tooltip has to be drawn.
+    pub fn is_due(&self, now: Instant) -> bool {
+        self.popup.is_none() && self.get_next_deadline().is_some_and(|deadline| deadline <= now)
+    }
+
+    /// When the tooltip under the pointer opens, so the window can sleep until then.
+    pub fn get_next_deadline(&self) -> Option<Instant> {
+        match (&self.hover, &self.popup) {
+            (Some(hover), None) => Some(hover.since + hover.options.delay),
+            _ => None,
+        }
+    }
+
+    /// Draws the tooltip, kept inside a window of `window_size` pixels.
+    pub fn render<'a>(&'a mut self, render_pass: &mut RenderPass<'a>, context: &RenderContext, window_size: (u32, u32)) {
+        let now = context.clock.get_now();
+        let hover = match self.hover.as_ref() {
+            Some(hover) => hover,
+            None => return,
+        };
+        if self.popup.is_none() && now < hover.since + hover.options.delay {
+            return;
+        }
+
+        let popup = self.popup.get_or_insert_with(|| {
+            let size = hover.options.get_size(context.theme.font.size);
+            let rect = place_tooltip(hover.anchor, size, hover.options.side, window_size);
+            create_popup(rect, &hover.options, window_size, now, context)
+        });
+
+        let fade = Easing::EaseOut.apply(now.saturating_duration_since(popup.shown).as_secs_f32() / FADE_DURATION.as_secs_f32(), FADE_DURATION);
+        if fade < 1.0 {
+            context.invalidation.request_redraw();
+        }
+
+        let opacity = context.opacity * fade;
+        for shape in popup.border.iter_mut().chain(popup.background.iter_mut()) {
+            shape.set_transform(context.transform, context.queue);
+            shape.set_opacity(opacity, context.queue);
+            shape.draw(render_pass);
+        }
+
+        if let Some(content) = popup.content.as_mut() {
+            content.render(&popup.rect.0, &popup.rect.1, render_pass, &context.with_opacity(opacity));
+        }
+    }
+}
+
+fn create_popup(rect: Rect, options: &TooltipOptions, window_size: (u32, u32), now: Instant, context: &RenderContext) -> Popup {
+    let style = options.style;
+    let placement = ComponentPlacement {
+        absolute_top_left: rect.0,
+        absolute_bottom_right: rect.1,
+        world_transform: context.transform,
+        window_size,
+    };
+    let (top_left, bottom_right) = rect;
+    let inset = placement.radius_to_view(style.border_width);
+    let radius = placement.radius_to_view(style.corner_radius);
+    let inner_radius = placement.radius_to_view((style.corner_radius - style.border_width).max(0.0));
+
+    Popup {
+        shown: now,
+        rect,
+        border: (style.border_width > 0.0).then(|| RoundedQuad::new(top_left,
+                                                                   bottom_right,
+                                                                   radius,
+                                                                   style.border,
+                                                                   StencilMode::Test,
+                                                                   context.device,
+                                                                   context.pipelines)),
+        background: Some(RoundedQuad::new((top_left.0 + inset.0, top_left.1 - inset.1),
+                                          (bottom_right.0 - inset.0, bottom_right.1 + inset.1),
+                                          inner_radius,
+                                          style.background,
+                                          StencilMode::Test,
+                                          context.device,
+                                          context.pipelines)),
+        content: options.content.as_ref().map(|content| content()),
+    }
+}
+
+/// The innermost component with a tooltip under `position`.
+fn find_tooltip(component: &dyn Component, position: (f32, f32)) -> Option<&dyn Component> {
+    for child in component.get_children().into_iter().rev() {
+        if let Some(found) = find_tooltip(child, position) {
+            return Some(found);
+        }
+    }
+
+    let placement = component.get_placement();
+    let rendered = placement.window_size != (0, 0);
+    (component.get_tooltip().is_some() && rendered && placement.contains(position)).then_some(component)
+}
+
+/// The axis-aligned bounds of the transformed rectangle of a component.
+fn get_bounds(placement: &ComponentPlacement) -> Rect {
+    let ((left, top), (right, bottom)) = (placement.absolute_top_left, placement.absolute_bottom_right);
+    let corners = [(left, top), (right, top), (left, bottom), (right, bottom)].map(|corner| placement.world_transform.apply(corner));
+
+    let min = corners.iter().fold((f32::MAX, f32::MAX), |min, corner| (min.0.min(corner.0), min.1.min(corner.1)));
+    let max = corners.iter().fold((f32::MIN, f32::MIN), |max, corner| (max.0.max(corner.0), max.1.max(corner.1)));
+    ((min.0, max.1), (max.0, min.1))
+}
+
+/// Places a popup of `size` pixels next to `anchor` on the preferred `side`, or on the opposite one
+/// if it only fits there, and moves it along the side to stay inside the window.
+fn place_tooltip(anchor: Rect, size: (f32, f32), side: TooltipSide, window_size: (u32, u32)) -> Rect {
+    let window = (window_size.0 as f32, window_size.1 as f32);
+    // Pixels with y down
+    let to_pixels = |(x, y): (f32, f32)| ((x + 1.0) / 2.0 * window.0, (1.0 - y) / 2.0 * window.1);
+    let to_view = |(x, y): (f32, f32)| (x / window.0 * 2.0 - 1.0, 1.0 - y / window.1 * 2.0);
+    let (anchor_min, anchor_max) = (to_pixels(anchor.0), to_pixels(anchor.1));
+
+    // The start of the popup along an axis, on the preferred side of the anchor unless it only fits on the other one
+    let place = |before: bool, start: f32, end: f32, length: f32, window: f32| {
+        let before_start = start - GAP - length;
+        let after_start = end + GAP;
+        let fits_before = before_start >= MARGIN;
+        let fits_after = after_start + length <= window - MARGIN;
+
+        let flip = if before { !fits_before && fits_after } else { !fits_after && fits_before };
+        if before != flip { before_start } else { after_start }
+    };
+    let center = |start: f32, end: f32, length: f32, window: f32| {
+        ((start + end - length) / 2.0).min(window - MARGIN - length).max(MARGIN)
+    };
+
+    let top_left = match side {
+        TooltipSide::Above | TooltipSide::Below => (
+            center(anchor_min.0, anchor_max.0, size.0, window.0),
+            place(side == TooltipSide::Above, anchor_min.1, anchor_max.1, size.1, window.1),
+        ),
+        TooltipSide::Left | TooltipSide::Right => (
+            place(side == TooltipSide::Left, anchor_min.0, anchor_max.0, size.0, window.0),
+            center(anchor_min.1, anchor_max.1, size.1, window.1),
+        ),
+    };
+
+    (to_view(top_left), to_view((top_left.0 + size.0, top_left.1 + size.1)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: (u32, u32) = (200, 100);
+
+    /// Places a tooltip in a 200 by 100 pixel window, with the anchor and the result in pixels with y down.
+    fn place(anchor: Rect, size: (f32, f32), side: TooltipSide) -> Rect {
+        let to_view = |(x, y): (f32, f32)| (x / 100.0 - 1.0, 1.0 - y / 50.0);
+        let to_pixels = |(x, y): (f32, f32)| ((x + 1.0) * 100.0, (1.0 - y) * 50.0);
+        let (top_left, bottom_right) = place_tooltip((to_view(anchor.0), to_view(anchor.1)), size, side, WINDOW);
+        (to_pixels(top_left), to_pixels(bottom_right))
+    }
+
+    fn assert_close(actual: Rect, expected: Rect) {
+        let close = |a: (f32, f32), b: (f32, f32)| (a.0 - b.0).abs() < 1e-3 && (a.1 - b.1).abs() < 1e-3;
+        assert!(close(actual.0, expected.0) && close(actual.1, expected.1), "{:?} != {:?}", actual, expected);
+    }
+
+    #[test]
+    fn tooltips_stay_on_the_preferred_side_when_they_fit() {
+        let anchor = ((80.0, 40.0), (120.0, 60.0));
+        assert_close(place(anchor, (40.0, 20.0), TooltipSide::Below), ((80.0, 66.0), (120.0, 86.0)));
+        assert_close(place(anchor, (40.0, 20.0), TooltipSide::Above), ((80.0, 14.0), (120.0, 34.0)));
+        assert_close(place(anchor, (40.0, 20.0), TooltipSide::Left), ((34.0, 40.0), (74.0, 60.0)));
+        assert_close(place(anchor, (40.0, 20.0), TooltipSide::Right), ((126.0, 40.0), (166.0, 60.0)));
+    }
+
+    #[test]
+    fn tooltips_flip_at_the_top_and_bottom_edges() {
+        let top = ((80.0, 10.0), (120.0, 30.0));
+        assert_close(place(top, (40.0, 20.0), TooltipSide::Above), ((80.0, 36.0), (120.0, 56.0)));
+
+        let bottom = ((80.0, 70.0), (120.0, 90.0));
+        assert_close(place(bottom, (40.0, 20.0), TooltipSide::Below), ((80.0, 44.0), (120.0, 64.0)));
+    }
+
+    #[test]
+    fn tooltips_flip_at_the_side_edges() {
+        let left = ((10.0, 40.0), (30.0, 60.0));
+        assert_close(place(left, (50.0, 20.0), TooltipSide::Left), ((36.0, 40.0), (86.0, 60.0)));
+
+        let right = ((170.0, 40.0), (190.0, 60.0));
+        assert_close(place(right, (50.0, 20.0), TooltipSide::Right), ((114.0, 40.0), (164.0, 60.0)));
+    }
+
+    #[test]
+    fn tooltips_that_fit_on_neither_side_keep_the_preferred_one() {
+        let anchor = ((80.0, 30.0), (120.0, 70.0));
+        assert_close(place(anchor, (40.0, 30.0), TooltipSide::Below), ((80.0, 76.0), (120.0, 106.0)));
+    }
+
+    #[test]
+    fn tooltips_are_moved_along_the_side_to_stay_inside_the_window() {
+        let corner = ((0.0, 0.0), (10.0, 10.0));
+        assert_close(place(corner, (60.0, 20.0), TooltipSide::Below), ((4.0, 16.0), (64.0, 36.0)));
+
+        let corner = ((190.0, 90.0), (200.0, 100.0));
+        assert_close(place(corner, (30.0, 40.0), TooltipSide::Left), ((154.0, 56.0), (184.0, 96.0)));
+    }
+}
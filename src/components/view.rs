@@ -0,0 +1,109 @@
+use crate::components::component::Component;
+use crate::components::layout::LayoutComponent;
+use crate::components::scroll_view::ScrollView;
+
+/// Builder methods available on every component, so a tree can be described in one expression.
+pub trait ViewBuilder: Component + Sized + 'static {
+    /// Applies `f` to the component and returns it, e.g. to call setters while building.
+    fn with(mut self, f: impl FnOnce(&mut Self)) -> Self {
+        f(&mut self);
+        self
+    }
+
+    fn boxed(self) -> Box<dyn Component> {
+        Box::new(self)
+    }
+}
+
+impl<C: Component + 'static> ViewBuilder for C {}
+
+/// Components that hold a list of children.
+pub trait Container: Component {
+    fn add_component(&mut self, component: Box<dyn Component>);
+
+    fn child(mut self, component: impl Component + 'static) -> Self where Self: Sized {
+        Container::add_component(&mut self, Box::new(component));
+        self
+    }
+}
+
+impl Container for LayoutComponent {
+    fn add_component(&mut self, component: Box<dyn Component>) {
+        LayoutComponent::add_component(self, component);
+    }
+}
+
+impl Container for ScrollView {
+    fn add_component(&mut self, component: Box<dyn Component>) {
+        ScrollView::add_component(self, component);
+    }
+}
+
+/// Builds a boxed component tree. Every component is an expression, optionally followed by `=>` and a body
+/// of method calls, which are called on it in order, and a list of children, which are added with `Container::add_component`.
+///
+/// ```
+/// use rui::view;
+/// use rui::components::button::Button;
+/// use rui::components::clip::Overflow;
+/// use rui::components::layout::LayoutComponent;
+/// use rui::components::plain::PlainComponent;
+///
+/// enum Message {
+///     Pressed,
+/// }
+///
+/// let tree = view! {
+///     LayoutComponent::new((-1.0, 1.0), (1.0, -1.0)) => {
+///         .set_overflow(Overflow::Clip)
+///         .set_id("root")
+///         [
+///             PlainComponent::new((-1.0, 1.0), (0.0, -1.0), [1.0, 0.0, 0.0]),
+///             Button::new((0.0, 1.0), (1.0, -1.0)) => {
+///                 .set_key("ok")
+///                 .set_on_press(|context| context.emit(Message::Pressed))
+///             },
+///         ]
+///     }
+/// };
+///
+/// let children = tree.get_children();
+/// assert_eq!(tree.get_id(), Some("root"));
+/// assert_eq!(children.len(), 2);
+/// assert_eq!(children[0].get_type_name(), "PlainComponent");
+/// assert_eq!(children[1].get_key(), Some("ok"));
+/// ```
+#[macro_export]
+macro_rules! view {
+    (@body $component:ident; ) => {};
+    (@body $component:ident; . $method:ident ( $($args:tt)* ) $($rest:tt)*) => {
+        $component.$method($($args)*);
+        $crate::view!(@body $component; $($rest)*);
+    };
+    (@body $component:ident; [ $($children:tt)* ] $($rest:tt)*) => {
+        $crate::view!(@children $component; $($children)*);
+        $crate::view!(@body $component; $($rest)*);
+    };
+
+    (@children $component:ident; ) => {};
+    (@children $component:ident; $child:expr => { $($body:tt)* } $(, $($rest:tt)*)?) => {
+        $crate::components::view::Container::add_component(&mut $component, $crate::view!($child => { $($body)* }));
+        $crate::view!(@children $component; $($($rest)*)?);
+    };
+    (@children $component:ident; $child:expr $(, $($rest:tt)*)?) => {
+        $crate::components::view::Container::add_component(&mut $component, $crate::view!($child));
+        $crate::view!(@children $component; $($($rest)*)?);
+    };
+
+    ($component:expr => { $($body:tt)* }) => {{
+        #[allow(unused_imports)]
+        use $crate::components::component::Component as _;
+
+        let mut component = $component;
+        $crate::view!(@body component; $($body)*);
+        ::std::boxed::Box::new(component) as ::std::boxed::Box<dyn $crate::components::component::Component>
+    }};
+    ($component:expr) => {
+        ::std::boxed::Box::new($component) as ::std::boxed::Box<dyn $crate::components::component::Component>
+    };
+}
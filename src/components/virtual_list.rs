@@ -0,0 +1,748 @@
+use std::time::Instant;
+use wgpu::RenderPass;
+use winit::event::MouseButton;
+use crate::components::clip::{Clipper, Overflow};
+use crate::components::component::{Component, ComponentBasicData, ComponentPlacement, DirtyFlags, RenderContext, downcast_component};
+use crate::components::event::{ComponentEvent, EventContext};
+use crate::components::scroll_view::{FRICTION, MIN_THUMB_LENGTH, MIN_VELOCITY, SCROLLBAR_THICKNESS, Scrollbar};
+use crate::Shape;
+use crate::stylesheet::{ElementState, StyleElement};
+use crate::transform::Transform;
+
+pub type RowBuilder<R> = Box<dyn Fn(usize) -> R>;
+pub type RowUpdater<R> = Box<dyn Fn(usize, &mut R)>;
+pub type RowMeasure<R> = Box<dyn Fn(usize, &R) -> f32>;
+
+/// How the height of the rows of a `VirtualList` is determined.
+pub enum ItemHeight<R> {
+    /// Every row is this many pixels high.
+    Fixed(f32),
+    /// Every row is measured in pixels once it was built. Rows that were not built yet count with `estimate`.
+    Measured { estimate: f32, measure: RowMeasure<R> },
+}
+
+enum ListDrag {
+    Content { last_position: (f32, f32), last_time: Instant },
+    Thumb { last_position: (f32, f32) },
+}
+
+struct VirtualRow<R> {
+    index: usize,
+    component: R,
+}
+
+/// A vertical list that only keeps components for the rows that are visible.
+/// Rows that scroll out of view are handed back to `update` for a different index instead of being dropped.
+pub struct VirtualList<R: Component> {
+    basic: ComponentBasicData,
+
+    item_count: usize,
+    item_height: ItemHeight<R>,
+    /// Measured heights of the rows, only used with `ItemHeight::Measured`.
+    heights: Vec<Option<f32>>,
+    /// Top of every row in pixels followed by the total height, only used with `ItemHeight::Measured`.
+    row_tops: Vec<f32>,
+    /// The first row whose top has to be computed again.
+    row_tops_dirty_from: Option<usize>,
+
+    build: RowBuilder<R>,
+    update: RowUpdater<R>,
+
+    /// Number of rows instantiated above and below the visible ones.
+    overscan: usize,
+    rows: Vec<VirtualRow<R>>,
+    recycled: Vec<R>,
+
+    /// Scroll position in pixels.
+    offset: f32,
+    /// Top of the first instantiated row in pixels. Rows are laid out relative to it, so their
+    /// coordinates stay small however far the list is scrolled.
+    anchor: f32,
+    /// Kinetic scroll speed in pixels per second.
+    velocity: f32,
+    drag: Option<ListDrag>,
+    last_frame: Option<Instant>,
+
+    scrollbar: Option<Scrollbar>,
+    clipper: Clipper,
+}
+
+impl<R: Component> VirtualList<R> {
+    /// `build` creates the component for a row, `update` reuses a component of a row that went out of view for another row.
+    pub fn new(top_left: (f32, f32), bottom_right: (f32, f32), item_count: usize, item_height: ItemHeight<R>,
+               build: impl Fn(usize) -> R + 'static, update: impl Fn(usize, &mut R) + 'static) -> Self {
+        Self {
+            basic: ComponentBasicData {
+                top_left,
+                bottom_right,
+                transform: Transform::IDENTITY,
+                z_index: 0,
+                key: None,
+                id: None,
+                classes: vec![],
+                placement: ComponentPlacement::default(),
+            },
+            item_count,
+            item_height,
+            heights: vec![None; item_count],
+            row_tops: vec![],
+            row_tops_dirty_from: Some(0),
+            build: Box::new(build),
+            update: Box::new(update),
+            overscan: 3,
+            rows: vec![],
+            recycled: vec![],
+            offset: 0.0,
+            anchor: 0.0,
+            velocity: 0.0,
+            drag: None,
+            last_frame: None,
+            scrollbar: None,
+            clipper: Clipper::new(),
+        }
+    }
+
+    pub fn get_item_count(&self) -> usize {
+        self.item_count
+    }
+
+    /// Changes the number of items. All visible rows are updated as their data might have moved.
+    pub fn set_item_count(&mut self, item_count: usize) {
+        self.item_count = item_count;
+        self.heights = vec![None; item_count];
+        self.invalidate_row_tops(0);
+        self.refresh();
+    }
+
+    /// Calls `update` for every instantiated row, e.g. after the underlying data changed.
+    pub fn refresh(&mut self) {
+        let mut rows = std::mem::take(&mut self.rows);
+        rows.retain(|row| row.index < self.item_count);
+
+        for row in rows.iter_mut() {
+            (self.update)(row.index, &mut row.component);
+            self.measure(row.index, &row.component);
+        }
+
+        self.rows = rows;
+    }
+
+    pub fn set_overscan(&mut self, overscan: usize) {
+        self.overscan = overscan;
+    }
+
+    pub fn get_offset(&self) -> f32 {
+        self.offset
+    }
+
+    pub fn scroll_to(&mut self, offset: f32) {
+        self.offset = offset;
+        self.velocity = 0.0;
+        self.clamp_offset();
+    }
+
+    /// Scrolls so that the row with the given index is at the top of the list.
+    pub fn scroll_to_index(&mut self, index: usize) {
+        self.update_row_tops();
+        let row_top = self.row_top(index.min(self.item_count));
+        self.scroll_to(row_top);
+    }
+
+    fn measure(&mut self, index: usize, component: &R) {
+        if let ItemHeight::Measured { measure, .. } = &self.item_height {
+            let height = measure(index, component);
+            if self.heights[index] != Some(height) {
+                self.heights[index] = Some(height);
+                // The top of the measured row itself did not change
+                self.invalidate_row_tops(index + 1);
+            }
+        }
+    }
+
+    fn invalidate_row_tops(&mut self, from: usize) {
+        self.row_tops_dirty_from = Some(self.row_tops_dirty_from.map_or(from, |dirty_from| dirty_from.min(from)));
+    }
+
+    /// Computes the tops of the rows again, starting at the first one that changed.
+    fn update_row_tops(&mut self) {
+        let from = match self.row_tops_dirty_from.take() {
+            None => return,
+            Some(from) => from,
+        };
+
+        if let ItemHeight::Measured { estimate, .. } = &self.item_height {
+            let from = from.min(self.item_count).min(self.row_tops.len().saturating_sub(1));
+            self.row_tops.truncate(from);
+            self.row_tops.reserve(self.item_count + 1 - from);
+
+            let mut top = self.row_tops.last().copied().unwrap_or(0.0) + match from {
+                0 => 0.0,
+                _ => self.heights[from - 1].unwrap_or(*estimate),
+            };
+            for height in self.heights[from..].iter() {
+                self.row_tops.push(top);
+                top += height.unwrap_or(*estimate);
+            }
+            self.row_tops.push(top);
+        }
+    }
+
+    /// Top of the row in pixels. The index one past the last row gives the total height.
+    fn row_top(&self, index: usize) -> f32 {
+        match &self.item_height {
+            ItemHeight::Fixed(height) => index as f32 * height,
+            ItemHeight::Measured { .. } => self.row_tops.get(index).copied().unwrap_or(0.0),
+        }
+    }
+
+    /// Index of the row at the given pixel offset.
+    fn row_at(&self, offset: f32) -> usize {
+        let index = match &self.item_height {
+            ItemHeight::Fixed(height) if *height > 0.0 => (offset / height).floor().max(0.0) as usize,
+            ItemHeight::Fixed(_) => 0,
+            ItemHeight::Measured { .. } => self.row_tops[..self.item_count.min(self.row_tops.len())].partition_point(|top| *top <= offset).saturating_sub(1),
+        };
+
+        index.min(self.item_count.saturating_sub(1))
+    }
+
+    fn total_height(&self) -> f32 {
+        self.row_top(self.item_count)
+    }
+
+    fn view_height(&self) -> f32 {
+        self.basic.placement.pixel_size().1
+    }
+
+    fn max_offset(&self) -> f32 {
+        (self.total_height() - self.view_height()).max(0.0)
+    }
+
+    fn clamp_offset(&mut self) {
+        // Before the first render the size of the view is unknown
+        if self.basic.placement.window_size == (0, 0) {
+            return;
+        }
+
+        self.offset = self.offset.clamp(0.0, self.max_offset());
+    }
+
+    /// Whether any part of the row is inside the view, unlike the rows instantiated for the overscan.
+    fn is_row_visible(&self, index: usize) -> bool {
+        self.row_top(index + 1) > self.offset && self.row_top(index) < self.offset + self.view_height()
+    }
+
+    /// Moves the rows, which are laid out relative to the anchor, to the scroll position.
+    fn get_row_transform(&self) -> Transform {
+        let scroll = Transform::translation(0.0, (self.offset - self.anchor) * 2.0 / self.basic.placement.window_size.1.max(1) as f32);
+        scroll.then(&self.basic.placement.world_transform)
+    }
+
+    fn progress(&self) -> f32 {
+        let max_offset = self.max_offset();
+        if max_offset > 0.0 { self.offset / max_offset } else { 0.0 }
+    }
+
+    fn handle_event(&mut self, event: &ComponentEvent, context: &mut EventContext) -> bool {
+        match *event {
+            ComponentEvent::CursorMoved { position } => {
+                // Rows of the overscan are clipped away, the cursor cannot be over them
+                let mut consumed = false;
+                for row_index in 0..self.rows.len() {
+                    if self.is_row_visible(self.rows[row_index].index) {
+                        consumed |= self.rows[row_index].component.on_event(event, context);
+                    }
+                }
+
+                if self.drag.is_some() {
+                    self.drag_to(position);
+                    return true;
+                }
+                consumed
+            }
+            ComponentEvent::MouseReleased { button, .. } => {
+                let mut consumed = false;
+                for row in self.rows.iter_mut() {
+                    consumed |= row.component.on_event(event, context);
+                }
+
+                if button != MouseButton::Left {
+                    return consumed;
+                }
+
+                match self.drag.take() {
+                    Some(ListDrag::Content { last_time, .. }) => {
+                        // Only keep the momentum of a drag that was still moving when it was released
+                        if last_time.elapsed().as_secs_f32() > 0.1 {
+                            self.velocity = 0.0;
+                        }
+                        self.last_frame = None;
+                        true
+                    }
+                    Some(ListDrag::Thumb { .. }) => true,
+                    None => consumed,
+                }
+            }
+            ComponentEvent::MousePressed { position, button } => {
+                if !self.in_bound(position) {
+                    return false;
+                }
+
+                if button == MouseButton::Left && self.press_scrollbar(position) {
+                    return true;
+                }
+
+                for row in self.rows.iter_mut() {
+                    if row.component.on_event(event, context) {
+                        return true;
+                    }
+                }
+
+                if button == MouseButton::Left {
+                    self.velocity = 0.0;
+                    self.drag = Some(ListDrag::Content { last_position: position, last_time: Instant::now() });
+                    return true;
+                }
+
+                false
+            }
+            ComponentEvent::MouseWheel { position, delta } => {
+                if !self.in_bound(position) {
+                    return false;
+                }
+
+                for row in self.rows.iter_mut() {
+                    if row.component.on_event(event, context) {
+                        return true;
+                    }
+                }
+
+                let before = self.offset;
+                self.scroll_to(self.offset - delta.1);
+                before != self.offset
+            }
+            ComponentEvent::KeyPressed { .. } | ComponentEvent::KeyReleased { .. } => {
+                let mut consumed = false;
+                for row in self.rows.iter_mut() {
+                    consumed |= row.component.on_event(event, context);
+                }
+                consumed
+            }
+        }
+    }
+
+    fn apply_momentum(&mut self) {
+        let now = Instant::now();
+        let delta_time = self.last_frame.map(|last_frame| (now - last_frame).as_secs_f32()).unwrap_or(0.0);
+        self.last_frame = Some(now);
+
+        if self.drag.is_some() || self.velocity.abs() < MIN_VELOCITY {
+            return;
+        }
+
+        self.offset += self.velocity * delta_time;
+        self.velocity *= (-FRICTION * delta_time).exp();
+
+        let unclamped = self.offset;
+        self.clamp_offset();
+        if unclamped != self.offset {
+            self.velocity = 0.0;
+        }
+    }
+
+    /// Recycles rows that left the visible range and instantiates the ones that entered it.
+    fn update_rows(&mut self) {
+        if self.item_count == 0 {
+            self.recycled.extend(self.rows.drain(..).map(|row| row.component));
+            return;
+        }
+
+        let view_height = self.view_height();
+        if view_height <= 0.0 {
+            return;
+        }
+
+        let first = self.row_at(self.offset).saturating_sub(self.overscan);
+        let last = (self.row_at(self.offset + view_height) + self.overscan).min(self.item_count - 1);
+
+        let mut index = 0;
+        while index < self.rows.len() {
+            if self.rows[index].index < first || self.rows[index].index > last {
+                let row = self.rows.swap_remove(index);
+                self.recycled.push(row.component);
+            } else {
+                index += 1;
+            }
+        }
+
+        for index in first..=last {
+            if self.rows.iter().any(|row| row.index == index) {
+                continue;
+            }
+
+            let component = match self.recycled.pop() {
+                Some(mut component) => {
+                    (self.update)(index, &mut component);
+                    component
+                }
+                None => (self.build)(index),
+            };
+
+            self.measure(index, &component);
+            self.rows.push(VirtualRow { index, component });
+        }
+
+        self.update_row_tops();
+
+        // Rows are laid out relative to the first one, scrolling only moves them with a transform
+        self.anchor = self.row_top(first);
+        for row_index in 0..self.rows.len() {
+            let index = self.rows[row_index].index;
+            let top = self.row_top(index) - self.anchor;
+            let bottom = self.row_top(index + 1) - self.anchor;
+
+            let top_left = (-1.0, 1.0 - 2.0 * top / view_height);
+            let bottom_right = (1.0, 1.0 - 2.0 * bottom / view_height);
+
+            let component = &mut self.rows[row_index].component;
+            if component.get_top_left() != top_left || component.get_bottom_right() != bottom_right {
+                component.resize(top_left, bottom_right);
+            }
+        }
+    }
+
+    fn update_scrollbar(&mut self, context: &RenderContext) {
+        let placement = self.basic.placement;
+        let total_height = self.total_height();
+        let view_height = self.view_height();
+
+        if total_height <= view_height || view_height <= 0.0 {
+            self.scrollbar = None;
+            return;
+        }
+
+        let thickness = placement.radius_to_view(SCROLLBAR_THICKNESS);
+        let min_thumb_length = placement.radius_to_view(MIN_THUMB_LENGTH);
+
+        let track_top_left = (placement.absolute_bottom_right.0 - thickness.0, placement.absolute_top_left.1);
+        let track_bottom_right = placement.absolute_bottom_right;
+        let thumb_length = ((track_top_left.1 - track_bottom_right.1) * view_height / total_height).max(min_thumb_length.1);
+
+        let scrollbar = match self.scrollbar.take() {
+            Some(scrollbar) if scrollbar.matches(track_top_left, track_bottom_right, thumb_length) => scrollbar,
+            _ => Scrollbar::new(true, track_top_left, track_bottom_right, thumb_length, context),
+        };
+        let progress = self.progress();
+        let scrollbar = self.scrollbar.insert(scrollbar);
+
+        let translation = scrollbar.thumb_translation(true, progress);
+        scrollbar.track.set_transform(placement.world_transform, context.queue);
+        scrollbar.track.set_opacity(context.opacity, context.queue);
+        scrollbar.thumb.set_transform(Transform::translation(translation.0, translation.1).then(&placement.world_transform), context.queue);
+        scrollbar.thumb.set_opacity(context.opacity, context.queue);
+    }
+
+    fn press_scrollbar(&mut self, position: (f32, f32)) -> bool {
+        let local_point = match self.basic.placement.to_local(position) {
+            None => return false,
+            Some(local_point) => local_point,
+        };
+        let progress = self.progress();
+
+        let scrollbar = match &self.scrollbar {
+            Some(scrollbar) if scrollbar.track_contains(local_point) => scrollbar,
+            _ => return false,
+        };
+
+        if scrollbar.thumb_contains(true, progress, local_point) {
+            self.drag = Some(ListDrag::Thumb { last_position: position });
+            self.velocity = 0.0;
+            return true;
+        }
+
+        let thumb_top = scrollbar.thumb.top_left.1 + scrollbar.thumb_translation(true, progress).1;
+        let direction = if local_point.1 > thumb_top { -1.0 } else { 1.0 };
+        let view_height = self.view_height();
+        self.scroll_to(self.offset + direction * view_height);
+        true
+    }
+
+    fn drag_to(&mut self, position: (f32, f32)) {
+        let window_height = self.basic.placement.window_size.1 as f32;
+
+        match self.drag.as_mut() {
+            None => {}
+            Some(ListDrag::Content { last_position, last_time }) => {
+                let delta = (last_position.1 - position.1) * window_height / 2.0;
+                let now = Instant::now();
+                let elapsed = (now - *last_time).as_secs_f32().max(0.001);
+
+                *last_position = position;
+                *last_time = now;
+
+                self.velocity = -delta / elapsed;
+                self.offset -= delta;
+                self.clamp_offset();
+            }
+            Some(ListDrag::Thumb { last_position }) => {
+                let delta = (last_position.1 - position.1) * window_height / 2.0;
+                *last_position = position;
+
+                let travel = match &self.scrollbar {
+                    None => return,
+                    Some(scrollbar) => scrollbar.travel * window_height / 2.0,
+                };
+
+                if travel > 0.0 {
+                    self.offset += delta / travel * self.max_offset();
+                    self.clamp_offset();
+                }
+            }
+        }
+    }
+}
+
+impl<R: Component> Component for VirtualList<R> {
+    fn render<'a>(&'a mut self, parent_top_left: &(f32, f32), parent_bottom_right: &(f32, f32), render_pass: &mut RenderPass<'a>, context: &RenderContext) {
+        self.basic.placement.update(*parent_top_left, *parent_bottom_right, self.basic.top_left, self.basic.bottom_right, &self.basic.transform, context);
+
+        self.update_row_tops();
+        self.apply_momentum();
+        if self.velocity.abs() >= MIN_VELOCITY {
+            // Keep rendering until the content came to rest
+            context.invalidation.invalidate_placement(&self.basic.placement);
+        }
+        self.clamp_offset();
+        self.update_rows();
+        self.update_scrollbar(context);
+
+        let absolute_top_left = self.basic.placement.absolute_top_left;
+        let absolute_bottom_right = self.basic.placement.absolute_bottom_right;
+        let world_transform = self.basic.placement.world_transform;
+        let context = context.with_transform(world_transform);
+
+        // Selectors can match the content as a descendant of this component
+        let path = context.get_style_path(StyleElement {
+            type_name: self.get_type_name(),
+            id: self.basic.id.as_deref(),
+            classes: &self.basic.classes,
+            state: ElementState::default(),
+        });
+        let context = context.with_style_path(&path);
+
+        self.clipper.prepare(&self.basic.placement, 0.0, &context);
+
+        let clip_context = match self.clipper.begin(render_pass, &context) {
+            None => return,
+            Some(clip_context) => clip_context,
+        };
+
+        let row_context = clip_context.with_transform(self.get_row_transform());
+
+        for row in self.rows.iter_mut() {
+            row.component.render(&absolute_top_left, &absolute_bottom_right, render_pass, &row_context);
+        }
+
+        if let Some(scrollbar) = self.scrollbar.as_ref() {
+            scrollbar.track.draw(render_pass);
+            scrollbar.thumb.draw(render_pass);
+        }
+
+        self.clipper.end(render_pass, &context);
+    }
+
+    fn get_top_left(&self) -> (f32, f32) {
+        self.basic.top_left
+    }
+
+    fn get_bottom_right(&self) -> (f32, f32) {
+        self.basic.bottom_right
+    }
+
+    fn get_transform(&self) -> Transform {
+        self.basic.transform
+    }
+
+    fn set_transform(&mut self, transform: Transform) {
+        self.basic.transform = transform;
+    }
+
+    fn get_z_index(&self) -> i32 {
+        self.basic.z_index
+    }
+
+    fn set_z_index(&mut self, z_index: i32) {
+        self.basic.z_index = z_index;
+    }
+
+    fn get_key(&self) -> Option<&str> {
+        self.basic.key.as_deref()
+    }
+
+    fn set_key(&mut self, key: &str) {
+        self.basic.key = Some(key.to_string());
+    }
+
+    fn get_id(&self) -> Option<&str> {
+        self.basic.id.as_deref()
+    }
+
+    fn set_id(&mut self, id: &str) {
+        self.basic.id = Some(id.to_string());
+    }
+
+    fn get_classes(&self) -> &[String] {
+        &self.basic.classes
+    }
+
+    fn add_class(&mut self, class: &str) {
+        self.basic.add_class(class);
+    }
+
+    fn get_children(&self) -> Vec<&dyn Component> {
+        self.rows.iter().map(|row| &row.component as &dyn Component).collect()
+    }
+
+    fn get_overflow(&self) -> Overflow {
+        Overflow::Clip
+    }
+
+    fn get_dirty_flags(&self) -> DirtyFlags {
+        DirtyFlags {
+            needs_layout: self.row_tops_dirty_from.is_some(),
+            ..DirtyFlags::default()
+        }
+    }
+
+    fn get_placement(&self) -> &ComponentPlacement {
+        &self.basic.placement
+    }
+
+    fn layout(&mut self, parent: &ComponentPlacement) {
+        self.basic.placement.update_in(parent, self.basic.top_left, self.basic.bottom_right, &self.basic.transform);
+
+        self.update_row_tops();
+        self.clamp_offset();
+        self.update_rows();
+
+        let row_parent = ComponentPlacement {
+            world_transform: self.get_row_transform(),
+            ..self.basic.placement
+        };
+        for row in self.rows.iter_mut() {
+            row.component.layout(&row_parent);
+        }
+    }
+
+    fn on_event(&mut self, event: &ComponentEvent, context: &mut EventContext) -> bool {
+        let offset = self.offset;
+        let consumed = self.handle_event(event, context);
+
+        // Scrolling, drags and everything the content consumed only change what is inside the view
+        if consumed || self.offset != offset {
+            context.invalidate(&self.basic.placement);
+        }
+
+        consumed
+    }
+
+    fn reconcile(&mut self, new: Box<dyn Component>) -> Result<(), Box<dyn Component>> {
+        let new = *downcast_component::<Self>(new)?;
+
+        // The rows are kept and updated with the new closures instead of being built again
+        self.basic.reconcile(new.basic);
+        self.item_height = new.item_height;
+        self.build = new.build;
+        self.update = new.update;
+        self.overscan = new.overscan;
+        self.invalidate_row_tops(0);
+
+        if self.item_count != new.item_count {
+            self.set_item_count(new.item_count);
+        } else {
+            self.refresh();
+        }
+        self.clamp_offset();
+
+        Ok(())
+    }
+
+    fn resize(&mut self, new_box_top_left: (f32, f32), new_box_bottom_right: (f32, f32)) {
+        self.basic.top_left = new_box_top_left;
+        self.basic.bottom_right = new_box_bottom_right;
+
+        self.on_resize();
+    }
+
+    fn on_resize(&mut self) {
+        for row in self.rows.iter_mut() {
+            row.component.on_resize();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::component::layout;
+    use crate::components::plain::PlainComponent;
+
+    fn list(item_count: usize, item_height: ItemHeight<PlainComponent>) -> VirtualList<PlainComponent> {
+        let mut list = VirtualList::new((-1.0, 1.0), (1.0, -1.0), item_count, item_height,
+                                        |_| PlainComponent::new((0.0, 0.0), (0.0, 0.0), [0.0; 3]),
+                                        |_, _| {});
+        layout(&mut list, (100, 100));
+        list
+    }
+
+    #[test]
+    fn measured_row_tops_are_updated_from_the_changed_row() {
+        let mut list = list(10, ItemHeight::Measured { estimate: 10.0, measure: Box::new(|index, _| 10.0 + index as f32) });
+        list.update_rows();
+        let measured: Vec<f32> = list.row_tops.clone();
+
+        // The same heights computed from scratch
+        let mut top = 0.0;
+        let mut expected = vec![];
+        for index in 0..10 {
+            expected.push(top);
+            top += list.heights[index].unwrap_or(10.0);
+        }
+        expected.push(top);
+        assert_eq!(measured, expected);
+
+        list.heights[7] = Some(50.0);
+        list.invalidate_row_tops(8);
+        list.update_row_tops();
+        assert_eq!(&list.row_tops[..8], &expected[..8]);
+        assert_eq!(list.row_tops[8] - list.row_tops[7], 50.0);
+        assert_eq!(list.total_height(), expected[10] - (expected[8] - expected[7]) + 50.0);
+    }
+
+    #[test]
+    fn rows_are_laid_out_relative_to_the_first_instantiated_row() {
+        let mut list = list(2_000_000, ItemHeight::Fixed(20.0));
+        list.set_overscan(0);
+        list.scroll_to_index(1_000_000);
+        list.update_rows();
+
+        assert_eq!(list.anchor, list.row_top(1_000_000));
+        let first = list.rows.iter().find(|row| row.index == 1_000_000).expect("the first visible row is instantiated");
+        assert_eq!(first.component.get_top_left(), (-1.0, 1.0));
+        assert_eq!(first.component.get_bottom_right(), (1.0, 0.6));
+        assert_eq!(list.rows.len(), 6);
+    }
+
+    #[test]
+    fn only_rows_inside_the_view_are_visible() {
+        let mut list = list(100, ItemHeight::Fixed(20.0));
+        list.scroll_to(30.0);
+        list.update_rows();
+
+        assert!(list.rows.iter().any(|row| row.index == 0));
+        assert!(!list.is_row_visible(0));
+        assert!(list.is_row_visible(1));
+        assert!(list.is_row_visible(6));
+        assert!(!list.is_row_visible(7));
+    }
+}
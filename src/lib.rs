@@ -1,22 +1,60 @@
 extern crate core;
 
 use winit::{
+    dpi::PhysicalPosition,
     event::*,
     event_loop::{ControlFlow, EventLoop},
     window::WindowBuilder,
     window::Window,
 };
-use crate::components::clickable::ClickableComponent;
-use crate::components::component::Component;
-use crate::components::layout::LayoutComponent;
+use std::any::Any;
+use std::rc::Rc;
+use std::time::Instant;
+use crate::animation::FrameClock;
+use crate::application::{AnyApplication, Application};
+use crate::back_buffer::BackBuffer;
+use crate::components::clip::ScissorRect;
+use crate::components::component::{Component, RenderContext};
+use crate::components::event::{ComponentEvent, EventContext};
+use crate::components::focus::FocusId;
+use crate::components::inspector::Inspector;
+use crate::components::invalidation::{Damage, Invalidation};
+use crate::components::oval::OvalComponent;
+use crate::components::overlay::OverlayLayer;
+use crate::components::performance_hud::PerformanceHud;
+use crate::components::toast::{self, ToastManager};
+use crate::components::tooltip::TooltipManager;
+use crate::components::reactive::invalidate_outdated;
+use crate::components::reconcile::reconcile_component;
 
 use crate::shape::Shape;
-use crate::shapes::oval::Oval;
+use crate::shapes::pipeline::PipelineCache;
+use crate::profiler::{Phase, Profiler};
+use crate::shapes::quad::Quad;
 use crate::shapes::shape;
-
-mod texture;
-mod shapes;
-mod components;
+use crate::stylesheet::Stylesheet;
+use crate::texture::Texture;
+use crate::theme::Theme;
+use crate::transform::Transform;
+
+pub mod texture;
+pub mod shapes;
+pub mod components;
+pub mod transform;
+pub mod application;
+pub mod reactive;
+pub mod back_buffer;
+pub mod animation;
+pub mod style;
+pub mod theme;
+pub mod stylesheet;
+pub mod watcher;
+pub mod markup;
+pub mod profiler;
+pub mod font;
+
+/// Pixels scrolled per line reported by the mouse wheel.
+const WHEEL_LINE_HEIGHT: f32 = 40.0;
 
 pub struct State {
     surface: wgpu::Surface,
@@ -24,15 +62,38 @@ pub struct State {
     queue: wgpu::Queue,
     pub config: wgpu::SurfaceConfiguration,
     size: winit::dpi::PhysicalSize<u32>,
+    depth_stencil_texture: Texture,
+    pipelines: PipelineCache,
+    back_buffer: BackBuffer,
+    /// Clears the damaged region when only a part of the back buffer is drawn again.
+    background: Option<Quad>,
+    invalidation: Invalidation,
+    clock: FrameClock,
+    theme: Theme,
+    stylesheet: Rc<Stylesheet>,
 
     last_mouse_position: (f32, f32),
 
-    root: Option<LayoutComponent>,
+    application: Box<dyn AnyApplication>,
+    messages: Vec<Box<dyn Any>>,
+
+    /// Drawn below the root. Their GPU buffers are created on the first frame and kept afterwards.
+    decorations: Vec<OvalComponent>,
+    root: Box<dyn Component>,
+    overlays: OverlayLayer,
+    /// Drawn above the overlays.
+    toasts: ToastManager,
+    /// Drawn above the toasts.
+    tooltips: TooltipManager,
+    focused: Option<FocusId>,
+    inspector: Inspector,
+    profiler: Profiler,
+    hud: PerformanceHud,
 }
 
 impl State {
     // Creating some of the wgpu types requires async code
-    async fn new(window: &Window) -> Self {
+    async fn new<A: Application>(window: &Window, application: A) -> Self {
         let size = window.inner_size();
 
         // The instance is a handle to our GPU
@@ -49,7 +110,8 @@ impl State {
 
         let (device, queue) = adapter.request_device(
             &wgpu::DeviceDescriptor {
-                features: wgpu::Features::empty(),
+                // Frames are timed on the GPU where the adapter supports it
+                features: adapter.features() & wgpu::Features::TIMESTAMP_QUERY,
                 // WebGL doesn't support all of wgpu's features, so if
                 // we're building for the web we'll have to disable some.
                 limits: if cfg!(target_arch = "wasm32") {
@@ -71,158 +133,359 @@ impl State {
         };
         surface.configure(&device, &config);
 
-        surface.configure(&device, &config);
+        let depth_stencil_texture = Texture::create_depth_stencil_texture(&device, &config, "Depth Stencil Texture");
+        let pipelines = PipelineCache::new(config.format, &device);
+
+        let root = application.view();
+        let theme = application.theme();
+        let stylesheet = application.stylesheet();
+
+        let back_buffer = BackBuffer::new(&config, &device);
+        let profiler = Profiler::new(&device, &queue);
+        let invalidation = Invalidation::new();
+        invalidation.request_redraw();
 
         Self {
-            root: None,
+            application: Box::new(application),
+            messages: vec![],
+            decorations: vec![
+                OvalComponent::new((0.9, 1.1), (1.1, 0.9), [1.0, 0.1, 0.1]),
+                OvalComponent::new((-1.5, 1.1), (-0.5, 0.9), [0.1, 1.0, 0.1]),
+                OvalComponent::new((0.5, -0.9), (1.5, -1.1), [0.1, 0.1, 1.0]),
+                OvalComponent::new((-1.1, -0.9), (-0.9, -1.1), [1.0, 1.0, 0.1]),
+            ],
+            root,
+            overlays: OverlayLayer::new(),
+            toasts: ToastManager::new(),
+            tooltips: TooltipManager::new(),
+            focused: None,
+            inspector: Inspector::new(),
+            profiler,
+            hud: PerformanceHud::new(),
             surface,
             device,
             queue,
             config,
             size,
+            depth_stencil_texture,
+            pipelines,
+            back_buffer,
+            background: None,
+            invalidation,
+            clock: FrameClock::new(),
+            theme,
+            stylesheet,
             last_mouse_position: (0.0, 0.0),
         }
     }
 
+    /// The timings and counters of the last frames.
+    pub fn get_profiler(&self) -> &Profiler {
+        &self.profiler
+    }
+
     fn resize(&mut self, new_size: winit::dpi::PhysicalSize<u32>) {
         if new_size.width > 0 && new_size.height > 0 {
             self.size = new_size;
             self.config.width = new_size.width;
             self.config.height = new_size.height;
             self.surface.configure(&self.device, &self.config);
+            self.depth_stencil_texture = Texture::create_depth_stencil_texture(&self.device, &self.config, "Depth Stencil Texture");
+            self.back_buffer.resize(&self.config, &self.device);
+            self.invalidation.request_redraw();
         }
     }
 
-    fn input(&mut self, _event: &WindowEvent) -> bool {
-        match _event {
-            WindowEvent::CursorMoved { position, .. } => {
-                self.last_mouse_position = ((position.x as f32 / self.config.width as f32 * 2.0) - 1.0,
-                                            ((position.y as f32 / self.config.height as f32 * 2.0) - 1.0) * -1.0);
+    fn to_view_position(&self, position: &PhysicalPosition<f64>) -> (f32, f32) {
+        ((position.x as f32 / self.config.width as f32 * 2.0) - 1.0,
+         -((position.y as f32 / self.config.height as f32 * 2.0) - 1.0))
+    }
+
+    fn input(&mut self, event: &WindowEvent) -> bool {
+        let start = Instant::now();
+        let consumed = self.dispatch_input(event);
+        self.profiler.record(Phase::Events, start.elapsed());
+        consumed
+    }
+
+    fn dispatch_input(&mut self, _event: &WindowEvent) -> bool {
+        if let WindowEvent::CursorMoved { position, .. } = _event {
+            self.last_mouse_position = self.to_view_position(position);
+        }
+
+        // The inspector sees every event first and keeps the pointer away from the tree while it is open
+        if self.inspector.input(_event, self.last_mouse_position, self.root.as_ref(), &self.config) {
+            self.invalidation.request_redraw();
+            return true;
+        }
+        if self.hud.input(_event) {
+            self.invalidation.request_redraw();
+            return true;
+        }
+
+        let event = match _event {
+            WindowEvent::CursorLeft { .. } => {
+                if self.tooltips.hide() {
+                    self.invalidation.request_redraw();
+                }
+                return false;
             }
-            WindowEvent::MouseInput { state: ElementState::Pressed, button: MouseButton::Left, .. } => {
-                match &mut self.root {
-                    None => {}
-                    Some(root) => {
-                        root.on_click(self.last_mouse_position);
-                        root.resize((0.0, 1.0), (1.0, -1.0));
-                    }
+            WindowEvent::CursorMoved { .. } => ComponentEvent::CursorMoved { position: self.last_mouse_position },
+            WindowEvent::MouseInput { state: ElementState::Pressed, button, .. } => {
+                if *button == MouseButton::Left && (self.root.get_top_left() != (0.0, 1.0) || self.root.get_bottom_right() != (1.0, -1.0)) {
+                    self.root.resize((0.0, 1.0), (1.0, -1.0));
+                    self.invalidation.request_redraw();
                 }
+                ComponentEvent::MousePressed { position: self.last_mouse_position, button: *button }
+            }
+            WindowEvent::MouseInput { state: ElementState::Released, button, .. } => {
+                ComponentEvent::MouseReleased { position: self.last_mouse_position, button: *button }
+            }
+            WindowEvent::MouseWheel { delta, .. } => {
+                let delta = match delta {
+                    MouseScrollDelta::LineDelta(x, y) => (x * WHEEL_LINE_HEIGHT, y * WHEEL_LINE_HEIGHT),
+                    MouseScrollDelta::PixelDelta(position) => (position.x as f32, position.y as f32),
+                };
+                ComponentEvent::MouseWheel { position: self.last_mouse_position, delta }
+            }
+            // Touches are handled like a mouse with the left button
+            WindowEvent::Touch(Touch { phase, location, .. }) => {
+                self.last_mouse_position = self.to_view_position(location);
+                match phase {
+                    TouchPhase::Started => ComponentEvent::MousePressed { position: self.last_mouse_position, button: MouseButton::Left },
+                    TouchPhase::Moved => ComponentEvent::CursorMoved { position: self.last_mouse_position },
+                    TouchPhase::Ended | TouchPhase::Cancelled => ComponentEvent::MouseReleased { position: self.last_mouse_position, button: MouseButton::Left },
+                }
+            }
+            WindowEvent::KeyboardInput { input: KeyboardInput { state, virtual_keycode: Some(key), .. }, .. } => {
+                match state {
+                    ElementState::Pressed => ComponentEvent::KeyPressed { key: *key },
+                    ElementState::Released => ComponentEvent::KeyReleased { key: *key },
+                }
+            }
+            _ => return false,
+        };
+
+        let mut context = EventContext::new(self.focused);
+
+        // Overlays lie above the tree, so they get the first chance. Below a modal one, the tree
+        // only sees releases, so drags that started before it opened can finish
+        // Toasts lie above everything else, but only take presses on themselves
+        let mut consumed = self.toasts.on_event(&event, &mut context);
+        if !consumed || !event.is_targeted() {
+            let blocked = self.overlays.has_modal() && !matches!(event, ComponentEvent::MouseReleased { .. });
+            consumed |= self.overlays.on_event(&event, &mut context);
+            if !blocked && (!consumed || !event.is_targeted()) {
+                consumed |= self.root.on_event(&event, &mut context);
+            }
+        }
+
+        context.apply_overlays(&mut self.overlays);
+        if self.tooltips.on_event(&event, self.root.as_ref(), &self.overlays) {
+            context.request_redraw();
+        }
+        self.messages.append(&mut context.take_messages());
+
+        let focused = self.focused;
+        match context.take_focus_request() {
+            Some(focused) => self.focused = focused,
+            // Pressing anywhere else takes the focus away
+            None if matches!(event, ComponentEvent::MousePressed { .. }) => self.focused = None,
+            None => {}
+        }
+        if self.focused != focused {
+            context.request_redraw();
+        }
+
+        // Components that consume an event without saying what changed get the whole window redrawn
+        if consumed && !context.get_invalidation().is_pending() {
+            context.request_redraw();
+        }
+        self.invalidation.merge(context.get_invalidation());
+
+        consumed
+    }
+
+    /// Whether something changed since the last frame.
+    fn needs_redraw(&mut self) -> bool {
+        // Both flags are taken, so neither reports the same change again
+        let signals_changed = reactive::take_changes();
+        let redraw_requested = watcher::take_redraw_request();
+        for shown in toast::take_shown_toasts() {
+            self.toasts.show(shown);
+        }
+        let now = Instant::now();
+        if redraw_requested || self.toasts.is_due(now) || self.tooltips.is_due(now) {
+            self.invalidation.request_redraw();
+        }
+        if signals_changed {
+            // Only the components that read a changed signal are drawn again
+            let trees = std::iter::once(self.root.as_ref())
+                .chain(self.overlays.get_components())
+                .chain(self.toasts.get_contents())
+                .chain(self.tooltips.get_content());
+            for tree in trees {
+                invalidate_outdated(tree, &self.invalidation);
             }
-            _ => {}
         }
-        false
+        self.invalidation.is_pending()
+    }
+
+    /// When the window has to wake up without any input, because a toast runs out or a tooltip opens.
+    fn get_next_deadline(&self) -> Option<Instant> {
+        self.toasts.get_next_deadline().into_iter().chain(self.tooltips.get_next_deadline()).min()
     }
 
-    fn update(&mut self) {}
+    /// Hands the messages emitted since the last frame to the application and reconciles its new view.
+    fn update(&mut self) {
+        if self.messages.is_empty() {
+            return;
+        }
+        let start = Instant::now();
+
+        for message in self.messages.drain(..) {
+            self.application.update(message);
+        }
+
+        reconcile_component(&mut self.root, self.application.view());
+
+        let theme = self.application.theme();
+        if theme != self.theme {
+            self.theme = theme;
+            // The background quad has the color of the old theme
+            self.background = None;
+            self.invalidation.request_redraw();
+        }
+
+        let stylesheet = self.application.stylesheet();
+        if !Rc::ptr_eq(&stylesheet, &self.stylesheet) && stylesheet != self.stylesheet {
+            self.stylesheet = stylesheet;
+            self.invalidation.request_redraw();
+        }
+
+        self.profiler.record(Phase::Update, start.elapsed());
+    }
 
     fn render(&mut self) -> Result<(), wgpu::SurfaceError> {
         let output = self.surface.get_current_texture()?;
         let view = output.texture.create_view(&wgpu::TextureViewDescriptor::default());
+        let start = Instant::now();
 
         let mut encoder = self.device.create_command_encoder(&wgpu::CommandEncoderDescriptor {
             label: Some("Render Encoder"),
         });
+        self.profiler.begin_gpu_frame(&mut encoder);
+
+        self.clock.tick();
+
+        // The outlines of the inspector can move anywhere, so it draws the whole window
+        let damage = self.invalidation.take()
+            .map(|damage| if self.inspector.is_enabled() { Damage::Full } else { damage });
+        let scissor = match damage {
+            None => None,
+            Some(Damage::Full) => Some(ScissorRect::full(&self.config)),
+            Some(Damage::Region(top_left, bottom_right)) => Some(ScissorRect::from_view_points(&[top_left, bottom_right], &self.config)),
+        }.filter(|scissor| !scissor.is_empty());
+
+        if let Some(scissor) = scissor {
+            let partial = damage != Some(Damage::Full);
+            if partial && self.background.is_none() {
+                self.background = Some(Quad::new((-1.0, 1.0), (1.0, -1.0), self.theme.background, &self.device, &self.pipelines));
+            }
 
-        use components::plain::PlainComponent;
-
-        if self.root.is_none() {
-            let mut layout_component = LayoutComponent::new(
-                (-0.7, 0.7),
-                (0.7, -0.7));
-
-            let mut layout_component2 = LayoutComponent::new(
-                (-1.0, 0.7),
-                (1.0, -1.0));
-
-            let mut layout_component3 = LayoutComponent::new(
-                (0.0, 1.0),
-                (1.0, -1.0));
-
-            let plain_component = PlainComponent::new(
-                (-1.0, 1.0),
-                (1.0, 0.7),
-                [1.0, 1.0, 0.0],
-                &self.device,
-                &self.config,
-            );
-
-            let plain_component2 = PlainComponent::new(
-                (-1.0, 1.0),
-                (0.0, -1.0),
-                [1.0, 0.0, 0.0],
-                &self.device,
-                &self.config,
-            );
-
-            let plain_component3 = PlainComponent::new(
-                (-1.0, 1.0),
-                (1.0, 0.0),
-                [0.0, 1.0, 0.0],
-                &self.device,
-                &self.config,
-            );
-            let clickable_component = ClickableComponent::new(
-                (-1.0, 0.0),
-                (1.0, -1.0),
-                [0.0, 0.0, 1.0],
-                &self.device,
-                &self.config);
-
-            layout_component3.add_component(Box::new(plain_component3));
-            layout_component3.add_component(Box::new(clickable_component));
-
-            layout_component2.add_component(Box::new(layout_component3));
-            layout_component2.add_component(Box::new(plain_component2));
-
-            layout_component.add_component(Box::new(layout_component2));
-            layout_component.add_component(Box::new(plain_component));
-
-            self.root = Some(layout_component);
-        }
-
-        let oval = Oval::new((1.0, 1.0), (0.1, 0.1), 64, [1.0, 0.1, 0.1], &self.device, &self);
-        let oval1 = Oval::new((-1.0, 1.0), (0.5, 0.1), 64, [0.1, 1.0, 0.1], &self.device, &self);
-        let oval2 = Oval::new((1.0, -1.0), (0.5, 0.1), 64, [0.1, 0.1, 1.0], &self.device, &self);
-        let oval3 = Oval::new((-1.0, -1.0), (0.1, 0.1), 64, [1.0, 1.0, 0.1], &self.device, &self);
-
-        {
             let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
                 label: Some("Render Pass"),
                 color_attachments: &[
                     // This is what @location(0) in the fragment shader targets
                     Some(wgpu::RenderPassColorAttachment {
-                        view: &view,
+                        view: self.back_buffer.get_view(),
                         resolve_target: None,
                         ops: wgpu::Operations {
-                            load: wgpu::LoadOp::Clear(
-                                wgpu::Color {
-                                    r: 0.1,
-                                    g: 0.2,
-                                    b: 0.3,
-                                    a: 1.0,
-                                }
-                            ),
+                            // Everything outside of the damaged region is kept from the last frame
+                            load: if partial {
+                                wgpu::LoadOp::Load
+                            } else {
+                                wgpu::LoadOp::Clear(
+                                    wgpu::Color {
+                                        r: self.theme.background[0] as f64,
+                                        g: self.theme.background[1] as f64,
+                                        b: self.theme.background[2] as f64,
+                                        a: 1.0,
+                                    }
+                                )
+                            },
                             store: true,
                         },
                     })
                 ],
-                depth_stencil_attachment: None,
+                depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                    view: &self.depth_stencil_texture.view,
+                    depth_ops: None,
+                    stencil_ops: Some(wgpu::Operations {
+                        load: wgpu::LoadOp::Clear(0),
+                        store: true,
+                    }),
+                }),
             });
 
-            oval.draw(&mut render_pass);
-            oval1.draw(&mut render_pass);
-            oval2.draw(&mut render_pass);
-            oval3.draw(&mut render_pass);
+            scissor.apply(&mut render_pass);
+            if partial {
+                if let Some(background) = self.background.as_ref() {
+                    background.draw(&mut render_pass);
+                }
+            }
 
             //quadrat.draw(&mut render_pass);
             //quadrat2.draw(&mut render_pass);
 
-            if self.root.is_some()
-            {
-                self.root.as_mut().unwrap().render(&(-1.0, 1.0), &(1.0, -1.0), &mut render_pass, &self.device, &self.config);
+            let context = RenderContext {
+                device: &self.device,
+                queue: &self.queue,
+                config: &self.config,
+                pipelines: &self.pipelines,
+                transform: Transform::IDENTITY,
+                opacity: 1.0,
+                clip: scissor,
+                stencil_depth: 0,
+                focused: self.focused,
+                invalidation: &self.invalidation,
+                clock: &self.clock,
+                theme: &self.theme,
+                inherited: self.theme.get_inherited(),
+                stylesheet: &self.stylesheet,
+                style_path: None,
+            };
+
+            for decoration in self.decorations.iter_mut() {
+                decoration.render(&(-1.0, 1.0), &(1.0, -1.0), &mut render_pass, &context);
             }
+
+            // The inspector reads the tree, which is borrowed by the render pass once it is drawn
+            self.inspector.prepare(self.root.as_ref(), &context);
+
+            self.root.render(&(-1.0, 1.0), &(1.0, -1.0), &mut render_pass, &context);
+
+            self.overlays.render(&mut render_pass, &context);
+            self.toasts.render(&mut render_pass, &context);
+            self.tooltips.render(&mut render_pass, &context, (self.size.width, self.size.height));
+            self.inspector.render(&mut render_pass);
+        }
+
+        self.back_buffer.present(&view, &mut encoder);
+        self.profiler.take_counters();
+        // Components are laid out while they are encoded, the layout was timed on its own
+        let layout = profiler::take_layout_time();
+        self.profiler.record(Phase::Layout, layout);
+        self.profiler.record(Phase::Encode, start.elapsed().saturating_sub(layout));
+
+        // The HUD is drawn onto the surface instead of the back buffer, so it is neither retained nor measured
+        if self.hud.is_enabled() {
+            self.render_hud(&view, &mut encoder);
         }
 
+        let start = Instant::now();
+        self.profiler.end_gpu_frame(&mut encoder);
+
         /*
         let render_pipeline = Quad::create_render_pipeline(&self);
         let quad2 = Quad::new(&self.device, (-0.9,0.9), (0.3,-0.9));
@@ -251,16 +514,69 @@ impl State {
         self.queue.submit(std::iter::once(encoder.finish()));
         output.present();
 
+        self.profiler.record(Phase::Submit, start.elapsed());
+        self.profiler.end_frame(&self.device);
+
         Ok(())
     }
+
+    fn render_hud(&mut self, view: &wgpu::TextureView, encoder: &mut wgpu::CommandEncoder) {
+        let context = RenderContext {
+            device: &self.device,
+            queue: &self.queue,
+            config: &self.config,
+            pipelines: &self.pipelines,
+            transform: Transform::IDENTITY,
+            opacity: 1.0,
+            clip: ScissorRect::full(&self.config),
+            stencil_depth: 0,
+            focused: self.focused,
+            invalidation: &self.invalidation,
+            clock: &self.clock,
+            theme: &self.theme,
+            inherited: self.theme.get_inherited(),
+            stylesheet: &self.stylesheet,
+            style_path: None,
+        };
+        self.hud.prepare(self.profiler.get_history(), &context);
+
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: Some("HUD Pass"),
+            color_attachments: &[
+                Some(wgpu::RenderPassColorAttachment {
+                    view,
+                    resolve_target: None,
+                    ops: wgpu::Operations {
+                        load: wgpu::LoadOp::Load,
+                        store: true,
+                    },
+                })
+            ],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &self.depth_stencil_texture.view,
+                depth_ops: None,
+                stencil_ops: Some(wgpu::Operations {
+                    load: wgpu::LoadOp::Clear(0),
+                    store: false,
+                }),
+            }),
+        });
+        self.hud.render(&mut render_pass);
+        drop(render_pass);
+
+        // The draws of the HUD do not count towards the next frame
+        profiler::take_counters();
+        profiler::take_layout_time();
+    }
 }
 
-pub async fn run() {
+pub async fn run<A: Application>(application: A) {
     env_logger::init();
     let event_loop = EventLoop::new();
+    watcher::set_event_loop(event_loop.create_proxy());
     let window = WindowBuilder::new().build(&event_loop).unwrap();
 
-    let mut state = State::new(&window).await;
+    let mut state = State::new(&window, application).await;
 
     event_loop.run(move |event, _, control_flow| match event {
         Event::RedrawRequested(window_id) if window_id == window.id() => {
@@ -275,15 +591,22 @@ pub async fn run() {
                 Err(e) => eprintln!("{:?}", e),
             }
         }
-        Event::MainEventsCleared => {
-            // RedrawRequested will only trigger once, unless we manually
-            // request it.
+        // Only draw when something changed since the last frame
+        Event::MainEventsCleared if state.needs_redraw() => {
             window.request_redraw();
         }
+        // Animations request the next frame while they are drawn, everything else waits for input or a toast to run out
+        Event::RedrawEventsCleared if *control_flow != ControlFlow::Exit => {
+            *control_flow = if state.needs_redraw() {
+                ControlFlow::Poll
+            } else {
+                state.get_next_deadline().map_or(ControlFlow::Wait, ControlFlow::WaitUntil)
+            };
+        }
         Event::WindowEvent {
             ref event,
             window_id,
-        } if window_id == window.id() => if !state.input(event) { // UPDATED!
+        } if window_id == window.id() && !state.input(event) => {
             match event {
                 WindowEvent::CloseRequested
                 | WindowEvent::KeyboardInput {
@@ -1,5 +1,175 @@
-use rui::run;
+use std::rc::Rc;
+use std::time::Duration;
+use rui::animation::{Animation, Easing};
+use rui::application::Application;
+use rui::components::animated::AnimatedComponent;
+use rui::components::button::Button;
+use rui::components::clip::Overflow;
+use rui::components::component::Component;
+use rui::components::layout::LayoutComponent;
+use rui::components::markup::MarkupComponent;
+use rui::components::overlay::OverlayOptions;
+use rui::components::plain::PlainComponent;
+use rui::components::reactive::ReactiveComponent;
+use rui::components::scroll_view::ScrollView;
+use rui::components::view::ViewBuilder;
+use rui::components::virtual_list::{ItemHeight, VirtualList};
+use rui::markup::{MarkupNode, MarkupRegistry};
+use rui::reactive::{Computed, Signal};
+use rui::stylesheet::Stylesheet;
+use rui::theme::Theme;
+use rui::{run, view};
+use rui::transform::Transform;
+
+const FOOTER_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/src/demo.ui");
+
+#[derive(Clone, Debug)]
+enum Message {
+    ButtonPressed,
+}
+
+struct Demo {
+    presses: Signal<u32>,
+    stylesheet: Rc<Stylesheet>,
+    registry: Rc<MarkupRegistry>,
+    /// The footer, parsed once. Debug builds reload the file when it changes.
+    footer: Option<MarkupNode>,
+}
+
+impl Demo {
+    fn new() -> Self {
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/src/demo.css");
+        let stylesheet = Stylesheet::load(path).unwrap_or_else(|error| {
+            eprintln!("Could not load {}: {}", path, error);
+            Stylesheet::default()
+        });
+
+        let mut registry = MarkupRegistry::new();
+        registry.register_handler("press", |context| context.emit(Message::ButtonPressed));
+
+        let footer = MarkupNode::load(FOOTER_PATH).map_err(|error| eprintln!("Could not load {}: {:#}", FOOTER_PATH, error)).ok();
+
+        Self {
+            presses: Signal::new(0),
+            stylesheet: Rc::new(stylesheet),
+            registry: Rc::new(registry),
+            footer,
+        }
+    }
+}
+
+impl Application for Demo {
+    type Message = Message;
+
+    fn view(&self) -> Box<dyn Component> {
+        // The header changes its color with every press of the button
+        let presses = self.presses.clone();
+        let header_color = Computed::new(move || {
+            let brightness = 1.0 - (presses.get() % 5) as f32 / 5.0;
+            [1.0, brightness, 0.0]
+        });
+
+        // Every press adds a stripe, the rest of the tree is kept when the view is reconciled
+        let stripes = 6 + self.presses.get_untracked() as usize;
+
+        // The footer comes from a markup file, the retained one is reloaded when the file changes
+        let footer = self.footer.as_ref().map(|node| MarkupComponent::from_node(FOOTER_PATH, node, self.registry.clone()));
+        let footer: Box<dyn Component> = match footer {
+            Some(Ok(footer)) => Box::new(footer),
+            Some(Err(error)) => {
+                eprintln!("Could not build {}: {}", FOOTER_PATH, error);
+                Box::new(PlainComponent::new((-1.0, -0.75), (1.0, -1.0), [1.0, 0.0, 0.0]))
+            }
+            None => Box::new(PlainComponent::new((-1.0, -0.75), (1.0, -1.0), [1.0, 0.0, 0.0])),
+        };
+
+        let row_color = |index: usize| if index.is_multiple_of(2) { [0.0, 1.0, 0.0] } else { [0.0, 0.6, 0.0] };
+
+        view! {
+            LayoutComponent::new((-0.7, 0.7), (0.7, -0.7)) => {
+                .set_overflow(Overflow::Clip)
+                .set_corner_radius(24.0)
+                .set_id("root")
+                [
+                    LayoutComponent::new((-1.0, 0.7), (1.0, -0.75)) => {
+                        [
+                            LayoutComponent::new((0.0, 1.0), (1.0, -1.0)) => {
+                                .set_overflow(Overflow::Clip)
+                                [
+                                    Button::new((-1.0, 0.0), (1.0, -1.0)) => {
+                                        .set_content(view!(PlainComponent::new((-0.3, 0.3), (0.3, -0.3), [1.0, 1.0, 1.0])))
+                                        .set_on_press(|context| {
+                                            context.emit(Message::ButtonPressed);
+
+                                            // A popup that closes on the first press outside of it. It fades in and springs into place
+                                            let popup = view!(AnimatedComponent::new(PlainComponent::new((0.5, 0.95), (0.95, 0.75), [1.0, 0.5, 0.0])) => {
+                                                .animate_opacity(Animation::tween(0.0, 1.0, Duration::from_millis(200), Easing::EaseOut))
+                                                .animate_transform(Animation::tween(Transform::translation(0.0, 40.0),
+                                                                                    Transform::IDENTITY,
+                                                                                    Duration::from_millis(600),
+                                                                                    Easing::Spring { stiffness: 200.0, damping: 15.0 }))
+                                            });
+                                            context.show_overlay(popup,
+                                                                 OverlayOptions {
+                                                                     z_index: 1,
+                                                                     ..OverlayOptions::default()
+                                                                 });
+                                        })
+                                        .set_transform(Transform::rotation(0.3).scale(0.8, 0.8))
+                                        .set_z_index(1)
+                                    },
+                                    VirtualList::new(
+                                        (-1.0, 1.0),
+                                        (1.0, 0.0),
+                                        10_000,
+                                        ItemHeight::Fixed(24.0),
+                                        move |index| PlainComponent::new((-1.0, 1.0), (1.0, -1.0), row_color(index)),
+                                        move |index, row: &mut PlainComponent| row.set_color(row_color(index))),
+                                ]
+                            },
+                            ScrollView::new((-1.0, 1.0), (0.0, -1.0), (1.5, stripes as f32 / 2.0)).with(|scroll_view| {
+                                for i in 0..stripes {
+                                    let top = 1.0 - 2.0 * i as f32 / stripes as f32;
+                                    scroll_view.add_component(view!(PlainComponent::new((-1.0, top), (1.0, top - 2.0 / stripes as f32), [1.0, i as f32 / stripes as f32, 0.0]) => {
+                                        .set_key(&format!("stripe-{}", i))
+                                        .add_class("stripe")
+                                    }));
+                                }
+                            }),
+                        ]
+                    },
+                    ReactiveComponent::new(
+                        PlainComponent::new((-1.0, 1.0), (1.0, 0.7), [1.0, 1.0, 0.0]),
+                        move |header| header.set_color(header_color.get())),
+                ]
+                .add_component(footer)
+            }
+        }
+    }
+
+    /// Every press switches between the dark and the light theme.
+    fn theme(&self) -> Theme {
+        if self.presses.get_untracked().is_multiple_of(2) {
+            Theme::dark()
+        } else {
+            Theme::light()
+        }
+    }
+
+    fn stylesheet(&self) -> Rc<Stylesheet> {
+        self.stylesheet.clone()
+    }
+
+    fn update(&mut self, message: Message) {
+        match message {
+            Message::ButtonPressed => {
+                // The count shows as the number of stripes and the header color
+                self.presses.update(|presses| *presses += 1);
+            }
+        }
+    }
+}
 
 fn main() {
-    pollster::block_on(run());
-}
\ No newline at end of file
+    pollster::block_on(run(Demo::new()));
+}
@@ -0,0 +1,579 @@
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::rc::Rc;
+use std::str::FromStr;
+use crate::components::button::Button;
+use crate::components::clickable::ClickableComponent;
+use crate::components::clip::Overflow;
+use crate::components::component::{Component, Rect};
+use crate::components::event::EventContext;
+use crate::components::image::ImageComponent;
+use crate::components::label::Label;
+use crate::components::layout::LayoutComponent;
+use crate::components::oval::OvalComponent;
+use crate::components::plain::PlainComponent;
+use crate::components::scroll_view::ScrollView;
+use crate::components::view::Container;
+use crate::style::{Color, Style};
+use crate::stylesheet::Stylesheet;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MarkupError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for MarkupError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for MarkupError {}
+
+/// An element of a markup file with its attributes and child elements.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MarkupNode {
+    pub name: String,
+    pub attributes: Vec<(String, String)>,
+    pub children: Vec<MarkupNode>,
+    /// Where the element starts, for error messages.
+    pub line: usize,
+}
+
+impl MarkupNode {
+    /// Parses a document with a single root element.
+    pub fn parse(source: &str) -> Result<MarkupNode, MarkupError> {
+        let mut parser = MarkupParser { source, position: 0 };
+
+        parser.skip_misc()?;
+        let root = parser.parse_element()?;
+        parser.skip_misc()?;
+
+        if parser.position < source.len() {
+            return Err(parser.error(parser.position, "expected the end of the document after the root element"));
+        }
+        Ok(root)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> anyhow::Result<MarkupNode> {
+        let source = std::fs::read_to_string(path)?;
+        Ok(Self::parse(&source)?)
+    }
+
+    pub fn error(&self, message: &str) -> MarkupError {
+        MarkupError {
+            line: self.line,
+            message: message.to_string(),
+        }
+    }
+
+    pub fn get_attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.iter()
+            .find(|(attribute, _)| attribute == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    pub fn parse_attribute<T: FromStr>(&self, name: &str) -> Result<Option<T>, MarkupError> {
+        self.get_attribute(name)
+            .map(|value| value.trim().parse().map_err(|_| self.error(&format!("invalid value '{}' of '{}'", value, name))))
+            .transpose()
+    }
+
+    /// Parses `count` numbers separated by whitespace.
+    pub fn get_numbers(&self, name: &str, count: usize) -> Result<Option<Vec<f32>>, MarkupError> {
+        let value = match self.get_attribute(name) {
+            None => return Ok(None),
+            Some(value) => value,
+        };
+
+        let numbers = value.split_whitespace()
+            .map(|number| number.parse::<f32>())
+            .collect::<Result<Vec<_>, _>>()
+            .ok()
+            .filter(|numbers| numbers.len() == count);
+
+        match numbers {
+            None => Err(self.error(&format!("expected {} numbers in '{}', found '{}'", count, name, value))),
+            Some(numbers) => Ok(Some(numbers)),
+        }
+    }
+
+    /// The required `rect="left top right bottom"` relative to the parent box.
+    pub fn get_rect(&self) -> Result<Rect, MarkupError> {
+        match self.get_numbers("rect", 4)? {
+            None => Err(self.error(&format!("<{}> needs a rect=\"left top right bottom\"", self.name))),
+            Some(rect) => Ok(((rect[0], rect[1]), (rect[2], rect[3]))),
+        }
+    }
+
+    /// A color given as `#rrggbb` or as three numbers from 0 to 1.
+    pub fn get_color(&self, name: &str) -> Result<Option<[f32; 3]>, MarkupError> {
+        let value = match self.get_attribute(name) {
+            None => return Ok(None),
+            Some(value) => value.trim(),
+        };
+
+        if value.starts_with('#') {
+            return match Stylesheet::parse_declarations(&format!("background: {}", value)) {
+                Ok(Style { background: Some(Color::Rgb(color)), .. }) => Ok(Some(color)),
+                _ => Err(self.error(&format!("invalid color '{}'", value))),
+            };
+        }
+
+        let color = self.get_numbers(name, 3)?.expect("the attribute exists");
+        Ok(Some([color[0], color[1], color[2]]))
+    }
+
+    /// The inline `style` in the syntax of stylesheet declarations.
+    pub fn get_style(&self) -> Result<Option<Style>, MarkupError> {
+        self.get_attribute("style")
+            .map(|style| Stylesheet::parse_declarations(style)
+                .map_err(|error| self.error(&format!("invalid style: {}", error.message))))
+            .transpose()
+    }
+
+    /// The flags `true` and `false`.
+    pub fn get_flag(&self, name: &str) -> Result<Option<bool>, MarkupError> {
+        self.parse_attribute(name)
+    }
+}
+
+struct MarkupParser<'s> {
+    source: &'s str,
+    position: usize,
+}
+
+impl<'s> MarkupParser<'s> {
+    fn rest(&self) -> &'s str {
+        &self.source[self.position..]
+    }
+
+    fn skip_whitespace(&mut self) {
+        let rest = self.rest();
+        self.position += rest.len() - rest.trim_start().len();
+    }
+
+    /// Skips whitespace, comments and declarations like `<?xml version="1.0"?>`.
+    fn skip_misc(&mut self) -> Result<(), MarkupError> {
+        loop {
+            self.skip_whitespace();
+
+            let (start, end) = if self.rest().starts_with("<!--") {
+                ("<!--", "-->")
+            } else if self.rest().starts_with("<?") {
+                ("<?", "?>")
+            } else {
+                return Ok(());
+            };
+
+            match self.rest().find(end) {
+                None => return Err(self.error(self.position, &format!("'{}' is never closed", start))),
+                Some(index) => self.position += index + end.len(),
+            }
+        }
+    }
+
+    fn expect(&mut self, expected: &str) -> Result<(), MarkupError> {
+        if !self.rest().starts_with(expected) {
+            let found = self.rest().chars().next().map_or("the end".to_string(), |c| format!("'{}'", c));
+            return Err(self.error(self.position, &format!("expected '{}', found {}", expected, found)));
+        }
+
+        self.position += expected.len();
+        Ok(())
+    }
+
+    fn parse_name(&mut self) -> Result<String, MarkupError> {
+        let rest = self.rest();
+        let length = rest.find(|c: char| !(c.is_alphanumeric() || matches!(c, '-' | '_' | '.' | ':'))).unwrap_or(rest.len());
+
+        if length == 0 {
+            return Err(self.error(self.position, "expected a name"));
+        }
+
+        self.position += length;
+        Ok(rest[..length].to_string())
+    }
+
+    fn parse_element(&mut self) -> Result<MarkupNode, MarkupError> {
+        let line = self.line(self.position);
+
+        self.expect("<")?;
+        let name = self.parse_name()?;
+
+        let mut attributes: Vec<(String, String)> = vec![];
+        loop {
+            self.skip_whitespace();
+
+            if self.rest().starts_with("/>") {
+                self.position += 2;
+                return Ok(MarkupNode { name, attributes, children: vec![], line });
+            }
+            if self.rest().starts_with('>') {
+                self.position += 1;
+                break;
+            }
+
+            let attribute_start = self.position;
+            let attribute = self.parse_name()?;
+            self.skip_whitespace();
+            self.expect("=")?;
+            self.skip_whitespace();
+            self.expect("\"")?;
+
+            let end = self.rest().find('"').ok_or_else(|| self.error(self.position, "the attribute value is never closed"))?;
+            let value = unescape(&self.rest()[..end]);
+            self.position += end + 1;
+
+            if attributes.iter().any(|(existing, _)| *existing == attribute) {
+                return Err(self.error(attribute_start, &format!("duplicate attribute '{}'", attribute)));
+            }
+            attributes.push((attribute, value));
+        }
+
+        let mut children = vec![];
+        loop {
+            self.skip_misc()?;
+
+            if self.rest().starts_with("</") {
+                self.position += 2;
+                let closing_start = self.position;
+                let closing = self.parse_name()?;
+                if closing != name {
+                    return Err(self.error(closing_start, &format!("expected '</{}>', found '</{}>'", name, closing)));
+                }
+
+                self.skip_whitespace();
+                self.expect(">")?;
+                return Ok(MarkupNode { name, attributes, children, line });
+            }
+
+            if self.rest().starts_with('<') {
+                children.push(self.parse_element()?);
+            } else if self.rest().is_empty() {
+                return Err(self.error(self.position, &format!("<{}> is never closed", name)));
+            } else {
+                return Err(self.error(self.position, "text content is not supported, use attributes"));
+            }
+        }
+    }
+
+    fn line(&self, offset: usize) -> usize {
+        self.source[..offset].matches('\n').count() + 1
+    }
+
+    fn error(&self, offset: usize, message: &str) -> MarkupError {
+        MarkupError {
+            line: self.line(offset),
+            message: message.to_string(),
+        }
+    }
+}
+
+fn unescape(value: &str) -> String {
+    value.replace("&quot;", "\"")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+pub type MarkupConstructor = Box<dyn Fn(&MarkupNode, &MarkupRegistry) -> Result<Box<dyn Component>, MarkupError>>;
+pub type MarkupHandler = Rc<dyn Fn(&mut EventContext)>;
+
+/// Maps element names to the constructors of components and handler names to event handlers.
+///
+/// ```xml
+/// <Layout rect="-1 1 1 -1" id="panel" class="card" style="background: surface; padding: spacing(2)">
+///     <Plain rect="-1 1 0 -1" color="#ff8000"/>
+///     <Button rect="0 1 1 -1" on-press="increment">
+///         <Plain rect="-0.5 0.5 0.5 -0.5" color="1 1 1"/>
+///     </Button>
+/// </Layout>
+/// ```
+///
+/// Every element takes `id`, `class` (separated by whitespace), `key` and `z-index`.
+/// Built in are `Layout` (`overflow="clip"`, `corner-radius`, `style`), `Plain` and `Clickable` (`color`, `style`, `on-click`),
+/// `Button` (`enabled`, `on-press`, the first child is its content), `Oval` (`color`), `ScrollView` (`content-size`),
+/// `Label` (`text`, `color` of the text, `style`) and `Image` (`src`, a path relative to the working directory, `style`).
+pub struct MarkupRegistry {
+    constructors: HashMap<String, MarkupConstructor>,
+    handlers: HashMap<String, MarkupHandler>,
+}
+
+impl MarkupRegistry {
+    /// A registry without any elements.
+    pub fn empty() -> Self {
+        Self {
+            constructors: HashMap::new(),
+            handlers: HashMap::new(),
+        }
+    }
+
+    /// A registry with the built in components.
+    pub fn new() -> Self {
+        let mut registry = Self::empty();
+
+        registry.register("Layout", |node, registry| {
+            let (top_left, bottom_right) = node.get_rect()?;
+            let mut layout = LayoutComponent::new(top_left, bottom_right);
+
+            match node.get_attribute("overflow") {
+                None | Some("visible") => {}
+                Some("clip") => layout.set_overflow(Overflow::Clip),
+                Some(overflow) => return Err(node.error(&format!("invalid overflow '{}'", overflow))),
+            }
+            if let Some(corner_radius) = node.parse_attribute("corner-radius")? {
+                layout.set_corner_radius(corner_radius);
+            }
+            if let Some(style) = node.get_style()? {
+                layout.set_style(style);
+            }
+
+            Ok(Box::new(registry.build_children(node, layout)?))
+        });
+
+        registry.register("Plain", |node, _| {
+            let (top_left, bottom_right) = node.get_rect()?;
+            let mut plain = PlainComponent::new(top_left, bottom_right, [1.0, 1.0, 1.0]);
+            plain.set_style(Self::get_box_style(node)?);
+
+            Ok(Box::new(plain))
+        });
+
+        registry.register("Clickable", |node, registry| {
+            let (top_left, bottom_right) = node.get_rect()?;
+            let mut clickable = ClickableComponent::new(top_left, bottom_right, [1.0, 1.0, 1.0]);
+            clickable.set_style(Self::get_box_style(node)?);
+
+            if let Some(handler) = registry.get_handler_of(node, "on-click")? {
+                clickable.set_on_click(move |context| handler(context));
+            }
+
+            Ok(Box::new(clickable))
+        });
+
+        registry.register("Button", |node, registry| {
+            let (top_left, bottom_right) = node.get_rect()?;
+            let mut button = Button::new(top_left, bottom_right);
+
+            if let Some(enabled) = node.get_flag("enabled")? {
+                button.set_enabled(enabled);
+            }
+            if let Some(handler) = registry.get_handler_of(node, "on-press")? {
+                button.set_on_press(move |context| handler(context));
+            }
+
+            match &node.children[..] {
+                [] => {}
+                [content] => button.set_content(registry.build(content)?),
+                _ => return Err(node.error("a <Button> has at most one child, its content")),
+            }
+
+            Ok(Box::new(button))
+        });
+
+        registry.register("Oval", |node, _| {
+            let (top_left, bottom_right) = node.get_rect()?;
+            let color = node.get_color("color")?.unwrap_or([1.0, 1.0, 1.0]);
+
+            Ok(Box::new(OvalComponent::new(top_left, bottom_right, color)))
+        });
+
+        registry.register("Label", |node, _| {
+            let (top_left, bottom_right) = node.get_rect()?;
+            let mut label = Label::new(top_left, bottom_right, node.get_attribute("text").unwrap_or_default());
+
+            let mut style = node.get_style()?.unwrap_or_default();
+            if style.foreground.is_none() {
+                style.foreground = node.get_color("color")?.map(Color::Rgb);
+            }
+            label.set_style(style);
+
+            Ok(Box::new(label))
+        });
+
+        registry.register("Image", |node, _| {
+            let (top_left, bottom_right) = node.get_rect()?;
+            let path = node.get_attribute("src").ok_or_else(|| node.error("an <Image> needs a 'src'"))?;
+            let mut image = ImageComponent::load(top_left, bottom_right, path)
+                .map_err(|error| node.error(&format!("could not load '{}': {:#}", path, error)))?;
+            image.set_style(node.get_style()?.unwrap_or_default());
+
+            Ok(Box::new(image))
+        });
+
+        registry.register("ScrollView", |node, registry| {
+            let (top_left, bottom_right) = node.get_rect()?;
+            let content_size = node.get_numbers("content-size", 2)?.unwrap_or(vec![1.0, 1.0]);
+            let scroll_view = ScrollView::new(top_left, bottom_right, (content_size[0], content_size[1]));
+
+            Ok(Box::new(registry.build_children(node, scroll_view)?))
+        });
+
+        registry
+    }
+
+    /// Registers the constructor of the elements called `name`, replacing a previous one.
+    /// The attributes every element takes are applied by the registry afterwards.
+    pub fn register(&mut self, name: &str, constructor: impl Fn(&MarkupNode, &MarkupRegistry) -> Result<Box<dyn Component>, MarkupError> + 'static) {
+        self.constructors.insert(name.to_string(), Box::new(constructor));
+    }
+
+    /// Registers a handler that elements refer to by `name`, like `on-press="name"`.
+    pub fn register_handler(&mut self, name: &str, handler: impl Fn(&mut EventContext) + 'static) {
+        self.handlers.insert(name.to_string(), Rc::new(handler));
+    }
+
+    pub fn get_handler(&self, name: &str) -> Option<MarkupHandler> {
+        self.handlers.get(name).cloned()
+    }
+
+    /// The handler named by the attribute `attribute` of `node`, an error if there is no such handler.
+    pub fn get_handler_of(&self, node: &MarkupNode, attribute: &str) -> Result<Option<MarkupHandler>, MarkupError> {
+        node.get_attribute(attribute)
+            .map(|name| self.get_handler(name).ok_or_else(|| node.error(&format!("unknown handler '{}'", name))))
+            .transpose()
+    }
+
+    /// Builds the component for `node` and its children.
+    pub fn build(&self, node: &MarkupNode) -> Result<Box<dyn Component>, MarkupError> {
+        let constructor = self.constructors.get(&node.name)
+            .ok_or_else(|| node.error(&format!("unknown element <{}>", node.name)))?;
+        let mut component = constructor(node, self)?;
+
+        if let Some(id) = node.get_attribute("id") {
+            component.set_id(id);
+        }
+        for class in node.get_attribute("class").unwrap_or_default().split_whitespace() {
+            component.add_class(class);
+        }
+        if let Some(key) = node.get_attribute("key") {
+            component.set_key(key);
+        }
+        if let Some(z_index) = node.parse_attribute("z-index")? {
+            component.set_z_index(z_index);
+        }
+
+        Ok(component)
+    }
+
+    /// Builds the children of `node` into `container`.
+    pub fn build_children<C: Container>(&self, node: &MarkupNode, mut container: C) -> Result<C, MarkupError> {
+        for child in node.children.iter() {
+            container.add_component(self.build(child)?);
+        }
+        Ok(container)
+    }
+
+    /// Parses `source` and builds its root element.
+    pub fn build_source(&self, source: &str) -> Result<Box<dyn Component>, MarkupError> {
+        self.build(&MarkupNode::parse(source)?)
+    }
+
+    pub fn load<P: AsRef<Path>>(&self, path: P) -> anyhow::Result<Box<dyn Component>> {
+        Ok(self.build(&MarkupNode::load(path)?)?)
+    }
+
+    /// The inline style with the `color` attribute as the background, unless the style sets one.
+    fn get_box_style(node: &MarkupNode) -> Result<Style, MarkupError> {
+        let mut style = node.get_style()?.unwrap_or_default();
+        if style.background.is_none() {
+            style.background = Some(Color::Rgb(node.get_color("color")?.unwrap_or([1.0, 1.0, 1.0])));
+        }
+        Ok(style)
+    }
+}
+
+impl Default for MarkupRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::components::label::Label;
+
+    #[test]
+    fn elements_attributes_and_children_are_parsed() {
+        let node = MarkupNode::parse(r#"<?xml version="1.0"?>
+            <!-- A comment -->
+            <Layout rect="-1 1 1 -1" id="root">
+                <Plain rect="0 0 1 -1" color="&quot;red&quot;"/>
+                <Button rect="-1 1 0 0"></Button>
+            </Layout>"#).unwrap();
+
+        assert_eq!(node.name, "Layout");
+        assert_eq!(node.line, 3);
+        assert_eq!(node.get_attribute("id"), Some("root"));
+        assert_eq!(node.get_rect().unwrap(), ((-1.0, 1.0), (1.0, -1.0)));
+        assert_eq!(node.children.iter().map(|child| child.name.as_str()).collect::<Vec<_>>(), ["Plain", "Button"]);
+        assert_eq!(node.children[0].get_attribute("color"), Some("\"red\""));
+        assert_eq!(node.children[1].line, 5);
+    }
+
+    #[test]
+    fn malformed_documents_report_their_line() {
+        let cases = [
+            ("<Layout>\n</Plain>", 2),
+            ("<Layout>\n  text\n</Layout>", 2),
+            ("<Plain a=\"1\" a=\"2\"/>", 1),
+            ("<Plain/>\n<Plain/>", 2),
+            ("<Layout>\n<!-- never closed", 2),
+        ];
+
+        for (source, line) in cases {
+            assert_eq!(MarkupNode::parse(source).unwrap_err().line, line, "{}", source);
+        }
+    }
+
+    #[test]
+    fn colors_are_hex_or_three_numbers() {
+        let node = MarkupNode::parse(r##"<Plain a="#ff0000" b="0 0.5 1" c="1 2"/>"##).unwrap();
+
+        assert_eq!(node.get_color("a").unwrap(), Some([1.0, 0.0, 0.0]));
+        assert_eq!(node.get_color("b").unwrap(), Some([0.0, 0.5, 1.0]));
+        assert!(node.get_color("c").is_err());
+        assert_eq!(node.get_color("d").unwrap(), None);
+    }
+
+    #[test]
+    fn the_registry_builds_the_common_attributes() {
+        let registry = MarkupRegistry::new();
+        let root = registry.build_source(r#"<Layout rect="-1 1 1 -1" id="root" class="a b" key="k" z-index="2">
+                                                <Label rect="-1 1 1 0" text="Hello" color="1 1 1"/>
+                                            </Layout>"#).unwrap();
+
+        assert_eq!(root.get_id(), Some("root"));
+        assert_eq!(root.get_classes(), ["a", "b"]);
+        assert_eq!(root.get_key(), Some("k"));
+        assert_eq!(root.get_z_index(), 2);
+
+        let label = root.get_children()[0].as_any().downcast_ref::<Label>().unwrap();
+        assert_eq!(label.get_text(), "Hello");
+        assert_eq!(label.get_style().and_then(|style| style.foreground), Some(Color::Rgb([1.0; 3])));
+    }
+
+    #[test]
+    fn images_are_loaded_from_their_source() {
+        let registry = MarkupRegistry::new();
+
+        assert!(registry.build_source(r#"<Image rect="-1 1 1 -1" src="src/happy-tree.png"/>"#).is_ok());
+        assert!(registry.build_source(r#"<Image rect="-1 1 1 -1"/>"#).is_err());
+        assert!(registry.build_source(r#"<Image rect="-1 1 1 -1" src="missing.png"/>"#).is_err());
+    }
+
+    #[test]
+    fn unknown_elements_and_handlers_are_errors() {
+        let mut registry = MarkupRegistry::new();
+        registry.register_handler("press", |_| {});
+
+        assert!(registry.build_source(r#"<Button rect="-1 1 1 -1" on-press="press"/>"#).is_ok());
+        assert!(registry.build_source(r#"<Button rect="-1 1 1 -1" on-press="other"/>"#).is_err());
+        assert!(registry.build_source(r#"<Text rect="-1 1 1 -1"/>"#).is_err());
+        assert!(registry.build_source(r#"<Plain/>"#).is_err());
+    }
+}
@@ -0,0 +1,417 @@
+use std::collections::VecDeque;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU32, AtomicU64, AtomicU8, Ordering};
+use std::time::{Duration, Instant};
+use wgpu::util::DeviceExt;
+use wgpu::{Buffer, CommandEncoder, Device, Queue};
+
+/// Number of frames kept for `Profiler::get_history`.
+const HISTORY_LENGTH: usize = 240;
+
+static DRAW_CALLS: AtomicU32 = AtomicU32::new(0);
+static BUFFERS_CREATED: AtomicU32 = AtomicU32::new(0);
+static PIPELINES_CREATED: AtomicU32 = AtomicU32::new(0);
+static TEXTURES_CREATED: AtomicU32 = AtomicU32::new(0);
+static GEOMETRY_NANOS: AtomicU64 = AtomicU64::new(0);
+static LAYOUT_NANOS: AtomicU64 = AtomicU64::new(0);
+
+pub(crate) fn count_draw_call() {
+    DRAW_CALLS.fetch_add(1, Ordering::Relaxed);
+}
+
+pub(crate) fn count_pipeline() {
+    PIPELINES_CREATED.fetch_add(1, Ordering::Relaxed);
+}
+
+pub(crate) fn count_texture() {
+    TEXTURES_CREATED.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Runs `layout` and counts the time it took as layout. Components are laid out while they are encoded,
+/// so the time is summed up separately and taken out of the encoding.
+pub(crate) fn time_layout<T>(layout: impl FnOnce() -> T) -> T {
+    let start = Instant::now();
+    let result = layout();
+
+    LAYOUT_NANOS.fetch_add(start.elapsed().as_nanos() as u64, Ordering::Relaxed);
+    result
+}
+
+/// Creates a buffer with its contents and counts it, together with the time it took, as geometry generation.
+pub(crate) fn create_buffer_init(device: &Device, descriptor: &wgpu::util::BufferInitDescriptor) -> Buffer {
+    let start = Instant::now();
+    let buffer = device.create_buffer_init(descriptor);
+
+    BUFFERS_CREATED.fetch_add(1, Ordering::Relaxed);
+    GEOMETRY_NANOS.fetch_add(start.elapsed().as_nanos() as u64, Ordering::Relaxed);
+    buffer
+}
+
+/// What the renderer did since the counters were last taken.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct FrameCounters {
+    pub draw_calls: u32,
+    pub buffers_created: u32,
+    pub pipelines_created: u32,
+    pub textures_created: u32,
+}
+
+/// Returns the counters and starts counting from zero.
+pub fn take_counters() -> FrameCounters {
+    FrameCounters {
+        draw_calls: DRAW_CALLS.swap(0, Ordering::Relaxed),
+        buffers_created: BUFFERS_CREATED.swap(0, Ordering::Relaxed),
+        pipelines_created: PIPELINES_CREATED.swap(0, Ordering::Relaxed),
+        textures_created: TEXTURES_CREATED.swap(0, Ordering::Relaxed),
+    }
+}
+
+fn take_geometry_time() -> Duration {
+    Duration::from_nanos(GEOMETRY_NANOS.swap(0, Ordering::Relaxed))
+}
+
+/// Returns the layout time and starts measuring from zero.
+pub fn take_layout_time() -> Duration {
+    Duration::from_nanos(LAYOUT_NANOS.swap(0, Ordering::Relaxed))
+}
+
+/// The parts of a frame timed on the CPU.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Phase {
+    /// Passing input events to the overlays and the tree.
+    Events,
+    /// Handing messages to the application and reconciling its new view.
+    Update,
+    /// Placing the components and resolving their styles.
+    Layout,
+    /// Walking the tree apart from the layout, which generates the geometry and records the draws.
+    Encode,
+    /// Finishing the command buffer, submitting it and presenting the frame.
+    Submit,
+}
+
+/// Where the time of a frame went.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct FrameStats {
+    pub frame: u64,
+    /// Input handled since the previous frame.
+    pub events: Duration,
+    pub update: Duration,
+    pub layout: Duration,
+    pub encode: Duration,
+    /// The part of `encode` spent creating buffers.
+    pub geometry: Duration,
+    pub submit: Duration,
+    /// The time between the first and the last command of the frame on the GPU,
+    /// `None` without timestamp queries or while the GPU has not finished it.
+    pub gpu: Option<Duration>,
+    pub counters: FrameCounters,
+}
+
+impl FrameStats {
+    /// The CPU time of all phases.
+    pub fn get_cpu_time(&self) -> Duration {
+        self.events + self.update + self.layout + self.encode + self.submit
+    }
+}
+
+/// Collects the `FrameStats` of the last frames.
+pub struct Profiler {
+    history: VecDeque<FrameStats>,
+    current: FrameStats,
+    gpu_timer: Option<GpuTimer>,
+}
+
+impl Profiler {
+    /// GPU times are only measured if the device was created with `Features::TIMESTAMP_QUERY`.
+    pub fn new(device: &Device, queue: &Queue) -> Self {
+        let gpu_timer = device.features().contains(wgpu::Features::TIMESTAMP_QUERY)
+            .then(|| GpuTimer::new(device, queue));
+
+        Self {
+            history: VecDeque::with_capacity(HISTORY_LENGTH),
+            current: FrameStats::default(),
+            gpu_timer,
+        }
+    }
+
+    pub fn has_gpu_timer(&self) -> bool {
+        self.gpu_timer.is_some()
+    }
+
+    /// Adds time spent in a phase to the current frame.
+    pub fn record(&mut self, phase: Phase, duration: Duration) {
+        match phase {
+            Phase::Events => self.current.events += duration,
+            Phase::Update => self.current.update += duration,
+            Phase::Layout => self.current.layout += duration,
+            Phase::Encode => self.current.encode += duration,
+            Phase::Submit => self.current.submit += duration,
+        }
+    }
+
+    /// Writes the GPU timestamp at the start of the frame.
+    pub fn begin_gpu_frame(&mut self, encoder: &mut CommandEncoder) {
+        if let Some(gpu_timer) = self.gpu_timer.as_mut() {
+            gpu_timer.begin(encoder);
+        }
+    }
+
+    /// Writes the GPU timestamp at the end of the frame, before the encoder is finished.
+    pub fn end_gpu_frame(&mut self, encoder: &mut CommandEncoder) {
+        if let Some(gpu_timer) = self.gpu_timer.as_mut() {
+            gpu_timer.end(encoder);
+        }
+    }
+
+    /// Takes the counters of the renderer. Draws recorded afterwards count towards the next frame.
+    pub fn take_counters(&mut self) {
+        self.current.counters = take_counters();
+        self.current.geometry = take_geometry_time();
+    }
+
+    /// Completes the current frame after it was submitted.
+    pub fn end_frame(&mut self, device: &Device) {
+        let frame = self.current.frame;
+        if self.history.len() == HISTORY_LENGTH {
+            self.history.pop_front();
+        }
+        self.history.push_back(self.current);
+
+        self.current = FrameStats {
+            frame: frame + 1,
+            ..FrameStats::default()
+        };
+
+        if let Some(gpu_timer) = self.gpu_timer.as_mut() {
+            gpu_timer.after_submit();
+            gpu_timer.set_frame(frame + 1);
+
+            // The GPU finishes frames later, their time is filled in once it is read back
+            if let Some((frame, duration)) = gpu_timer.read(device) {
+                if let Some(stats) = self.history.iter_mut().find(|stats| stats.frame == frame) {
+                    stats.gpu = Some(duration);
+                }
+            }
+        }
+    }
+
+    pub fn get_last_frame(&self) -> Option<&FrameStats> {
+        self.history.back()
+    }
+
+    /// The last frames, oldest first.
+    pub fn get_history(&self) -> &VecDeque<FrameStats> {
+        &self.history
+    }
+}
+
+const MAP_PENDING: u8 = 0;
+const MAP_DONE: u8 = 1;
+const MAP_FAILED: u8 = 2;
+
+/// Measures frames on the GPU with timestamp queries. The results are read back without waiting,
+/// frames submitted while the previous result is still being read are not measured.
+struct GpuTimer {
+    query_set: wgpu::QuerySet,
+    /// The timestamps are resolved into it and mapped from it.
+    read_buffer: Buffer,
+    /// Nanoseconds per timestamp tick.
+    period: f32,
+
+    frame: u64,
+    /// The frame whose timestamps are written in the encoder that is being recorded.
+    writing: Option<u64>,
+    /// The frame whose timestamps are being read back.
+    reading: Option<u64>,
+    map_state: Arc<AtomicU8>,
+}
+
+impl GpuTimer {
+    fn new(device: &Device, queue: &Queue) -> Self {
+        Self {
+            query_set: device.create_query_set(&wgpu::QuerySetDescriptor {
+                label: Some("Frame Timestamps"),
+                ty: wgpu::QueryType::Timestamp,
+                count: 2,
+            }),
+            read_buffer: device.create_buffer(&wgpu::BufferDescriptor {
+                label: Some("Timestamp Buffer"),
+                size: 2 * std::mem::size_of::<u64>() as u64,
+                usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+                mapped_at_creation: false,
+            }),
+            period: queue.get_timestamp_period(),
+            frame: 0,
+            writing: None,
+            reading: None,
+            map_state: Arc::new(AtomicU8::new(MAP_PENDING)),
+        }
+    }
+
+    fn set_frame(&mut self, frame: u64) {
+        self.frame = frame;
+    }
+
+    fn begin(&mut self, encoder: &mut CommandEncoder) {
+        if self.reading.is_none() {
+            encoder.write_timestamp(&self.query_set, 0);
+            self.writing = Some(self.frame);
+        }
+    }
+
+    fn end(&mut self, encoder: &mut CommandEncoder) {
+        if self.writing.is_some() {
+            encoder.write_timestamp(&self.query_set, 1);
+            encoder.resolve_query_set(&self.query_set, 0..2, &self.read_buffer, 0);
+        }
+    }
+
+    fn after_submit(&mut self) {
+        if let Some(frame) = self.writing.take() {
+            self.map_state.store(MAP_PENDING, Ordering::SeqCst);
+            let map_state = self.map_state.clone();
+            self.read_buffer.slice(..).map_async(wgpu::MapMode::Read, move |result| {
+                map_state.store(if result.is_ok() { MAP_DONE } else { MAP_FAILED }, Ordering::SeqCst);
+            });
+            self.reading = Some(frame);
+        }
+    }
+
+    /// Returns the frame and its duration once the GPU finished it.
+    fn read(&mut self, device: &Device) -> Option<(u64, Duration)> {
+        let frame = self.reading?;
+        device.poll(wgpu::Maintain::Poll);
+
+        match self.map_state.load(Ordering::SeqCst) {
+            MAP_PENDING => None,
+            MAP_FAILED => {
+                self.reading = None;
+                None
+            }
+            _ => {
+                let timestamps: [u64; 2] = {
+                    let range = self.read_buffer.slice(..).get_mapped_range();
+                    let timestamps: &[u64] = bytemuck::cast_slice(&range);
+                    [timestamps[0], timestamps[1]]
+                };
+                self.read_buffer.unmap();
+                self.reading = None;
+
+                let ticks = timestamps[1].saturating_sub(timestamps[0]);
+                Some((frame, Duration::from_nanos((ticks as f64 * self.period as f64) as u64)))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::animation::FrameClock;
+    use crate::components::button::Button;
+    use crate::components::clip::ScissorRect;
+    use crate::components::component::{Component, RenderContext};
+    use crate::components::invalidation::Invalidation;
+    use crate::components::label::Label;
+    use crate::components::layout::LayoutComponent;
+    use crate::components::plain::PlainComponent;
+    use crate::shapes::pipeline::PipelineCache;
+    use crate::style::{Color, Length, Style};
+    use crate::stylesheet::Stylesheet;
+    use crate::texture::Texture;
+    use crate::theme::Theme;
+    use crate::transform::Transform;
+
+    /// A device of the first adapter, `None` on machines without a GPU or a software renderer.
+    fn request_device() -> (Device, Queue) {
+        let instance = wgpu::Instance::new(wgpu::Backends::all());
+        let adapter = pollster::block_on(instance.request_adapter(&wgpu::RequestAdapterOptions::default())).expect("no GPU adapter");
+        let descriptor = wgpu::DeviceDescriptor {
+            features: wgpu::Features::empty(),
+            limits: adapter.limits(),
+            label: None,
+        };
+        pollster::block_on(adapter.request_device(&descriptor, None)).expect("no GPU device")
+    }
+
+    fn render_frame(root: &mut dyn Component, device: &Device, queue: &Queue, config: &wgpu::SurfaceConfiguration, pipelines: &PipelineCache) {
+        let target = Texture::create_render_target(device, config, "Target");
+        let depth_stencil = Texture::create_depth_stencil_texture(device, config, "Depth Stencil");
+        // The targets stand in for the window and are not part of the frame
+        take_counters();
+
+        let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor { label: None });
+        let mut render_pass = encoder.begin_render_pass(&wgpu::RenderPassDescriptor {
+            label: None,
+            color_attachments: &[Some(wgpu::RenderPassColorAttachment {
+                view: &target.view,
+                resolve_target: None,
+                ops: wgpu::Operations { load: wgpu::LoadOp::Clear(wgpu::Color::BLACK), store: true },
+            })],
+            depth_stencil_attachment: Some(wgpu::RenderPassDepthStencilAttachment {
+                view: &depth_stencil.view,
+                depth_ops: None,
+                stencil_ops: Some(wgpu::Operations { load: wgpu::LoadOp::Clear(0), store: true }),
+            }),
+        });
+
+        let theme = Theme::dark();
+        let context = RenderContext {
+            device,
+            queue,
+            config,
+            pipelines,
+            transform: Transform::IDENTITY,
+            opacity: 1.0,
+            clip: ScissorRect::full(config),
+            stencil_depth: 0,
+            focused: None,
+            invalidation: &Invalidation::new(),
+            clock: &FrameClock::new(),
+            theme: &theme,
+            inherited: theme.get_inherited(),
+            stylesheet: &Stylesheet::default(),
+            style_path: None,
+        };
+        root.render(&(-1.0, 1.0), &(1.0, -1.0), &mut render_pass, &context);
+
+        drop(render_pass);
+        queue.submit(std::iter::once(encoder.finish()));
+    }
+
+    #[test]
+    #[ignore = "needs a GPU adapter"]
+    fn idle_frames_create_no_buffers() {
+        let (device, queue) = request_device();
+        let config = wgpu::SurfaceConfiguration {
+            usage: wgpu::TextureUsages::RENDER_ATTACHMENT,
+            format: wgpu::TextureFormat::Rgba8UnormSrgb,
+            width: 200,
+            height: 100,
+            present_mode: wgpu::PresentMode::Fifo,
+        };
+        let pipelines = PipelineCache::new(config.format, &device);
+
+        let mut root = LayoutComponent::new((-1.0, 1.0), (1.0, -1.0));
+        root.set_style(Style {
+            background: Some(Color::Surface),
+            radius: Some(Length::Px(6.0)),
+            ..Style::default()
+        });
+        root.add_component(Box::new(PlainComponent::new((-1.0, 1.0), (0.0, 0.0), [1.0, 0.0, 0.0])));
+        root.add_component(Box::new(Label::new((0.0, 1.0), (1.0, 0.0), "Idle")));
+        root.add_component(Box::new(Button::new((-1.0, 0.0), (1.0, -1.0))));
+
+        render_frame(&mut root, &device, &queue, &config, &pipelines);
+        let first = take_counters();
+        render_frame(&mut root, &device, &queue, &config, &pipelines);
+        let idle = take_counters();
+
+        assert!(first.buffers_created > 0 && first.textures_created > 0);
+        assert_eq!(idle.buffers_created, 0);
+        assert_eq!(idle.textures_created, 0);
+        assert_eq!(idle.pipelines_created, 0);
+        assert_eq!(idle.draw_calls, first.draw_calls);
+    }
+}
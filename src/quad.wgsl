@@ -1,5 +1,14 @@
 // Vertex shader
 
+struct TransformUniform {
+    view: mat4x4<f32>,
+    opacity: f32,
+    tint: vec3<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> transform: TransformUniform;
+
 struct VertexInput {
     @location(0) position: vec3<f32>,
     @location(1) color: vec3<f32>,
@@ -16,7 +25,7 @@ fn vs_main(
 ) -> VertexOutput {
     var out: VertexOutput;
     out.color = model.color;
-    out.clip_position = vec4<f32>(model.position, 1.0);
+    out.clip_position = transform.view * vec4<f32>(model.position, 1.0);
     return out;
 }
 
@@ -24,5 +33,5 @@ fn vs_main(
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
-    return vec4<f32>(in.color, 1.0);
+    return vec4<f32>(in.color * transform.tint, transform.opacity);
 }
\ No newline at end of file
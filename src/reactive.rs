@@ -0,0 +1,316 @@
+use std::cell::{Cell, RefCell};
+use std::rc::{Rc, Weak};
+
+thread_local! {
+    /// The observer whose closure is running, every signal read while it runs becomes one of its dependencies.
+    static CURRENT_OBSERVER: RefCell<Option<Rc<Node>>> = const { RefCell::new(None) };
+    /// Set whenever a signal changed, so the window knows it has to be redrawn.
+    static CHANGED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Whether any signal changed since the last call.
+pub fn take_changes() -> bool {
+    CHANGED.with(|changed| changed.replace(false))
+}
+
+/// A vertex of the dependency graph. Signals only use the subscribers,
+/// observers use the dirty flag and the sources, computed values use all of them.
+#[derive(Default)]
+struct Node {
+    dirty: Cell<bool>,
+    subscribers: RefCell<Vec<Weak<Node>>>,
+    /// The nodes this one subscribed to while it was tracked last.
+    sources: RefCell<Vec<Weak<Node>>>,
+}
+
+impl Node {
+    fn subscribe_current(self: &Rc<Self>) {
+        CURRENT_OBSERVER.with(|current| {
+            if let Some(observer) = current.borrow().as_ref() {
+                let mut subscribers = self.subscribers.borrow_mut();
+                if !subscribers.iter().any(|subscriber| std::ptr::eq(subscriber.as_ptr(), Rc::as_ptr(observer))) {
+                    subscribers.push(Rc::downgrade(observer));
+                    observer.sources.borrow_mut().push(Rc::downgrade(self));
+                }
+            }
+        });
+    }
+
+    /// Removes this node from the subscribers of everything it read, before it is tracked again.
+    fn unsubscribe(&self) {
+        for source in self.sources.take() {
+            if let Some(source) = source.upgrade() {
+                source.subscribers.borrow_mut().retain(|subscriber| !std::ptr::eq(subscriber.as_ptr(), self));
+            }
+        }
+    }
+
+    fn notify(&self) {
+        // Collect first, marking a subscriber dirty may read this node again
+        let subscribers: Vec<Rc<Node>> = {
+            let mut subscribers = self.subscribers.borrow_mut();
+            subscribers.retain(|subscriber| subscriber.strong_count() > 0);
+            subscribers.iter().filter_map(Weak::upgrade).collect()
+        };
+
+        for subscriber in subscribers {
+            subscriber.mark_dirty();
+        }
+    }
+
+    fn mark_dirty(&self) {
+        // A dirty node already notified its subscribers
+        if self.dirty.replace(true) {
+            return;
+        }
+        self.notify();
+    }
+}
+
+/// Records which signals are read inside `track` and becomes dirty when one of them changes.
+#[derive(Clone)]
+pub struct Observer {
+    node: Rc<Node>,
+}
+
+impl Observer {
+    /// The observer starts dirty, so its closure runs at least once.
+    pub fn new() -> Self {
+        let node = Rc::new(Node::default());
+        node.dirty.set(true);
+        Self { node }
+    }
+
+    pub fn is_dirty(&self) -> bool {
+        self.node.dirty.get()
+    }
+
+    pub fn mark_dirty(&self) {
+        self.node.mark_dirty();
+    }
+
+    /// Runs `f` and subscribes to every signal and computed value it reads.
+    /// The subscriptions of the previous run are dropped, so values that are no longer read do not mark it dirty.
+    pub fn track<R>(&self, f: impl FnOnce() -> R) -> R {
+        self.node.dirty.set(false);
+        self.node.unsubscribe();
+
+        let previous = CURRENT_OBSERVER.with(|current| current.replace(Some(self.node.clone())));
+        let result = f();
+        CURRENT_OBSERVER.with(|current| *current.borrow_mut() = previous);
+
+        result
+    }
+}
+
+impl Default for Observer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+struct SignalInner<T> {
+    value: RefCell<T>,
+    node: Rc<Node>,
+}
+
+/// A value that marks everything that read it dirty when it changes.
+/// Clones share the same value.
+pub struct Signal<T> {
+    inner: Rc<SignalInner<T>>,
+}
+
+impl<T> Clone for Signal<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: 'static> Signal<T> {
+    pub fn new(value: T) -> Self {
+        Self {
+            inner: Rc::new(SignalInner {
+                value: RefCell::new(value),
+                node: Rc::new(Node::default()),
+            }),
+        }
+    }
+
+    /// Reads the value without cloning it.
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        self.inner.node.subscribe_current();
+        f(&self.inner.value.borrow())
+    }
+
+    pub fn with_untracked<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        f(&self.inner.value.borrow())
+    }
+
+    pub fn set(&self, value: T) {
+        *self.inner.value.borrow_mut() = value;
+        self.changed();
+    }
+
+    pub fn update(&self, f: impl FnOnce(&mut T)) {
+        f(&mut self.inner.value.borrow_mut());
+        self.changed();
+    }
+
+    fn changed(&self) {
+        CHANGED.with(|changed| changed.set(true));
+        self.inner.node.notify();
+    }
+}
+
+impl<T: Clone + 'static> Signal<T> {
+    pub fn get(&self) -> T {
+        self.with(T::clone)
+    }
+
+    pub fn get_untracked(&self) -> T {
+        self.with_untracked(T::clone)
+    }
+}
+
+struct ComputedInner<T> {
+    compute: Box<dyn Fn() -> T>,
+    value: RefCell<Option<T>>,
+    observer: Observer,
+}
+
+/// A value derived from signals. It is recomputed lazily on the first read after one of them changed.
+pub struct Computed<T> {
+    inner: Rc<ComputedInner<T>>,
+}
+
+impl<T> Clone for Computed<T> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<T: 'static> Computed<T> {
+    pub fn new(compute: impl Fn() -> T + 'static) -> Self {
+        Self {
+            inner: Rc::new(ComputedInner {
+                compute: Box::new(compute),
+                value: RefCell::new(None),
+                observer: Observer::new(),
+            }),
+        }
+    }
+
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        self.inner.observer.node.subscribe_current();
+
+        if self.inner.observer.is_dirty() || self.inner.value.borrow().is_none() {
+            let value = self.inner.observer.track(|| (self.inner.compute)());
+            *self.inner.value.borrow_mut() = Some(value);
+        }
+
+        let value = self.inner.value.borrow();
+        f(value.as_ref().expect("computed value is set above"))
+    }
+}
+
+impl<T: Clone + 'static> Computed<T> {
+    pub fn get(&self) -> T {
+        self.with(T::clone)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subscriber_count<T>(signal: &Signal<T>) -> usize {
+        signal.inner.node.subscribers.borrow().len()
+    }
+
+    #[test]
+    fn observer_becomes_dirty_when_a_read_signal_changes() {
+        let signal = Signal::new(1);
+        let observer = Observer::new();
+        assert!(observer.is_dirty());
+
+        assert_eq!(observer.track(|| signal.get()), 1);
+        assert!(!observer.is_dirty());
+
+        signal.set(2);
+        assert!(observer.is_dirty());
+        assert!(take_changes());
+        assert!(!take_changes());
+    }
+
+    #[test]
+    fn untracked_reads_do_not_subscribe() {
+        let signal = Signal::new(1);
+        let observer = Observer::new();
+        observer.track(|| signal.get_untracked());
+
+        signal.set(2);
+        assert!(!observer.is_dirty());
+    }
+
+    #[test]
+    fn tracking_again_drops_the_old_subscriptions() {
+        let first = Signal::new(1);
+        let second = Signal::new(1);
+        let use_first = Signal::new(true);
+        let observer = Observer::new();
+
+        let read = || if use_first.get() { first.get() } else { second.get() };
+        observer.track(read);
+        assert_eq!(subscriber_count(&first), 1);
+
+        use_first.set(false);
+        observer.track(read);
+        assert_eq!(subscriber_count(&first), 0);
+        assert_eq!(subscriber_count(&second), 1);
+        assert_eq!(subscriber_count(&use_first), 1);
+
+        first.set(2);
+        assert!(!observer.is_dirty());
+        second.set(2);
+        assert!(observer.is_dirty());
+    }
+
+    #[test]
+    fn repeated_tracking_keeps_one_subscription() {
+        let signal = Signal::new(1);
+        let observer = Observer::new();
+        for _ in 0..10 {
+            observer.track(|| signal.get() + signal.get());
+        }
+        assert_eq!(subscriber_count(&signal), 1);
+    }
+
+    #[test]
+    fn computed_values_are_recomputed_lazily() {
+        let signal = Signal::new(2);
+        let runs = Rc::new(Cell::new(0));
+        let computed = {
+            let signal = signal.clone();
+            let runs = runs.clone();
+            Computed::new(move || {
+                runs.set(runs.get() + 1);
+                signal.get() * 10
+            })
+        };
+        let observer = Observer::new();
+
+        assert_eq!(observer.track(|| computed.get()), 20);
+        assert_eq!(computed.get(), 20);
+        assert_eq!(runs.get(), 1);
+
+        signal.set(3);
+        assert!(observer.is_dirty());
+        assert_eq!(runs.get(), 1);
+        assert_eq!(computed.get(), 30);
+        assert_eq!(runs.get(), 2);
+    }
+}
@@ -1,4 +1,8 @@
 pub mod shape;
 pub mod quad;
 pub mod oval;
+pub mod rounded_quad;
 pub mod vertex;
+pub mod uniform;
+pub mod pipeline;
+pub mod textured_quad;
\ No newline at end of file
@@ -1,8 +1,12 @@
 use std::f32::consts::PI;
+use std::rc::Rc;
 use wgpu::{Buffer, Device, RenderPipeline};
-use wgpu::util::DeviceExt;
-use crate::{Shape, State};
-use crate::shapes::vertex::{BasicColorVertex, Vertex};
+use crate::profiler;
+use crate::Shape;
+use crate::shapes::pipeline::{PipelineCache, StencilMode};
+use crate::shapes::uniform::TransformBinding;
+use crate::shapes::vertex::BasicColorVertex;
+use crate::transform::Transform;
 
 pub struct Oval {
     pub center: (f32, f32),
@@ -12,71 +16,23 @@ pub struct Oval {
 
     vertex_buffer: Buffer,
     indices_buffer: Buffer,
+    transform_binding: TransformBinding,
 
-    render_pipeline: RenderPipeline,
+    render_pipeline: Rc<RenderPipeline>,
 }
 
 impl Oval {
-    pub fn new(center: (f32, f32), diameter: (f32, f32), triangle_count: u16, color: [f32; 3], device: &Device, state: &State) -> Self {
-        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: None,
-            source: wgpu::ShaderSource::Wgsl(include_str!("../quad.wgsl").into()),
-        });
-
-        let render_pipeline_layout =
-            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-                label: Some("Render Pipeline Layout"),
-                bind_group_layouts: &[],
-                push_constant_ranges: &[],
-            });
-
-        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: None,
-            layout: Some(&render_pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader,
-                entry_point: "vs_main", // 1.
-                buffers: &[
-                    BasicColorVertex::get_descriptor(),
-                ], // 2.
-            },
-            fragment: Some(wgpu::FragmentState { // 3.
-                module: &shader,
-                entry_point: "fs_main",
-                targets: &[Some(wgpu::ColorTargetState { // 4.
-                    format: state.config.format,
-                    blend: Some(wgpu::BlendState::REPLACE),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList, // 1.
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Ccw, // 2.
-                cull_mode: Some(wgpu::Face::Back),
-                // Setting this to anything other than Fill requires Features::NON_FILL_POLYGON_MODE
-                polygon_mode: wgpu::PolygonMode::Fill,
-                // Requires Features::DEPTH_CLIP_CONTROL
-                unclipped_depth: false,
-                // Requires Features::CONSERVATIVE_RASTERIZATION
-                conservative: false,
-            },
-            depth_stencil: None, // 1.
-            multisample: wgpu::MultisampleState {
-                count: 1, // 2.
-                mask: !0, // 3.
-                alpha_to_coverage_enabled: false, // 4.
-            },
-            multiview: None, // 5.
-        });
+    pub fn new(center: (f32, f32), diameter: (f32, f32), triangle_count: u16, color: [f32; 3], device: &Device, pipelines: &PipelineCache) -> Self {
+        let render_pipeline = pipelines.get(StencilMode::Test, device);
 
         Self {
             center,
             diameter,
             triangle_count,
             color,
-            vertex_buffer: Self::generate_vertex_buffer(&center, &diameter, &triangle_count, &color, &device),
-            indices_buffer: Self::generate_indices_buffer(&triangle_count, &device),
+            vertex_buffer: Self::generate_vertex_buffer(&center, &diameter, &triangle_count, &color, device),
+            indices_buffer: Self::generate_indices_buffer(&triangle_count, device),
+            transform_binding: TransformBinding::new(Transform::IDENTITY, pipelines.get_transform_bind_group_layout(), device),
             render_pipeline,
         }
     }
@@ -89,10 +45,10 @@ impl Oval {
         for i in 0..vertex_count {
             let angle = ((PI * 2.0) / vertex_count as f32) * i as f32;
 
-            vertices.push(BasicColorVertex { position: [angle.cos() * diameter.0 + center.0, angle.sin() * diameter.1 + center.1, 0.0], color: color.clone() });
+            vertices.push(BasicColorVertex { position: [angle.cos() * diameter.0 + center.0, angle.sin() * diameter.1 + center.1, 0.0], color: *color });
         }
 
-        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        let vertex_buffer = profiler::create_buffer_init(device, &wgpu::util::BufferInitDescriptor {
             label: None,
             contents: bytemuck::cast_slice(&vertices),
             usage: wgpu::BufferUsages::VERTEX,
@@ -110,7 +66,7 @@ impl Oval {
             indices.push(i + 1);
         }
 
-        let indices_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        let indices_buffer = profiler::create_buffer_init(device, &wgpu::util::BufferInitDescriptor {
             label: Some("Index Buffer"),
             contents: bytemuck::cast_slice(&indices),
             usage: wgpu::BufferUsages::INDEX,
@@ -126,7 +82,7 @@ impl Shape for Oval {
     }
 
     fn update_vertex_buffer(&mut self, device: &Device) {
-        self.vertex_buffer = Oval::generate_vertex_buffer(&self.center, &self.diameter, &self.triangle_count, &self.color, &device);
+        self.vertex_buffer = Oval::generate_vertex_buffer(&self.center, &self.diameter, &self.triangle_count, &self.color, device);
     }
 
     fn get_indices_buffer(&self) -> &Buffer {
@@ -134,13 +90,21 @@ impl Shape for Oval {
     }
 
     fn update_indices_buffer(&mut self, device: &Device) {
-        self.indices_buffer = Oval::generate_indices_buffer(&self.triangle_count, &device);
+        self.indices_buffer = Oval::generate_indices_buffer(&self.triangle_count, device);
     }
 
     fn get_number_indices(&self) -> u32 {
         self.triangle_count as u32 * 3
     }
 
+    fn get_transform_binding(&self) -> &TransformBinding {
+        &self.transform_binding
+    }
+
+    fn get_transform_binding_mut(&mut self) -> &mut TransformBinding {
+        &mut self.transform_binding
+    }
+
     fn get_render_pipeline(&self) -> &RenderPipeline {
         &self.render_pipeline
     }
@@ -0,0 +1,174 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use wgpu::{BindGroupLayout, Device, RenderPipeline, TextureFormat, VertexBufferLayout};
+use crate::profiler;
+use crate::shapes::uniform::TransformBinding;
+use crate::shapes::vertex::{BasicColorVertex, TexturedVertex, Vertex};
+use crate::texture::Texture;
+
+/// Format of the depth stencil attachment every shape pipeline is created for.
+pub const DEPTH_STENCIL_FORMAT: TextureFormat = TextureFormat::Depth24PlusStencil8;
+
+/// How a pipeline interacts with the stencil buffer used for clipping.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum StencilMode {
+    /// Draws color where the stencil value equals the reference.
+    Test,
+    /// Increments the stencil value where it equals the reference without drawing color.
+    Increment,
+    /// Decrements the stencil value where it equals the reference without drawing color.
+    Decrement,
+}
+
+pub fn create_shape_pipeline(format: TextureFormat, stencil_mode: StencilMode, transform_bind_group_layout: &BindGroupLayout, device: &Device) -> RenderPipeline {
+    create_pipeline(format,
+                    stencil_mode,
+                    include_str!("../quad.wgsl"),
+                    BasicColorVertex::get_descriptor(),
+                    &[transform_bind_group_layout],
+                    device)
+}
+
+/// The pipeline of textured shapes, which bind their texture at `@group(1)`. They are only drawn, never used for clipping.
+pub fn create_textured_pipeline(format: TextureFormat, transform_bind_group_layout: &BindGroupLayout, texture_bind_group_layout: &BindGroupLayout, device: &Device) -> RenderPipeline {
+    create_pipeline(format,
+                    StencilMode::Test,
+                    include_str!("../textured_quad.wgsl"),
+                    TexturedVertex::get_descriptor(),
+                    &[transform_bind_group_layout, texture_bind_group_layout],
+                    device)
+}
+
+fn create_pipeline(format: TextureFormat, stencil_mode: StencilMode, shader: &str, vertex_layout: VertexBufferLayout, bind_group_layouts: &[&BindGroupLayout], device: &Device) -> RenderPipeline {
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: None,
+        source: wgpu::ShaderSource::Wgsl(shader.into()),
+    });
+
+    let render_pipeline_layout =
+        device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
+            label: Some("Render Pipeline Layout"),
+            bind_group_layouts,
+            push_constant_ranges: &[],
+        });
+
+    let (pass_op, write_mask) = match stencil_mode {
+        StencilMode::Test => (wgpu::StencilOperation::Keep, wgpu::ColorWrites::ALL),
+        StencilMode::Increment => (wgpu::StencilOperation::IncrementClamp, wgpu::ColorWrites::empty()),
+        StencilMode::Decrement => (wgpu::StencilOperation::DecrementClamp, wgpu::ColorWrites::empty()),
+    };
+
+    let stencil_face = wgpu::StencilFaceState {
+        compare: wgpu::CompareFunction::Equal,
+        fail_op: wgpu::StencilOperation::Keep,
+        depth_fail_op: wgpu::StencilOperation::Keep,
+        pass_op,
+    };
+
+    device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
+        label: None,
+        layout: Some(&render_pipeline_layout),
+        vertex: wgpu::VertexState {
+            module: &shader,
+            entry_point: "vs_main",
+            buffers: &[
+                vertex_layout,
+            ],
+        },
+        fragment: Some(wgpu::FragmentState {
+            module: &shader,
+            entry_point: "fs_main",
+            targets: &[Some(wgpu::ColorTargetState {
+                format,
+                blend: Some(wgpu::BlendState::ALPHA_BLENDING),
+                write_mask,
+            })],
+        }),
+        primitive: wgpu::PrimitiveState {
+            topology: wgpu::PrimitiveTopology::TriangleList,
+            strip_index_format: None,
+            front_face: wgpu::FrontFace::Ccw,
+            // Mirroring transforms flip the winding order, so nothing is culled
+            cull_mode: None,
+            // Setting this to anything other than Fill requires Features::NON_FILL_POLYGON_MODE
+            polygon_mode: wgpu::PolygonMode::Fill,
+            // Requires Features::DEPTH_CLIP_CONTROL
+            unclipped_depth: false,
+            // Requires Features::CONSERVATIVE_RASTERIZATION
+            conservative: false,
+        },
+        depth_stencil: Some(wgpu::DepthStencilState {
+            format: DEPTH_STENCIL_FORMAT,
+            depth_write_enabled: false,
+            depth_compare: wgpu::CompareFunction::Always,
+            stencil: wgpu::StencilState {
+                front: stencil_face,
+                back: stencil_face,
+                read_mask: 0xff,
+                write_mask: if stencil_mode == StencilMode::Test { 0 } else { 0xff },
+            },
+            bias: wgpu::DepthBiasState::default(),
+        }),
+        multisample: wgpu::MultisampleState {
+            count: 1,
+            mask: !0,
+            alpha_to_coverage_enabled: false,
+        },
+        multiview: None,
+    })
+}
+
+/// Shares the shape pipelines between all shapes drawn to the same surface,
+/// so creating a shape does not compile a shader.
+pub struct PipelineCache {
+    format: TextureFormat,
+    transform_bind_group_layout: BindGroupLayout,
+    texture_bind_group_layout: BindGroupLayout,
+
+    pipelines: RefCell<HashMap<StencilMode, Rc<RenderPipeline>>>,
+    textured_pipeline: RefCell<Option<Rc<RenderPipeline>>>,
+}
+
+impl PipelineCache {
+    pub fn new(format: TextureFormat, device: &Device) -> Self {
+        Self {
+            format,
+            transform_bind_group_layout: TransformBinding::create_bind_group_layout(device),
+            texture_bind_group_layout: Texture::create_bind_group_layout(device, "Texture Bind Group Layout"),
+            pipelines: RefCell::new(HashMap::new()),
+            textured_pipeline: RefCell::new(None),
+        }
+    }
+
+    pub fn get_format(&self) -> TextureFormat {
+        self.format
+    }
+
+    pub fn get_transform_bind_group_layout(&self) -> &BindGroupLayout {
+        &self.transform_bind_group_layout
+    }
+
+    pub fn get_texture_bind_group_layout(&self) -> &BindGroupLayout {
+        &self.texture_bind_group_layout
+    }
+
+    pub fn get(&self, stencil_mode: StencilMode, device: &Device) -> Rc<RenderPipeline> {
+        self.pipelines.borrow_mut()
+            .entry(stencil_mode)
+            .or_insert_with(|| {
+                profiler::count_pipeline();
+                Rc::new(create_shape_pipeline(self.format, stencil_mode, &self.transform_bind_group_layout, device))
+            })
+            .clone()
+    }
+
+    pub fn get_textured(&self, device: &Device) -> Rc<RenderPipeline> {
+        self.textured_pipeline.borrow_mut()
+            .get_or_insert_with(|| {
+                profiler::count_pipeline();
+                Rc::new(create_textured_pipeline(self.format, &self.transform_bind_group_layout, &self.texture_bind_group_layout, device))
+            })
+            .clone()
+    }
+}
@@ -1,7 +1,11 @@
-use wgpu::{Buffer, BufferAddress, Device, RenderPipeline, SurfaceConfiguration, VertexBufferLayout};
-use wgpu::util::DeviceExt;
+use std::rc::Rc;
+use wgpu::{Buffer, Device, RenderPipeline};
+use crate::profiler;
 use crate::{Shape};
-use crate::shapes::vertex::{BasicColorVertex, Vertex};
+use crate::shapes::pipeline::{PipelineCache, StencilMode};
+use crate::shapes::uniform::TransformBinding;
+use crate::shapes::vertex::BasicColorVertex;
+use crate::transform::Transform;
 
 pub struct Quad {
     pub top_left: (f32, f32),
@@ -10,85 +14,37 @@ pub struct Quad {
 
     vertex_buffer: Buffer,
     indices_buffer: Buffer,
+    transform_binding: TransformBinding,
 
-    render_pipeline: RenderPipeline,
+    render_pipeline: Rc<RenderPipeline>,
 }
 
 impl Quad {
-    pub fn new(top_left: (f32, f32), bottom_right: (f32, f32), color: [f32; 3], device: &Device, config: &SurfaceConfiguration) -> Self {
-        let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
-            label: None,
-            source: wgpu::ShaderSource::Wgsl(include_str!("../quad.wgsl").into()),
-        });
-
-        let render_pipeline_layout =
-            device.create_pipeline_layout(&wgpu::PipelineLayoutDescriptor {
-                label: Some("Render Pipeline Layout"),
-                bind_group_layouts: &[],
-                push_constant_ranges: &[],
-            });
-
-        let render_pipeline = device.create_render_pipeline(&wgpu::RenderPipelineDescriptor {
-            label: None,
-            layout: Some(&render_pipeline_layout),
-            vertex: wgpu::VertexState {
-                module: &shader,
-                entry_point: "vs_main", // 1.
-                buffers: &[
-                    BasicColorVertex::get_descriptor(),
-                ], // 2.
-            },
-            fragment: Some(wgpu::FragmentState { // 3.
-                module: &shader,
-                entry_point: "fs_main",
-                targets: &[Some(wgpu::ColorTargetState { // 4.
-                    format: config.format,
-                    blend: Some(wgpu::BlendState::REPLACE),
-                    write_mask: wgpu::ColorWrites::ALL,
-                })],
-            }),
-            primitive: wgpu::PrimitiveState {
-                topology: wgpu::PrimitiveTopology::TriangleList, // 1.
-                strip_index_format: None,
-                front_face: wgpu::FrontFace::Ccw, // 2.
-                cull_mode: Some(wgpu::Face::Back),
-                // Setting this to anything other than Fill requires Features::NON_FILL_POLYGON_MODE
-                polygon_mode: wgpu::PolygonMode::Fill,
-                // Requires Features::DEPTH_CLIP_CONTROL
-                unclipped_depth: false,
-                // Requires Features::CONSERVATIVE_RASTERIZATION
-                conservative: false,
-            },
-            depth_stencil: None, // 1.
-            multisample: wgpu::MultisampleState {
-                count: 1, // 2.
-                mask: !0, // 3.
-                alpha_to_coverage_enabled: false, // 4.
-            },
-            multiview: None, // 5.
-        });
+    pub fn new(top_left: (f32, f32), bottom_right: (f32, f32), color: [f32; 3], device: &Device, pipelines: &PipelineCache) -> Self {
+        let render_pipeline = pipelines.get(StencilMode::Test, device);
 
         Self {
             top_left,
             bottom_right,
             color,
-            vertex_buffer: Self::generate_vertex_buffer(&top_left, &bottom_right, &color, &device),
-            indices_buffer: Self::generate_indices_buffer(&device),
+            vertex_buffer: Self::generate_vertex_buffer(&top_left, &bottom_right, &color, device),
+            indices_buffer: Self::generate_indices_buffer(device),
+            transform_binding: TransformBinding::new(Transform::IDENTITY, pipelines.get_transform_bind_group_layout(), device),
             render_pipeline,
         }
     }
 
     fn generate_vertex_buffer(top_left: &(f32, f32), bottom_right: &(f32, f32), color: &[f32; 3], device: &Device) -> Buffer {
         let vertices: &[BasicColorVertex] = &[
-            BasicColorVertex { position: [top_left.0, top_left.1, 0.0], color: color.clone() },
-            BasicColorVertex { position: [top_left.0, bottom_right.1, 0.0], color: color.clone() },
-            BasicColorVertex { position: [bottom_right.0, bottom_right.1, 0.0], color: color.clone() },
-            BasicColorVertex { position: [bottom_right.0, top_left.1, 0.0], color: color.clone() },
+            BasicColorVertex { position: [top_left.0, top_left.1, 0.0], color: *color },
+            BasicColorVertex { position: [top_left.0, bottom_right.1, 0.0], color: *color },
+            BasicColorVertex { position: [bottom_right.0, bottom_right.1, 0.0], color: *color },
+            BasicColorVertex { position: [bottom_right.0, top_left.1, 0.0], color: *color },
         ];
 
-        let vertex_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        let vertex_buffer = profiler::create_buffer_init(device, &wgpu::util::BufferInitDescriptor {
             label: None,
-            contents: bytemuck::cast_slice(&vertices),
+            contents: bytemuck::cast_slice(vertices),
             usage: wgpu::BufferUsages::VERTEX,
         });
 
@@ -101,7 +57,7 @@ impl Quad {
             0, 2, 3
         ];
 
-        let indices_buffer = device.create_buffer_init(&wgpu::util::BufferInitDescriptor {
+        let indices_buffer = profiler::create_buffer_init(device, &wgpu::util::BufferInitDescriptor {
             label: Some("Index Buffer"),
             contents: bytemuck::cast_slice(indices),
             usage: wgpu::BufferUsages::INDEX,
@@ -117,7 +73,7 @@ impl Shape for Quad {
     }
 
     fn update_vertex_buffer(&mut self, device: &Device) {
-        self.vertex_buffer = Quad::generate_vertex_buffer(&self.top_left, &self.bottom_right, &self.color, &device);
+        self.vertex_buffer = Quad::generate_vertex_buffer(&self.top_left, &self.bottom_right, &self.color, device);
     }
 
     fn get_indices_buffer(&self) -> &Buffer {
@@ -125,13 +81,21 @@ impl Shape for Quad {
     }
 
     fn update_indices_buffer(&mut self, device: &Device) {
-        self.indices_buffer = Quad::generate_indices_buffer(&device);
+        self.indices_buffer = Quad::generate_indices_buffer(device);
     }
 
     fn get_number_indices(&self) -> u32 {
         6
     }
 
+    fn get_transform_binding(&self) -> &TransformBinding {
+        &self.transform_binding
+    }
+
+    fn get_transform_binding_mut(&mut self) -> &mut TransformBinding {
+        &mut self.transform_binding
+    }
+
     fn get_render_pipeline(&self) -> &RenderPipeline {
         &self.render_pipeline
     }
@@ -0,0 +1,128 @@
+use std::rc::Rc;
+use std::f32::consts::PI;
+use wgpu::{Buffer, Device, RenderPipeline};
+use crate::profiler;
+use crate::Shape;
+use crate::shapes::pipeline::{PipelineCache, StencilMode};
+use crate::shapes::uniform::TransformBinding;
+use crate::shapes::vertex::BasicColorVertex;
+use crate::transform::Transform;
+
+/// A rectangle with elliptic corners. The radius is given in view units per axis
+/// so that corners look round on screens that are not square.
+pub struct RoundedQuad {
+    pub top_left: (f32, f32),
+    pub bottom_right: (f32, f32),
+    pub radius: (f32, f32),
+    pub segments: u16,
+    pub color: [f32; 3],
+
+    vertex_buffer: Buffer,
+    indices_buffer: Buffer,
+    transform_binding: TransformBinding,
+
+    render_pipeline: Rc<RenderPipeline>,
+}
+
+impl RoundedQuad {
+    pub fn new(top_left: (f32, f32), bottom_right: (f32, f32), radius: (f32, f32), color: [f32; 3], stencil_mode: StencilMode, device: &Device, pipelines: &PipelineCache) -> Self {
+        let render_pipeline = pipelines.get(stencil_mode, device);
+
+        let segments = 8;
+
+        Self {
+            top_left,
+            bottom_right,
+            radius,
+            segments,
+            color,
+            vertex_buffer: Self::generate_vertex_buffer(&top_left, &bottom_right, &radius, &segments, &color, device),
+            indices_buffer: Self::generate_indices_buffer(&segments, device),
+            transform_binding: TransformBinding::new(Transform::IDENTITY, pipelines.get_transform_bind_group_layout(), device),
+            render_pipeline,
+        }
+    }
+
+    fn generate_vertex_buffer(top_left: &(f32, f32), bottom_right: &(f32, f32), radius: &(f32, f32), segments: &u16, color: &[f32; 3], device: &Device) -> Buffer {
+        let radius_x = radius.0.clamp(0.0, (bottom_right.0 - top_left.0).abs() / 2.0);
+        let radius_y = radius.1.clamp(0.0, (top_left.1 - bottom_right.1).abs() / 2.0);
+
+        let center = ((top_left.0 + bottom_right.0) / 2.0, (top_left.1 + bottom_right.1) / 2.0);
+
+        // Corner centers counter clockwise, starting at the top right one
+        let corners = [
+            (bottom_right.0 - radius_x, top_left.1 - radius_y),
+            (top_left.0 + radius_x, top_left.1 - radius_y),
+            (top_left.0 + radius_x, bottom_right.1 + radius_y),
+            (bottom_right.0 - radius_x, bottom_right.1 + radius_y),
+        ];
+
+        let mut vertices = vec![BasicColorVertex { position: [center.0, center.1, 0.0], color: *color }];
+
+        for (corner_index, corner) in corners.iter().enumerate() {
+            for i in 0..=*segments {
+                let angle = (PI / 2.0) * (corner_index as f32 + i as f32 / *segments as f32);
+
+                vertices.push(BasicColorVertex { position: [angle.cos() * radius_x + corner.0, angle.sin() * radius_y + corner.1, 0.0], color: *color });
+            }
+        }
+
+        profiler::create_buffer_init(device, &wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: bytemuck::cast_slice(&vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        })
+    }
+
+    fn generate_indices_buffer(segments: &u16, device: &Device) -> Buffer {
+        let mut indices = Vec::new();
+
+        let perimeter_count = (*segments + 1) * 4;
+
+        for i in 0..perimeter_count {
+            indices.push(0);
+            indices.push(i + 1);
+            indices.push((i + 1) % perimeter_count + 1);
+        }
+
+        profiler::create_buffer_init(device, &wgpu::util::BufferInitDescriptor {
+            label: Some("Index Buffer"),
+            contents: bytemuck::cast_slice(&indices),
+            usage: wgpu::BufferUsages::INDEX,
+        })
+    }
+}
+
+impl Shape for RoundedQuad {
+    fn get_vertex_buffer(&self) -> &Buffer {
+        &self.vertex_buffer
+    }
+
+    fn update_vertex_buffer(&mut self, device: &Device) {
+        self.vertex_buffer = RoundedQuad::generate_vertex_buffer(&self.top_left, &self.bottom_right, &self.radius, &self.segments, &self.color, device);
+    }
+
+    fn get_indices_buffer(&self) -> &Buffer {
+        &self.indices_buffer
+    }
+
+    fn update_indices_buffer(&mut self, device: &Device) {
+        self.indices_buffer = RoundedQuad::generate_indices_buffer(&self.segments, device);
+    }
+
+    fn get_number_indices(&self) -> u32 {
+        (self.segments as u32 + 1) * 4 * 3
+    }
+
+    fn get_transform_binding(&self) -> &TransformBinding {
+        &self.transform_binding
+    }
+
+    fn get_transform_binding_mut(&mut self) -> &mut TransformBinding {
+        &mut self.transform_binding
+    }
+
+    fn get_render_pipeline(&self) -> &RenderPipeline {
+        &self.render_pipeline
+    }
+}
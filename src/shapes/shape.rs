@@ -1,4 +1,7 @@
-use wgpu::{Buffer, Device, IndexFormat, RenderPass, RenderPipeline};
+use wgpu::{BindGroup, Buffer, Device, IndexFormat, Queue, RenderPass, RenderPipeline};
+use crate::profiler;
+use crate::shapes::uniform::TransformBinding;
+use crate::transform::Transform;
 
 pub trait Shape {
     fn get_vertex_buffer(&self) -> &Buffer;
@@ -9,14 +12,46 @@ pub trait Shape {
 
     fn get_number_indices(&self) -> u32;
 
+    fn get_transform_binding(&self) -> &TransformBinding;
+    fn get_transform_binding_mut(&mut self) -> &mut TransformBinding;
+
+    fn set_transform(&mut self, transform: Transform, queue: &Queue) {
+        self.get_transform_binding_mut().set_transform(transform, queue);
+    }
+
+    /// Multiplies the alpha of every fragment of the shape.
+    fn set_opacity(&mut self, opacity: f32, queue: &Queue) {
+        self.get_transform_binding_mut().set_opacity(opacity, queue);
+    }
+
+    /// Multiplies the color of every vertex, so the color can change without new vertex buffers.
+    fn set_tint(&mut self, tint: [f32; 3], queue: &Queue) {
+        self.get_transform_binding_mut().set_tint(tint, queue);
+    }
+
+    /// The texture bound at `@group(1)` of textured shapes.
+    fn get_texture_bind_group(&self) -> Option<&BindGroup> {
+        None
+    }
+
     fn get_render_pipeline(&self) -> &RenderPipeline;
 
     fn draw<'a>(&'a self, render_pass: &mut RenderPass<'a>) {
-        render_pass.set_pipeline(self.get_render_pipeline());
+        self.draw_with_pipeline(self.get_render_pipeline(), render_pass);
+    }
+
+    /// Draws the geometry of the shape with a pipeline other than its own.
+    fn draw_with_pipeline<'a>(&'a self, render_pipeline: &'a RenderPipeline, render_pass: &mut RenderPass<'a>) {
+        render_pass.set_pipeline(render_pipeline);
 
+        render_pass.set_bind_group(0, self.get_transform_binding().get_bind_group(), &[]);
+        if let Some(texture_bind_group) = self.get_texture_bind_group() {
+            render_pass.set_bind_group(1, texture_bind_group, &[]);
+        }
         render_pass.set_vertex_buffer(0, self.get_vertex_buffer().slice(..));
         render_pass.set_index_buffer(self.get_indices_buffer().slice(..), IndexFormat::Uint16);
 
         render_pass.draw_indexed(0..self.get_number_indices(), 0, 0..1);
+        profiler::count_draw_call();
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,111 @@
+use std::rc::Rc;
+use wgpu::{BindGroup, Buffer, Device, RenderPipeline};
+use crate::profiler;
+use crate::Shape;
+use crate::shapes::pipeline::PipelineCache;
+use crate::shapes::uniform::TransformBinding;
+use crate::shapes::vertex::TexturedVertex;
+use crate::texture::Texture;
+use crate::transform::Transform;
+
+/// A rectangle filled with a texture, stretched to fit. The tint multiplies the colors of the texture.
+pub struct TexturedQuad {
+    pub top_left: (f32, f32),
+    pub bottom_right: (f32, f32),
+
+    texture: Rc<Texture>,
+    texture_bind_group: BindGroup,
+    vertex_buffer: Buffer,
+    indices_buffer: Buffer,
+    transform_binding: TransformBinding,
+
+    render_pipeline: Rc<RenderPipeline>,
+}
+
+impl TexturedQuad {
+    pub fn new(top_left: (f32, f32), bottom_right: (f32, f32), texture: Rc<Texture>, device: &Device, pipelines: &PipelineCache) -> Self {
+        let render_pipeline = pipelines.get_textured(device);
+        let texture_bind_group = texture.create_bind_group(pipelines.get_texture_bind_group_layout(), device, "Texture Bind Group");
+
+        Self {
+            top_left,
+            bottom_right,
+            texture,
+            texture_bind_group,
+            vertex_buffer: Self::generate_vertex_buffer(&top_left, &bottom_right, device),
+            indices_buffer: Self::generate_indices_buffer(device),
+            transform_binding: TransformBinding::new(Transform::IDENTITY, pipelines.get_transform_bind_group_layout(), device),
+            render_pipeline,
+        }
+    }
+
+    pub fn get_texture(&self) -> &Rc<Texture> {
+        &self.texture
+    }
+
+    fn generate_vertex_buffer(top_left: &(f32, f32), bottom_right: &(f32, f32), device: &Device) -> Buffer {
+        let vertices: &[TexturedVertex] = &[
+            TexturedVertex { position: [top_left.0, top_left.1, 0.0], tex_coords: [0.0, 0.0] },
+            TexturedVertex { position: [top_left.0, bottom_right.1, 0.0], tex_coords: [0.0, 1.0] },
+            TexturedVertex { position: [bottom_right.0, bottom_right.1, 0.0], tex_coords: [1.0, 1.0] },
+            TexturedVertex { position: [bottom_right.0, top_left.1, 0.0], tex_coords: [1.0, 0.0] },
+        ];
+
+        profiler::create_buffer_init(device, &wgpu::util::BufferInitDescriptor {
+            label: None,
+            contents: bytemuck::cast_slice(vertices),
+            usage: wgpu::BufferUsages::VERTEX,
+        })
+    }
+
+    fn generate_indices_buffer(device: &Device) -> Buffer {
+        let indices: &[u16] = &[
+            0, 1, 2,
+            0, 2, 3
+        ];
+
+        profiler::create_buffer_init(device, &wgpu::util::BufferInitDescriptor {
+            label: Some("Index Buffer"),
+            contents: bytemuck::cast_slice(indices),
+            usage: wgpu::BufferUsages::INDEX,
+        })
+    }
+}
+
+impl Shape for TexturedQuad {
+    fn get_vertex_buffer(&self) -> &Buffer {
+        &self.vertex_buffer
+    }
+
+    fn update_vertex_buffer(&mut self, device: &Device) {
+        self.vertex_buffer = TexturedQuad::generate_vertex_buffer(&self.top_left, &self.bottom_right, device);
+    }
+
+    fn get_indices_buffer(&self) -> &Buffer {
+        &self.indices_buffer
+    }
+
+    fn update_indices_buffer(&mut self, device: &Device) {
+        self.indices_buffer = TexturedQuad::generate_indices_buffer(device);
+    }
+
+    fn get_number_indices(&self) -> u32 {
+        6
+    }
+
+    fn get_transform_binding(&self) -> &TransformBinding {
+        &self.transform_binding
+    }
+
+    fn get_transform_binding_mut(&mut self) -> &mut TransformBinding {
+        &mut self.transform_binding
+    }
+
+    fn get_texture_bind_group(&self) -> Option<&BindGroup> {
+        Some(&self.texture_bind_group)
+    }
+
+    fn get_render_pipeline(&self) -> &RenderPipeline {
+        &self.render_pipeline
+    }
+}
@@ -0,0 +1,129 @@
+use wgpu::{BindGroup, BindGroupLayout, Buffer, Device, Queue};
+use crate::profiler;
+use crate::transform::Transform;
+
+#[repr(C)]
+#[derive(Copy, Clone, Debug, bytemuck::Pod, bytemuck::Zeroable)]
+pub struct TransformUniform {
+    pub view: [[f32; 4]; 4],
+    pub opacity: f32,
+    pub _padding: [f32; 3],
+    pub tint: [f32; 3],
+    pub _tint_padding: f32,
+}
+
+impl TransformUniform {
+    pub fn new(transform: Transform, opacity: f32, tint: [f32; 3]) -> Self {
+        Self {
+            view: transform.to_matrix(),
+            opacity,
+            _padding: [0.0; 3],
+            tint,
+            _tint_padding: 0.0,
+        }
+    }
+}
+
+/// The transform, opacity and tint uniform bound at `@group(0) @binding(0)` of a shape.
+pub struct TransformBinding {
+    transform: Transform,
+    opacity: f32,
+    tint: [f32; 3],
+
+    buffer: Buffer,
+    bind_group: BindGroup,
+}
+
+impl TransformBinding {
+    pub fn create_bind_group_layout(device: &Device) -> BindGroupLayout {
+        device.create_bind_group_layout(&wgpu::BindGroupLayoutDescriptor {
+            label: Some("Transform Bind Group Layout"),
+            entries: &[
+                wgpu::BindGroupLayoutEntry {
+                    binding: 0,
+                    visibility: wgpu::ShaderStages::VERTEX | wgpu::ShaderStages::FRAGMENT,
+                    ty: wgpu::BindingType::Buffer {
+                        ty: wgpu::BufferBindingType::Uniform,
+                        has_dynamic_offset: false,
+                        min_binding_size: None,
+                    },
+                    count: None,
+                }
+            ],
+        })
+    }
+
+    pub fn new(transform: Transform, layout: &BindGroupLayout, device: &Device) -> Self {
+        let buffer = profiler::create_buffer_init(device, &wgpu::util::BufferInitDescriptor {
+            label: Some("Transform Buffer"),
+            contents: bytemuck::cast_slice(&[TransformUniform::new(transform, 1.0, [1.0; 3])]),
+            usage: wgpu::BufferUsages::UNIFORM | wgpu::BufferUsages::COPY_DST,
+        });
+
+        let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+            label: Some("Transform Bind Group"),
+            layout,
+            entries: &[
+                wgpu::BindGroupEntry {
+                    binding: 0,
+                    resource: buffer.as_entire_binding(),
+                }
+            ],
+        });
+
+        Self {
+            transform,
+            opacity: 1.0,
+            tint: [1.0; 3],
+            buffer,
+            bind_group,
+        }
+    }
+
+    pub fn get_transform(&self) -> Transform {
+        self.transform
+    }
+
+    pub fn set_transform(&mut self, transform: Transform, queue: &Queue) {
+        if self.transform == transform {
+            return;
+        }
+
+        self.transform = transform;
+        self.write(queue);
+    }
+
+    pub fn get_opacity(&self) -> f32 {
+        self.opacity
+    }
+
+    pub fn set_opacity(&mut self, opacity: f32, queue: &Queue) {
+        if self.opacity == opacity {
+            return;
+        }
+
+        self.opacity = opacity;
+        self.write(queue);
+    }
+
+    pub fn get_tint(&self) -> [f32; 3] {
+        self.tint
+    }
+
+    pub fn set_tint(&mut self, tint: [f32; 3], queue: &Queue) {
+        if self.tint == tint {
+            return;
+        }
+
+        self.tint = tint;
+        self.write(queue);
+    }
+
+    fn write(&self, queue: &Queue) {
+        queue.write_buffer(&self.buffer, 0, bytemuck::cast_slice(&[TransformUniform::new(self.transform, self.opacity, self.tint)]));
+    }
+
+    pub fn get_bind_group(&self) -> &BindGroup {
+        &self.bind_group
+    }
+}
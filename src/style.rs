@@ -0,0 +1,156 @@
+use crate::theme::Theme;
+
+/// A color, either given directly or looked up in the theme when the component is rendered.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Color {
+    Rgb([f32; 3]),
+    Primary,
+    OnPrimary,
+    Secondary,
+    Surface,
+    OnSurface,
+    Background,
+    Error,
+    OnError,
+    Outline,
+}
+
+/// A size, either in pixels or a step of the theme's spacing scale.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Length {
+    Px(f32),
+    Spacing(usize),
+}
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Edges<T> {
+    pub top: T,
+    pub right: T,
+    pub bottom: T,
+    pub left: T,
+}
+
+impl<T: Copy> Edges<T> {
+    pub fn all(value: T) -> Self {
+        Self::symmetric(value, value)
+    }
+
+    pub fn symmetric(vertical: T, horizontal: T) -> Self {
+        Self {
+            top: vertical,
+            right: horizontal,
+            bottom: vertical,
+            left: horizontal,
+        }
+    }
+
+    pub fn map<U>(&self, mut f: impl FnMut(T) -> U) -> Edges<U> {
+        Edges {
+            top: f(self.top),
+            right: f(self.right),
+            bottom: f(self.bottom),
+            left: f(self.left),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Border {
+    pub color: Color,
+    pub width: Length,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FontFamily {
+    SansSerif,
+    Serif,
+    Monospace,
+}
+
+/// Every property left at `None` is inherited from the parent.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Font {
+    pub family: Option<FontFamily>,
+    pub size: Option<Length>,
+    pub bold: Option<bool>,
+}
+
+/// How a component looks. Properties left at `None` fall back to the parent for the foreground and the font,
+/// like `color` and `font` in CSS, and to nothing for the others.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Style {
+    pub background: Option<Color>,
+    pub border: Option<Border>,
+    pub radius: Option<Length>,
+    pub padding: Option<Edges<Length>>,
+    pub margin: Option<Edges<Length>>,
+    pub font: Font,
+    pub foreground: Option<Color>,
+}
+
+impl Style {
+    /// Returns this style with every property that is set in `over` replaced.
+    pub fn merge(&self, over: &Style) -> Style {
+        Style {
+            background: over.background.or(self.background),
+            border: over.border.or(self.border),
+            radius: over.radius.or(self.radius),
+            padding: over.padding.or(self.padding),
+            margin: over.margin.or(self.margin),
+            font: Font {
+                family: over.font.family.or(self.font.family),
+                size: over.font.size.or(self.font.size),
+                bold: over.font.bold.or(self.font.bold),
+            },
+            foreground: over.foreground.or(self.foreground),
+        }
+    }
+
+    /// Looks up the theme tokens and fills in what is inherited from `parent`. Sizes end up in pixels.
+    pub fn resolve(&self, theme: &Theme, parent: &InheritedStyle) -> ResolvedStyle {
+        let length = |length: Length| theme.length(length);
+
+        ResolvedStyle {
+            background: self.background.map(|color| theme.color(color)),
+            border: self.border.map(|border| (theme.color(border.color), length(border.width))),
+            radius: self.radius.map_or(0.0, length),
+            padding: self.padding.map_or(Edges::all(0.0), |padding| padding.map(length)),
+            margin: self.margin.map_or(Edges::all(0.0), |margin| margin.map(length)),
+            inherited: InheritedStyle {
+                foreground: self.foreground.map_or(parent.foreground, |color| theme.color(color)),
+                font: ResolvedFont {
+                    family: self.font.family.unwrap_or(parent.font.family),
+                    size: self.font.size.map_or(parent.font.size, length),
+                    bold: self.font.bold.unwrap_or(parent.font.bold),
+                },
+            },
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ResolvedFont {
+    pub family: FontFamily,
+    /// In pixels.
+    pub size: f32,
+    pub bold: bool,
+}
+
+/// The properties passed down to the children through the `RenderContext`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct InheritedStyle {
+    pub foreground: [f32; 3],
+    pub font: ResolvedFont,
+}
+
+/// A `Style` with the theme applied. Sizes are given in pixels.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ResolvedStyle {
+    pub background: Option<[f32; 3]>,
+    /// The color and width of the border.
+    pub border: Option<([f32; 3], f32)>,
+    pub radius: f32,
+    pub padding: Edges<f32>,
+    pub margin: Edges<f32>,
+    pub inherited: InheritedStyle,
+}
@@ -0,0 +1,550 @@
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use crate::style::{Border, Color, Edges, FontFamily, Length, Style};
+
+/// The interaction state of a component, matched by the pseudo-classes.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq)]
+pub struct ElementState {
+    pub hovered: bool,
+    pub pressed: bool,
+    pub focused: bool,
+    pub disabled: bool,
+    /// Checked checkboxes, radio options and toggles.
+    pub checked: bool,
+}
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum PseudoClass {
+    Hover,
+    Pressed,
+    Focus,
+    Disabled,
+    Checked,
+}
+
+impl PseudoClass {
+    fn matches(&self, state: &ElementState) -> bool {
+        match self {
+            PseudoClass::Hover => state.hovered,
+            PseudoClass::Pressed => state.pressed,
+            PseudoClass::Focus => state.focused,
+            PseudoClass::Disabled => state.disabled,
+            PseudoClass::Checked => state.checked,
+        }
+    }
+}
+
+/// What selectors see of a component.
+#[derive(Copy, Clone, Debug)]
+pub struct StyleElement<'a> {
+    pub type_name: &'a str,
+    pub id: Option<&'a str>,
+    pub classes: &'a [String],
+    pub state: ElementState,
+}
+
+/// A component and its styled ancestors, the parent first.
+#[derive(Copy, Clone, Debug)]
+pub struct StylePath<'a> {
+    pub element: StyleElement<'a>,
+    pub parent: Option<&'a StylePath<'a>>,
+}
+
+impl<'a> StylePath<'a> {
+    pub fn ancestors(&self) -> impl Iterator<Item = &StyleElement<'a>> {
+        let mut next = self.parent;
+
+        std::iter::from_fn(move || {
+            let path = next?;
+            next = path.parent;
+            Some(&path.element)
+        })
+    }
+}
+
+/// A type, ids, classes and pseudo-classes that all have to match one component, like `Button.primary:hover`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct CompoundSelector {
+    pub type_name: Option<String>,
+    pub id: Option<String>,
+    pub classes: Vec<String>,
+    pub pseudo_classes: Vec<PseudoClass>,
+}
+
+impl CompoundSelector {
+    pub fn matches(&self, element: &StyleElement) -> bool {
+        self.type_name.as_ref().is_none_or(|type_name| type_name == element.type_name)
+            && self.id.as_ref().is_none_or(|id| Some(id.as_str()) == element.id)
+            && self.classes.iter().all(|class| element.classes.contains(class))
+            && self.pseudo_classes.iter().all(|pseudo_class| pseudo_class.matches(&element.state))
+    }
+}
+
+/// Ordered like in CSS: ids, then classes and pseudo-classes, then types.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Specificity(pub u32, pub u32, pub u32);
+
+/// Compound selectors separated by descendant combinators. The last one has to match the component itself,
+/// the others any of its ancestors in order.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Selector {
+    pub compounds: Vec<CompoundSelector>,
+}
+
+impl Selector {
+    pub fn get_specificity(&self) -> Specificity {
+        self.compounds.iter().fold(Specificity::default(), |specificity, compound| {
+            Specificity(specificity.0 + compound.id.is_some() as u32,
+                        specificity.1 + (compound.classes.len() + compound.pseudo_classes.len()) as u32,
+                        specificity.2 + compound.type_name.is_some() as u32)
+        })
+    }
+
+    pub fn matches(&self, path: &StylePath) -> bool {
+        let (subject, ancestors) = match self.compounds.split_last() {
+            None => return false,
+            Some(split) => split,
+        };
+
+        if !subject.matches(&path.element) {
+            return false;
+        }
+
+        // Matching every compound with the nearest fitting ancestor is enough for descendant combinators
+        let mut elements = path.ancestors();
+        ancestors.iter().rev().all(|compound| elements.any(|element| compound.matches(element)))
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Rule {
+    pub selectors: Vec<Selector>,
+    pub style: Style,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StylesheetError {
+    pub line: usize,
+    pub message: String,
+}
+
+impl Display for StylesheetError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.message)
+    }
+}
+
+impl std::error::Error for StylesheetError {}
+
+/// Styles for components picked by selectors, so the look can be changed without recompiling.
+///
+/// ```css
+/// /* Comments are allowed */
+/// LayoutComponent#root { background: surface; padding: spacing(2); radius: 24px }
+/// .stripe, Button:hover { background: #ff8000; border: 2px outline }
+/// ```
+///
+/// Colors are `#rrggbb`, `rgb(r, g, b)` with values up to 255, or a theme token like `on-primary`.
+/// Lengths are pixels like `4px` or steps of the theme's spacing scale like `spacing(2)`.
+/// The properties are `background`, `border` (width and color), `radius`, `padding` and `margin` (one to four lengths),
+/// `color`, `font-family`, `font-size` and `font-weight`.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Stylesheet {
+    pub rules: Vec<Rule>,
+}
+
+impl Stylesheet {
+    pub fn parse(source: &str) -> Result<Stylesheet, StylesheetError> {
+        Parser::new(source).parse()
+    }
+
+    /// Parses the declarations of a single rule without the braces, like `background: primary; padding: 4px`.
+    pub fn parse_declarations(source: &str) -> Result<Style, StylesheetError> {
+        Parser::new(source).parse_declarations(source, 0)
+    }
+
+    pub fn load<P: AsRef<Path>>(path: P) -> anyhow::Result<Stylesheet> {
+        let source = std::fs::read_to_string(path)?;
+        Ok(Self::parse(&source)?)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    /// Cascades the rules matching `path`. More specific rules win, later ones win among equally specific ones.
+    pub fn compute(&self, path: &StylePath) -> Style {
+        let mut matching = vec![];
+        for (order, rule) in self.rules.iter().enumerate() {
+            let specificity = rule.selectors.iter()
+                .filter(|selector| selector.matches(path))
+                .map(|selector| selector.get_specificity())
+                .max();
+
+            if let Some(specificity) = specificity {
+                matching.push((specificity, order, &rule.style));
+            }
+        }
+
+        matching.sort_by_key(|(specificity, order, _)| (*specificity, *order));
+        matching.iter().fold(Style::default(), |style, (_, _, rule_style)| style.merge(rule_style))
+    }
+}
+
+struct Parser<'s> {
+    source: &'s str,
+    position: usize,
+}
+
+impl<'s> Parser<'s> {
+    fn new(source: &'s str) -> Self {
+        Self {
+            source,
+            position: 0,
+        }
+    }
+
+    fn parse(mut self) -> Result<Stylesheet, StylesheetError> {
+        let mut rules = vec![];
+
+        loop {
+            self.skip_whitespace()?;
+            if self.position >= self.source.len() {
+                return Ok(Stylesheet { rules });
+            }
+
+            let selectors_start = self.position;
+            let selectors = self.read_until('{')?;
+            let selectors = selectors.split(',')
+                .map(|selector| self.parse_selector(selector.trim(), selectors_start))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let declarations_start = self.position;
+            let declarations = self.read_until('}')?;
+            let style = self.parse_declarations(&declarations, declarations_start)?;
+
+            rules.push(Rule { selectors, style });
+        }
+    }
+
+    /// Skips whitespace and comments.
+    fn skip_whitespace(&mut self) -> Result<(), StylesheetError> {
+        loop {
+            let rest = &self.source[self.position..];
+            let trimmed = rest.trim_start();
+            self.position += rest.len() - trimmed.len();
+
+            if !trimmed.starts_with("/*") {
+                return Ok(());
+            }
+
+            match trimmed.find("*/") {
+                None => return Err(self.error(self.position, "unterminated comment")),
+                Some(end) => self.position += end + 2,
+            }
+        }
+    }
+
+    /// Returns the text up to `delimiter` without comments and moves behind the delimiter.
+    fn read_until(&mut self, delimiter: char) -> Result<String, StylesheetError> {
+        let start = self.position;
+        let mut text = String::new();
+
+        loop {
+            let rest = &self.source[self.position..];
+            let next = rest.find([delimiter, '/', '{', '}']);
+
+            match next {
+                None => return Err(self.error(start, &format!("expected '{}'", delimiter))),
+                Some(index) => {
+                    text.push_str(&rest[..index]);
+                    self.position += index;
+                }
+            }
+
+            let c = self.source[self.position..].chars().next().expect("a delimiter was found");
+            if c == delimiter {
+                self.position += 1;
+                return Ok(text);
+            }
+
+            if c == '/' && self.source[self.position..].starts_with("/*") {
+                let before = self.position;
+                self.skip_whitespace()?;
+                // Keeps the line count of the declaration offsets right
+                text.push_str(&" ".repeat(self.position - before));
+                continue;
+            }
+
+            if c == '/' {
+                text.push(c);
+                self.position += 1;
+                continue;
+            }
+
+            return Err(self.error(self.position, &format!("unexpected '{}', expected '{}'", c, delimiter)));
+        }
+    }
+
+    fn parse_selector(&self, text: &str, offset: usize) -> Result<Selector, StylesheetError> {
+        if text.is_empty() {
+            return Err(self.error(offset, "empty selector"));
+        }
+
+        let compounds = text.split_whitespace()
+            .map(|compound| self.parse_compound(compound, offset))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok(Selector { compounds })
+    }
+
+    fn parse_compound(&self, text: &str, offset: usize) -> Result<CompoundSelector, StylesheetError> {
+        let mut compound = CompoundSelector::default();
+
+        let mut rest = text;
+        if let Some(universal) = rest.strip_prefix('*') {
+            rest = universal;
+        } else {
+            let end = rest.find(['#', '.', ':']).unwrap_or(rest.len());
+            if end > 0 {
+                compound.type_name = Some(self.parse_identifier(&rest[..end], offset)?);
+            }
+            rest = &rest[end..];
+        }
+
+        while let Some(prefix) = rest.chars().next() {
+            if !matches!(prefix, '#' | '.' | ':') {
+                return Err(self.error(offset, &format!("unexpected '{}' in selector '{}'", prefix, text)));
+            }
+
+            let start = prefix.len_utf8();
+            let end = rest[start..].find(['#', '.', ':']).map_or(rest.len(), |end| end + start);
+            let name = self.parse_identifier(&rest[start..end], offset)?;
+
+            match prefix {
+                '#' => compound.id = Some(name),
+                '.' => compound.classes.push(name),
+                ':' => compound.pseudo_classes.push(match name.as_str() {
+                    "hover" => PseudoClass::Hover,
+                    "pressed" | "active" => PseudoClass::Pressed,
+                    "focus" => PseudoClass::Focus,
+                    "disabled" => PseudoClass::Disabled,
+                    "checked" => PseudoClass::Checked,
+                    _ => return Err(self.error(offset, &format!("unknown pseudo-class ':{}'", name))),
+                }),
+                _ => unreachable!("the prefix was checked"),
+            }
+
+            rest = &rest[end..];
+        }
+
+        Ok(compound)
+    }
+
+    fn parse_identifier(&self, text: &str, offset: usize) -> Result<String, StylesheetError> {
+        let valid = !text.is_empty() && text.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_');
+
+        if valid {
+            Ok(text.to_string())
+        } else {
+            Err(self.error(offset, &format!("invalid name '{}'", text)))
+        }
+    }
+
+    fn parse_declarations(&self, text: &str, mut offset: usize) -> Result<Style, StylesheetError> {
+        let mut style = Style::default();
+
+        for declaration in text.split(';') {
+            if !declaration.trim().is_empty() {
+                self.parse_declaration(declaration, offset, &mut style)?;
+            }
+            offset += declaration.len() + 1;
+        }
+
+        Ok(style)
+    }
+
+    fn parse_declaration(&self, text: &str, offset: usize, style: &mut Style) -> Result<(), StylesheetError> {
+        let offset = offset + (text.len() - text.trim_start().len());
+
+        let (property, value) = text.split_once(':')
+            .ok_or_else(|| self.error(offset, &format!("expected 'property: value', found '{}'", text.trim())))?;
+        let property = property.trim();
+        let value = value.trim();
+
+        let result = match property {
+            "background" | "background-color" => self.parse_color(value).map(|color| style.background = Some(color)),
+            "border" => self.parse_border(value).map(|border| style.border = Some(border)),
+            "radius" | "border-radius" => self.parse_length(value).map(|radius| style.radius = Some(radius)),
+            "padding" => self.parse_edges(value).map(|padding| style.padding = Some(padding)),
+            "margin" => self.parse_edges(value).map(|margin| style.margin = Some(margin)),
+            "color" => self.parse_color(value).map(|color| style.foreground = Some(color)),
+            "font-family" => self.parse_font_family(value).map(|family| style.font.family = Some(family)),
+            "font-size" => self.parse_length(value).map(|size| style.font.size = Some(size)),
+            "font-weight" => self.parse_font_weight(value).map(|bold| style.font.bold = Some(bold)),
+            _ => Err(format!("unknown property '{}'", property)),
+        };
+
+        result.map_err(|message| self.error(offset, &message))
+    }
+
+    fn parse_color(&self, value: &str) -> Result<Color, String> {
+        if let Some(hex) = value.strip_prefix('#') {
+            let channel = |index: usize| hex.get(index..index + 2)
+                .and_then(|channel| u8::from_str_radix(channel, 16).ok())
+                .map(|channel| channel as f32 / 255.0);
+
+            return match (hex.len(), channel(0), channel(2), channel(4)) {
+                (6, Some(r), Some(g), Some(b)) => Ok(Color::Rgb([r, g, b])),
+                _ => Err(format!("invalid color '{}'", value)),
+            };
+        }
+
+        if let Some(channels) = value.strip_prefix("rgb(").and_then(|value| value.strip_suffix(')')) {
+            let channels = channels.split(',')
+                .map(|channel| channel.trim().parse::<f32>().map(|channel| channel / 255.0))
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|_| format!("invalid color '{}'", value))?;
+
+            return match channels[..] {
+                [r, g, b] => Ok(Color::Rgb([r, g, b])),
+                _ => Err(format!("invalid color '{}'", value)),
+            };
+        }
+
+        match value {
+            "primary" => Ok(Color::Primary),
+            "on-primary" => Ok(Color::OnPrimary),
+            "secondary" => Ok(Color::Secondary),
+            "surface" => Ok(Color::Surface),
+            "on-surface" => Ok(Color::OnSurface),
+            "background" => Ok(Color::Background),
+            "error" => Ok(Color::Error),
+            "on-error" => Ok(Color::OnError),
+            "outline" => Ok(Color::Outline),
+            _ => Err(format!("invalid color '{}'", value)),
+        }
+    }
+
+    fn parse_length(&self, value: &str) -> Result<Length, String> {
+        if let Some(step) = value.strip_prefix("spacing(").and_then(|value| value.strip_suffix(')')) {
+            return step.trim().parse().map(Length::Spacing).map_err(|_| format!("invalid spacing step '{}'", value));
+        }
+
+        let pixels = value.strip_suffix("px").unwrap_or(value);
+        pixels.parse().map(Length::Px).map_err(|_| format!("invalid length '{}'", value))
+    }
+
+    /// One to four lengths, in the order of CSS.
+    fn parse_edges(&self, value: &str) -> Result<Edges<Length>, String> {
+        let lengths = value.split_whitespace()
+            .map(|length| self.parse_length(length))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match lengths[..] {
+            [all] => Ok(Edges::all(all)),
+            [vertical, horizontal] => Ok(Edges::symmetric(vertical, horizontal)),
+            [top, horizontal, bottom] => Ok(Edges { top, right: horizontal, bottom, left: horizontal }),
+            [top, right, bottom, left] => Ok(Edges { top, right, bottom, left }),
+            _ => Err(format!("expected one to four lengths, found '{}'", value)),
+        }
+    }
+
+    fn parse_border(&self, value: &str) -> Result<Border, String> {
+        match value.split_whitespace().collect::<Vec<_>>()[..] {
+            [width, color] => Ok(Border { width: self.parse_length(width)?, color: self.parse_color(color)? }),
+            _ => Err(format!("expected a width and a color, found '{}'", value)),
+        }
+    }
+
+    fn parse_font_family(&self, value: &str) -> Result<FontFamily, String> {
+        match value {
+            "sans-serif" => Ok(FontFamily::SansSerif),
+            "serif" => Ok(FontFamily::Serif),
+            "monospace" => Ok(FontFamily::Monospace),
+            _ => Err(format!("unknown font family '{}'", value)),
+        }
+    }
+
+    /// Returns whether the weight is bold.
+    fn parse_font_weight(&self, value: &str) -> Result<bool, String> {
+        match value {
+            "bold" => Ok(true),
+            "normal" => Ok(false),
+            _ => Err(format!("invalid font weight '{}'", value)),
+        }
+    }
+
+    fn error(&self, offset: usize, message: &str) -> StylesheetError {
+        StylesheetError {
+            line: self.source[..offset.min(self.source.len())].matches('\n').count() + 1,
+            message: message.to_string(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn element<'a>(type_name: &'a str, id: Option<&'a str>, classes: &'a [String], state: ElementState) -> StyleElement<'a> {
+        StyleElement { type_name, id, classes, state }
+    }
+
+    #[test]
+    fn compound_selectors_are_parsed() {
+        let stylesheet = Stylesheet::parse("Button#ok.primary.large:hover { background: primary }").unwrap();
+        let compound = &stylesheet.rules[0].selectors[0].compounds[0];
+
+        assert_eq!(compound.type_name.as_deref(), Some("Button"));
+        assert_eq!(compound.id.as_deref(), Some("ok"));
+        assert_eq!(compound.classes, ["primary", "large"]);
+        assert_eq!(compound.pseudo_classes, [PseudoClass::Hover]);
+        assert_eq!(stylesheet.rules[0].style.background, Some(Color::Primary));
+    }
+
+    #[test]
+    fn specificity_counts_ids_classes_and_types() {
+        let stylesheet = Stylesheet::parse("LayoutComponent #root .stripe:hover, * { color: primary }").unwrap();
+        let selectors = &stylesheet.rules[0].selectors;
+
+        assert_eq!(selectors[0].get_specificity(), Specificity(1, 2, 1));
+        assert_eq!(selectors[1].get_specificity(), Specificity(0, 0, 0));
+        assert!(Specificity(1, 0, 0) > Specificity(0, 9, 9));
+    }
+
+    #[test]
+    fn more_specific_rules_win_over_later_ones() {
+        let stylesheet = Stylesheet::parse("Button.primary { background: primary } Button { background: error }").unwrap();
+        let classes = ["primary".to_string()];
+        let path = StylePath { element: element("Button", None, &classes, ElementState::default()), parent: None };
+
+        assert_eq!(stylesheet.compute(&path).background, Some(Color::Primary));
+    }
+
+    #[test]
+    fn descendant_selectors_match_ancestors() {
+        let stylesheet = Stylesheet::parse("#root Button { background: primary }").unwrap();
+        let root = StylePath { element: element("LayoutComponent", Some("root"), &[], ElementState::default()), parent: None };
+        let layout = StylePath { element: element("LayoutComponent", None, &[], ElementState::default()), parent: Some(&root) };
+        let button = StylePath { element: element("Button", None, &[], ElementState::default()), parent: Some(&layout) };
+        let outside = StylePath { element: element("Button", None, &[], ElementState::default()), parent: None };
+
+        assert_eq!(stylesheet.compute(&button).background, Some(Color::Primary));
+        assert_eq!(stylesheet.compute(&outside).background, None);
+    }
+
+    #[test]
+    fn unexpected_characters_in_selectors_are_errors() {
+        for source in ["*é { color: primary }", "Button*é { color: primary }", "*&.a { color: primary }"] {
+            assert!(Stylesheet::parse(source).is_err(), "{}", source);
+        }
+    }
+
+    #[test]
+    fn errors_report_their_line() {
+        let error = Stylesheet::parse("Button { color: primary }\n/* comment */\n.a { colour: primary }").unwrap_err();
+
+        assert_eq!(error.line, 3);
+    }
+}
@@ -0,0 +1,42 @@
+// Vertex shader
+
+struct TransformUniform {
+    view: mat4x4<f32>,
+    opacity: f32,
+    tint: vec3<f32>,
+};
+
+@group(0) @binding(0)
+var<uniform> transform: TransformUniform;
+
+struct VertexInput {
+    @location(0) position: vec3<f32>,
+    @location(1) tex_coords: vec2<f32>,
+};
+
+struct VertexOutput {
+    @builtin(position) clip_position: vec4<f32>,
+    @location(0) tex_coords: vec2<f32>,
+};
+
+@vertex
+fn vs_main(
+    model: VertexInput,
+) -> VertexOutput {
+    var out: VertexOutput;
+    out.tex_coords = model.tex_coords;
+    out.clip_position = transform.view * vec4<f32>(model.position, 1.0);
+    return out;
+}
+
+// Fragment shader
+
+@group(1) @binding(0)
+var t_image: texture_2d<f32>;
+@group(1) @binding(1)
+var s_image: sampler;
+
+@fragment
+fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
+    return textureSample(t_image, s_image, in.tex_coords) * vec4<f32>(transform.tint, transform.opacity);
+}
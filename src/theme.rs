@@ -0,0 +1,95 @@
+use crate::style::{Color, FontFamily, InheritedStyle, Length, ResolvedFont};
+
+/// The named colors and sizes styles refer to. Switching the theme restyles every component on the next frame.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Theme {
+    pub primary: [f32; 3],
+    pub on_primary: [f32; 3],
+    pub secondary: [f32; 3],
+    pub surface: [f32; 3],
+    pub on_surface: [f32; 3],
+    /// Also the clear color of the window.
+    pub background: [f32; 3],
+    pub error: [f32; 3],
+    pub on_error: [f32; 3],
+    pub outline: [f32; 3],
+
+    /// The pixel sizes of `Length::Spacing`. Larger steps are clamped to the last one.
+    pub spacing: [f32; 6],
+    /// The font of the root, inherited by everything that does not set its own.
+    pub font: ResolvedFont,
+}
+
+impl Theme {
+    pub fn dark() -> Self {
+        Self {
+            primary: [0.2, 0.4, 0.8],
+            on_primary: [1.0, 1.0, 1.0],
+            secondary: [0.3, 0.7, 0.6],
+            surface: [0.15, 0.25, 0.35],
+            on_surface: [0.9, 0.9, 0.9],
+            background: [0.1, 0.2, 0.3],
+            error: [0.8, 0.2, 0.2],
+            on_error: [1.0, 1.0, 1.0],
+            outline: [0.4, 0.5, 0.6],
+            spacing: [0.0, 4.0, 8.0, 16.0, 24.0, 32.0],
+            font: ResolvedFont {
+                family: FontFamily::SansSerif,
+                size: 14.0,
+                bold: false,
+            },
+        }
+    }
+
+    pub fn light() -> Self {
+        Self {
+            primary: [0.15, 0.35, 0.75],
+            on_primary: [1.0, 1.0, 1.0],
+            secondary: [0.1, 0.55, 0.5],
+            surface: [1.0, 1.0, 1.0],
+            on_surface: [0.1, 0.1, 0.1],
+            background: [0.92, 0.93, 0.95],
+            error: [0.75, 0.1, 0.1],
+            on_error: [1.0, 1.0, 1.0],
+            outline: [0.7, 0.72, 0.75],
+            ..Self::dark()
+        }
+    }
+
+    pub fn color(&self, color: Color) -> [f32; 3] {
+        match color {
+            Color::Rgb(rgb) => rgb,
+            Color::Primary => self.primary,
+            Color::OnPrimary => self.on_primary,
+            Color::Secondary => self.secondary,
+            Color::Surface => self.surface,
+            Color::OnSurface => self.on_surface,
+            Color::Background => self.background,
+            Color::Error => self.error,
+            Color::OnError => self.on_error,
+            Color::Outline => self.outline,
+        }
+    }
+
+    /// Converts a length to pixels.
+    pub fn length(&self, length: Length) -> f32 {
+        match length {
+            Length::Px(pixels) => pixels,
+            Length::Spacing(step) => self.spacing[step.min(self.spacing.len() - 1)],
+        }
+    }
+
+    /// What the root passes down to its children.
+    pub fn get_inherited(&self) -> InheritedStyle {
+        InheritedStyle {
+            foreground: self.on_surface,
+            font: self.font,
+        }
+    }
+}
+
+impl Default for Theme {
+    fn default() -> Self {
+        Self::dark()
+    }
+}
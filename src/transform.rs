@@ -0,0 +1,180 @@
+/// A 2D affine transform.
+///
+/// A point `(x, y)` is mapped to `(a * x + c * y + tx, b * x + d * y + ty)`.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transform {
+    pub a: f32,
+    pub b: f32,
+    pub c: f32,
+    pub d: f32,
+    pub tx: f32,
+    pub ty: f32,
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform::IDENTITY
+    }
+}
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform { a: 1.0, b: 0.0, c: 0.0, d: 1.0, tx: 0.0, ty: 0.0 };
+
+    pub fn translation(x: f32, y: f32) -> Self {
+        Transform { tx: x, ty: y, ..Transform::IDENTITY }
+    }
+
+    /// Rotation by `angle` radians. In pixel space (y pointing down) a positive angle rotates clockwise.
+    pub fn rotation(angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Transform { a: cos, b: sin, c: -sin, d: cos, tx: 0.0, ty: 0.0 }
+    }
+
+    pub fn scaling(x: f32, y: f32) -> Self {
+        Transform { a: x, d: y, ..Transform::IDENTITY }
+    }
+
+    /// Skew by `angle_x` radians along the x axis and `angle_y` radians along the y axis.
+    pub fn skewing(angle_x: f32, angle_y: f32) -> Self {
+        Transform { c: angle_x.tan(), b: angle_y.tan(), ..Transform::IDENTITY }
+    }
+
+    /// Returns the transform that applies `self` first and `next` afterwards.
+    pub fn then(&self, next: &Transform) -> Transform {
+        Transform {
+            a: next.a * self.a + next.c * self.b,
+            b: next.b * self.a + next.d * self.b,
+            c: next.a * self.c + next.c * self.d,
+            d: next.b * self.c + next.d * self.d,
+            tx: next.a * self.tx + next.c * self.ty + next.tx,
+            ty: next.b * self.tx + next.d * self.ty + next.ty,
+        }
+    }
+
+    pub fn translate(self, x: f32, y: f32) -> Self {
+        self.then(&Transform::translation(x, y))
+    }
+
+    pub fn rotate(self, angle: f32) -> Self {
+        self.then(&Transform::rotation(angle))
+    }
+
+    pub fn scale(self, x: f32, y: f32) -> Self {
+        self.then(&Transform::scaling(x, y))
+    }
+
+    pub fn skew(self, angle_x: f32, angle_y: f32) -> Self {
+        self.then(&Transform::skewing(angle_x, angle_y))
+    }
+
+    pub fn inverse(&self) -> Option<Transform> {
+        let determinant = self.a * self.d - self.b * self.c;
+        if determinant.abs() < f32::EPSILON {
+            return None;
+        }
+
+        let a = self.d / determinant;
+        let b = -self.b / determinant;
+        let c = -self.c / determinant;
+        let d = self.a / determinant;
+
+        Some(Transform {
+            a,
+            b,
+            c,
+            d,
+            tx: -(a * self.tx + c * self.ty),
+            ty: -(b * self.tx + d * self.ty),
+        })
+    }
+
+    pub fn apply(&self, point: (f32, f32)) -> (f32, f32) {
+        (self.a * point.0 + self.c * point.1 + self.tx,
+         self.b * point.0 + self.d * point.1 + self.ty)
+    }
+
+    pub fn is_identity(&self) -> bool {
+        *self == Transform::IDENTITY
+    }
+
+    /// Whether axis aligned rectangles stay axis aligned, i.e. there is no rotation or skew.
+    pub fn is_axis_aligned(&self) -> bool {
+        self.b == 0.0 && self.c == 0.0
+    }
+
+    /// Converts a transform given in pixels around `center` (a view point) into one that works on view points.
+    /// Working in pixels keeps rotations and skews free of the window's aspect ratio.
+    pub fn to_view_space(&self, center: (f32, f32), width: u32, height: u32) -> Transform {
+        let (width, height) = (width.max(1) as f32, height.max(1) as f32);
+
+        let view_to_pixel = Transform::scaling(width / 2.0, -height / 2.0)
+            .translate(width / 2.0, height / 2.0);
+        let pixel_center = view_to_pixel.apply(center);
+
+        view_to_pixel
+            .translate(-pixel_center.0, -pixel_center.1)
+            .then(self)
+            .translate(pixel_center.0, pixel_center.1)
+            .then(&view_to_pixel.inverse().unwrap_or_default())
+    }
+
+    /// Column major 4x4 matrix as expected by `mat4x4<f32>` in the shaders.
+    pub fn to_matrix(&self) -> [[f32; 4]; 4] {
+        [
+            [self.a, self.b, 0.0, 0.0],
+            [self.c, self.d, 0.0, 0.0],
+            [0.0, 0.0, 1.0, 0.0],
+            [self.tx, self.ty, 0.0, 1.0],
+        ]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(actual: (f32, f32), expected: (f32, f32)) {
+        assert!((actual.0 - expected.0).abs() < 1e-5 && (actual.1 - expected.1).abs() < 1e-5, "{:?} != {:?}", actual, expected);
+    }
+
+    #[test]
+    fn then_applies_self_first() {
+        let transform = Transform::translation(1.0, 0.0).scale(2.0, 3.0);
+
+        assert_close(transform.apply((0.0, 0.0)), (2.0, 0.0));
+        assert_close(transform.apply((1.0, 1.0)), (4.0, 3.0));
+        assert_close(Transform::scaling(2.0, 3.0).translate(1.0, 0.0).apply((1.0, 1.0)), (3.0, 3.0));
+    }
+
+    #[test]
+    fn inverses_undo_the_transform() {
+        let transform = Transform::rotation(0.7).skew(0.2, -0.1).scale(2.0, 0.5).translate(3.0, -4.0);
+        let inverse = transform.inverse().unwrap();
+
+        for point in [(0.0, 0.0), (1.0, -2.0), (-3.5, 0.25)] {
+            assert_close(inverse.apply(transform.apply(point)), point);
+        }
+        assert!(Transform::scaling(0.0, 1.0).inverse().is_none());
+    }
+
+    #[test]
+    fn rotations_and_skews_are_not_axis_aligned() {
+        assert!(Transform::IDENTITY.is_identity());
+        assert!(Transform::translation(1.0, 2.0).scale(2.0, 3.0).is_axis_aligned());
+        assert!(!Transform::rotation(0.1).is_axis_aligned());
+        assert!(!Transform::skewing(0.1, 0.0).is_axis_aligned());
+    }
+
+    #[test]
+    fn view_space_transforms_work_in_pixels_around_the_center() {
+        // 50 pixels right of the center of a 200 by 100 window end up 50 pixels below it
+        let quarter = Transform::rotation(std::f32::consts::FRAC_PI_2).to_view_space((0.0, 0.0), 200, 100);
+        assert_close(quarter.apply((0.5, 0.0)), (0.0, -1.0));
+        assert_close(quarter.apply((0.0, 0.0)), (0.0, 0.0));
+
+        // The center stays where it is
+        let scaled = Transform::scaling(2.0, 2.0).to_view_space((0.5, 0.5), 200, 100);
+        assert_close(scaled.apply((0.5, 0.5)), (0.5, 0.5));
+        assert_close(scaled.apply((0.6, 0.5)), (0.7, 0.5));
+    }
+}
@@ -0,0 +1,78 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, OnceLock};
+use std::thread;
+use std::time::{Duration, SystemTime};
+use winit::event_loop::EventLoopProxy;
+
+static EVENT_LOOP: OnceLock<Mutex<EventLoopProxy<()>>> = OnceLock::new();
+static REDRAW_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+pub(crate) fn set_event_loop(proxy: EventLoopProxy<()>) {
+    let _ = EVENT_LOOP.set(Mutex::new(proxy));
+}
+
+/// Requests the next frame from any thread, waking up the event loop if it waits for input.
+pub fn request_redraw() {
+    REDRAW_REQUESTED.store(true, Ordering::SeqCst);
+
+    if let Some(event_loop) = EVENT_LOOP.get() {
+        if let Ok(event_loop) = event_loop.lock() {
+            // Fails only if the event loop already exited
+            let _ = event_loop.send_event(());
+        }
+    }
+}
+
+/// Returns whether a frame was requested from another thread since the last call.
+pub(crate) fn take_redraw_request() -> bool {
+    REDRAW_REQUESTED.swap(false, Ordering::SeqCst)
+}
+
+/// Polls the modification time of a file on a background thread and requests a frame when it changed.
+/// The thread stops when the watcher is dropped.
+pub struct FileWatcher {
+    changed: Arc<AtomicBool>,
+    stopped: Arc<AtomicBool>,
+}
+
+impl FileWatcher {
+    pub fn new(path: PathBuf, interval: Duration) -> Self {
+        let changed = Arc::new(AtomicBool::new(false));
+        let stopped = Arc::new(AtomicBool::new(false));
+
+        let thread_changed = changed.clone();
+        let thread_stopped = stopped.clone();
+        thread::spawn(move || {
+            let modified = |path: &PathBuf| -> Option<SystemTime> { std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok() };
+
+            let mut last_modified = modified(&path);
+            while !thread_stopped.load(Ordering::SeqCst) {
+                thread::sleep(interval);
+
+                let current = modified(&path);
+                if current != last_modified {
+                    last_modified = current;
+                    thread_changed.store(true, Ordering::SeqCst);
+                    request_redraw();
+                }
+            }
+        });
+
+        Self {
+            changed,
+            stopped,
+        }
+    }
+
+    /// Returns whether the file changed since the last call.
+    pub fn take_change(&self) -> bool {
+        self.changed.swap(false, Ordering::SeqCst)
+    }
+}
+
+impl Drop for FileWatcher {
+    fn drop(&mut self) {
+        self.stopped.store(true, Ordering::SeqCst);
+    }
+}